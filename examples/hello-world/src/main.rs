@@ -1,6 +1,6 @@
 use std::num::NonZeroIsize;
 
-use oxidx::dx::*;
+use oxidx::{dx::*, vertex::VertexLayout};
 
 use winit::{
     dpi::PhysicalSize,
@@ -371,9 +371,9 @@ fn create_root_signature(device: &Device) -> RootSignature {
 
 fn create_pipeline_state(device: &Device, root_signature: &RootSignature) -> PipelineState {
     let compile_flags = if cfg!(debug_assertions) {
-        COMPILE_DEBUG | COMPILE_SKIP_OPT
+        CompileFlags::Debug | CompileFlags::SkipOptimization
     } else {
-        0
+        CompileFlags::empty()
     };
 
     let exe_path = std::env::current_exe().ok().unwrap();
@@ -386,7 +386,7 @@ fn create_pipeline_state(device: &Device, root_signature: &RootSignature) -> Pip
         c"VSMain",
         c"vs_5_0",
         compile_flags,
-        0,
+        CompileEffectFlags::empty(),
     )
     .unwrap();
     let pixel_shader = Blob::compile_from_file(
@@ -395,18 +395,13 @@ fn create_pipeline_state(device: &Device, root_signature: &RootSignature) -> Pip
         c"PSMain",
         c"ps_5_0",
         compile_flags,
-        0,
+        CompileEffectFlags::empty(),
     )
     .unwrap();
 
-    let input_element_descs: [InputElementDesc; 2] = [
-        InputElementDesc::per_vertex(SemanticName::Position(0), Format::Rgb32Float, 0),
-        InputElementDesc::per_vertex(SemanticName::Color(0), Format::Rgba32Float, 0),
-    ];
-
     let desc = GraphicsPipelineDesc::new(&vertex_shader)
         .with_root_signature(root_signature)
-        .with_input_layout(&input_element_descs)
+        .with_input_layout(Vertex::INPUT_LAYOUT)
         .with_ps(&pixel_shader)
         .with_rasterizer_state(
             RasterizerDesc::default()
@@ -461,6 +456,7 @@ fn create_vertex_buffer(device: &Device, aspect_ratio: f32) -> (Resource, Vertex
 }
 
 #[repr(C)]
+#[derive(Clone, Copy, VertexLayout)]
 struct Vertex {
     position: [f32; 3],
     color: [f32; 4],
@@ -0,0 +1,136 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr, Type};
+
+use crate::has_repr_c;
+
+struct CbufferField {
+    member: syn::Member,
+    name: String,
+    ty: Type,
+    padding: bool,
+}
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "CbufferLayout can't be derived for generic structs",
+        ));
+    }
+
+    if !has_repr_c(&input) {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "CbufferLayout requires #[repr(C)]",
+        ));
+    }
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "CbufferLayout can only be derived for structs",
+        ));
+    };
+
+    let fields = match &data.fields {
+        Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+        Fields::Unnamed(fields) => fields.unnamed.iter().collect::<Vec<_>>(),
+        Fields::Unit => vec![],
+    };
+
+    let mut parsed = Vec::with_capacity(fields.len());
+
+    for (i, field) in fields.into_iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(i.into()),
+        };
+
+        let mut name = match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => i.to_string(),
+        };
+        let mut padding = false;
+
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("cbuffer")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("padding") {
+                    padding = true;
+                    Ok(())
+                } else if meta.path.is_ident("name") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    name = lit.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `padding` or `name = \"...\"`"))
+                }
+            })?;
+        }
+
+        parsed.push(CbufferField {
+            member,
+            name,
+            ty: field.ty.clone(),
+            padding,
+        });
+    }
+
+    let ident = &input.ident;
+    let visible = parsed.iter().filter(|f| !f.padding).collect::<Vec<_>>();
+    let count = visible.len();
+
+    let types = visible.iter().map(|f| &f.ty).collect::<Vec<_>>();
+    let names = visible.iter().map(|f| &f.name).collect::<Vec<_>>();
+    let indices = (0..count).collect::<Vec<_>>();
+
+    let checks = visible.iter().enumerate().map(|(i, f)| {
+        let member = &f.member;
+        let message = format!(
+            "field `{}` of `{}` doesn't match HLSL constant buffer packing, check the padding before it",
+            f.name, ident
+        );
+
+        quote! {
+            assert!(
+                ::core::mem::offset_of!(#ident, #member)
+                    == <#ident as ::oxidx::cbuffer::CbufferLayout>::FIELDS[#i].offset as usize,
+                #message
+            );
+        }
+    });
+
+    Ok(quote! {
+        unsafe impl ::oxidx::cbuffer::HlslType for #ident {
+            const SIZE: usize = ::oxidx::cbuffer::align_to_register(
+                ::oxidx::cbuffer::packed_size(<#ident as ::oxidx::cbuffer::CbufferLayout>::FIELDS)
+            );
+            const REGISTER_ALIGNED: bool = true;
+        }
+
+        unsafe impl ::oxidx::cbuffer::HlslArrayElement for #ident {}
+
+        unsafe impl ::oxidx::cbuffer::CbufferLayout for #ident {
+            const FIELDS: &'static [::oxidx::cbuffer::CbufferField] = &{
+                const OFFSETS: [usize; #count] = ::oxidx::cbuffer::pack_offsets([
+                    #((
+                        <#types as ::oxidx::cbuffer::HlslType>::SIZE,
+                        <#types as ::oxidx::cbuffer::HlslType>::REGISTER_ALIGNED
+                    )),*
+                ]);
+
+                [
+                    #(::oxidx::cbuffer::CbufferField::new(
+                        #names,
+                        OFFSETS[#indices],
+                        <#types as ::oxidx::cbuffer::HlslType>::SIZE,
+                    )),*
+                ]
+            };
+        }
+
+        const _: () = {
+            #(#checks)*
+        };
+    })
+}
@@ -0,0 +1,58 @@
+mod cbuffer;
+mod vertex;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Derives `oxidx::cbuffer::CbufferLayout` for a `#[repr(C)]` struct.
+///
+/// The macro computes the HLSL constant buffer packing of every field and checks at compile time
+/// that the Rust layout matches it. Fields marked with `#[cbuffer(padding)]` are explicit padding
+/// and are excluded from the HLSL layout. `#[cbuffer(name = "...")]` overrides the HLSL name of a field.
+#[proc_macro_derive(CbufferLayout, attributes(cbuffer))]
+pub fn derive_cbuffer_layout(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    cbuffer::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `oxidx::vertex::VertexLayout` for a `#[repr(C)]` vertex struct.
+///
+/// Every field becomes an input element with an offset computed by `offset_of!`. The semantic is the
+/// uppercased field name, trailing digits are the semantic index (`texcoord1` is `TEXCOORD1`).
+/// `#[vertex(semantic = "...", index = N)]` overrides them and `#[vertex(skip)]` excludes a field.
+/// The format comes from `oxidx::vertex::VertexAttribute` unless the field has `#[format(...)]`,
+/// which accepts both `Format` variants (`Rgba8Unorm`) and DXGI names (`R8G8B8A8Unorm`).
+/// `#[vertex(slot = N, instance_step_rate = N)]` on the struct places all elements in another slot
+/// and makes them per-instance data.
+#[proc_macro_derive(VertexLayout, attributes(vertex, format))]
+pub fn derive_vertex_layout(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    vertex::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+pub(crate) fn has_repr_c(input: &DeriveInput) -> bool {
+    let mut found = false;
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("repr")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") {
+                found = true;
+            }
+
+            if meta.input.peek(syn::token::Paren) {
+                let _content;
+                syn::parenthesized!(_content in meta.input);
+            }
+
+            Ok(())
+        });
+    }
+
+    found
+}
@@ -0,0 +1,239 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Ident, LitByteStr, LitInt, LitStr};
+
+use crate::has_repr_c;
+
+struct VertexField {
+    member: syn::Member,
+    semantic_name: String,
+    semantic_index: u32,
+    ty: syn::Type,
+    format: Option<Ident>,
+}
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "VertexLayout can't be derived for generic structs",
+        ));
+    }
+
+    if !has_repr_c(&input) {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "VertexLayout requires #[repr(C)]",
+        ));
+    }
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "VertexLayout can only be derived for structs",
+        ));
+    };
+
+    let mut input_slot = 0u32;
+    let mut instance_step_rate = None::<u32>;
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("vertex")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("slot") {
+                let lit: LitInt = meta.value()?.parse()?;
+                input_slot = lit.base10_parse()?;
+                Ok(())
+            } else if meta.path.is_ident("instance_step_rate") {
+                let lit: LitInt = meta.value()?.parse()?;
+                instance_step_rate = Some(lit.base10_parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `slot = N` or `instance_step_rate = N`"))
+            }
+        })?;
+    }
+
+    let fields = match &data.fields {
+        Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+        Fields::Unnamed(fields) => fields.unnamed.iter().collect::<Vec<_>>(),
+        Fields::Unit => vec![],
+    };
+
+    let mut parsed = Vec::with_capacity(fields.len());
+
+    for (i, field) in fields.into_iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(i.into()),
+        };
+
+        let mut semantic = field.ident.as_ref().map(|ident| {
+            ident
+                .to_string()
+                .trim_start_matches("r#")
+                .trim_start_matches('_')
+                .to_ascii_uppercase()
+        });
+        let mut semantic_index = None;
+        let mut format = None;
+        let mut skip = false;
+
+        for attr in &field.attrs {
+            if attr.path().is_ident("format") {
+                let ident: Ident = attr.parse_args()?;
+                format = Some(Ident::new(
+                    &normalize_format(&ident.to_string()),
+                    ident.span(),
+                ));
+            } else if attr.path().is_ident("vertex") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("semantic") {
+                        let lit: LitStr = meta.value()?.parse()?;
+                        semantic = Some(lit.value());
+                        Ok(())
+                    } else if meta.path.is_ident("index") {
+                        let lit: LitInt = meta.value()?.parse()?;
+                        semantic_index = Some(lit.base10_parse()?);
+                        Ok(())
+                    } else if meta.path.is_ident("skip") {
+                        skip = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected `semantic = \"...\"`, `index = N` or `skip`"))
+                    }
+                })?;
+            }
+        }
+
+        if skip {
+            continue;
+        }
+
+        let Some(semantic) = semantic else {
+            return Err(syn::Error::new_spanned(
+                field,
+                "tuple fields need #[vertex(semantic = \"...\")]",
+            ));
+        };
+
+        // HLSL semantics like `TEXCOORD1` are the name `TEXCOORD` with the index 1.
+        let (semantic_name, semantic_index) = match semantic_index {
+            Some(index) => (semantic, index),
+            None => split_semantic(&semantic),
+        };
+
+        if semantic_name.is_empty() || semantic_name.contains('\0') {
+            return Err(syn::Error::new_spanned(field, "invalid semantic name"));
+        }
+
+        parsed.push(VertexField {
+            member,
+            semantic_name,
+            semantic_index,
+            ty: field.ty.clone(),
+            format,
+        });
+    }
+
+    let ident = &input.ident;
+
+    let elements = parsed.iter().map(|f| {
+        let member = &f.member;
+        let semantic_index = f.semantic_index;
+
+        let mut name = f.semantic_name.clone().into_bytes();
+        name.push(0);
+        let name = LitByteStr::new(&name, Span::call_site());
+
+        let format = match &f.format {
+            Some(format) => quote! { ::oxidx::dx::Format::#format },
+            None => {
+                let ty = &f.ty;
+                quote! { <#ty as ::oxidx::vertex::VertexAttribute>::FORMAT }
+            }
+        };
+
+        let step_rate = instance_step_rate.map(|step_rate| {
+            quote! { .with_instance_data_step_rate(#step_rate) }
+        });
+
+        quote! {
+            ::oxidx::dx::InputElementDesc::from_raw_per_vertex(
+                match ::core::ffi::CStr::from_bytes_with_nul(#name) {
+                    ::core::result::Result::Ok(name) => name,
+                    ::core::result::Result::Err(_) => ::core::panic!("invalid semantic name"),
+                },
+                #semantic_index,
+                #format,
+                #input_slot,
+            )
+            .with_offset(::core::mem::offset_of!(#ident, #member) as u32)
+            #step_rate
+        }
+    });
+
+    Ok(quote! {
+        impl ::oxidx::vertex::VertexLayout for #ident {
+            const INPUT_LAYOUT: &'static [::oxidx::dx::InputElementDesc<'static>] = &[
+                #(#elements),*
+            ];
+        }
+    })
+}
+
+fn split_semantic(semantic: &str) -> (String, u32) {
+    let name = semantic.trim_end_matches(|c: char| c.is_ascii_digit());
+
+    match semantic[name.len()..].parse() {
+        Ok(index) if !name.is_empty() => (name.to_string(), index),
+        _ => (semantic.to_string(), 0),
+    }
+}
+
+/// Converts DXGI-style names like `R8G8B8A8Unorm` to the names of `Format` variants like `Rgba8Unorm`.
+fn normalize_format(format: &str) -> String {
+    let bytes = format.as_bytes();
+    let mut channels = vec![];
+    let mut pos = 0;
+
+    while pos < bytes.len() && b"RGBAX".contains(&bytes[pos]) {
+        let start = pos + 1;
+        let end = bytes[start..]
+            .iter()
+            .position(|b| !b.is_ascii_digit())
+            .map_or(bytes.len(), |p| start + p);
+
+        if end == start {
+            break;
+        }
+
+        channels.push((bytes[pos] as char, &format[start..end]));
+        pos = end;
+    }
+
+    if channels.len() < 2 {
+        return format.to_string();
+    }
+
+    let mut result = String::new();
+    let mut i = 0;
+
+    while i < channels.len() {
+        let bits = channels[i].1;
+        let run = channels[i..].iter().take_while(|c| c.1 == bits).count();
+
+        for (j, (channel, _)) in channels[i..i + run].iter().enumerate() {
+            if j == 0 {
+                result.push(*channel);
+            } else {
+                result.push(channel.to_ascii_lowercase());
+            }
+        }
+
+        result.push_str(bits);
+        i += run;
+    }
+
+    result.push_str(&format[pos..]);
+    result
+}
@@ -0,0 +1,516 @@
+use std::marker::PhantomData;
+
+use windows::Win32::Graphics::Direct3D12::*;
+
+use crate::types::*;
+
+/// Alignment of the memory of an acceleration structure, `D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BYTE_ALIGNMENT`.
+pub const ACCELERATION_STRUCTURE_ALIGNMENT: u64 = 256;
+
+/// Alignment of the array of [`InstanceDesc`], `D3D12_RAYTRACING_INSTANCE_DESCS_BYTE_ALIGNMENT`.
+pub const INSTANCE_DESCS_ALIGNMENT: u64 = 16;
+
+const MAX_INSTANCE_ID: u32 = (1 << 24) - 1;
+
+/// Describes a set of geometry that is used in a bottom-level acceleration structure.
+///
+/// For more information: [`D3D12_RAYTRACING_GEOMETRY_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_raytracing_geometry_desc)
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct RaytracingGeometryDesc(pub(crate) D3D12_RAYTRACING_GEOMETRY_DESC);
+
+impl RaytracingGeometryDesc {
+    /// Describes non-indexed triangles, every three vertices form a triangle.
+    #[inline]
+    pub fn triangles(
+        vertex_buffer: GpuVirtualAddress,
+        vertex_stride: u64,
+        vertex_format: Format,
+        vertex_count: u32,
+    ) -> Self {
+        Self(D3D12_RAYTRACING_GEOMETRY_DESC {
+            Type: D3D12_RAYTRACING_GEOMETRY_TYPE_TRIANGLES,
+            Flags: D3D12_RAYTRACING_GEOMETRY_FLAG_NONE,
+            Anonymous: D3D12_RAYTRACING_GEOMETRY_DESC_0 {
+                Triangles: D3D12_RAYTRACING_GEOMETRY_TRIANGLES_DESC {
+                    VertexFormat: vertex_format.as_raw(),
+                    VertexCount: vertex_count,
+                    VertexBuffer: D3D12_GPU_VIRTUAL_ADDRESS_AND_STRIDE {
+                        StartAddress: vertex_buffer,
+                        StrideInBytes: vertex_stride,
+                    },
+                    ..Default::default()
+                },
+            },
+        })
+    }
+
+    /// Describes axis-aligned bounding boxes of procedural primitives, see [`RaytracingAabb`].
+    #[inline]
+    pub fn aabbs(aabbs: GpuVirtualAddress, stride: u64, count: u64) -> Self {
+        Self(D3D12_RAYTRACING_GEOMETRY_DESC {
+            Type: D3D12_RAYTRACING_GEOMETRY_TYPE_PROCEDURAL_PRIMITIVE_AABBS,
+            Flags: D3D12_RAYTRACING_GEOMETRY_FLAG_NONE,
+            Anonymous: D3D12_RAYTRACING_GEOMETRY_DESC_0 {
+                AABBs: D3D12_RAYTRACING_GEOMETRY_AABBS_DESC {
+                    AABBCount: count,
+                    AABBs: D3D12_GPU_VIRTUAL_ADDRESS_AND_STRIDE {
+                        StartAddress: aabbs,
+                        StrideInBytes: stride,
+                    },
+                },
+            },
+        })
+    }
+
+    /// Sets the index buffer of triangles. Ignored by AABB geometry.
+    #[inline]
+    pub fn with_index_buffer(
+        mut self,
+        index_buffer: GpuVirtualAddress,
+        index_format: Format,
+        index_count: u32,
+    ) -> Self {
+        if self.r#type() == RaytracingGeometryType::Triangles {
+            self.0.Anonymous.Triangles.IndexBuffer = index_buffer;
+            self.0.Anonymous.Triangles.IndexFormat = index_format.as_raw();
+            self.0.Anonymous.Triangles.IndexCount = index_count;
+        }
+        self
+    }
+
+    /// Sets the address of a 3x4 row-major affine transform applied to the vertices of triangles.
+    /// Ignored by AABB geometry.
+    #[inline]
+    pub fn with_transform(mut self, transform: GpuVirtualAddress) -> Self {
+        if self.r#type() == RaytracingGeometryType::Triangles {
+            self.0.Anonymous.Triangles.Transform3x4 = transform;
+        }
+        self
+    }
+
+    #[inline]
+    pub fn with_flags(mut self, flags: RaytracingGeometryFlags) -> Self {
+        self.0.Flags = flags.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn r#type(&self) -> RaytracingGeometryType {
+        self.0.Type.into()
+    }
+
+    #[inline]
+    pub fn flags(&self) -> RaytracingGeometryFlags {
+        self.0.Flags.into()
+    }
+}
+
+/// Represents an axis-aligned bounding box of a procedural primitive.
+///
+/// For more information: [`D3D12_RAYTRACING_AABB structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_raytracing_aabb)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct RaytracingAabb(pub(crate) D3D12_RAYTRACING_AABB);
+
+impl RaytracingAabb {
+    #[inline]
+    pub fn new(min: [f32; 3], max: [f32; 3]) -> Self {
+        Self(D3D12_RAYTRACING_AABB {
+            MinX: min[0],
+            MinY: min[1],
+            MinZ: min[2],
+            MaxX: max[0],
+            MaxY: max[1],
+            MaxZ: max[2],
+        })
+    }
+
+    #[inline]
+    pub fn min(&self) -> [f32; 3] {
+        [self.0.MinX, self.0.MinY, self.0.MinZ]
+    }
+
+    #[inline]
+    pub fn max(&self) -> [f32; 3] {
+        [self.0.MaxX, self.0.MaxY, self.0.MaxZ]
+    }
+}
+
+/// Describes an instance of a bottom-level acceleration structure in a top-level acceleration structure.
+///
+/// The struct has the layout the GPU expects, so a slice of instances can be copied as is to an upload buffer.
+///
+/// For more information: [`D3D12_RAYTRACING_INSTANCE_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_raytracing_instance_desc)
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct InstanceDesc(pub(crate) D3D12_RAYTRACING_INSTANCE_DESC);
+
+impl InstanceDesc {
+    /// Identity transform as a 3x4 row-major matrix.
+    pub const IDENTITY: [[f32; 4]; 3] = [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+    ];
+
+    /// Creates an instance with the identity transform, which is visible to all rays.
+    #[inline]
+    pub fn new(acceleration_structure: GpuVirtualAddress) -> Self {
+        Self(D3D12_RAYTRACING_INSTANCE_DESC {
+            Transform: flatten_transform(Self::IDENTITY),
+            _bitfield1: 0xFF << 24,
+            _bitfield2: 0,
+            AccelerationStructure: acceleration_structure,
+        })
+    }
+
+    /// Sets the 3x4 row-major transform from object space to world space.
+    #[inline]
+    pub fn with_transform(mut self, transform: [[f32; 4]; 3]) -> Self {
+        self.0.Transform = flatten_transform(transform);
+        self
+    }
+
+    /// Sets the value of `InstanceID()` in shaders.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the id doesn't fit into 24 bits.
+    #[inline]
+    pub fn with_instance_id(mut self, instance_id: u32) -> Self {
+        assert!(
+            instance_id <= MAX_INSTANCE_ID,
+            "instance id {instance_id:#x} doesn't fit into 24 bits"
+        );
+        self.0._bitfield1 = pack(instance_id, self.instance_mask() as u32);
+        self
+    }
+
+    /// Sets the mask, the instance is skipped by rays whose `InstanceInclusionMask` has no common bits with it.
+    #[inline]
+    pub fn with_instance_mask(mut self, instance_mask: u8) -> Self {
+        self.0._bitfield1 = pack(self.instance_id(), instance_mask as u32);
+        self
+    }
+
+    /// Sets the offset of the instance records in the hit group table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset doesn't fit into 24 bits.
+    #[inline]
+    pub fn with_contribution_to_hit_group_index(mut self, contribution: u32) -> Self {
+        assert!(
+            contribution <= MAX_INSTANCE_ID,
+            "hit group contribution {contribution:#x} doesn't fit into 24 bits"
+        );
+        self.0._bitfield2 = pack(contribution, self.flags().bits() as u32);
+        self
+    }
+
+    #[inline]
+    pub fn with_flags(mut self, flags: RaytracingInstanceFlags) -> Self {
+        self.0._bitfield2 = pack(self.contribution_to_hit_group_index(), flags.bits() as u32);
+        self
+    }
+
+    #[inline]
+    pub fn transform(&self) -> [[f32; 4]; 3] {
+        let t = &self.0.Transform;
+
+        [
+            [t[0], t[1], t[2], t[3]],
+            [t[4], t[5], t[6], t[7]],
+            [t[8], t[9], t[10], t[11]],
+        ]
+    }
+
+    #[inline]
+    pub fn instance_id(&self) -> u32 {
+        self.0._bitfield1 & MAX_INSTANCE_ID
+    }
+
+    #[inline]
+    pub fn instance_mask(&self) -> u8 {
+        (self.0._bitfield1 >> 24) as u8
+    }
+
+    #[inline]
+    pub fn contribution_to_hit_group_index(&self) -> u32 {
+        self.0._bitfield2 & MAX_INSTANCE_ID
+    }
+
+    #[inline]
+    pub fn flags(&self) -> RaytracingInstanceFlags {
+        RaytracingInstanceFlags::from_bits_retain((self.0._bitfield2 >> 24) as i32)
+    }
+
+    #[inline]
+    pub fn acceleration_structure(&self) -> GpuVirtualAddress {
+        self.0.AccelerationStructure
+    }
+}
+
+#[inline]
+fn pack(low: u32, high: u32) -> u32 {
+    (low & MAX_INSTANCE_ID) | (high << 24)
+}
+
+#[inline]
+fn flatten_transform(transform: [[f32; 4]; 3]) -> [f32; 12] {
+    let [r0, r1, r2] = transform;
+
+    [
+        r0[0], r0[1], r0[2], r0[3], r1[0], r1[1], r1[2], r1[3], r2[0], r2[1], r2[2], r2[3],
+    ]
+}
+
+/// Describes the inputs of an acceleration structure build, either the geometry of a bottom-level structure or the
+/// instances of a top-level structure.
+///
+/// For more information: [`D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_build_raytracing_acceleration_structure_inputs)
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct BuildAccelerationStructureInputs<'a>(
+    pub(crate) D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS,
+    PhantomData<&'a ()>,
+);
+
+impl<'a> BuildAccelerationStructureInputs<'a> {
+    #[inline]
+    pub fn bottom_level(geometries: &'a [RaytracingGeometryDesc]) -> Self {
+        Self(
+            D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS {
+                Type: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE_BOTTOM_LEVEL,
+                Flags: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_NONE,
+                NumDescs: geometries.len() as u32,
+                DescsLayout: D3D12_ELEMENTS_LAYOUT_ARRAY,
+                Anonymous: D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS_0 {
+                    pGeometryDescs: geometries.as_ptr() as *const _,
+                },
+            },
+            PhantomData,
+        )
+    }
+
+    /// Describes a top-level structure of `count` [`InstanceDesc`] stored in GPU memory at `instance_descs`.
+    #[inline]
+    pub fn top_level(instance_descs: GpuVirtualAddress, count: u32) -> Self {
+        Self(
+            D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS {
+                Type: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE_TOP_LEVEL,
+                Flags: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_NONE,
+                NumDescs: count,
+                DescsLayout: D3D12_ELEMENTS_LAYOUT_ARRAY,
+                Anonymous: D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS_0 {
+                    InstanceDescs: instance_descs,
+                },
+            },
+            PhantomData,
+        )
+    }
+
+    #[inline]
+    pub fn with_flags(mut self, flags: RaytracingAccelerationStructureBuildFlags) -> Self {
+        self.0.Flags = flags.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn r#type(&self) -> RaytracingAccelerationStructureType {
+        self.0.Type.into()
+    }
+
+    #[inline]
+    pub fn flags(&self) -> RaytracingAccelerationStructureBuildFlags {
+        self.0.Flags.into()
+    }
+
+    #[inline]
+    pub fn count(&self) -> u32 {
+        self.0.NumDescs
+    }
+}
+
+/// Describes a raytracing acceleration structure build.
+///
+/// For more information: [`D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_build_raytracing_acceleration_structure_desc)
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct BuildAccelerationStructureDesc<'a>(
+    pub(crate) D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_DESC,
+    PhantomData<&'a ()>,
+);
+
+impl<'a> BuildAccelerationStructureDesc<'a> {
+    /// Both addresses must be aligned to [`ACCELERATION_STRUCTURE_ALIGNMENT`], their sizes come from
+    /// [`AccelerationStructurePrebuildInfo`].
+    #[inline]
+    pub fn new(
+        inputs: BuildAccelerationStructureInputs<'a>,
+        dest: GpuVirtualAddress,
+        scratch: GpuVirtualAddress,
+    ) -> Self {
+        Self(
+            D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_DESC {
+                DestAccelerationStructureData: dest,
+                Inputs: inputs.0,
+                SourceAccelerationStructureData: 0,
+                ScratchAccelerationStructureData: scratch,
+            },
+            PhantomData,
+        )
+    }
+
+    /// Sets the structure to update, requires [`RaytracingAccelerationStructureBuildFlags::PerformUpdate`].
+    /// It may be the same as the destination for an in-place update.
+    #[inline]
+    pub fn with_source(mut self, source: GpuVirtualAddress) -> Self {
+        self.0.SourceAccelerationStructureData = source;
+        self
+    }
+}
+
+/// Represents prebuild information about a raytracing acceleration structure.
+///
+/// For more information: [`D3D12_RAYTRACING_ACCELERATION_STRUCTURE_PREBUILD_INFO structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_raytracing_acceleration_structure_prebuild_info)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct AccelerationStructurePrebuildInfo(
+    pub(crate) D3D12_RAYTRACING_ACCELERATION_STRUCTURE_PREBUILD_INFO,
+);
+
+impl AccelerationStructurePrebuildInfo {
+    #[inline]
+    pub fn result_data_max_size(&self) -> u64 {
+        self.0.ResultDataMaxSizeInBytes
+    }
+
+    #[inline]
+    pub fn scratch_data_size(&self) -> u64 {
+        self.0.ScratchDataSizeInBytes
+    }
+
+    #[inline]
+    pub fn update_scratch_data_size(&self) -> u64 {
+        self.0.UpdateScratchDataSizeInBytes
+    }
+}
+
+/// Describes the post-build information to generate for an acceleration structure.
+///
+/// The GPU writes `D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_*_DESC` of the requested type to
+/// the destination buffer, for example the compacted size as a `u64`.
+///
+/// For more information: [`D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_raytracing_acceleration_structure_postbuild_info_desc)
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct AccelerationStructurePostbuildInfoDesc(
+    pub(crate) D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_DESC,
+);
+
+impl AccelerationStructurePostbuildInfoDesc {
+    /// The destination must be aligned to 8 bytes.
+    #[inline]
+    pub fn new(
+        dest_buffer: GpuVirtualAddress,
+        info_type: RaytracingAccelerationStructurePostbuildInfoType,
+    ) -> Self {
+        Self(
+            D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_DESC {
+                DestBuffer: dest_buffer,
+                InfoType: info_type.as_raw(),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instance_desc_test() {
+        let transform = [
+            [1.0, 0.0, 0.0, 10.0],
+            [0.0, 2.0, 0.0, 20.0],
+            [0.0, 0.0, 3.0, 30.0],
+        ];
+
+        let instance = InstanceDesc::new(0xABCD_0000)
+            .with_transform(transform)
+            .with_instance_id(0x12_3456)
+            .with_instance_mask(0x0F)
+            .with_contribution_to_hit_group_index(0xAB_CDEF)
+            .with_flags(
+                RaytracingInstanceFlags::TriangleCullDisable | RaytracingInstanceFlags::ForceOpaque,
+            );
+
+        assert_eq!(size_of::<InstanceDesc>(), 64);
+        assert_eq!(instance.0._bitfield1, 0x0F12_3456);
+        assert_eq!(instance.0._bitfield2, 0x05AB_CDEF);
+        assert_eq!(instance.0.Transform[3], 10.0);
+        assert_eq!(instance.0.Transform[7], 20.0);
+        assert_eq!(instance.0.Transform[10], 3.0);
+        assert_eq!(instance.transform(), transform);
+
+        // Setting one half of a bitfield keeps the other.
+        let instance = instance
+            .with_instance_mask(0xFF)
+            .with_flags(RaytracingInstanceFlags::empty());
+        assert_eq!(instance.instance_id(), 0x12_3456);
+        assert_eq!(instance.instance_mask(), 0xFF);
+        assert_eq!(instance.contribution_to_hit_group_index(), 0xAB_CDEF);
+        assert_eq!(instance.flags(), RaytracingInstanceFlags::empty());
+        assert_eq!(instance.acceleration_structure(), 0xABCD_0000);
+
+        let instance = InstanceDesc::new(0);
+        assert_eq!(instance.transform(), InstanceDesc::IDENTITY);
+        assert_eq!(instance.instance_mask(), 0xFF);
+        assert_eq!(instance.instance_id(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn instance_id_overflow_test() {
+        let _ = InstanceDesc::new(0).with_instance_id(1 << 24);
+    }
+
+    #[test]
+    fn geometry_desc_test() {
+        let geometries = [
+            RaytracingGeometryDesc::triangles(0x1000, 12, Format::Rgb32Float, 3)
+                .with_index_buffer(0x2000, Format::R16Uint, 6)
+                .with_flags(RaytracingGeometryFlags::Opaque),
+            RaytracingGeometryDesc::aabbs(0x3000, 24, 2).with_transform(0x4000),
+        ];
+
+        unsafe {
+            let triangles = geometries[0].0.Anonymous.Triangles;
+            assert_eq!(triangles.VertexBuffer.StartAddress, 0x1000);
+            assert_eq!(triangles.IndexBuffer, 0x2000);
+            assert_eq!(triangles.IndexCount, 6);
+            assert_eq!(triangles.Transform3x4, 0);
+
+            let aabbs = geometries[1].0.Anonymous.AABBs;
+            assert_eq!(aabbs.AABBCount, 2);
+            assert_eq!(aabbs.AABBs.StrideInBytes, 24);
+        }
+
+        assert_eq!(geometries[0].flags(), RaytracingGeometryFlags::Opaque);
+        assert_eq!(
+            geometries[1].r#type(),
+            RaytracingGeometryType::ProceduralPrimitiveAabbs
+        );
+
+        let inputs = BuildAccelerationStructureInputs::bottom_level(&geometries)
+            .with_flags(RaytracingAccelerationStructureBuildFlags::AllowCompaction);
+        assert_eq!(inputs.count(), 2);
+        assert_eq!(
+            inputs.r#type(),
+            RaytracingAccelerationStructureType::BottomLevel
+        );
+        assert_eq!(size_of::<RaytracingAabb>(), 24);
+    }
+}
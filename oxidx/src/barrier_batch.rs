@@ -0,0 +1,563 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{command_recorder::CommandRecorder, resources::Resource, types::*};
+
+/// A barrier queued in a [`BarrierBatch`]. A [`None`] subresource means all subresources.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum BatchedBarrier<K> {
+    Transition {
+        resource: K,
+        subresource: Option<u32>,
+        before: ResourceStates,
+        after: ResourceStates,
+        flags: ResourceBarrierFlags,
+    },
+    Uav(K),
+    Aliasing {
+        before: K,
+        after: K,
+    },
+}
+
+impl<K: Copy + Eq> BatchedBarrier<K> {
+    #[inline]
+    fn touches(&self, resource: K) -> bool {
+        match *self {
+            BatchedBarrier::Transition { resource: r, .. } | BatchedBarrier::Uav(r) => {
+                r == resource
+            }
+            BatchedBarrier::Aliasing { before, after } => before == resource || after == resource,
+        }
+    }
+}
+
+impl<'a> BatchedBarrier<&'a Resource> {
+    #[inline]
+    pub fn barrier(&self) -> ResourceBarrier<'a> {
+        match *self {
+            BatchedBarrier::Transition {
+                resource,
+                subresource,
+                before,
+                after,
+                flags,
+            } => {
+                ResourceBarrier::transition(resource, before, after, subresource).with_flags(flags)
+            }
+            BatchedBarrier::Uav(resource) => ResourceBarrier::uav(resource),
+            BatchedBarrier::Aliasing { before, after } => ResourceBarrier::aliasing(before, after),
+        }
+    }
+}
+
+/// A split transition that has begun and is waiting for its end barrier.
+#[derive(Clone, Copy, Debug)]
+struct SplitTransition<K> {
+    resource: K,
+    subresource: Option<u32>,
+    before: ResourceStates,
+    after: ResourceStates,
+    remaining: u32,
+}
+
+impl<K> SplitTransition<K> {
+    #[inline]
+    fn end(&self) -> BatchedBarrier<K>
+    where
+        K: Copy,
+    {
+        BatchedBarrier::Transition {
+            resource: self.resource,
+            subresource: self.subresource,
+            before: self.before,
+            after: self.after,
+            flags: ResourceBarrierFlags::EndOnly,
+        }
+    }
+}
+
+/// Accumulates resource barriers and submits them with a single [`CommandRecorder::resource_barrier`] call.
+///
+/// Queued barriers are optimized before submission:
+/// * chained transitions of the same subresource are merged, `A -> B -> C` becomes `A -> C`;
+/// * transitions that end in their initial state are dropped;
+/// * transitions covering every subresource with the same states are collapsed into one transition of all subresources,
+///   when the subresource count is known, see [`BarrierBatch::set_subresource_count`];
+/// * duplicated UAV and aliasing barriers are dropped.
+///
+/// Transitions queued with [`BarrierBatch::transition_ahead`] can be split into a begin and an end barrier
+/// issued by different flushes, see [`BarrierBatch::with_split_distance`].
+///
+/// For more information: [`Using resource barriers to synchronize resource states`](https://learn.microsoft.com/en-us/windows/win32/direct3d12/using-resource-barriers-to-synchronize-resource-states-in-direct3d-12)
+#[derive(Clone, Debug)]
+pub struct BarrierBatch<K> {
+    barriers: Vec<BatchedBarrier<K>>,
+    subresource_counts: HashMap<K, u32>,
+    in_flight: Vec<SplitTransition<K>>,
+    split_distance: Option<u32>,
+}
+
+impl<K: Copy + Eq + Hash> Default for BarrierBatch<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Copy + Eq + Hash> BarrierBatch<K> {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            barriers: vec![],
+            subresource_counts: HashMap::new(),
+            in_flight: vec![],
+            split_distance: None,
+        }
+    }
+
+    /// Enables split barriers for transitions queued at least `distance` flushes ahead of their use.
+    #[inline]
+    pub fn with_split_distance(mut self, distance: u32) -> Self {
+        self.split_distance = Some(distance.max(1));
+        self
+    }
+
+    /// Sets the number of subresources of a resource, allowing per-subresource transitions to be collapsed.
+    #[inline]
+    pub fn set_subresource_count(&mut self, resource: K, count: u32) {
+        self.subresource_counts.insert(resource, count);
+    }
+
+    /// Returns the number of queued barriers, without split transitions waiting for their end barrier.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.barriers.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.barriers.is_empty()
+    }
+
+    /// Queues a transition needed by the next flush.
+    pub fn transition(
+        &mut self,
+        resource: K,
+        before: ResourceStates,
+        after: ResourceStates,
+        subresource: Option<u32>,
+    ) {
+        self.end_splits_of(resource);
+
+        let last = self.barriers.iter().rposition(|b| b.touches(resource));
+
+        if let Some(index) = last {
+            if let BatchedBarrier::Transition {
+                subresource: s,
+                after: a,
+                flags,
+                ..
+            } = &mut self.barriers[index]
+            {
+                if *s == subresource && flags.is_empty() && *a == before {
+                    *a = after;
+
+                    if let BatchedBarrier::Transition { before, after, .. } = self.barriers[index] {
+                        if before == after {
+                            self.barriers.remove(index);
+                        }
+                    }
+
+                    return;
+                }
+            }
+        }
+
+        if before == after {
+            return;
+        }
+
+        self.barriers.push(BatchedBarrier::Transition {
+            resource,
+            subresource,
+            before,
+            after,
+            flags: ResourceBarrierFlags::empty(),
+        });
+    }
+
+    /// Queues a transition needed only `distance` flushes after the next one.
+    ///
+    /// With split barriers enabled and a long enough distance, the next flush begins the transition
+    /// and the flush `distance` later ends it. Otherwise it is queued like [`BarrierBatch::transition`].
+    /// Queuing another barrier touching the resource ends the split transition early.
+    pub fn transition_ahead(
+        &mut self,
+        resource: K,
+        before: ResourceStates,
+        after: ResourceStates,
+        subresource: Option<u32>,
+        distance: u32,
+    ) {
+        match self.split_distance {
+            Some(split) if distance >= split && before != after => {
+                self.end_splits_of(resource);
+
+                self.barriers.push(BatchedBarrier::Transition {
+                    resource,
+                    subresource,
+                    before,
+                    after,
+                    flags: ResourceBarrierFlags::BeginOnly,
+                });
+
+                self.in_flight.push(SplitTransition {
+                    resource,
+                    subresource,
+                    before,
+                    after,
+                    remaining: distance,
+                });
+            }
+            _ => self.transition(resource, before, after, subresource),
+        }
+    }
+
+    /// Queues a UAV barrier.
+    pub fn uav(&mut self, resource: K) {
+        self.end_splits_of(resource);
+
+        let barrier = BatchedBarrier::Uav(resource);
+        if !self.barriers.contains(&barrier) {
+            self.barriers.push(barrier);
+        }
+    }
+
+    /// Queues an aliasing barrier.
+    pub fn aliasing(&mut self, before: K, after: K) {
+        self.end_splits_of(before);
+        self.end_splits_of(after);
+
+        let barrier = BatchedBarrier::Aliasing { before, after };
+        if !self.barriers.contains(&barrier) {
+            self.barriers.push(barrier);
+        }
+    }
+
+    /// Queues the end barriers of all split transitions, so the next flush completes them.
+    pub fn end_splits(&mut self) {
+        self.barriers
+            .extend(self.in_flight.drain(..).map(|split| split.end()));
+    }
+
+    /// Takes the optimized barriers of the next flush and advances split transitions.
+    pub fn drain(&mut self) -> Vec<BatchedBarrier<K>> {
+        self.collapse_subresources();
+
+        let mut barriers = vec![];
+        self.in_flight.retain(|split| {
+            if split.remaining == 0 {
+                barriers.push(split.end());
+                false
+            } else {
+                true
+            }
+        });
+
+        for split in self.in_flight.iter_mut() {
+            split.remaining -= 1;
+        }
+
+        barriers.append(&mut self.barriers);
+        barriers
+    }
+
+    #[inline]
+    fn end_splits_of(&mut self, resource: K) {
+        let barriers = &mut self.barriers;
+        self.in_flight.retain(|split| {
+            if split.resource == resource {
+                barriers.push(split.end());
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    fn collapse_subresources(&mut self) {
+        for (&resource, &count) in self.subresource_counts.iter() {
+            let mut states = None;
+            let mut covered = vec![false; count as usize];
+            let mut indices = vec![];
+
+            let collapsible = self
+                .barriers
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| b.touches(resource))
+                .all(|(index, barrier)| {
+                    let BatchedBarrier::Transition {
+                        subresource: Some(subresource),
+                        before,
+                        after,
+                        flags,
+                        ..
+                    } = *barrier
+                    else {
+                        return false;
+                    };
+
+                    let Some(slot) = covered.get_mut(subresource as usize) else {
+                        return false;
+                    };
+
+                    if !flags.is_empty()
+                        || *slot
+                        || *states.get_or_insert((before, after)) != (before, after)
+                    {
+                        return false;
+                    }
+
+                    *slot = true;
+                    indices.push(index);
+                    true
+                });
+
+            if !collapsible || indices.is_empty() || covered.iter().any(|c| !c) {
+                continue;
+            }
+
+            if let BatchedBarrier::Transition { subresource, .. } = &mut self.barriers[indices[0]] {
+                *subresource = None;
+            }
+
+            for &index in indices[1..].iter().rev() {
+                self.barriers.remove(index);
+            }
+        }
+    }
+}
+
+impl BarrierBatch<&Resource> {
+    /// Records the optimized barriers with a single call, doing nothing when there are none.
+    pub fn flush(&mut self, recorder: &impl CommandRecorder) {
+        let barriers = self
+            .drain()
+            .iter()
+            .map(|barrier| barrier.barrier())
+            .collect::<Vec<_>>();
+
+        if !barriers.is_empty() {
+            recorder.resource_barrier(&barriers);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_transitions_test() {
+        let mut batch = BarrierBatch::new();
+        batch.transition(0, ResourceStates::Common, ResourceStates::CopyDest, None);
+        batch.transition(
+            0,
+            ResourceStates::CopyDest,
+            ResourceStates::PixelShaderResource,
+            None,
+        );
+        batch.transition(
+            1,
+            ResourceStates::RenderTarget,
+            ResourceStates::CopySource,
+            None,
+        );
+        batch.transition(
+            1,
+            ResourceStates::CopySource,
+            ResourceStates::RenderTarget,
+            None,
+        );
+        batch.transition(2, ResourceStates::Present, ResourceStates::Present, None);
+        batch.uav(3);
+        batch.uav(3);
+
+        assert_eq!(
+            batch.drain(),
+            vec![
+                BatchedBarrier::Transition {
+                    resource: 0,
+                    subresource: None,
+                    before: ResourceStates::Common,
+                    after: ResourceStates::PixelShaderResource,
+                    flags: ResourceBarrierFlags::empty()
+                },
+                BatchedBarrier::Uav(3),
+            ]
+        );
+        assert!(batch.is_empty());
+
+        batch.transition(
+            0,
+            ResourceStates::Common,
+            ResourceStates::UnorderedAccess,
+            None,
+        );
+        batch.uav(0);
+        batch.transition(
+            0,
+            ResourceStates::UnorderedAccess,
+            ResourceStates::Common,
+            None,
+        );
+        assert_eq!(batch.drain().len(), 3);
+    }
+
+    #[test]
+    fn collapse_subresources_test() {
+        let mut batch = BarrierBatch::new();
+        batch.set_subresource_count(0, 3);
+        batch.set_subresource_count(1, 3);
+
+        for subresource in 0..3 {
+            batch.transition(
+                0,
+                ResourceStates::RenderTarget,
+                ResourceStates::PixelShaderResource,
+                Some(subresource),
+            );
+        }
+
+        for subresource in 0..2 {
+            batch.transition(
+                1,
+                ResourceStates::RenderTarget,
+                ResourceStates::PixelShaderResource,
+                Some(subresource),
+            );
+        }
+
+        let barriers = batch.drain();
+        assert_eq!(barriers.len(), 3);
+        assert_eq!(
+            barriers[0],
+            BatchedBarrier::Transition {
+                resource: 0,
+                subresource: None,
+                before: ResourceStates::RenderTarget,
+                after: ResourceStates::PixelShaderResource,
+                flags: ResourceBarrierFlags::empty()
+            }
+        );
+    }
+
+    #[test]
+    fn split_transitions_test() {
+        let mut batch = BarrierBatch::new().with_split_distance(2);
+        batch.transition_ahead(
+            0,
+            ResourceStates::RenderTarget,
+            ResourceStates::PixelShaderResource,
+            None,
+            2,
+        );
+        batch.transition_ahead(1, ResourceStates::Common, ResourceStates::CopyDest, None, 1);
+
+        assert_eq!(
+            batch.drain(),
+            vec![
+                BatchedBarrier::Transition {
+                    resource: 0,
+                    subresource: None,
+                    before: ResourceStates::RenderTarget,
+                    after: ResourceStates::PixelShaderResource,
+                    flags: ResourceBarrierFlags::BeginOnly
+                },
+                BatchedBarrier::Transition {
+                    resource: 1,
+                    subresource: None,
+                    before: ResourceStates::Common,
+                    after: ResourceStates::CopyDest,
+                    flags: ResourceBarrierFlags::empty()
+                },
+            ]
+        );
+        assert!(batch.drain().is_empty());
+        assert_eq!(
+            batch.drain(),
+            vec![BatchedBarrier::Transition {
+                resource: 0,
+                subresource: None,
+                before: ResourceStates::RenderTarget,
+                after: ResourceStates::PixelShaderResource,
+                flags: ResourceBarrierFlags::EndOnly
+            }]
+        );
+
+        batch.transition_ahead(
+            0,
+            ResourceStates::PixelShaderResource,
+            ResourceStates::RenderTarget,
+            None,
+            4,
+        );
+        batch.drain();
+        batch.uav(0);
+        assert_eq!(
+            batch.drain(),
+            vec![
+                BatchedBarrier::Transition {
+                    resource: 0,
+                    subresource: None,
+                    before: ResourceStates::PixelShaderResource,
+                    after: ResourceStates::RenderTarget,
+                    flags: ResourceBarrierFlags::EndOnly
+                },
+                BatchedBarrier::Uav(0),
+            ]
+        );
+    }
+
+    #[test]
+    fn flush_test() {
+        use crate::command_recorder::{
+            detached_resource, Command, InMemoryRecorder, ObjectId, RecordedBarrier,
+        };
+
+        let recorder = InMemoryRecorder::new();
+        let texture = detached_resource(0x1000);
+        let buffer = detached_resource(0x2000);
+
+        let mut batch = BarrierBatch::new();
+        batch.flush(&recorder);
+        assert!(recorder.is_empty());
+
+        batch.transition(
+            &*texture,
+            ResourceStates::RenderTarget,
+            ResourceStates::PixelShaderResource,
+            None,
+        );
+        batch.uav(&*buffer);
+        batch.flush(&recorder);
+
+        assert!(batch.is_empty());
+        assert_eq!(
+            recorder.take_commands(),
+            [Command::ResourceBarrier(vec![
+                RecordedBarrier::Transition {
+                    resource: Some(ObjectId::from(&*texture)),
+                    subresource: None,
+                    before: ResourceStates::RenderTarget,
+                    after: ResourceStates::PixelShaderResource,
+                    flags: ResourceBarrierFlags::empty(),
+                },
+                RecordedBarrier::Uav {
+                    resource: Some(ObjectId::from(&*buffer)),
+                    flags: ResourceBarrierFlags::empty(),
+                },
+            ])]
+        );
+    }
+}
@@ -1,18 +1,27 @@
-use std::{ffi::CStr, path::Path};
+use std::{
+    ffi::{CStr, CString},
+    path::{Path, PathBuf},
+};
 
 use bytes::Bytes;
 use windows::{
     core::{Interface, HSTRING, PCSTR},
     Win32::Graphics::{
         Direct3D::{
-            Fxc::{D3DCompileFromFile, D3DReflect},
-            ID3DInclude,
+            Fxc::{D3DCompile, D3DCompileFromFile, D3DReflect},
+            ID3DBlob, ID3DInclude, D3D_SHADER_MACRO,
         },
         Direct3D12::{ID3D12ShaderReflection, D3D12_CACHED_PIPELINE_STATE, D3D12_SHADER_BYTECODE},
     },
 };
 
-use crate::{error::DxError, reflection::ShaderReflection, types::*};
+use crate::{
+    dxbc::DxbcContainer,
+    error::DxError,
+    include::{IncludeBridge, IncludeHandler},
+    reflection::ShaderReflection,
+    types::*,
+};
 
 pub type Blob = Bytes;
 
@@ -28,8 +37,27 @@ pub trait Blobby {
         defines: &[ShaderMacro],
         entry_point: impl AsRef<CStr>,
         target: impl AsRef<CStr>,
-        flags1: u32,
-        flags2: u32,
+        flags: CompileFlags,
+        effect_flags: CompileEffectFlags,
+    ) -> Result<Self, DxError>
+    where
+        Self: Sized;
+
+    /// Compiles Microsoft High Level Shader Language (HLSL) code from memory into bytecode for a given target.
+    ///
+    /// `source_name` is used in error messages and as the parent path of includes of the root source.
+    /// If `include` is `None` the standard file include handler is used.
+    ///
+    /// For more information: [`D3DCompile function`](https://learn.microsoft.com/en-us/windows/win32/api/d3dcompiler/nf-d3dcompiler-d3dcompile)
+    fn compile_from_source(
+        source: impl AsRef<[u8]>,
+        source_name: impl AsRef<CStr>,
+        defines: &[ShaderMacro],
+        include: Option<&dyn IncludeHandler>,
+        entry_point: impl AsRef<CStr>,
+        target: impl AsRef<CStr>,
+        flags: CompileFlags,
+        effect_flags: CompileEffectFlags,
     ) -> Result<Self, DxError>
     where
         Self: Sized;
@@ -38,6 +66,19 @@ pub trait Blobby {
     ///
     /// For more information: [`D3DReflect function`](https://learn.microsoft.com/en-us/windows/win32/api/d3dcompiler/nf-d3dcompiler-d3dreflect)
     fn reflect(&self) -> Result<ShaderReflection, DxError>;
+
+    /// Disassembles DXBC bytecode in the format of `fxc /dumpbin`.
+    fn disassemble(&self) -> Result<String, DxError>;
+
+    /// Returns `true` if the checksum in the DXBC header matches the contents.
+    fn verify_checksum(&self) -> Result<bool, DxError>;
+
+    /// Returns a copy of the DXBC container with a recomputed checksum, e.g. after patching its parts in place.
+    ///
+    /// Use [`DxbcBuilder`](crate::dxbc::DxbcBuilder) to add, replace or remove parts.
+    fn resign(&self) -> Result<Self, DxError>
+    where
+        Self: Sized;
 }
 
 pub(crate) trait BlobbyInternal {
@@ -67,66 +108,47 @@ impl Blobby for Blob {
         defines: &[ShaderMacro],
         entry_point: impl AsRef<CStr>,
         target: impl AsRef<CStr>,
-        flags1: u32,
-        flags2: u32,
+        flags: CompileFlags,
+        effect_flags: CompileEffectFlags,
     ) -> Result<Self, DxError>
     where
         Self: Sized,
     {
-        let filename: HSTRING = filename.as_ref().to_str().unwrap_or("").into();
-        let entry_point = PCSTR::from_raw(entry_point.as_ref().as_ptr() as *const _);
-        let target = PCSTR::from_raw(target.as_ref().as_ptr() as *const _);
-
-        let mut shader = None;
-
-        let defines = if !defines.is_empty() {
-            Some(defines.as_ptr() as *const _)
-        } else {
-            None
-        };
-
-        let mut error_msg = None;
-
-        unsafe {
-            let res = D3DCompileFromFile(
-                &filename,
-                defines,
-                Some(&std::mem::transmute::<isize, ID3DInclude>(1isize)),
-                entry_point,
-                target,
-                flags1,
-                flags2,
-                &mut shader,
-                Some(&mut error_msg),
-            )
-            .map_err(DxError::from);
-
-            if let Err(err) = res {
-                if let Some(error_msg) = error_msg {
-                    let pointer = error_msg.GetBufferPointer() as *mut u8;
-                    let size = error_msg.GetBufferSize();
-
-                    let slice = std::slice::from_raw_parts(pointer, size);
-
-                    return Err(DxError::ShaderCompilationError(
-                        std::str::from_utf8(slice).unwrap_or_default().to_string(),
-                    ));
-                } else {
-                    return Err(DxError::ShaderCompilationError(err.to_string()));
-                }
-            }
-        }
-
-        let shader = shader.unwrap();
-        let bytes = unsafe {
-            std::slice::from_raw_parts(
-                shader.GetBufferPointer() as *const u8,
-                shader.GetBufferSize(),
-            )
-            .to_vec()
-        };
+        compile_file(
+            filename.as_ref(),
+            defines,
+            entry_point.as_ref(),
+            target.as_ref(),
+            flags,
+            effect_flags,
+        )
+        .map(|(blob, _)| blob)
+    }
 
-        Ok(bytes.into())
+    fn compile_from_source(
+        source: impl AsRef<[u8]>,
+        source_name: impl AsRef<CStr>,
+        defines: &[ShaderMacro],
+        include: Option<&dyn IncludeHandler>,
+        entry_point: impl AsRef<CStr>,
+        target: impl AsRef<CStr>,
+        flags: CompileFlags,
+        effect_flags: CompileEffectFlags,
+    ) -> Result<Self, DxError>
+    where
+        Self: Sized,
+    {
+        compile_source(
+            source.as_ref(),
+            source_name.as_ref(),
+            defines,
+            include,
+            entry_point.as_ref(),
+            target.as_ref(),
+            flags,
+            effect_flags,
+        )
+        .map(|(blob, _)| blob)
     }
 
     fn reflect(&self) -> Result<ShaderReflection, DxError> {
@@ -144,4 +166,477 @@ impl Blobby for Blob {
             Ok(ShaderReflection(shader_reflection))
         }
     }
+    fn disassemble(&self) -> Result<String, DxError> {
+        crate::disasm::disassemble(self)
+    }
+
+    fn verify_checksum(&self) -> Result<bool, DxError> {
+        DxbcContainer::parse(self).map(|container| container.verify_checksum())
+    }
+
+    fn resign(&self) -> Result<Self, DxError>
+    where
+        Self: Sized,
+    {
+        crate::dxbc::resign(self)
+    }
+}
+
+/// Owned set of options for the shader compiler.
+///
+/// Unlike [`ShaderMacro`], macro definitions are owned, so the options can be stored and reused.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct CompileOptions {
+    entry_point: String,
+    target: String,
+    defines: Vec<(String, String)>,
+    flags: CompileFlags,
+    optimization_level: OptimizationLevel,
+    effect_flags: CompileEffectFlags,
+}
+
+impl CompileOptions {
+    #[inline]
+    pub fn new(entry_point: impl Into<String>, target: impl Into<String>) -> Self {
+        Self {
+            entry_point: entry_point.into(),
+            target: target.into(),
+            ..Default::default()
+        }
+    }
+
+    #[inline]
+    pub fn with_define(mut self, name: impl Into<String>, definition: impl Into<String>) -> Self {
+        self.defines.push((name.into(), definition.into()));
+        self
+    }
+
+    #[inline]
+    pub fn with_defines<N: Into<String>, D: Into<String>>(
+        mut self,
+        defines: impl IntoIterator<Item = (N, D)>,
+    ) -> Self {
+        self.defines
+            .extend(defines.into_iter().map(|(n, d)| (n.into(), d.into())));
+        self
+    }
+
+    /// Sets the flags, an optimization level in them is replaced by [`CompileOptions::with_optimization_level`].
+    #[inline]
+    pub fn with_flags(mut self, flags: CompileFlags) -> Self {
+        self.flags = flags.without_optimization_level();
+        self
+    }
+
+    #[inline]
+    pub fn with_optimization_level(mut self, level: OptimizationLevel) -> Self {
+        self.optimization_level = level;
+        self
+    }
+
+    #[inline]
+    pub fn with_effect_flags(mut self, effect_flags: CompileEffectFlags) -> Self {
+        self.effect_flags = effect_flags;
+        self
+    }
+
+    #[inline]
+    pub fn entry_point(&self) -> &str {
+        &self.entry_point
+    }
+
+    #[inline]
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    #[inline]
+    pub fn defines(&self) -> &[(String, String)] {
+        &self.defines
+    }
+
+    #[inline]
+    pub fn flags(&self) -> CompileFlags {
+        self.flags
+    }
+
+    #[inline]
+    pub fn optimization_level(&self) -> OptimizationLevel {
+        self.optimization_level
+    }
+
+    #[inline]
+    pub fn effect_flags(&self) -> CompileEffectFlags {
+        self.effect_flags
+    }
+
+    /// Compiles a file with the standard file include handler.
+    ///
+    /// For more information: [`D3DCompileFromFile function`](https://learn.microsoft.com/en-us/windows/win32/api/d3dcompiler/nf-d3dcompiler-d3dcompilefromfile)
+    pub fn compile_from_file(&self, filename: impl AsRef<Path>) -> Result<CompiledShader, DxError> {
+        let strings = self.to_c_strings()?;
+        let defines = strings.macros();
+
+        compile_file(
+            filename.as_ref(),
+            &defines,
+            &strings.entry_point,
+            &strings.target,
+            self.flags | self.optimization_level.into(),
+            self.effect_flags,
+        )
+        .map(|(blob, warnings)| CompiledShader::new(blob, &warnings))
+    }
+
+    /// Compiles source code from memory.
+    ///
+    /// If `include` is `None` the standard file include handler is used.
+    ///
+    /// For more information: [`D3DCompile function`](https://learn.microsoft.com/en-us/windows/win32/api/d3dcompiler/nf-d3dcompiler-d3dcompile)
+    pub fn compile_from_source(
+        &self,
+        source: impl AsRef<[u8]>,
+        source_name: impl AsRef<str>,
+        include: Option<&dyn IncludeHandler>,
+    ) -> Result<CompiledShader, DxError> {
+        let strings = self.to_c_strings()?;
+        let defines = strings.macros();
+        let source_name = to_c_string(source_name.as_ref())?;
+
+        compile_source(
+            source.as_ref(),
+            &source_name,
+            &defines,
+            include,
+            &strings.entry_point,
+            &strings.target,
+            self.flags | self.optimization_level.into(),
+            self.effect_flags,
+        )
+        .map(|(blob, warnings)| CompiledShader::new(blob, &warnings))
+    }
+
+    fn to_c_strings(&self) -> Result<CompileStrings, DxError> {
+        Ok(CompileStrings {
+            entry_point: to_c_string(&self.entry_point)?,
+            target: to_c_string(&self.target)?,
+            defines: self
+                .defines
+                .iter()
+                .map(|(name, definition)| Ok((to_c_string(name)?, to_c_string(definition)?)))
+                .collect::<Result<_, DxError>>()?,
+        })
+    }
+}
+
+/// C strings of [`CompileOptions`] that have to outlive the compiler call.
+struct CompileStrings {
+    entry_point: CString,
+    target: CString,
+    defines: Vec<(CString, CString)>,
+}
+
+impl CompileStrings {
+    fn macros(&self) -> Vec<ShaderMacro<'_>> {
+        self.defines
+            .iter()
+            .map(|(name, definition)| ShaderMacro::new(name, definition))
+            .collect()
+    }
+}
+
+fn to_c_string(value: &str) -> Result<CString, DxError> {
+    CString::new(value).map_err(|_| {
+        DxError::ShaderCompilationError(format!("`{value}` contains an interior nul byte"))
+    })
+}
+
+/// Bytecode produced by the compiler together with the warnings it reported.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompiledShader {
+    pub blob: Blob,
+    pub warnings: Vec<CompileMessage>,
+}
+
+impl CompiledShader {
+    fn new(blob: Blob, warnings: &str) -> Self {
+        Self {
+            blob,
+            warnings: CompileMessage::parse(warnings),
+        }
+    }
+}
+
+/// Kind of a message reported by the shader compiler.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum CompileMessageKind {
+    /// The compilation failed.
+    Error,
+
+    /// The compilation succeeded, but the code is suspicious.
+    Warning,
+
+    /// Any other line of the compiler output.
+    #[default]
+    Info,
+}
+
+/// A single diagnostic reported by the shader compiler.
+///
+/// Messages are parsed from the compiler output, e.g. `shader.hlsl(12,5-20): warning X3206: implicit truncation of vector type`.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct CompileMessage {
+    /// File the message refers to.
+    pub file: Option<PathBuf>,
+
+    /// One-based line of the file.
+    pub line: Option<u32>,
+
+    /// One-based column of the line.
+    pub column: Option<u32>,
+
+    pub kind: CompileMessageKind,
+
+    /// Compiler code of the message, e.g. `X3206`.
+    pub code: Option<String>,
+
+    pub message: String,
+}
+
+impl CompileMessage {
+    /// Parses the output of the shader compiler.
+    ///
+    /// Can be used with the text of [`DxError::ShaderCompilationError`] to get structured errors.
+    pub fn parse(output: &str) -> Vec<Self> {
+        output
+            .lines()
+            .map(|line| line.trim_matches(|c: char| c.is_whitespace() || c == '\0'))
+            .filter(|line| !line.is_empty())
+            .map(Self::parse_line)
+            .collect()
+    }
+
+    fn parse_line(line: &str) -> Self {
+        let mut result = Self::default();
+        let mut rest = line;
+
+        if let Some(end) = line.find("): ") {
+            let location = &line[..=end];
+
+            if let Some(start) = location.rfind('(') {
+                let mut numbers = location[start + 1..end].split(',');
+                let line_number = numbers.next().and_then(|n| n.trim().parse().ok());
+                let column = numbers
+                    .next()
+                    .and_then(|n| n.split('-').next())
+                    .and_then(|n| n.trim().parse().ok());
+
+                if line_number.is_some() {
+                    result.file = Some(PathBuf::from(&location[..start]));
+                    result.line = line_number;
+                    result.column = column;
+                    rest = &line[end + 3..];
+                }
+            }
+        }
+
+        let (kind, rest) = if let Some(rest) = rest.strip_prefix("error ") {
+            (CompileMessageKind::Error, rest)
+        } else if let Some(rest) = rest.strip_prefix("warning ") {
+            (CompileMessageKind::Warning, rest)
+        } else {
+            (CompileMessageKind::Info, rest)
+        };
+
+        result.kind = kind;
+
+        match rest.split_once(": ") {
+            Some((code, message))
+                if kind != CompileMessageKind::Info && !code.contains(char::is_whitespace) =>
+            {
+                result.code = Some(code.to_string());
+                result.message = message.to_string();
+            }
+            _ => result.message = rest.to_string(),
+        }
+
+        result
+    }
+}
+
+fn compile_file(
+    filename: &Path,
+    defines: &[ShaderMacro],
+    entry_point: &CStr,
+    target: &CStr,
+    flags: CompileFlags,
+    effect_flags: CompileEffectFlags,
+) -> Result<(Blob, String), DxError> {
+    let filename: HSTRING = filename.to_str().unwrap_or("").into();
+    let entry_point = PCSTR::from_raw(entry_point.as_ptr() as *const _);
+    let target = PCSTR::from_raw(target.as_ptr() as *const _);
+    let defines = terminate_defines(defines);
+
+    let mut shader = None;
+    let mut error_msg = None;
+
+    let res = unsafe {
+        D3DCompileFromFile(
+            &filename,
+            defines.as_ref().map(|d| d.as_ptr()),
+            Some(&standard_include()),
+            entry_point,
+            target,
+            flags.as_raw(),
+            effect_flags.as_raw(),
+            &mut shader,
+            Some(&mut error_msg),
+        )
+    };
+
+    compile_output(res, shader, error_msg)
+}
+
+fn compile_source(
+    source: &[u8],
+    source_name: &CStr,
+    defines: &[ShaderMacro],
+    include: Option<&dyn IncludeHandler>,
+    entry_point: &CStr,
+    target: &CStr,
+    flags: CompileFlags,
+    effect_flags: CompileEffectFlags,
+) -> Result<(Blob, String), DxError> {
+    let root = source_name.to_str().ok().map(Path::new);
+    let source_name = PCSTR::from_raw(source_name.as_ptr() as *const _);
+    let entry_point = PCSTR::from_raw(entry_point.as_ptr() as *const _);
+    let target = PCSTR::from_raw(target.as_ptr() as *const _);
+    let defines = terminate_defines(defines);
+
+    let bridge = include.map(|handler| IncludeBridge::new(handler, root));
+    let custom_include = bridge.as_ref().map(ID3DInclude::new);
+    let standard_include = standard_include();
+
+    let mut shader = None;
+    let mut error_msg = None;
+
+    let res = unsafe {
+        D3DCompile(
+            source.as_ptr() as *const _,
+            source.len(),
+            source_name,
+            defines.as_ref().map(|d| d.as_ptr()),
+            custom_include.as_deref().unwrap_or(&standard_include),
+            entry_point,
+            target,
+            flags.as_raw(),
+            effect_flags.as_raw(),
+            &mut shader,
+            Some(&mut error_msg),
+        )
+    };
+
+    compile_output(res, shader, error_msg)
+}
+
+/// Returns the `D3D_COMPILE_STANDARD_FILE_INCLUDE` handler.
+fn standard_include() -> ID3DInclude {
+    unsafe { std::mem::transmute::<isize, ID3DInclude>(1isize) }
+}
+
+/// The compiler expects a null-terminated array of defines.
+fn terminate_defines(defines: &[ShaderMacro]) -> Option<Vec<D3D_SHADER_MACRO>> {
+    if defines.is_empty() {
+        return None;
+    }
+
+    let mut raw = defines.iter().map(|d| d.0).collect::<Vec<_>>();
+    raw.push(D3D_SHADER_MACRO::default());
+
+    Some(raw)
+}
+
+/// Returns the bytecode and the warnings text.
+fn compile_output(
+    res: windows::core::Result<()>,
+    shader: Option<ID3DBlob>,
+    error_msg: Option<ID3DBlob>,
+) -> Result<(Blob, String), DxError> {
+    let messages = error_msg
+        .as_ref()
+        .map(|msg| {
+            String::from_utf8_lossy(blob_bytes(msg))
+                .trim_end_matches('\0')
+                .to_string()
+        })
+        .unwrap_or_default();
+
+    if let Err(err) = res {
+        if error_msg.is_some() {
+            return Err(DxError::ShaderCompilationError(messages));
+        } else {
+            return Err(DxError::ShaderCompilationError(err.to_string()));
+        }
+    }
+
+    let shader = shader.ok_or_else(|| DxError::ShaderCompilationError("empty blob".to_string()))?;
+
+    Ok((blob_bytes(&shader).to_vec().into(), messages))
+}
+
+fn blob_bytes(blob: &ID3DBlob) -> &[u8] {
+    unsafe {
+        std::slice::from_raw_parts(blob.GetBufferPointer() as *const u8, blob.GetBufferSize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_compile_messages_test() {
+        let output =
+            "C:\\shaders\\lit.hlsl(12,5-20): warning X3206: implicit truncation of vector type\n\
+                      shaders/lit.hlsl(40,1): error X3000: syntax error: unexpected token '}'\n\
+                      error X3501: 'main': entrypoint not found\n\
+                      compilation failed; no code produced\n\0";
+
+        let messages = CompileMessage::parse(output);
+
+        assert_eq!(messages.len(), 4);
+
+        assert_eq!(
+            messages[0].file.as_deref(),
+            Some(Path::new("C:\\shaders\\lit.hlsl"))
+        );
+        assert_eq!(messages[0].line, Some(12));
+        assert_eq!(messages[0].column, Some(5));
+        assert_eq!(messages[0].kind, CompileMessageKind::Warning);
+        assert_eq!(messages[0].code.as_deref(), Some("X3206"));
+        assert_eq!(messages[0].message, "implicit truncation of vector type");
+
+        assert_eq!(messages[1].kind, CompileMessageKind::Error);
+        assert_eq!(messages[1].line, Some(40));
+        assert_eq!(messages[1].message, "syntax error: unexpected token '}'");
+
+        assert_eq!(messages[2].file, None);
+        assert_eq!(messages[2].kind, CompileMessageKind::Error);
+        assert_eq!(messages[2].code.as_deref(), Some("X3501"));
+
+        assert_eq!(messages[3].kind, CompileMessageKind::Info);
+        assert_eq!(messages[3].message, "compilation failed; no code produced");
+    }
+
+    #[test]
+    fn optimization_level_test() {
+        let options = CompileOptions::new("main", "ps_5_0")
+            .with_flags(CompileFlags::Debug | OptimizationLevel::Level3.into())
+            .with_optimization_level(OptimizationLevel::Level0);
+
+        assert_eq!(options.flags(), CompileFlags::Debug);
+        assert_eq!(options.optimization_level(), OptimizationLevel::Level0);
+        assert_eq!(format!("{:?}", options.flags()), "CompileFlags(Debug)");
+        assert!(!CompileFlags::empty().contains(OptimizationLevel::Level2.into()));
+    }
 }
@@ -0,0 +1,324 @@
+use std::ffi::CString;
+
+use crate::{error::DxError, reflection::ShaderReflectionConstantBuffer};
+
+#[cfg(feature = "derive")]
+pub use oxidx_derive::CbufferLayout;
+
+/// Size of a single constant buffer register (`float4`) in bytes.
+pub const CBUFFER_REGISTER_SIZE: usize = 16;
+
+/// A type that has a well-known layout inside an HLSL constant buffer.
+///
+/// # Safety
+///
+/// `SIZE` must be the number of bytes HLSL reads for the type, and the Rust representation of the type must
+/// be bit-compatible with the HLSL one.
+pub unsafe trait HlslType: Copy {
+    /// Size of the value in bytes as seen by HLSL.
+    const SIZE: usize;
+
+    /// Whether the value always starts on a new 16-byte register (arrays, matrices and structs).
+    const REGISTER_ALIGNED: bool;
+}
+
+/// A type that can be used as an element of a constant buffer array.
+///
+/// Every element of an HLSL array starts on a new 16-byte register, so only types that occupy whole registers are allowed.
+/// Scalars and vectors are wrapped into [`Padded`].
+///
+/// # Safety
+///
+/// The type must occupy a whole number of 16-byte registers both in Rust and in HLSL.
+pub unsafe trait HlslArrayElement: HlslType {}
+
+/// A struct whose layout matches the HLSL constant buffer packing rules.
+///
+/// Usually implemented with `#[derive(CbufferLayout)]`, which checks the offsets of all fields at compile time.
+///
+/// # Safety
+///
+/// `FIELDS` must describe the HLSL-visible fields in declaration order with their HLSL offsets.
+pub unsafe trait CbufferLayout: HlslType {
+    /// HLSL-visible fields in declaration order.
+    const FIELDS: &'static [CbufferField];
+
+    /// Checks that the layout matches the reflected constant buffer of a compiled shader.
+    fn validate(buffer: &ShaderReflectionConstantBuffer) -> Result<(), DxError> {
+        validate_cbuffer_layout(Self::FIELDS, buffer)
+    }
+}
+
+/// Describes a field of a [`CbufferLayout`] struct.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct CbufferField {
+    pub name: &'static str,
+    pub offset: u32,
+    pub size: u32,
+}
+
+impl CbufferField {
+    #[inline]
+    pub const fn new(name: &'static str, offset: usize, size: usize) -> Self {
+        Self {
+            name,
+            offset: offset as u32,
+            size: size as u32,
+        }
+    }
+}
+
+/// A scalar or a vector padded to a whole 16-byte register, like an element of an HLSL array.
+///
+/// `float weights[4]` is declared as `[Padded<f32>; 4]`. The last element is padded too, so the value after
+/// such an array starts on a new register.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(C, align(16))]
+pub struct Padded<T>(pub T);
+
+impl<T> From<T> for Padded<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+unsafe impl<T: HlslType> HlslType for Padded<T> {
+    const SIZE: usize = T::SIZE;
+    const REGISTER_ALIGNED: bool = T::REGISTER_ALIGNED;
+}
+
+unsafe impl<T: HlslType> HlslArrayElement for Padded<T> {}
+
+macro_rules! impl_hlsl_type {
+    ($($t:ty),*) => {
+        $(
+            unsafe impl HlslType for $t {
+                const SIZE: usize = size_of::<$t>();
+                const REGISTER_ALIGNED: bool = false;
+            }
+
+            unsafe impl HlslType for [$t; 2] {
+                const SIZE: usize = size_of::<[$t; 2]>();
+                const REGISTER_ALIGNED: bool = false;
+            }
+
+            unsafe impl HlslType for [$t; 3] {
+                const SIZE: usize = size_of::<[$t; 3]>();
+                const REGISTER_ALIGNED: bool = false;
+            }
+
+            unsafe impl HlslType for [$t; 4] {
+                const SIZE: usize = size_of::<[$t; 4]>();
+                const REGISTER_ALIGNED: bool = false;
+            }
+
+            unsafe impl HlslArrayElement for [$t; 4] {}
+        )*
+    };
+}
+
+impl_hlsl_type!(f32, i32, u32);
+
+unsafe impl<T: HlslArrayElement, const N: usize> HlslType for [T; N] {
+    const SIZE: usize = {
+        assert!(
+            align_to_register(size_of::<T>()) == size_of::<T>(),
+            "array elements must occupy whole 16-byte registers"
+        );
+
+        // The last element isn't padded in HLSL.
+        if N == 0 {
+            0
+        } else {
+            (N - 1) * size_of::<T>() + T::SIZE
+        }
+    };
+    const REGISTER_ALIGNED: bool = true;
+}
+
+/// Rounds `offset` up to the next 16-byte register.
+#[inline]
+pub const fn align_to_register(offset: usize) -> usize {
+    offset.div_ceil(CBUFFER_REGISTER_SIZE) * CBUFFER_REGISTER_SIZE
+}
+
+/// Returns the HLSL offset of a value of `size` bytes placed right after `offset`.
+///
+/// A value never straddles a register boundary, and register aligned values always start a new register.
+#[inline]
+pub const fn place(offset: usize, size: usize, register_aligned: bool) -> usize {
+    if register_aligned || (offset % CBUFFER_REGISTER_SIZE) + size > CBUFFER_REGISTER_SIZE {
+        align_to_register(offset)
+    } else {
+        offset
+    }
+}
+
+/// Computes HLSL offsets of a sequence of `(size, register_aligned)` fields.
+pub const fn pack_offsets<const N: usize>(fields: [(usize, bool); N]) -> [usize; N] {
+    let mut offsets = [0; N];
+    let mut offset = 0;
+    let mut i = 0;
+
+    while i < N {
+        let (size, register_aligned) = fields[i];
+        offsets[i] = place(offset, size, register_aligned);
+        offset = offsets[i] + size;
+
+        // The next value after a register aligned one starts a new register.
+        if register_aligned {
+            offset = align_to_register(offset);
+        }
+
+        i += 1;
+    }
+
+    offsets
+}
+
+/// Returns the number of bytes occupied by the fields, without trailing padding.
+pub const fn packed_size(fields: &[CbufferField]) -> usize {
+    let mut size = 0;
+    let mut i = 0;
+
+    while i < fields.len() {
+        let end = (fields[i].offset + fields[i].size) as usize;
+
+        if end > size {
+            size = end;
+        }
+
+        i += 1;
+    }
+
+    size
+}
+
+/// Compares field offsets with the reflection data (RDEF chunk) of a compiled shader.
+pub fn validate_cbuffer_layout(
+    fields: &[CbufferField],
+    buffer: &ShaderReflectionConstantBuffer,
+) -> Result<(), DxError> {
+    for field in fields {
+        let name = CString::new(field.name).map_err(|_| {
+            DxError::CbufferLayoutMismatch(format!("invalid field name `{}`", field.name))
+        })?;
+
+        let desc = buffer
+            .get_variable_by_name(&name)
+            .and_then(|v| v.get_desc().ok())
+            .ok_or_else(|| {
+                DxError::CbufferLayoutMismatch(format!(
+                    "field `{}` isn't present in the constant buffer",
+                    field.name
+                ))
+            })?;
+
+        if desc.start_offset() != field.offset {
+            return Err(DxError::CbufferLayoutMismatch(format!(
+                "field `{}` is at offset {} but the shader expects {}",
+                field.name,
+                field.offset,
+                desc.start_offset()
+            )));
+        }
+
+        if desc.size() > field.size {
+            return Err(DxError::CbufferLayoutMismatch(format!(
+                "field `{}` has size {} but the shader expects {}",
+                field.name,
+                field.size,
+                desc.size()
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, CbufferLayout)]
+    #[repr(C)]
+    struct Light {
+        position: [f32; 3],
+        range: f32,
+        color: [f32; 3],
+        intensity: f32,
+    }
+
+    #[derive(Clone, Copy, CbufferLayout)]
+    #[repr(C)]
+    struct PerFrame {
+        view_proj: [[f32; 4]; 4],
+        eye: [f32; 3],
+        #[cbuffer(padding)]
+        _pad0: f32,
+        lights: [Light; 2],
+        time: f32,
+        #[cbuffer(name = "gExposure")]
+        exposure: f32,
+    }
+
+    #[test]
+    fn float3_and_float_share_register_test() {
+        let offsets = Light::FIELDS.iter().map(|f| f.offset).collect::<Vec<_>>();
+
+        assert_eq!(offsets, [0, 12, 16, 28]);
+        assert_eq!(Light::SIZE, 32);
+    }
+
+    #[test]
+    fn nested_struct_and_array_test() {
+        let offsets = PerFrame::FIELDS
+            .iter()
+            .map(|f| (f.name, f.offset))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            offsets,
+            [
+                ("view_proj", 0),
+                ("eye", 64),
+                ("lights", 80),
+                ("time", 144),
+                ("gExposure", 148)
+            ]
+        );
+        assert_eq!(PerFrame::SIZE, 160);
+    }
+
+    #[derive(Clone, Copy, CbufferLayout)]
+    #[repr(C)]
+    struct Blur {
+        weights: [Padded<f32>; 4],
+        offsets: [Padded<[f32; 2]>; 2],
+        scale: [f32; 4],
+    }
+
+    #[test]
+    fn padded_array_test() {
+        let fields = Blur::FIELDS
+            .iter()
+            .map(|f| (f.offset, f.size))
+            .collect::<Vec<_>>();
+
+        assert_eq!(fields, [(0, 52), (64, 24), (96, 16)]);
+        assert_eq!(Blur::SIZE, 112);
+        assert_eq!(size_of::<Padded<[f32; 3]>>(), 16);
+        assert_eq!(<[Padded<[f32; 3]>; 2]>::SIZE, 28);
+        assert_eq!(<[[f32; 4]; 3]>::SIZE, 48);
+    }
+
+    #[test]
+    fn pack_offsets_test() {
+        assert_eq!(pack_offsets([(8, false), (12, false)]), [0, 16]);
+        assert_eq!(
+            pack_offsets([(4, false), (32, true), (4, false)]),
+            [0, 16, 48]
+        );
+    }
+}
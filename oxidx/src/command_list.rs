@@ -1,24 +1,109 @@
 use std::ops::Range;
 
-use windows::Win32::Graphics::Direct3D12::*;
+use windows::{core::Interface, Win32::Graphics::Direct3D12::*};
 
 use crate::{
+    acceleration_structure::{AccelerationStructurePostbuildInfoDesc, BuildAccelerationStructureDesc},
     create_type,
     descriptor_heap::DescriptorHeap,
+    enhanced_barrier::BarrierGroup,
     dx::{
         CommandAllocator, CommandSignature, Device, PipelineState, QueryHeap, Resource,
-        RootSignature,
+        RootSignature, StateObject,
     },
     error::DxError,
     ext::memcpy_subresource,
     impl_interface,
+    protected_session::ProtectedResourceSession,
+    render_pass::{RenderPassDepthStencilDesc, RenderPassRenderTargetDesc},
     types::*,
+    work_graph::{DispatchGraphDesc, SetProgramDesc},
 };
 
 create_type! { GraphicsCommandList wrap ID3D12GraphicsCommandList }
 
+create_type! {
+    /// Adds support for depth bounds testing, programmable sample positions, region resolves and view instancing.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList1 interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12graphicscommandlist1)
+    GraphicsCommandList1 wrap ID3D12GraphicsCommandList1; decorator for GraphicsCommandList
+}
+
+create_type! {
+    /// Adds support for writing immediate values directly from the command list.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList2 interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12graphicscommandlist2)
+    GraphicsCommandList2 wrap ID3D12GraphicsCommandList2; decorator for GraphicsCommandList1, GraphicsCommandList
+}
+
+create_type! {
+    /// Adds support for protected resource sessions.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList3 interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12graphicscommandlist3)
+    GraphicsCommandList3 wrap ID3D12GraphicsCommandList3; decorator for GraphicsCommandList2, GraphicsCommandList1, GraphicsCommandList
+}
+
+create_type! {
+    /// Adds support for render passes and raytracing.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList4 interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12graphicscommandlist4)
+    GraphicsCommandList4 wrap ID3D12GraphicsCommandList4; decorator for GraphicsCommandList3, GraphicsCommandList2, GraphicsCommandList1, GraphicsCommandList
+}
+
+create_type! {
+    /// Adds support for variable-rate shading.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList5 interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12graphicscommandlist5)
+    GraphicsCommandList5 wrap ID3D12GraphicsCommandList5; decorator for GraphicsCommandList4, GraphicsCommandList3, GraphicsCommandList2, GraphicsCommandList1, GraphicsCommandList
+}
+
+create_type! {
+    /// Adds support for mesh and amplification shaders.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList6 interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12graphicscommandlist6)
+    GraphicsCommandList6 wrap ID3D12GraphicsCommandList6; decorator for GraphicsCommandList5, GraphicsCommandList4, GraphicsCommandList3, GraphicsCommandList2, GraphicsCommandList1, GraphicsCommandList
+}
+
+create_type! {
+    /// Adds support for enhanced barriers.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList7 interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12graphicscommandlist7)
+    GraphicsCommandList7 wrap ID3D12GraphicsCommandList7; decorator for GraphicsCommandList6, GraphicsCommandList5, GraphicsCommandList4, GraphicsCommandList3, GraphicsCommandList2, GraphicsCommandList1, GraphicsCommandList
+}
+
+create_type! {
+    /// Adds support for separate front and back stencil reference values.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList8 interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12graphicscommandlist8)
+    GraphicsCommandList8 wrap ID3D12GraphicsCommandList8; decorator for GraphicsCommandList7, GraphicsCommandList6, GraphicsCommandList5, GraphicsCommandList4, GraphicsCommandList3, GraphicsCommandList2, GraphicsCommandList1, GraphicsCommandList
+}
+
+create_type! {
+    /// Adds support for dynamic depth bias and index buffer strip cut values.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList9 interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12graphicscommandlist9)
+    GraphicsCommandList9 wrap ID3D12GraphicsCommandList9; decorator for GraphicsCommandList8, GraphicsCommandList7, GraphicsCommandList6, GraphicsCommandList5, GraphicsCommandList4, GraphicsCommandList3, GraphicsCommandList2, GraphicsCommandList1, GraphicsCommandList
+}
+
+create_type! {
+    /// Adds support for work graphs.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList10 interface`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#id3d12graphicscommandlist10-methods)
+    GraphicsCommandList10 wrap ID3D12GraphicsCommandList10; decorator for GraphicsCommandList9, GraphicsCommandList8, GraphicsCommandList7, GraphicsCommandList6, GraphicsCommandList5, GraphicsCommandList4, GraphicsCommandList3, GraphicsCommandList2, GraphicsCommandList1, GraphicsCommandList
+}
+
 impl_interface! {
-    GraphicsCommandList;
+    GraphicsCommandList,
+    GraphicsCommandList1,
+    GraphicsCommandList2,
+    GraphicsCommandList3,
+    GraphicsCommandList4,
+    GraphicsCommandList5,
+    GraphicsCommandList6,
+    GraphicsCommandList7,
+    GraphicsCommandList8,
+    GraphicsCommandList9,
+    GraphicsCommandList10;
     /// Gets the type of the command list, such as direct, bundle, compute, or copy.
     ///
     /// For more information: [`ID3D12CommandList::GetType method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12commandlist-gettype)
@@ -30,7 +115,17 @@ impl_interface! {
 }
 
 impl_interface! {
-    GraphicsCommandList;
+    GraphicsCommandList,
+    GraphicsCommandList1,
+    GraphicsCommandList2,
+    GraphicsCommandList3,
+    GraphicsCommandList4,
+    GraphicsCommandList5,
+    GraphicsCommandList6,
+    GraphicsCommandList7,
+    GraphicsCommandList8,
+    GraphicsCommandList9,
+    GraphicsCommandList10;
 
     /// Marks the start of a user-defined region of work.
     #[cfg(feature = "pix")]
@@ -918,7 +1013,17 @@ impl_interface! {
 }
 
 impl_interface! {
-    GraphicsCommandList;
+    GraphicsCommandList,
+    GraphicsCommandList1,
+    GraphicsCommandList2,
+    GraphicsCommandList3,
+    GraphicsCommandList4,
+    GraphicsCommandList5,
+    GraphicsCommandList6,
+    GraphicsCommandList7,
+    GraphicsCommandList8,
+    GraphicsCommandList9,
+    GraphicsCommandList10;
 
     pub fn update_subresources_raw<T: Clone>(
         &self,
@@ -1079,3 +1184,401 @@ impl_interface! {
         )
     }
 }
+
+impl_interface! {
+    GraphicsCommandList1,
+    GraphicsCommandList2,
+    GraphicsCommandList3,
+    GraphicsCommandList4,
+    GraphicsCommandList5,
+    GraphicsCommandList6,
+    GraphicsCommandList7,
+    GraphicsCommandList8,
+    GraphicsCommandList9,
+    GraphicsCommandList10;
+
+    /// Sets the minimum and maximum depth of the depth bounds test.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList1::OMSetDepthBounds method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist1-omsetdepthbounds)
+    pub fn om_set_depth_bounds(&self, min: f32, max: f32) {
+        unsafe {
+            self.0.OMSetDepthBounds(min, max);
+        }
+    }
+
+    /// Configures the sample positions used by subsequent draw, copy, resolve, and similar operations.
+    /// Passing an empty slice reverts the sample positions to their default values.
+    ///
+    /// `positions` holds `num_samples_per_pixel * num_pixels` positions, where `num_pixels` is either 1 or 4 (a 2x2 pixel quad).
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList1::SetSamplePositions method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist1-setsamplepositions)
+    pub fn set_sample_positions(&self, num_samples_per_pixel: u32, num_pixels: u32, positions: &[SamplePosition]) {
+        unsafe {
+            if positions.is_empty() {
+                self.0.SetSamplePositions(0, 0, std::ptr::null());
+            } else {
+                assert!(
+                    matches!(num_pixels, 1 | 4),
+                    "num_pixels must be 1 or 4, got {num_pixels}"
+                );
+                assert_eq!(
+                    num_samples_per_pixel
+                        .checked_mul(num_pixels)
+                        .map(|count| count as usize),
+                    Some(positions.len()),
+                    "positions must hold num_samples_per_pixel * num_pixels sample positions"
+                );
+
+                self.0.SetSamplePositions(
+                    num_samples_per_pixel,
+                    num_pixels,
+                    positions.as_ptr() as *const _
+                );
+            }
+        }
+    }
+
+    /// Copy a region of a multisampled or compressed resource into a non-multisampled or non-compressed resource.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList1::ResolveSubresourceRegion method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist1-resolvesubresourceregion)
+    pub fn resolve_subresource_region(
+        &self,
+        dst_resource: impl AsRef<Resource>,
+        dst_subresource: u32,
+        dst_x: u32,
+        dst_y: u32,
+        src_resource: impl AsRef<Resource>,
+        src_subresource: u32,
+        src_rect: Option<&Rect>,
+        format: Format,
+        resolve_mode: ResolveMode,
+    ) {
+        unsafe {
+            self.0.ResolveSubresourceRegion(
+                &dst_resource.as_ref().0,
+                dst_subresource,
+                dst_x,
+                dst_y,
+                &src_resource.as_ref().0,
+                src_subresource,
+                src_rect.map(|r| &r.0 as *const _),
+                format.as_raw(),
+                resolve_mode.as_raw()
+            );
+        }
+    }
+
+    /// Set a mask that controls which view instances are enabled for subsequent draws.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList1::SetViewInstanceMask method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist1-setviewinstancemask)
+    pub fn set_view_instance_mask(&self, mask: u32) {
+        unsafe {
+            self.0.SetViewInstanceMask(mask);
+        }
+    }
+}
+
+impl_interface! {
+    GraphicsCommandList2,
+    GraphicsCommandList3,
+    GraphicsCommandList4,
+    GraphicsCommandList5,
+    GraphicsCommandList6,
+    GraphicsCommandList7,
+    GraphicsCommandList8,
+    GraphicsCommandList9,
+    GraphicsCommandList10;
+
+    /// Writes a number of 32-bit immediate values to the specified buffer locations directly from the command stream.
+    /// Without `modes`, every write uses [`WriteBufferImmediateMode::Default`].
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList2::WriteBufferImmediate method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist2-writebufferimmediate)
+    pub fn write_buffer_immediate(
+        &self,
+        params: &[WriteBufferImmediateParameter],
+        modes: Option<&[WriteBufferImmediateMode]>,
+    ) {
+        unsafe {
+            if let Some(modes) = modes {
+                assert_eq!(params.len(), modes.len(), "modes must hold one mode per parameter");
+            }
+
+            self.0.WriteBufferImmediate(
+                params.len() as u32,
+                params.as_ptr() as *const _,
+                modes.map(|m| m.as_ptr() as *const _)
+            );
+        }
+    }
+}
+
+impl_interface! {
+    GraphicsCommandList3,
+    GraphicsCommandList4,
+    GraphicsCommandList5,
+    GraphicsCommandList6,
+    GraphicsCommandList7,
+    GraphicsCommandList8,
+    GraphicsCommandList9,
+    GraphicsCommandList10;
+
+    /// Specifies whether or not protected resources can be accessed by subsequent commands in the command list.
+    /// Passing [`None`] disables access to protected resources.
+    /// Sessions are created with [`Device4::create_protected_resource_session`](crate::dx::Device4::create_protected_resource_session).
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList3::SetProtectedResourceSession method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist3-setprotectedresourcesession)
+    pub fn set_protected_resource_session(&self, session: Option<&ProtectedResourceSession>) {
+        unsafe {
+            self.0.SetProtectedResourceSession(session.map(|s| &s.0));
+        }
+    }
+}
+
+impl_interface! {
+    GraphicsCommandList4,
+    GraphicsCommandList5,
+    GraphicsCommandList6,
+    GraphicsCommandList7,
+    GraphicsCommandList8,
+    GraphicsCommandList9,
+    GraphicsCommandList10;
+
+    /// Marks the beginning of a render pass by binding a set of output resources for the duration of the render pass.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList4::BeginRenderPass method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist4-beginrenderpass)
+    pub fn begin_render_pass(
+        &self,
+        render_targets: &[RenderPassRenderTargetDesc<'_>],
+        depth_stencil: Option<&RenderPassDepthStencilDesc<'_>>,
+        flags: RenderPassFlags,
+    ) {
+        unsafe {
+            let render_targets = std::slice::from_raw_parts(
+                render_targets.as_ptr() as *const _,
+                render_targets.len()
+            );
+
+            self.0.BeginRenderPass(
+                Some(render_targets),
+                depth_stencil.map(|ds| &ds.0 as *const _),
+                flags.as_raw()
+            );
+        }
+    }
+
+    /// Marks the ending of a render pass.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList4::EndRenderPass method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist4-endrenderpass)
+    pub fn end_render_pass(&self) {
+        unsafe {
+            self.0.EndRenderPass();
+        }
+    }
+
+    /// Sets a state object on the command list, such as a raytracing pipeline.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList4::SetPipelineState1 method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist4-setpipelinestate1)
+    pub fn set_pipeline_state1(&self, state_object: &StateObject) {
+        unsafe {
+            self.0.SetPipelineState1(&state_object.0)
+        }
+    }
+
+    /// Launches the threads of a ray generation shader.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList4::DispatchRays method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist4-dispatchrays)
+    pub fn dispatch_rays(&self, desc: &DispatchRaysDesc) {
+        unsafe {
+            self.0.DispatchRays(&desc.0)
+        }
+    }
+
+    /// Performs a raytracing acceleration structure build on the GPU and optionally outputs post-build information
+    /// immediately after the build.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList4::BuildRaytracingAccelerationStructure method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist4-buildraytracingaccelerationstructure)
+    pub fn build_raytracing_acceleration_structure(
+        &self,
+        desc: &BuildAccelerationStructureDesc<'_>,
+        postbuild_info_descs: &[AccelerationStructurePostbuildInfoDesc],
+    ) {
+        unsafe {
+            let postbuild_info_descs = (!postbuild_info_descs.is_empty()).then(|| {
+                std::slice::from_raw_parts(postbuild_info_descs.as_ptr() as *const _, postbuild_info_descs.len())
+            });
+
+            self.0.BuildRaytracingAccelerationStructure(&desc.0, postbuild_info_descs)
+        }
+    }
+
+    /// Emits post-build properties for a set of acceleration structures, such as their compacted or serialized sizes.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList4::EmitRaytracingAccelerationStructurePostbuildInfo method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist4-emitraytracingaccelerationstructurepostbuildinfo)
+    pub fn emit_raytracing_acceleration_structure_postbuild_info(
+        &self,
+        desc: &AccelerationStructurePostbuildInfoDesc,
+        source_acceleration_structures: &[GpuVirtualAddress],
+    ) {
+        unsafe {
+            self.0.EmitRaytracingAccelerationStructurePostbuildInfo(&desc.0, source_acceleration_structures)
+        }
+    }
+
+    /// Copies a source acceleration structure to destination memory while applying the specified transformation.
+    /// Compaction and serialization are copies with [`RaytracingAccelerationStructureCopyMode::Compact`] and
+    /// [`RaytracingAccelerationStructureCopyMode::Serialize`].
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList4::CopyRaytracingAccelerationStructure method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist4-copyraytracingaccelerationstructure)
+    pub fn copy_raytracing_acceleration_structure(
+        &self,
+        dest: GpuVirtualAddress,
+        source: GpuVirtualAddress,
+        mode: RaytracingAccelerationStructureCopyMode,
+    ) {
+        unsafe {
+            self.0.CopyRaytracingAccelerationStructure(dest, source, mode.as_raw())
+        }
+    }
+}
+
+impl_interface! {
+    GraphicsCommandList5,
+    GraphicsCommandList6,
+    GraphicsCommandList7,
+    GraphicsCommandList8,
+    GraphicsCommandList9,
+    GraphicsCommandList10;
+
+    /// Sets the base shading rate, and how it combines with the per-primitive rate (first combiner)
+    /// and with the screen-space shading rate image (second combiner).
+    /// Without `combiners`, both use [`ShadingRateCombiner::Passthrough`].
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList5::RSSetShadingRate method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist5-rssetshadingrate)
+    pub fn rs_set_shading_rate(&self, base_shading_rate: ShadingRate, combiners: Option<[ShadingRateCombiner; 2]>) {
+        unsafe {
+            let combiners = combiners.map(|c| [c[0].as_raw(), c[1].as_raw()]);
+
+            self.0.RSSetShadingRate(
+                base_shading_rate.as_raw(),
+                combiners.as_ref().map(|c| c.as_ptr())
+            );
+        }
+    }
+
+    /// Sets the screen-space shading rate image for variable-rate shading. Passing [`None`] unbinds the image.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList5::RSSetShadingRateImage method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist5-rssetshadingrateimage)
+    pub fn rs_set_shading_rate_image(&self, shading_rate_image: Option<&Resource>) {
+        unsafe {
+            self.0.RSSetShadingRateImage(shading_rate_image.map(|r| &r.0));
+        }
+    }
+}
+
+impl_interface! {
+    GraphicsCommandList6,
+    GraphicsCommandList7,
+    GraphicsCommandList8,
+    GraphicsCommandList9,
+    GraphicsCommandList10;
+
+    /// Invokes a set of mesh shader thread groups, or amplification shader thread groups when the pipeline has one.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList6::DispatchMesh method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist6-dispatchmesh)
+    pub fn dispatch_mesh(
+        &self,
+        thread_group_count_x: u32,
+        thread_group_count_y: u32,
+        thread_group_count_z: u32,
+    ) {
+        unsafe {
+            self.0.DispatchMesh(
+                thread_group_count_x,
+                thread_group_count_y,
+                thread_group_count_z
+            )
+        }
+    }
+}
+
+impl_interface! {
+    GraphicsCommandList7,
+    GraphicsCommandList8,
+    GraphicsCommandList9,
+    GraphicsCommandList10;
+
+    /// Records enhanced barriers. The barriers of each type are submitted as one barrier group.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList7::Barrier method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist7-barrier)
+    pub fn barrier(&self, group: &BarrierGroup<'_>) {
+        let (groups, count) = group.as_raw();
+
+        if count == 0 {
+            return;
+        }
+
+        unsafe {
+            self.0.Barrier(&groups[..count]);
+        }
+    }
+}
+
+impl_interface! {
+    GraphicsCommandList8,
+    GraphicsCommandList9,
+    GraphicsCommandList10;
+
+    /// Sets separate front and back stencil reference values for the stencil test.
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList8::OMSetFrontAndBackStencilRef method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist8-omsetfrontandbackstencilref)
+    pub fn om_set_front_and_back_stencil_ref(&self, front_stencil_ref: u32, back_stencil_ref: u32) {
+        unsafe {
+            self.0.OMSetFrontAndBackStencilRef(front_stencil_ref, back_stencil_ref);
+        }
+    }
+}
+
+impl_interface! {
+    GraphicsCommandList9,
+    GraphicsCommandList10;
+
+    /// Sets the depth bias state. The pipeline state must have been created with [`PipelineStateFlags::DynamicDepthBias`].
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList9::RSSetDepthBias method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist9-rssetdepthbias)
+    pub fn rs_set_depth_bias(&self, depth_bias: f32, depth_bias_clamp: f32, slope_scaled_depth_bias: f32) {
+        unsafe {
+            self.0.RSSetDepthBias(depth_bias, depth_bias_clamp, slope_scaled_depth_bias);
+        }
+    }
+
+    /// Sets the index buffer strip cut value. The pipeline state must have been created with [`PipelineStateFlags::DynamicIndexBufferStripCut`].
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList9::IASetIndexBufferStripCutValue method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12graphicscommandlist9-iasetindexbufferstripcutvalue)
+    pub fn ia_set_index_buffer_strip_cut_value(&self, value: IndexBufferStripCutValue) {
+        unsafe {
+            self.0.IASetIndexBufferStripCutValue(value.as_raw());
+        }
+    }
+}
+
+impl_interface! {
+    GraphicsCommandList10;
+
+    /// Sets the program used by [`GraphicsCommandList10::dispatch_graph`].
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList10::SetProgram method`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#setprogram)
+    pub fn set_program(&self, desc: &SetProgramDesc) {
+        unsafe {
+            self.0.SetProgram(&desc.0)
+        }
+    }
+
+    /// Launches the work graph set by [`GraphicsCommandList10::set_program`].
+    ///
+    /// For more information: [`ID3D12GraphicsCommandList10::DispatchGraph method`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#dispatchgraph)
+    pub fn dispatch_graph(&self, desc: &DispatchGraphDesc<'_>) {
+        unsafe {
+            self.0.DispatchGraph(&desc.0)
+        }
+    }
+}
@@ -0,0 +1,1862 @@
+use std::{cell::RefCell, ops::Range};
+
+use windows::{core::Interface, Win32::Graphics::Direct3D12::*};
+
+use crate::{
+    command_list::*, command_signature::CommandSignature, descriptor_heap::DescriptorHeap,
+    pso::PipelineState, query_heap::QueryHeap, resources::Resource, root_signature::RootSignature,
+    types::*,
+};
+
+/// The recording methods of a [`GraphicsCommandList`], allowing rendering code to record into
+/// a real command list or into an [`InMemoryRecorder`].
+///
+/// [`GraphicsCommandList::close`] and [`GraphicsCommandList::reset`] are not part of the trait,
+/// they manage the lifetime of the list rather than record into it. The `update_subresources*`
+/// methods are excluded as well, they map the intermediate resource and so need a real device.
+pub trait CommandRecorder {
+    /// See [`GraphicsCommandList::begin_event`].
+    #[cfg(feature = "pix")]
+    fn begin_event(&self, color: impl Into<u64>, label: impl AsRef<std::ffi::CStr>);
+
+    /// See [`GraphicsCommandList::begin_query`].
+    fn begin_query(&self, query_heap: impl AsRef<QueryHeap>, r#type: QueryType, index: u32);
+
+    /// See [`GraphicsCommandList::clear_depth_stencil_view`].
+    fn clear_depth_stencil_view(
+        &self,
+        depth_stencil_view: CpuDescriptorHandle,
+        clear_flags: ClearFlags,
+        depth: f32,
+        stencil: u8,
+        rects: Option<&[Rect]>,
+    );
+
+    /// See [`GraphicsCommandList::clear_render_target_view`].
+    fn clear_render_target_view(
+        &self,
+        rtv_handle: CpuDescriptorHandle,
+        color: impl Into<[f32; 4]>,
+        rects: &[Rect],
+    );
+
+    /// See [`GraphicsCommandList::clear_state`].
+    fn clear_state<'a>(&self, pso: impl Into<Option<&'a PipelineState>>);
+
+    /// See [`GraphicsCommandList::clear_unordered_access_view_f32`].
+    fn clear_unordered_access_view_f32(
+        &self,
+        view_gpu_handle_in_current_heap: GpuDescriptorHandle,
+        view_cpu_handle: CpuDescriptorHandle,
+        resource: impl AsRef<Resource>,
+        values: impl Into<[f32; 4]>,
+        rects: &[Rect],
+    );
+
+    /// See [`GraphicsCommandList::clear_unordered_access_view_u32`].
+    fn clear_unordered_access_view_u32(
+        &self,
+        view_gpu_handle_in_current_heap: GpuDescriptorHandle,
+        view_cpu_handle: CpuDescriptorHandle,
+        resource: impl AsRef<Resource>,
+        values: impl Into<[u32; 4]>,
+        rects: &[Rect],
+    );
+
+    /// See [`GraphicsCommandList::copy_buffer_region`].
+    fn copy_buffer_region(
+        &self,
+        dst_buffer: impl AsRef<Resource>,
+        dst_offset: u64,
+        src_buffer: impl AsRef<Resource>,
+        src_offset: u64,
+        num_bytes: u64,
+    );
+
+    /// See [`GraphicsCommandList::copy_resource`].
+    fn copy_resource(&self, dst_resource: impl AsRef<Resource>, src_resource: impl AsRef<Resource>);
+
+    /// See [`GraphicsCommandList::copy_texture_region`].
+    fn copy_texture_region(
+        &self,
+        dst: &TextureCopyLocation<'_>,
+        dst_x: u32,
+        dst_y: u32,
+        dst_z: u32,
+        src: &TextureCopyLocation<'_>,
+        src_box: Option<&DxBox>,
+    );
+
+    /// See [`GraphicsCommandList::copy_tiles`].
+    fn copy_tiles(
+        &self,
+        tiled_resource: impl AsRef<Resource>,
+        tile_region_start_coordinate: &TiledResourceCoordinate,
+        tile_region_size: &TileRegionSize,
+        buffer: impl AsRef<Resource>,
+        buffer_start_offset: u64,
+        flags: TileCopyFlags,
+    );
+
+    /// See [`GraphicsCommandList::discard_resource`].
+    fn discard_resource(&self, resource: impl AsRef<Resource>, region: Option<&DiscardRegion<'_>>);
+
+    /// See [`GraphicsCommandList::dispatch`].
+    fn dispatch(
+        &self,
+        thread_group_count_x: u32,
+        thread_group_count_y: u32,
+        thread_group_count_z: u32,
+    );
+
+    /// See [`GraphicsCommandList::draw_indexed_instanced`].
+    fn draw_indexed_instanced(
+        &self,
+        index_count_per_instance: u32,
+        instance_count: u32,
+        start_index_location: u32,
+        base_vertex_location: i32,
+        start_instance_location: u32,
+    );
+
+    /// See [`GraphicsCommandList::draw_instanced`].
+    fn draw_instanced(
+        &self,
+        vertex_count_per_instance: u32,
+        instance_count: u32,
+        start_vertex_location: u32,
+        start_instance_location: u32,
+    );
+
+    /// See [`GraphicsCommandList::end_event`].
+    #[cfg(feature = "pix")]
+    fn end_event(&self);
+
+    /// See [`GraphicsCommandList::end_query`].
+    fn end_query(&self, query_heap: impl AsRef<QueryHeap>, r#type: QueryType, index: u32);
+
+    /// See [`GraphicsCommandList::execute_bundle`].
+    fn execute_bundle(&self, command_list: impl AsRef<GraphicsCommandList>);
+
+    /// See [`GraphicsCommandList::execute_indirect`].
+    fn execute_indirect<'a>(
+        &self,
+        command_signature: impl AsRef<CommandSignature>,
+        max_command_count: u32,
+        argument_buffer: impl AsRef<Resource>,
+        argument_buffer_offset: u64,
+        count_buffer: impl Into<Option<&'a Resource>>,
+        count_buffer_offset: u64,
+    );
+
+    /// See [`GraphicsCommandList::ia_set_index_buffer`].
+    fn ia_set_index_buffer(&self, view: Option<&IndexBufferView>);
+
+    /// See [`GraphicsCommandList::ia_set_primitive_topology`].
+    fn ia_set_primitive_topology(&self, topology: PrimitiveTopology);
+
+    /// See [`GraphicsCommandList::ia_set_vertex_buffers`].
+    fn ia_set_vertex_buffers(&self, slot: u32, buffers: &[VertexBufferView]);
+
+    /// See [`GraphicsCommandList::om_set_blend_factor`].
+    fn om_set_blend_factor(&self, blend_factor: Option<[f32; 4]>);
+
+    /// See [`GraphicsCommandList::om_set_render_targets`].
+    fn om_set_render_targets(
+        &self,
+        render_targets: &[CpuDescriptorHandle],
+        rts_single_handle_to_descriptor_range: bool,
+        depth_stencil: Option<CpuDescriptorHandle>,
+    );
+
+    /// See [`GraphicsCommandList::om_set_stencil_ref`].
+    fn om_set_stencil_ref(&self, stencil_ref: u32);
+
+    /// See [`GraphicsCommandList::resolve_query_data`].
+    fn resolve_query_data(
+        &self,
+        query_heap: impl AsRef<QueryHeap>,
+        r#type: QueryType,
+        range: Range<u32>,
+        dst_buffer: impl AsRef<Resource>,
+        aligned_dst_buffer_offset: u64,
+    );
+
+    /// See [`GraphicsCommandList::resolve_subresource`].
+    fn resolve_subresource(
+        &self,
+        dst_resource: impl AsRef<Resource>,
+        dst_subresource: u32,
+        src_resource: impl AsRef<Resource>,
+        src_subresource: u32,
+        format: Format,
+    );
+
+    /// See [`GraphicsCommandList::resource_barrier`].
+    fn resource_barrier(&self, barriers: &[ResourceBarrier<'_>]);
+
+    /// See [`GraphicsCommandList::rs_set_scissor_rects`].
+    fn rs_set_scissor_rects(&self, rects: &[Rect]);
+
+    /// See [`GraphicsCommandList::rs_set_viewports`].
+    fn rs_set_viewports(&self, viewport: &[Viewport]);
+
+    /// See [`GraphicsCommandList::set_compute_root_32bit_constant`].
+    fn set_compute_root_32bit_constant<T: Copy>(
+        &self,
+        root_parameter_index: u32,
+        src_data: T,
+        dest_offset_in_32bit_values: u32,
+    );
+
+    /// See [`GraphicsCommandList::set_compute_root_32bit_constants`].
+    fn set_compute_root_32bit_constants<T: Copy>(
+        &self,
+        root_parameter_index: u32,
+        src_data: &[T],
+        dest_offset_in_32bit_values: u32,
+    );
+
+    /// See [`GraphicsCommandList::set_compute_root_constant_buffer_view`].
+    fn set_compute_root_constant_buffer_view(
+        &self,
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    );
+
+    /// See [`GraphicsCommandList::set_compute_root_descriptor_table`].
+    fn set_compute_root_descriptor_table(
+        &self,
+        root_parameter_index: u32,
+        base_descriptor: GpuDescriptorHandle,
+    );
+
+    /// See [`GraphicsCommandList::set_compute_root_shader_resource_view`].
+    fn set_compute_root_shader_resource_view(
+        &self,
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    );
+
+    /// See [`GraphicsCommandList::set_compute_root_signature`].
+    fn set_compute_root_signature<'a>(&self, root_signature: impl Into<Option<&'a RootSignature>>);
+
+    /// See [`GraphicsCommandList::set_compute_root_unordered_access_view`].
+    fn set_compute_root_unordered_access_view(
+        &self,
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    );
+
+    /// See [`GraphicsCommandList::set_descriptor_heaps`].
+    fn set_descriptor_heaps(&self, descriptor_heaps: &[Option<DescriptorHeap>]);
+
+    /// See [`GraphicsCommandList::set_graphics_root_32bit_constant`].
+    fn set_graphics_root_32bit_constant<T: Copy>(
+        &self,
+        root_parameter_index: u32,
+        src_data: T,
+        dest_offset_in_32bit_values: u32,
+    );
+
+    /// See [`GraphicsCommandList::set_graphics_root_32bit_constants`].
+    fn set_graphics_root_32bit_constants<T: Copy>(
+        &self,
+        root_parameter_index: u32,
+        src_data: &[T],
+        dest_offset_in_32bit_values: u32,
+    );
+
+    /// See [`GraphicsCommandList::set_graphics_root_constant_buffer_view`].
+    fn set_graphics_root_constant_buffer_view(
+        &self,
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    );
+
+    /// See [`GraphicsCommandList::set_graphics_root_descriptor_table`].
+    fn set_graphics_root_descriptor_table(
+        &self,
+        root_parameter_index: u32,
+        base_descriptor: GpuDescriptorHandle,
+    );
+
+    /// See [`GraphicsCommandList::set_graphics_root_shader_resource_view`].
+    fn set_graphics_root_shader_resource_view(
+        &self,
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    );
+
+    /// See [`GraphicsCommandList::set_graphics_root_signature`].
+    fn set_graphics_root_signature<'a>(&self, root_signature: impl Into<Option<&'a RootSignature>>);
+
+    /// See [`GraphicsCommandList::set_graphics_root_unordered_access_view`].
+    fn set_graphics_root_unordered_access_view(
+        &self,
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    );
+
+    /// See [`GraphicsCommandList::set_marker`].
+    #[cfg(feature = "pix")]
+    fn set_marker(&self, color: impl Into<u64>, label: impl AsRef<std::ffi::CStr>);
+
+    /// See [`GraphicsCommandList::set_pipeline_state`].
+    fn set_pipeline_state(&self, pso: impl AsRef<PipelineState>);
+
+    /// See [`GraphicsCommandList::set_predication`].
+    fn set_predication<'a>(
+        &self,
+        buffer: impl Into<Option<&'a Resource>>,
+        aligned_buffer_offset: u64,
+        operation: PredicationOp,
+    );
+
+    /// See [`GraphicsCommandList::so_set_targets`].
+    fn so_set_targets(&self, start_slot: u32, views: Option<&[StreamOutputBufferView]>);
+}
+
+macro_rules! impl_command_recorder {
+    ($($t:ty),+) => {
+        $(
+            impl CommandRecorder for $t {
+                #[cfg(feature = "pix")]
+                #[inline]
+                fn begin_event(&self, color: impl Into<u64>, label: impl AsRef<std::ffi::CStr>) {
+                    <$t>::begin_event(self, color, label)
+                }
+
+                #[inline]
+                fn begin_query(&self, query_heap: impl AsRef<QueryHeap>, r#type: QueryType, index: u32) {
+                    <$t>::begin_query(self, query_heap, r#type, index)
+                }
+
+                #[inline]
+                fn clear_depth_stencil_view(
+                    &self,
+                    depth_stencil_view: CpuDescriptorHandle,
+                    clear_flags: ClearFlags,
+                    depth: f32,
+                    stencil: u8,
+                    rects: Option<&[Rect]>,
+                ) {
+                    <$t>::clear_depth_stencil_view(self, depth_stencil_view, clear_flags, depth, stencil, rects)
+                }
+
+                #[inline]
+                fn clear_render_target_view(
+                    &self,
+                    rtv_handle: CpuDescriptorHandle,
+                    color: impl Into<[f32; 4]>,
+                    rects: &[Rect],
+                ) {
+                    <$t>::clear_render_target_view(self, rtv_handle, color, rects)
+                }
+
+                #[inline]
+                fn clear_state<'a>(&self, pso: impl Into<Option<&'a PipelineState>>) {
+                    <$t>::clear_state(self, pso)
+                }
+
+                #[inline]
+                fn clear_unordered_access_view_f32(
+                    &self,
+                    view_gpu_handle_in_current_heap: GpuDescriptorHandle,
+                    view_cpu_handle: CpuDescriptorHandle,
+                    resource: impl AsRef<Resource>,
+                    values: impl Into<[f32; 4]>,
+                    rects: &[Rect],
+                ) {
+                    <$t>::clear_unordered_access_view_f32(
+                        self,
+                        view_gpu_handle_in_current_heap,
+                        view_cpu_handle,
+                        resource,
+                        values,
+                        rects,
+                    )
+                }
+
+                #[inline]
+                fn clear_unordered_access_view_u32(
+                    &self,
+                    view_gpu_handle_in_current_heap: GpuDescriptorHandle,
+                    view_cpu_handle: CpuDescriptorHandle,
+                    resource: impl AsRef<Resource>,
+                    values: impl Into<[u32; 4]>,
+                    rects: &[Rect],
+                ) {
+                    <$t>::clear_unordered_access_view_u32(
+                        self,
+                        view_gpu_handle_in_current_heap,
+                        view_cpu_handle,
+                        resource,
+                        values,
+                        rects,
+                    )
+                }
+
+                #[inline]
+                fn copy_buffer_region(
+                    &self,
+                    dst_buffer: impl AsRef<Resource>,
+                    dst_offset: u64,
+                    src_buffer: impl AsRef<Resource>,
+                    src_offset: u64,
+                    num_bytes: u64,
+                ) {
+                    <$t>::copy_buffer_region(self, dst_buffer, dst_offset, src_buffer, src_offset, num_bytes)
+                }
+
+                #[inline]
+                fn copy_resource(&self, dst_resource: impl AsRef<Resource>, src_resource: impl AsRef<Resource>) {
+                    <$t>::copy_resource(self, dst_resource, src_resource)
+                }
+
+                #[inline]
+                fn copy_texture_region(
+                    &self,
+                    dst: &TextureCopyLocation<'_>,
+                    dst_x: u32,
+                    dst_y: u32,
+                    dst_z: u32,
+                    src: &TextureCopyLocation<'_>,
+                    src_box: Option<&DxBox>,
+                ) {
+                    <$t>::copy_texture_region(self, dst, dst_x, dst_y, dst_z, src, src_box)
+                }
+
+                #[inline]
+                fn copy_tiles(
+                    &self,
+                    tiled_resource: impl AsRef<Resource>,
+                    tile_region_start_coordinate: &TiledResourceCoordinate,
+                    tile_region_size: &TileRegionSize,
+                    buffer: impl AsRef<Resource>,
+                    buffer_start_offset: u64,
+                    flags: TileCopyFlags,
+                ) {
+                    <$t>::copy_tiles(
+                        self,
+                        tiled_resource,
+                        tile_region_start_coordinate,
+                        tile_region_size,
+                        buffer,
+                        buffer_start_offset,
+                        flags,
+                    )
+                }
+
+                #[inline]
+                fn discard_resource(&self, resource: impl AsRef<Resource>, region: Option<&DiscardRegion<'_>>) {
+                    <$t>::discard_resource(self, resource, region)
+                }
+
+                #[inline]
+                fn dispatch(&self, thread_group_count_x: u32, thread_group_count_y: u32, thread_group_count_z: u32) {
+                    <$t>::dispatch(self, thread_group_count_x, thread_group_count_y, thread_group_count_z)
+                }
+
+                #[inline]
+                fn draw_indexed_instanced(
+                    &self,
+                    index_count_per_instance: u32,
+                    instance_count: u32,
+                    start_index_location: u32,
+                    base_vertex_location: i32,
+                    start_instance_location: u32,
+                ) {
+                    <$t>::draw_indexed_instanced(
+                        self,
+                        index_count_per_instance,
+                        instance_count,
+                        start_index_location,
+                        base_vertex_location,
+                        start_instance_location,
+                    )
+                }
+
+                #[inline]
+                fn draw_instanced(
+                    &self,
+                    vertex_count_per_instance: u32,
+                    instance_count: u32,
+                    start_vertex_location: u32,
+                    start_instance_location: u32,
+                ) {
+                    <$t>::draw_instanced(
+                        self,
+                        vertex_count_per_instance,
+                        instance_count,
+                        start_vertex_location,
+                        start_instance_location,
+                    )
+                }
+
+                #[cfg(feature = "pix")]
+                #[inline]
+                fn end_event(&self) {
+                    <$t>::end_event(self)
+                }
+
+                #[inline]
+                fn end_query(&self, query_heap: impl AsRef<QueryHeap>, r#type: QueryType, index: u32) {
+                    <$t>::end_query(self, query_heap, r#type, index)
+                }
+
+                #[inline]
+                fn execute_bundle(&self, command_list: impl AsRef<GraphicsCommandList>) {
+                    <$t>::execute_bundle(self, command_list)
+                }
+
+                #[inline]
+                fn execute_indirect<'a>(
+                    &self,
+                    command_signature: impl AsRef<CommandSignature>,
+                    max_command_count: u32,
+                    argument_buffer: impl AsRef<Resource>,
+                    argument_buffer_offset: u64,
+                    count_buffer: impl Into<Option<&'a Resource>>,
+                    count_buffer_offset: u64,
+                ) {
+                    <$t>::execute_indirect(
+                        self,
+                        command_signature,
+                        max_command_count,
+                        argument_buffer,
+                        argument_buffer_offset,
+                        count_buffer,
+                        count_buffer_offset,
+                    )
+                }
+
+                #[inline]
+                fn ia_set_index_buffer(&self, view: Option<&IndexBufferView>) {
+                    <$t>::ia_set_index_buffer(self, view)
+                }
+
+                #[inline]
+                fn ia_set_primitive_topology(&self, topology: PrimitiveTopology) {
+                    <$t>::ia_set_primitive_topology(self, topology)
+                }
+
+                #[inline]
+                fn ia_set_vertex_buffers(&self, slot: u32, buffers: &[VertexBufferView]) {
+                    <$t>::ia_set_vertex_buffers(self, slot, buffers)
+                }
+
+                #[inline]
+                fn om_set_blend_factor(&self, blend_factor: Option<[f32; 4]>) {
+                    <$t>::om_set_blend_factor(self, blend_factor)
+                }
+
+                #[inline]
+                fn om_set_render_targets(
+                    &self,
+                    render_targets: &[CpuDescriptorHandle],
+                    rts_single_handle_to_descriptor_range: bool,
+                    depth_stencil: Option<CpuDescriptorHandle>,
+                ) {
+                    <$t>::om_set_render_targets(self, render_targets, rts_single_handle_to_descriptor_range, depth_stencil)
+                }
+
+                #[inline]
+                fn om_set_stencil_ref(&self, stencil_ref: u32) {
+                    <$t>::om_set_stencil_ref(self, stencil_ref)
+                }
+
+                #[inline]
+                fn resolve_query_data(
+                    &self,
+                    query_heap: impl AsRef<QueryHeap>,
+                    r#type: QueryType,
+                    range: Range<u32>,
+                    dst_buffer: impl AsRef<Resource>,
+                    aligned_dst_buffer_offset: u64,
+                ) {
+                    <$t>::resolve_query_data(self, query_heap, r#type, range, dst_buffer, aligned_dst_buffer_offset)
+                }
+
+                #[inline]
+                fn resolve_subresource(
+                    &self,
+                    dst_resource: impl AsRef<Resource>,
+                    dst_subresource: u32,
+                    src_resource: impl AsRef<Resource>,
+                    src_subresource: u32,
+                    format: Format,
+                ) {
+                    <$t>::resolve_subresource(self, dst_resource, dst_subresource, src_resource, src_subresource, format)
+                }
+
+                #[inline]
+                fn resource_barrier(&self, barriers: &[ResourceBarrier<'_>]) {
+                    <$t>::resource_barrier(self, barriers)
+                }
+
+                #[inline]
+                fn rs_set_scissor_rects(&self, rects: &[Rect]) {
+                    <$t>::rs_set_scissor_rects(self, rects)
+                }
+
+                #[inline]
+                fn rs_set_viewports(&self, viewport: &[Viewport]) {
+                    <$t>::rs_set_viewports(self, viewport)
+                }
+
+                #[inline]
+                fn set_compute_root_32bit_constant<T: Copy>(
+                    &self,
+                    root_parameter_index: u32,
+                    src_data: T,
+                    dest_offset_in_32bit_values: u32,
+                ) {
+                    <$t>::set_compute_root_32bit_constant(self, root_parameter_index, src_data, dest_offset_in_32bit_values)
+                }
+
+                #[inline]
+                fn set_compute_root_32bit_constants<T: Copy>(
+                    &self,
+                    root_parameter_index: u32,
+                    src_data: &[T],
+                    dest_offset_in_32bit_values: u32,
+                ) {
+                    <$t>::set_compute_root_32bit_constants(self, root_parameter_index, src_data, dest_offset_in_32bit_values)
+                }
+
+                #[inline]
+                fn set_compute_root_constant_buffer_view(
+                    &self,
+                    root_parameter_index: u32,
+                    buffer_location: GpuVirtualAddress,
+                ) {
+                    <$t>::set_compute_root_constant_buffer_view(self, root_parameter_index, buffer_location)
+                }
+
+                #[inline]
+                fn set_compute_root_descriptor_table(
+                    &self,
+                    root_parameter_index: u32,
+                    base_descriptor: GpuDescriptorHandle,
+                ) {
+                    <$t>::set_compute_root_descriptor_table(self, root_parameter_index, base_descriptor)
+                }
+
+                #[inline]
+                fn set_compute_root_shader_resource_view(
+                    &self,
+                    root_parameter_index: u32,
+                    buffer_location: GpuVirtualAddress,
+                ) {
+                    <$t>::set_compute_root_shader_resource_view(self, root_parameter_index, buffer_location)
+                }
+
+                #[inline]
+                fn set_compute_root_signature<'a>(&self, root_signature: impl Into<Option<&'a RootSignature>>) {
+                    <$t>::set_compute_root_signature(self, root_signature)
+                }
+
+                #[inline]
+                fn set_compute_root_unordered_access_view(
+                    &self,
+                    root_parameter_index: u32,
+                    buffer_location: GpuVirtualAddress,
+                ) {
+                    <$t>::set_compute_root_unordered_access_view(self, root_parameter_index, buffer_location)
+                }
+
+                #[inline]
+                fn set_descriptor_heaps(&self, descriptor_heaps: &[Option<DescriptorHeap>]) {
+                    <$t>::set_descriptor_heaps(self, descriptor_heaps)
+                }
+
+                #[inline]
+                fn set_graphics_root_32bit_constant<T: Copy>(
+                    &self,
+                    root_parameter_index: u32,
+                    src_data: T,
+                    dest_offset_in_32bit_values: u32,
+                ) {
+                    <$t>::set_graphics_root_32bit_constant(self, root_parameter_index, src_data, dest_offset_in_32bit_values)
+                }
+
+                #[inline]
+                fn set_graphics_root_32bit_constants<T: Copy>(
+                    &self,
+                    root_parameter_index: u32,
+                    src_data: &[T],
+                    dest_offset_in_32bit_values: u32,
+                ) {
+                    <$t>::set_graphics_root_32bit_constants(self, root_parameter_index, src_data, dest_offset_in_32bit_values)
+                }
+
+                #[inline]
+                fn set_graphics_root_constant_buffer_view(
+                    &self,
+                    root_parameter_index: u32,
+                    buffer_location: GpuVirtualAddress,
+                ) {
+                    <$t>::set_graphics_root_constant_buffer_view(self, root_parameter_index, buffer_location)
+                }
+
+                #[inline]
+                fn set_graphics_root_descriptor_table(
+                    &self,
+                    root_parameter_index: u32,
+                    base_descriptor: GpuDescriptorHandle,
+                ) {
+                    <$t>::set_graphics_root_descriptor_table(self, root_parameter_index, base_descriptor)
+                }
+
+                #[inline]
+                fn set_graphics_root_shader_resource_view(
+                    &self,
+                    root_parameter_index: u32,
+                    buffer_location: GpuVirtualAddress,
+                ) {
+                    <$t>::set_graphics_root_shader_resource_view(self, root_parameter_index, buffer_location)
+                }
+
+                #[inline]
+                fn set_graphics_root_signature<'a>(&self, root_signature: impl Into<Option<&'a RootSignature>>) {
+                    <$t>::set_graphics_root_signature(self, root_signature)
+                }
+
+                #[inline]
+                fn set_graphics_root_unordered_access_view(
+                    &self,
+                    root_parameter_index: u32,
+                    buffer_location: GpuVirtualAddress,
+                ) {
+                    <$t>::set_graphics_root_unordered_access_view(self, root_parameter_index, buffer_location)
+                }
+
+                #[cfg(feature = "pix")]
+                #[inline]
+                fn set_marker(&self, color: impl Into<u64>, label: impl AsRef<std::ffi::CStr>) {
+                    <$t>::set_marker(self, color, label)
+                }
+
+                #[inline]
+                fn set_pipeline_state(&self, pso: impl AsRef<PipelineState>) {
+                    <$t>::set_pipeline_state(self, pso)
+                }
+
+                #[inline]
+                fn set_predication<'a>(
+                    &self,
+                    buffer: impl Into<Option<&'a Resource>>,
+                    aligned_buffer_offset: u64,
+                    operation: PredicationOp,
+                ) {
+                    <$t>::set_predication(self, buffer, aligned_buffer_offset, operation)
+                }
+
+                #[inline]
+                fn so_set_targets(&self, start_slot: u32, views: Option<&[StreamOutputBufferView]>) {
+                    <$t>::so_set_targets(self, start_slot, views)
+                }
+            }
+        )+
+    };
+}
+
+impl_command_recorder!(
+    GraphicsCommandList,
+    GraphicsCommandList1,
+    GraphicsCommandList2,
+    GraphicsCommandList3,
+    GraphicsCommandList4,
+    GraphicsCommandList5,
+    GraphicsCommandList6,
+    GraphicsCommandList7,
+    GraphicsCommandList8,
+    GraphicsCommandList9,
+    GraphicsCommandList10
+);
+
+/// An opaque identity of a COM object referenced by a recorded command, the address of its interface.
+///
+/// The recorder does not hold a reference to the object, so the id is only meaningful while the object is alive.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct ObjectId(usize);
+
+impl ObjectId {
+    #[inline]
+    fn of(object: &impl Interface) -> Self {
+        Self(object.as_raw() as usize)
+    }
+}
+
+macro_rules! impl_object_id {
+    ($($t:ty),+) => {
+        $(
+            impl From<&$t> for ObjectId {
+                #[inline]
+                fn from(value: &$t) -> Self {
+                    Self::of(&value.0)
+                }
+            }
+        )+
+    };
+}
+
+impl_object_id!(
+    CommandSignature,
+    DescriptorHeap,
+    GraphicsCommandList,
+    PipelineState,
+    QueryHeap,
+    Resource,
+    RootSignature
+);
+
+/// An owned copy of a [`ResourceBarrier`] captured by an [`InMemoryRecorder`].
+/// A [`None`] subresource means all subresources.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecordedBarrier {
+    Transition {
+        resource: Option<ObjectId>,
+        subresource: Option<u32>,
+        before: ResourceStates,
+        after: ResourceStates,
+        flags: ResourceBarrierFlags,
+    },
+    Aliasing {
+        before: Option<ObjectId>,
+        after: Option<ObjectId>,
+        flags: ResourceBarrierFlags,
+    },
+    Uav {
+        resource: Option<ObjectId>,
+        flags: ResourceBarrierFlags,
+    },
+}
+
+impl From<&ResourceBarrier<'_>> for RecordedBarrier {
+    fn from(barrier: &ResourceBarrier<'_>) -> Self {
+        let flags = barrier.0.Flags.into();
+
+        unsafe {
+            match barrier.0.Type {
+                D3D12_RESOURCE_BARRIER_TYPE_TRANSITION => {
+                    let transition = &barrier.0.Anonymous.Transition;
+
+                    RecordedBarrier::Transition {
+                        resource: (*transition.pResource).as_ref().map(ObjectId::of),
+                        subresource: (transition.Subresource != BARRIER_ALL_SUBRESOURCES)
+                            .then_some(transition.Subresource),
+                        before: transition.StateBefore.into(),
+                        after: transition.StateAfter.into(),
+                        flags,
+                    }
+                }
+                D3D12_RESOURCE_BARRIER_TYPE_ALIASING => {
+                    let aliasing = &barrier.0.Anonymous.Aliasing;
+
+                    RecordedBarrier::Aliasing {
+                        before: (*aliasing.pResourceBefore).as_ref().map(ObjectId::of),
+                        after: (*aliasing.pResourceAfter).as_ref().map(ObjectId::of),
+                        flags,
+                    }
+                }
+                _ => RecordedBarrier::Uav {
+                    resource: (*barrier.0.Anonymous.UAV.pResource)
+                        .as_ref()
+                        .map(ObjectId::of),
+                    flags,
+                },
+            }
+        }
+    }
+}
+
+/// An owned copy of a [`TextureCopyLocation`] captured by an [`InMemoryRecorder`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecordedCopyLocation {
+    Subresource {
+        resource: Option<ObjectId>,
+        index: u32,
+    },
+    PlacedFootprint {
+        resource: Option<ObjectId>,
+        footprint: PlacedSubresourceFootprint,
+    },
+}
+
+impl From<&TextureCopyLocation<'_>> for RecordedCopyLocation {
+    fn from(location: &TextureCopyLocation<'_>) -> Self {
+        let resource = (*location.0.pResource).as_ref().map(ObjectId::of);
+
+        unsafe {
+            if location.0.Type == D3D12_TEXTURE_COPY_TYPE_SUBRESOURCE_INDEX {
+                RecordedCopyLocation::Subresource {
+                    resource,
+                    index: location.0.Anonymous.SubresourceIndex,
+                }
+            } else {
+                RecordedCopyLocation::PlacedFootprint {
+                    resource,
+                    footprint: PlacedSubresourceFootprint(location.0.Anonymous.PlacedFootprint),
+                }
+            }
+        }
+    }
+}
+
+/// An owned copy of a [`DiscardRegion`] captured by an [`InMemoryRecorder`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RecordedDiscardRegion {
+    pub rects: Vec<Rect>,
+    pub first_subresource: u32,
+    pub num_subresources: u32,
+}
+
+impl From<&DiscardRegion<'_>> for RecordedDiscardRegion {
+    fn from(region: &DiscardRegion<'_>) -> Self {
+        let rects = if region.0.pRects.is_null() || region.0.NumRects == 0 {
+            vec![]
+        } else {
+            unsafe { std::slice::from_raw_parts(region.0.pRects, region.0.NumRects as usize) }
+                .iter()
+                .map(|rect| Rect(*rect))
+                .collect()
+        };
+
+        Self {
+            rects,
+            first_subresource: region.0.FirstSubresource,
+            num_subresources: region.0.NumSubresources,
+        }
+    }
+}
+
+/// A command captured by an [`InMemoryRecorder`], one variant per [`CommandRecorder`] method.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    #[cfg(feature = "pix")]
+    BeginEvent {
+        color: u64,
+        label: std::ffi::CString,
+    },
+    BeginQuery {
+        query_heap: ObjectId,
+        r#type: QueryType,
+        index: u32,
+    },
+    ClearDepthStencilView {
+        depth_stencil_view: CpuDescriptorHandle,
+        clear_flags: ClearFlags,
+        depth: f32,
+        stencil: u8,
+        rects: Option<Vec<Rect>>,
+    },
+    ClearRenderTargetView {
+        rtv_handle: CpuDescriptorHandle,
+        color: [f32; 4],
+        rects: Vec<Rect>,
+    },
+    ClearState(Option<ObjectId>),
+    ClearUnorderedAccessViewF32 {
+        view_gpu_handle_in_current_heap: GpuDescriptorHandle,
+        view_cpu_handle: CpuDescriptorHandle,
+        resource: ObjectId,
+        values: [f32; 4],
+        rects: Vec<Rect>,
+    },
+    ClearUnorderedAccessViewU32 {
+        view_gpu_handle_in_current_heap: GpuDescriptorHandle,
+        view_cpu_handle: CpuDescriptorHandle,
+        resource: ObjectId,
+        values: [u32; 4],
+        rects: Vec<Rect>,
+    },
+    CopyBufferRegion {
+        dst_buffer: ObjectId,
+        dst_offset: u64,
+        src_buffer: ObjectId,
+        src_offset: u64,
+        num_bytes: u64,
+    },
+    CopyResource {
+        dst_resource: ObjectId,
+        src_resource: ObjectId,
+    },
+    CopyTextureRegion {
+        dst: RecordedCopyLocation,
+        dst_x: u32,
+        dst_y: u32,
+        dst_z: u32,
+        src: RecordedCopyLocation,
+        src_box: Option<DxBox>,
+    },
+    CopyTiles {
+        tiled_resource: ObjectId,
+        tile_region_start_coordinate: TiledResourceCoordinate,
+        tile_region_size: TileRegionSize,
+        buffer: ObjectId,
+        buffer_start_offset: u64,
+        flags: TileCopyFlags,
+    },
+    DiscardResource {
+        resource: ObjectId,
+        region: Option<RecordedDiscardRegion>,
+    },
+    Dispatch {
+        thread_group_count_x: u32,
+        thread_group_count_y: u32,
+        thread_group_count_z: u32,
+    },
+    DrawIndexedInstanced {
+        index_count_per_instance: u32,
+        instance_count: u32,
+        start_index_location: u32,
+        base_vertex_location: i32,
+        start_instance_location: u32,
+    },
+    DrawInstanced {
+        vertex_count_per_instance: u32,
+        instance_count: u32,
+        start_vertex_location: u32,
+        start_instance_location: u32,
+    },
+    #[cfg(feature = "pix")]
+    EndEvent,
+    EndQuery {
+        query_heap: ObjectId,
+        r#type: QueryType,
+        index: u32,
+    },
+    ExecuteBundle(ObjectId),
+    ExecuteIndirect {
+        command_signature: ObjectId,
+        max_command_count: u32,
+        argument_buffer: ObjectId,
+        argument_buffer_offset: u64,
+        count_buffer: Option<ObjectId>,
+        count_buffer_offset: u64,
+    },
+    IaSetIndexBuffer(Option<IndexBufferView>),
+    IaSetPrimitiveTopology(PrimitiveTopology),
+    IaSetVertexBuffers {
+        slot: u32,
+        buffers: Vec<VertexBufferView>,
+    },
+    OmSetBlendFactor(Option<[f32; 4]>),
+    OmSetRenderTargets {
+        render_targets: Vec<CpuDescriptorHandle>,
+        rts_single_handle_to_descriptor_range: bool,
+        depth_stencil: Option<CpuDescriptorHandle>,
+    },
+    OmSetStencilRef(u32),
+    ResolveQueryData {
+        query_heap: ObjectId,
+        r#type: QueryType,
+        range: Range<u32>,
+        dst_buffer: ObjectId,
+        aligned_dst_buffer_offset: u64,
+    },
+    ResolveSubresource {
+        dst_resource: ObjectId,
+        dst_subresource: u32,
+        src_resource: ObjectId,
+        src_subresource: u32,
+        format: Format,
+    },
+    ResourceBarrier(Vec<RecordedBarrier>),
+    RsSetScissorRects(Vec<Rect>),
+    RsSetViewports(Vec<Viewport>),
+    SetComputeRoot32BitConstants {
+        root_parameter_index: u32,
+        src_data: Vec<u32>,
+        dest_offset_in_32bit_values: u32,
+    },
+    SetComputeRootConstantBufferView {
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    },
+    SetComputeRootDescriptorTable {
+        root_parameter_index: u32,
+        base_descriptor: GpuDescriptorHandle,
+    },
+    SetComputeRootShaderResourceView {
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    },
+    SetComputeRootSignature(Option<ObjectId>),
+    SetComputeRootUnorderedAccessView {
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    },
+    SetDescriptorHeaps(Vec<Option<ObjectId>>),
+    SetGraphicsRoot32BitConstants {
+        root_parameter_index: u32,
+        src_data: Vec<u32>,
+        dest_offset_in_32bit_values: u32,
+    },
+    SetGraphicsRootConstantBufferView {
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    },
+    SetGraphicsRootDescriptorTable {
+        root_parameter_index: u32,
+        base_descriptor: GpuDescriptorHandle,
+    },
+    SetGraphicsRootShaderResourceView {
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    },
+    SetGraphicsRootSignature(Option<ObjectId>),
+    SetGraphicsRootUnorderedAccessView {
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    },
+    #[cfg(feature = "pix")]
+    SetMarker {
+        color: u64,
+        label: std::ffi::CString,
+    },
+    SetPipelineState(ObjectId),
+    SetPredication {
+        buffer: Option<ObjectId>,
+        aligned_buffer_offset: u64,
+        operation: PredicationOp,
+    },
+    SoSetTargets {
+        start_slot: u32,
+        views: Option<Vec<StreamOutputBufferView>>,
+    },
+}
+
+/// A [`CommandRecorder`] capturing commands into memory instead of recording them for the GPU,
+/// so command streams can be inspected without a device.
+///
+/// Single root constants are captured as [`Command::SetComputeRoot32BitConstants`]
+/// and [`Command::SetGraphicsRoot32BitConstants`] with one value.
+#[derive(Debug, Default)]
+pub struct InMemoryRecorder {
+    commands: RefCell<Vec<Command>>,
+}
+
+impl InMemoryRecorder {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a copy of the recorded commands.
+    #[inline]
+    pub fn commands(&self) -> Vec<Command> {
+        self.commands.borrow().clone()
+    }
+
+    /// Takes the recorded commands, leaving the recorder empty.
+    #[inline]
+    pub fn take_commands(&self) -> Vec<Command> {
+        self.commands.take()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.commands.borrow().len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.commands.borrow().is_empty()
+    }
+
+    #[inline]
+    fn push(&self, command: Command) {
+        self.commands.borrow_mut().push(command);
+    }
+}
+
+#[inline]
+fn constants_to_bits<T: Copy>(src_data: &[T]) -> Vec<u32> {
+    const { assert!(size_of::<T>() == 4) }
+
+    src_data
+        .iter()
+        .map(|value| unsafe { std::mem::transmute_copy(value) })
+        .collect()
+}
+
+impl CommandRecorder for InMemoryRecorder {
+    #[cfg(feature = "pix")]
+    fn begin_event(&self, color: impl Into<u64>, label: impl AsRef<std::ffi::CStr>) {
+        self.push(Command::BeginEvent {
+            color: color.into(),
+            label: label.as_ref().to_owned(),
+        });
+    }
+
+    fn begin_query(&self, query_heap: impl AsRef<QueryHeap>, r#type: QueryType, index: u32) {
+        self.push(Command::BeginQuery {
+            query_heap: ObjectId::from(query_heap.as_ref()),
+            r#type,
+            index,
+        });
+    }
+
+    fn clear_depth_stencil_view(
+        &self,
+        depth_stencil_view: CpuDescriptorHandle,
+        clear_flags: ClearFlags,
+        depth: f32,
+        stencil: u8,
+        rects: Option<&[Rect]>,
+    ) {
+        self.push(Command::ClearDepthStencilView {
+            depth_stencil_view,
+            clear_flags,
+            depth,
+            stencil,
+            rects: rects.map(|rects| rects.to_vec()),
+        });
+    }
+
+    fn clear_render_target_view(
+        &self,
+        rtv_handle: CpuDescriptorHandle,
+        color: impl Into<[f32; 4]>,
+        rects: &[Rect],
+    ) {
+        self.push(Command::ClearRenderTargetView {
+            rtv_handle,
+            color: color.into(),
+            rects: rects.to_vec(),
+        });
+    }
+
+    fn clear_state<'a>(&self, pso: impl Into<Option<&'a PipelineState>>) {
+        self.push(Command::ClearState(pso.into().map(ObjectId::from)));
+    }
+
+    fn clear_unordered_access_view_f32(
+        &self,
+        view_gpu_handle_in_current_heap: GpuDescriptorHandle,
+        view_cpu_handle: CpuDescriptorHandle,
+        resource: impl AsRef<Resource>,
+        values: impl Into<[f32; 4]>,
+        rects: &[Rect],
+    ) {
+        self.push(Command::ClearUnorderedAccessViewF32 {
+            view_gpu_handle_in_current_heap,
+            view_cpu_handle,
+            resource: ObjectId::from(resource.as_ref()),
+            values: values.into(),
+            rects: rects.to_vec(),
+        });
+    }
+
+    fn clear_unordered_access_view_u32(
+        &self,
+        view_gpu_handle_in_current_heap: GpuDescriptorHandle,
+        view_cpu_handle: CpuDescriptorHandle,
+        resource: impl AsRef<Resource>,
+        values: impl Into<[u32; 4]>,
+        rects: &[Rect],
+    ) {
+        self.push(Command::ClearUnorderedAccessViewU32 {
+            view_gpu_handle_in_current_heap,
+            view_cpu_handle,
+            resource: ObjectId::from(resource.as_ref()),
+            values: values.into(),
+            rects: rects.to_vec(),
+        });
+    }
+
+    fn copy_buffer_region(
+        &self,
+        dst_buffer: impl AsRef<Resource>,
+        dst_offset: u64,
+        src_buffer: impl AsRef<Resource>,
+        src_offset: u64,
+        num_bytes: u64,
+    ) {
+        self.push(Command::CopyBufferRegion {
+            dst_buffer: ObjectId::from(dst_buffer.as_ref()),
+            dst_offset,
+            src_buffer: ObjectId::from(src_buffer.as_ref()),
+            src_offset,
+            num_bytes,
+        });
+    }
+
+    fn copy_resource(
+        &self,
+        dst_resource: impl AsRef<Resource>,
+        src_resource: impl AsRef<Resource>,
+    ) {
+        self.push(Command::CopyResource {
+            dst_resource: ObjectId::from(dst_resource.as_ref()),
+            src_resource: ObjectId::from(src_resource.as_ref()),
+        });
+    }
+
+    fn copy_texture_region(
+        &self,
+        dst: &TextureCopyLocation<'_>,
+        dst_x: u32,
+        dst_y: u32,
+        dst_z: u32,
+        src: &TextureCopyLocation<'_>,
+        src_box: Option<&DxBox>,
+    ) {
+        self.push(Command::CopyTextureRegion {
+            dst: dst.into(),
+            dst_x,
+            dst_y,
+            dst_z,
+            src: src.into(),
+            src_box: src_box.copied(),
+        });
+    }
+
+    fn copy_tiles(
+        &self,
+        tiled_resource: impl AsRef<Resource>,
+        tile_region_start_coordinate: &TiledResourceCoordinate,
+        tile_region_size: &TileRegionSize,
+        buffer: impl AsRef<Resource>,
+        buffer_start_offset: u64,
+        flags: TileCopyFlags,
+    ) {
+        self.push(Command::CopyTiles {
+            tiled_resource: ObjectId::from(tiled_resource.as_ref()),
+            tile_region_start_coordinate: *tile_region_start_coordinate,
+            tile_region_size: *tile_region_size,
+            buffer: ObjectId::from(buffer.as_ref()),
+            buffer_start_offset,
+            flags,
+        });
+    }
+
+    fn discard_resource(&self, resource: impl AsRef<Resource>, region: Option<&DiscardRegion<'_>>) {
+        self.push(Command::DiscardResource {
+            resource: ObjectId::from(resource.as_ref()),
+            region: region.map(RecordedDiscardRegion::from),
+        });
+    }
+
+    fn dispatch(
+        &self,
+        thread_group_count_x: u32,
+        thread_group_count_y: u32,
+        thread_group_count_z: u32,
+    ) {
+        self.push(Command::Dispatch {
+            thread_group_count_x,
+            thread_group_count_y,
+            thread_group_count_z,
+        });
+    }
+
+    fn draw_indexed_instanced(
+        &self,
+        index_count_per_instance: u32,
+        instance_count: u32,
+        start_index_location: u32,
+        base_vertex_location: i32,
+        start_instance_location: u32,
+    ) {
+        self.push(Command::DrawIndexedInstanced {
+            index_count_per_instance,
+            instance_count,
+            start_index_location,
+            base_vertex_location,
+            start_instance_location,
+        });
+    }
+
+    fn draw_instanced(
+        &self,
+        vertex_count_per_instance: u32,
+        instance_count: u32,
+        start_vertex_location: u32,
+        start_instance_location: u32,
+    ) {
+        self.push(Command::DrawInstanced {
+            vertex_count_per_instance,
+            instance_count,
+            start_vertex_location,
+            start_instance_location,
+        });
+    }
+
+    #[cfg(feature = "pix")]
+    fn end_event(&self) {
+        self.push(Command::EndEvent);
+    }
+
+    fn end_query(&self, query_heap: impl AsRef<QueryHeap>, r#type: QueryType, index: u32) {
+        self.push(Command::EndQuery {
+            query_heap: ObjectId::from(query_heap.as_ref()),
+            r#type,
+            index,
+        });
+    }
+
+    fn execute_bundle(&self, command_list: impl AsRef<GraphicsCommandList>) {
+        self.push(Command::ExecuteBundle(ObjectId::from(
+            command_list.as_ref(),
+        )));
+    }
+
+    fn execute_indirect<'a>(
+        &self,
+        command_signature: impl AsRef<CommandSignature>,
+        max_command_count: u32,
+        argument_buffer: impl AsRef<Resource>,
+        argument_buffer_offset: u64,
+        count_buffer: impl Into<Option<&'a Resource>>,
+        count_buffer_offset: u64,
+    ) {
+        self.push(Command::ExecuteIndirect {
+            command_signature: ObjectId::from(command_signature.as_ref()),
+            max_command_count,
+            argument_buffer: ObjectId::from(argument_buffer.as_ref()),
+            argument_buffer_offset,
+            count_buffer: count_buffer.into().map(ObjectId::from),
+            count_buffer_offset,
+        });
+    }
+
+    fn ia_set_index_buffer(&self, view: Option<&IndexBufferView>) {
+        self.push(Command::IaSetIndexBuffer(view.copied()));
+    }
+
+    fn ia_set_primitive_topology(&self, topology: PrimitiveTopology) {
+        self.push(Command::IaSetPrimitiveTopology(topology));
+    }
+
+    fn ia_set_vertex_buffers(&self, slot: u32, buffers: &[VertexBufferView]) {
+        self.push(Command::IaSetVertexBuffers {
+            slot,
+            buffers: buffers.to_vec(),
+        });
+    }
+
+    fn om_set_blend_factor(&self, blend_factor: Option<[f32; 4]>) {
+        self.push(Command::OmSetBlendFactor(blend_factor));
+    }
+
+    fn om_set_render_targets(
+        &self,
+        render_targets: &[CpuDescriptorHandle],
+        rts_single_handle_to_descriptor_range: bool,
+        depth_stencil: Option<CpuDescriptorHandle>,
+    ) {
+        self.push(Command::OmSetRenderTargets {
+            render_targets: render_targets.to_vec(),
+            rts_single_handle_to_descriptor_range,
+            depth_stencil,
+        });
+    }
+
+    fn om_set_stencil_ref(&self, stencil_ref: u32) {
+        self.push(Command::OmSetStencilRef(stencil_ref));
+    }
+
+    fn resolve_query_data(
+        &self,
+        query_heap: impl AsRef<QueryHeap>,
+        r#type: QueryType,
+        range: Range<u32>,
+        dst_buffer: impl AsRef<Resource>,
+        aligned_dst_buffer_offset: u64,
+    ) {
+        self.push(Command::ResolveQueryData {
+            query_heap: ObjectId::from(query_heap.as_ref()),
+            r#type,
+            range,
+            dst_buffer: ObjectId::from(dst_buffer.as_ref()),
+            aligned_dst_buffer_offset,
+        });
+    }
+
+    fn resolve_subresource(
+        &self,
+        dst_resource: impl AsRef<Resource>,
+        dst_subresource: u32,
+        src_resource: impl AsRef<Resource>,
+        src_subresource: u32,
+        format: Format,
+    ) {
+        self.push(Command::ResolveSubresource {
+            dst_resource: ObjectId::from(dst_resource.as_ref()),
+            dst_subresource,
+            src_resource: ObjectId::from(src_resource.as_ref()),
+            src_subresource,
+            format,
+        });
+    }
+
+    fn resource_barrier(&self, barriers: &[ResourceBarrier<'_>]) {
+        self.push(Command::ResourceBarrier(
+            barriers.iter().map(RecordedBarrier::from).collect(),
+        ));
+    }
+
+    fn rs_set_scissor_rects(&self, rects: &[Rect]) {
+        self.push(Command::RsSetScissorRects(rects.to_vec()));
+    }
+
+    fn rs_set_viewports(&self, viewport: &[Viewport]) {
+        self.push(Command::RsSetViewports(viewport.to_vec()));
+    }
+
+    fn set_compute_root_32bit_constant<T: Copy>(
+        &self,
+        root_parameter_index: u32,
+        src_data: T,
+        dest_offset_in_32bit_values: u32,
+    ) {
+        self.set_compute_root_32bit_constants(
+            root_parameter_index,
+            &[src_data],
+            dest_offset_in_32bit_values,
+        );
+    }
+
+    fn set_compute_root_32bit_constants<T: Copy>(
+        &self,
+        root_parameter_index: u32,
+        src_data: &[T],
+        dest_offset_in_32bit_values: u32,
+    ) {
+        self.push(Command::SetComputeRoot32BitConstants {
+            root_parameter_index,
+            src_data: constants_to_bits(src_data),
+            dest_offset_in_32bit_values,
+        });
+    }
+
+    fn set_compute_root_constant_buffer_view(
+        &self,
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    ) {
+        self.push(Command::SetComputeRootConstantBufferView {
+            root_parameter_index,
+            buffer_location,
+        });
+    }
+
+    fn set_compute_root_descriptor_table(
+        &self,
+        root_parameter_index: u32,
+        base_descriptor: GpuDescriptorHandle,
+    ) {
+        self.push(Command::SetComputeRootDescriptorTable {
+            root_parameter_index,
+            base_descriptor,
+        });
+    }
+
+    fn set_compute_root_shader_resource_view(
+        &self,
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    ) {
+        self.push(Command::SetComputeRootShaderResourceView {
+            root_parameter_index,
+            buffer_location,
+        });
+    }
+
+    fn set_compute_root_signature<'a>(&self, root_signature: impl Into<Option<&'a RootSignature>>) {
+        self.push(Command::SetComputeRootSignature(
+            root_signature.into().map(ObjectId::from),
+        ));
+    }
+
+    fn set_compute_root_unordered_access_view(
+        &self,
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    ) {
+        self.push(Command::SetComputeRootUnorderedAccessView {
+            root_parameter_index,
+            buffer_location,
+        });
+    }
+
+    fn set_descriptor_heaps(&self, descriptor_heaps: &[Option<DescriptorHeap>]) {
+        self.push(Command::SetDescriptorHeaps(
+            descriptor_heaps
+                .iter()
+                .map(|heap| heap.as_ref().map(ObjectId::from))
+                .collect(),
+        ));
+    }
+
+    fn set_graphics_root_32bit_constant<T: Copy>(
+        &self,
+        root_parameter_index: u32,
+        src_data: T,
+        dest_offset_in_32bit_values: u32,
+    ) {
+        self.set_graphics_root_32bit_constants(
+            root_parameter_index,
+            &[src_data],
+            dest_offset_in_32bit_values,
+        );
+    }
+
+    fn set_graphics_root_32bit_constants<T: Copy>(
+        &self,
+        root_parameter_index: u32,
+        src_data: &[T],
+        dest_offset_in_32bit_values: u32,
+    ) {
+        self.push(Command::SetGraphicsRoot32BitConstants {
+            root_parameter_index,
+            src_data: constants_to_bits(src_data),
+            dest_offset_in_32bit_values,
+        });
+    }
+
+    fn set_graphics_root_constant_buffer_view(
+        &self,
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    ) {
+        self.push(Command::SetGraphicsRootConstantBufferView {
+            root_parameter_index,
+            buffer_location,
+        });
+    }
+
+    fn set_graphics_root_descriptor_table(
+        &self,
+        root_parameter_index: u32,
+        base_descriptor: GpuDescriptorHandle,
+    ) {
+        self.push(Command::SetGraphicsRootDescriptorTable {
+            root_parameter_index,
+            base_descriptor,
+        });
+    }
+
+    fn set_graphics_root_shader_resource_view(
+        &self,
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    ) {
+        self.push(Command::SetGraphicsRootShaderResourceView {
+            root_parameter_index,
+            buffer_location,
+        });
+    }
+
+    fn set_graphics_root_signature<'a>(
+        &self,
+        root_signature: impl Into<Option<&'a RootSignature>>,
+    ) {
+        self.push(Command::SetGraphicsRootSignature(
+            root_signature.into().map(ObjectId::from),
+        ));
+    }
+
+    fn set_graphics_root_unordered_access_view(
+        &self,
+        root_parameter_index: u32,
+        buffer_location: GpuVirtualAddress,
+    ) {
+        self.push(Command::SetGraphicsRootUnorderedAccessView {
+            root_parameter_index,
+            buffer_location,
+        });
+    }
+
+    #[cfg(feature = "pix")]
+    fn set_marker(&self, color: impl Into<u64>, label: impl AsRef<std::ffi::CStr>) {
+        self.push(Command::SetMarker {
+            color: color.into(),
+            label: label.as_ref().to_owned(),
+        });
+    }
+
+    fn set_pipeline_state(&self, pso: impl AsRef<PipelineState>) {
+        self.push(Command::SetPipelineState(ObjectId::from(pso.as_ref())));
+    }
+
+    fn set_predication<'a>(
+        &self,
+        buffer: impl Into<Option<&'a Resource>>,
+        aligned_buffer_offset: u64,
+        operation: PredicationOp,
+    ) {
+        self.push(Command::SetPredication {
+            buffer: buffer.into().map(ObjectId::from),
+            aligned_buffer_offset,
+            operation,
+        });
+    }
+
+    fn so_set_targets(&self, start_slot: u32, views: Option<&[StreamOutputBufferView]>) {
+        self.push(Command::SoSetTargets {
+            start_slot,
+            views: views.map(|views| views.to_vec()),
+        });
+    }
+}
+
+/// A resource that is never dereferenced, released or cloned, for tests that only compare identities.
+#[cfg(test)]
+pub(crate) fn detached_resource(address: usize) -> std::mem::ManuallyDrop<Resource> {
+    std::mem::ManuallyDrop::new(Resource(unsafe {
+        ID3D12Resource::from_raw(address as *mut std::ffi::c_void)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record_pass(recorder: &impl CommandRecorder, rtv: CpuDescriptorHandle) {
+        recorder.om_set_render_targets(&[rtv], false, None);
+        recorder.clear_render_target_view(rtv, [0.0, 0.0, 0.0, 1.0], &[]);
+        recorder.rs_set_viewports(&[Viewport::from_size((1280.0, 720.0))]);
+        recorder.ia_set_primitive_topology(PrimitiveTopology::Triangle);
+        recorder.set_graphics_root_32bit_constant(0, 1.0f32, 0);
+        recorder.set_graphics_root_32bit_constants(0, &[2u32, 3], 1);
+        recorder.set_graphics_root_descriptor_table(1, GpuDescriptorHandle::default());
+        recorder.draw_instanced(3, 1, 0, 0);
+        recorder.dispatch(8, 8, 1);
+    }
+
+    #[test]
+    fn in_memory_recorder_test() {
+        let recorder = InMemoryRecorder::new();
+        let rtv = CpuDescriptorHandle::default();
+
+        record_pass(&recorder, rtv);
+
+        assert_eq!(recorder.len(), 9);
+        assert_eq!(
+            recorder.commands()[4..],
+            [
+                Command::SetGraphicsRoot32BitConstants {
+                    root_parameter_index: 0,
+                    src_data: vec![1.0f32.to_bits()],
+                    dest_offset_in_32bit_values: 0,
+                },
+                Command::SetGraphicsRoot32BitConstants {
+                    root_parameter_index: 0,
+                    src_data: vec![2, 3],
+                    dest_offset_in_32bit_values: 1,
+                },
+                Command::SetGraphicsRootDescriptorTable {
+                    root_parameter_index: 1,
+                    base_descriptor: GpuDescriptorHandle::default(),
+                },
+                Command::DrawInstanced {
+                    vertex_count_per_instance: 3,
+                    instance_count: 1,
+                    start_vertex_location: 0,
+                    start_instance_location: 0,
+                },
+                Command::Dispatch {
+                    thread_group_count_x: 8,
+                    thread_group_count_y: 8,
+                    thread_group_count_z: 1,
+                },
+            ]
+        );
+
+        let commands = recorder.take_commands();
+        assert!(recorder.is_empty());
+        assert_eq!(
+            commands[0],
+            Command::OmSetRenderTargets {
+                render_targets: vec![rtv],
+                rts_single_handle_to_descriptor_range: false,
+                depth_stencil: None,
+            }
+        );
+    }
+
+    #[test]
+    fn copy_commands_test() {
+        let recorder = InMemoryRecorder::new();
+        let dst = detached_resource(0x1000);
+        let src = detached_resource(0x2000);
+
+        recorder.copy_buffer_region(&*dst, 16, &*src, 0, 256);
+        recorder.copy_resource(&*dst, &*src);
+        recorder.copy_texture_region(
+            &TextureCopyLocation::subresource(&*dst, 1),
+            0,
+            0,
+            0,
+            &TextureCopyLocation::subresource(&*src, 2),
+            None,
+        );
+        recorder.resource_barrier(&[
+            ResourceBarrier::transition(
+                &dst,
+                ResourceStates::CopyDest,
+                ResourceStates::PixelShaderResource,
+                Some(1),
+            ),
+            ResourceBarrier::aliasing(&dst, &src),
+            ResourceBarrier::uav(&src),
+        ]);
+
+        let dst = ObjectId::from(&*dst);
+        let src = ObjectId::from(&*src);
+        assert_ne!(dst, src);
+        assert_eq!(
+            recorder.take_commands(),
+            [
+                Command::CopyBufferRegion {
+                    dst_buffer: dst,
+                    dst_offset: 16,
+                    src_buffer: src,
+                    src_offset: 0,
+                    num_bytes: 256,
+                },
+                Command::CopyResource {
+                    dst_resource: dst,
+                    src_resource: src,
+                },
+                Command::CopyTextureRegion {
+                    dst: RecordedCopyLocation::Subresource {
+                        resource: Some(dst),
+                        index: 1,
+                    },
+                    dst_x: 0,
+                    dst_y: 0,
+                    dst_z: 0,
+                    src: RecordedCopyLocation::Subresource {
+                        resource: Some(src),
+                        index: 2,
+                    },
+                    src_box: None,
+                },
+                Command::ResourceBarrier(vec![
+                    RecordedBarrier::Transition {
+                        resource: Some(dst),
+                        subresource: Some(1),
+                        before: ResourceStates::CopyDest,
+                        after: ResourceStates::PixelShaderResource,
+                        flags: ResourceBarrierFlags::empty(),
+                    },
+                    RecordedBarrier::Aliasing {
+                        before: Some(dst),
+                        after: Some(src),
+                        flags: ResourceBarrierFlags::empty(),
+                    },
+                    RecordedBarrier::Uav {
+                        resource: Some(src),
+                        flags: ResourceBarrierFlags::empty(),
+                    },
+                ]),
+            ]
+        );
+    }
+}
@@ -6,6 +6,7 @@ use super::*;
 
 conv_enum!(AddressMode to D3D12_TEXTURE_ADDRESS_MODE);
 conv_enum!(AlphaMode to DXGI_ALPHA_MODE);
+conv_enum!(BarrierLayout to D3D12_BARRIER_LAYOUT);
 conv_enum!(Blend to D3D12_BLEND);
 conv_enum!(BlendOp to D3D12_BLEND_OP);
 conv_enum!(BorderColor to D3D12_STATIC_BORDER_COLOR);
@@ -20,6 +21,8 @@ conv_enum!(CrossNodeSharingTier to D3D12_CROSS_NODE_SHARING_TIER);
 conv_enum!(CullMode to D3D12_CULL_MODE);
 conv_enum!(DescriptorHeapType to D3D12_DESCRIPTOR_HEAP_TYPE);
 conv_enum!(DescriptorRangeType to D3D12_DESCRIPTOR_RANGE_TYPE);
+conv_enum!(DispatchMode to D3D12_DISPATCH_MODE);
+conv_enum!(ExecuteIndirectTier to D3D12_EXECUTE_INDIRECT_TIER);
 conv_enum!(FeatureLevel to D3D_FEATURE_LEVEL);
 conv_enum!(FeatureType to D3D12_FEATURE);
 conv_enum!(FillMode to D3D12_FILL_MODE);
@@ -28,7 +31,10 @@ conv_enum!(Format to DXGI_FORMAT);
 conv_enum!(GpuPreference to DXGI_GPU_PREFERENCE);
 conv_enum!(HeapSerializationTier to D3D12_HEAP_SERIALIZATION_TIER);
 conv_enum!(HeapType to D3D12_HEAP_TYPE);
+conv_enum!(HitGroupType to D3D12_HIT_GROUP_TYPE);
+conv_enum!(IncludeType to D3D_INCLUDE_TYPE);
 conv_enum!(IndexBufferStripCutValue to D3D12_INDEX_BUFFER_STRIP_CUT_VALUE);
+conv_enum!(LineRasterizationMode to D3D12_LINE_RASTERIZATION_MODE);
 conv_enum!(LogicOp to D3D12_LOGIC_OP);
 conv_enum!(MemoryPool to D3D12_MEMORY_POOL);
 conv_enum!(MeshShaderTier to D3D12_MESH_SHADER_TIER);
@@ -38,15 +44,22 @@ conv_enum!(MessageSeverity to D3D12_MESSAGE_SEVERITY);
 conv_enum!(MinPrecision to D3D_MIN_PRECISION);
 conv_enum!(MinPrecisionSupport to D3D12_SHADER_MIN_PRECISION_SUPPORT);
 conv_enum!(PipelinePrimitiveTopology to D3D12_PRIMITIVE_TOPOLOGY_TYPE);
+conv_enum!(PipelineStateSubobjectType to D3D12_PIPELINE_STATE_SUBOBJECT_TYPE);
 conv_enum!(PredicationOp to D3D12_PREDICATION_OP);
 conv_enum!(Primitive to D3D_PRIMITIVE);
 conv_enum!(PrimitiveTopology to D3D_PRIMITIVE_TOPOLOGY);
 conv_enum!(ProgrammableSamplePositionsTier to D3D12_PROGRAMMABLE_SAMPLE_POSITIONS_TIER);
+conv_enum!(ProtectedSessionStatus to D3D12_PROTECTED_SESSION_STATUS);
 conv_enum!(QueryHeapType to D3D12_QUERY_HEAP_TYPE);
 conv_enum!(QueryType to D3D12_QUERY_TYPE);
+conv_enum!(RaytracingAccelerationStructureCopyMode to D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE);
+conv_enum!(RaytracingAccelerationStructurePostbuildInfoType to D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_TYPE);
+conv_enum!(RaytracingAccelerationStructureType to D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE);
+conv_enum!(RaytracingGeometryType to D3D12_RAYTRACING_GEOMETRY_TYPE);
 conv_enum!(RaytracingTier to D3D12_RAYTRACING_TIER);
 conv_enum!(RegisterComponentType to D3D_REGISTER_COMPONENT_TYPE);
 conv_enum!(RenderPassTier to D3D12_RENDER_PASS_TIER);
+conv_enum!(ResolveMode to D3D12_RESOLVE_MODE);
 conv_enum!(ResourceBindingTier to D3D12_RESOURCE_BINDING_TIER);
 conv_enum!(ResourceDimension to D3D12_RESOURCE_DIMENSION);
 conv_enum!(ResourceHeapTier to D3D12_RESOURCE_HEAP_TIER);
@@ -63,7 +76,11 @@ conv_enum!(ShaderVariableClass to D3D_SHADER_VARIABLE_CLASS);
 conv_enum!(ShaderVariableType to D3D_SHADER_VARIABLE_TYPE);
 conv_enum!(ShaderVarName to D3D_NAME);
 conv_enum!(ShaderVisibility to D3D12_SHADER_VISIBILITY);
+conv_enum!(ShadingRate to D3D12_SHADING_RATE);
+conv_enum!(ShadingRateCombiner to D3D12_SHADING_RATE_COMBINER);
 conv_enum!(SharedResourceCompatibilityTier to D3D12_SHARED_RESOURCE_COMPATIBILITY_TIER);
+conv_enum!(StateObjectType to D3D12_STATE_OBJECT_TYPE);
+conv_enum!(StateSubobjectType to D3D12_STATE_SUBOBJECT_TYPE);
 conv_enum!(StencilOp to D3D12_STENCIL_OP);
 conv_enum!(SrvDimension to D3D_SRV_DIMENSION);
 conv_enum!(SwapEffect to DXGI_SWAP_EFFECT);
@@ -75,6 +92,8 @@ conv_enum!(TiledResourcesTier to D3D12_TILED_RESOURCES_TIER);
 conv_enum!(VariableShadingRateTier to D3D12_VARIABLE_SHADING_RATE_TIER);
 conv_enum!(ViewInstancingTier to D3D12_VIEW_INSTANCING_TIER);
 conv_enum!(WaveMmaTier to D3D12_WAVE_MMA_TIER);
+conv_enum!(WorkGraphsTier to D3D12_WORK_GRAPHS_TIER);
+conv_enum!(WriteBufferImmediateMode to D3D12_WRITEBUFFERIMMEDIATE_MODE);
 
 impl CommandQueuePriority {
     #[inline]
@@ -4,6 +4,8 @@ use crate::conv_flags;
 
 use super::*;
 
+conv_flags!(BarrierAccess to D3D12_BARRIER_ACCESS);
+conv_flags!(BarrierSync to D3D12_BARRIER_SYNC);
 conv_flags!(BufferSrvFlags to D3D12_BUFFER_SRV_FLAGS);
 conv_flags!(BufferUavFlags to D3D12_BUFFER_UAV_FLAGS);
 conv_flags!(CacheSupportFlags to D3D12_SHADER_CACHE_SUPPORT_FLAGS);
@@ -27,16 +29,26 @@ conv_flags!(MultisampleQualityLevelFlags to D3D12_MULTISAMPLE_QUALITY_LEVEL_FLAG
 conv_flags!(PipelineStateFlags to D3D12_PIPELINE_STATE_FLAGS);
 conv_flags!(PresentFlags to DXGI_PRESENT);
 conv_flags!(ProtectedResourceSessionSupportFlags to D3D12_PROTECTED_RESOURCE_SESSION_SUPPORT_FLAGS);
+conv_flags!(RaytracingAccelerationStructureBuildFlags to D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS);
+conv_flags!(RaytracingGeometryFlags to D3D12_RAYTRACING_GEOMETRY_FLAGS);
+conv_flags!(RaytracingInstanceFlags to D3D12_RAYTRACING_INSTANCE_FLAGS);
+conv_flags!(RaytracingPipelineFlags to D3D12_RAYTRACING_PIPELINE_FLAGS);
+conv_flags!(RenderPassFlags to D3D12_RENDER_PASS_FLAGS);
 conv_flags!(ResourceBarrierFlags to D3D12_RESOURCE_BARRIER_FLAGS);
 conv_flags!(ResourceFlags to D3D12_RESOURCE_FLAGS);
 conv_flags!(ResourceStates to D3D12_RESOURCE_STATES);
 conv_flags!(RootSignatureFlags to D3D12_ROOT_SIGNATURE_FLAGS);
+conv_flags!(SetWorkGraphFlags to D3D12_SET_WORK_GRAPH_FLAGS);
 conv_flags!(ShaderVariableFlags to D3D_SHADER_VARIABLE_FLAGS);
+conv_flags!(StateObjectFlags to D3D12_STATE_OBJECT_FLAGS);
 conv_flags!(SwapchainColorSpaceSupportFlag to DXGI_SWAP_CHAIN_COLOR_SPACE_SUPPORT_FLAG);
 conv_flags!(SwapchainFlags to DXGI_SWAP_CHAIN_FLAG);
+conv_flags!(TextureBarrierFlags to D3D12_TEXTURE_BARRIER_FLAGS);
 conv_flags!(TileCopyFlags to D3D12_TILE_COPY_FLAGS);
 conv_flags!(TileRangeFlags to D3D12_TILE_RANGE_FLAGS);
+conv_flags!(ViewInstancingFlags to D3D12_VIEW_INSTANCING_FLAGS);
 conv_flags!(WindowAssociationFlags to DXGI_MWA_FLAGS);
+conv_flags!(WorkGraphFlags to D3D12_WORK_GRAPH_FLAGS);
 
 impl CbufferFlags {
     #[inline]
@@ -52,6 +64,38 @@ impl From<u32> for CbufferFlags {
     }
 }
 
+impl CompileFlags {
+    #[inline]
+    pub(crate) fn as_raw(&self) -> u32 {
+        self.bits()
+    }
+}
+
+impl CompileFlags {
+    /// Bits of the [`OptimizationLevel`].
+    pub(crate) const OPTIMIZATION_LEVEL_BITS: u32 =
+        OptimizationLevel::Level0 as u32 | OptimizationLevel::Level3 as u32;
+
+    #[inline]
+    pub(crate) fn without_optimization_level(self) -> Self {
+        Self::from_bits_retain(self.bits() & !Self::OPTIMIZATION_LEVEL_BITS)
+    }
+}
+
+impl From<OptimizationLevel> for CompileFlags {
+    #[inline]
+    fn from(value: OptimizationLevel) -> Self {
+        Self::from_bits_retain(value as u32)
+    }
+}
+
+impl CompileEffectFlags {
+    #[inline]
+    pub(crate) fn as_raw(&self) -> u32 {
+        self.bits()
+    }
+}
+
 impl ShaderRequirements {
     #[inline]
     pub(crate) fn as_raw(&self) -> u64 {
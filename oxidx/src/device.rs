@@ -2,18 +2,28 @@ use std::{ffi::CStr, ops::Range};
 
 use windows::{
     core::{Interface, PCWSTR},
-    Win32::Graphics::Direct3D12::{ID3D12Device, ID3D12DeviceChild},
+    Win32::Graphics::Direct3D12::{
+        ID3D12Device, ID3D12Device1, ID3D12Device10, ID3D12Device2, ID3D12Device4, ID3D12Device5, ID3D12DeviceChild, ID3D12Resource,
+        D3D12_PIPELINE_STATE_STREAM_DESC, D3D12_PROTECTED_RESOURCE_SESSION_DESC, D3D12_RESOURCE_DESC1,
+    },
 };
 
 use crate::{
+    acceleration_structure::{AccelerationStructurePrebuildInfo, BuildAccelerationStructureInputs},
     create_type,
     dx::{
         CommandAllocator, CommandQueue, CommandSignature, DescriptorHeap, DeviceChild, Fence,
-        GraphicsCommandList, Heap, Pageable, PipelineState, QueryHeap, Resource, RootSignature,
+        GraphicsCommandList, Heap, Pageable, PipelineLibrary, PipelineState, ProtectedResourceSession, QueryHeap, Resource, RootSignature,
+        StateObject,
     },
     error::DxError,
     impl_interface,
-    types::*,
+    pipeline_stream::PipelineStateStream,
+    state_object::StateObjectDesc,
+    types::{
+        features::{Options12Feature, Options21Feature, Options5Feature, Options7Feature},
+        *,
+    },
     FeatureObject,
 };
 
@@ -34,8 +44,48 @@ create_type! {
     Device wrap ID3D12Device
 }
 
+create_type! {
+    /// Adds the ability to create pipeline libraries.
+    ///
+    /// For more information: [`ID3D12Device1 interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12device1)
+    Device1 wrap ID3D12Device1; decorator for Device
+}
+
+create_type! {
+    /// Adds the ability to create pipeline state objects from pipeline state stream descriptions.
+    ///
+    /// For more information: [`ID3D12Device2 interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12device2)
+    Device2 wrap ID3D12Device2; decorator for Device1, Device
+}
+
+create_type! {
+    /// Adds the ability to create protected resource sessions.
+    ///
+    /// For more information: [`ID3D12Device4 interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12device4)
+    Device4 wrap ID3D12Device4; decorator for Device2, Device1, Device
+}
+
+create_type! {
+    /// Adds the ability to create raytracing state objects.
+    ///
+    /// For more information: [`ID3D12Device5 interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12device5)
+    Device5 wrap ID3D12Device5; decorator for Device4, Device2, Device1, Device
+}
+
+create_type! {
+    /// Adds the ability to create resources with an initial barrier layout, for use with enhanced barriers.
+    ///
+    /// For more information: [`ID3D12Device10 interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12device10)
+    Device10 wrap ID3D12Device10; decorator for Device5, Device4, Device2, Device1, Device
+}
+
 impl_interface! {
-    Device;
+    Device,
+    Device1,
+    Device2,
+    Device4,
+    Device5,
+    Device10;
 
     /// Gets information about the features that are supported by the current graphics driver.
     ///
@@ -466,9 +516,10 @@ impl_interface! {
         blob: &[u8],
     ) -> Result<RootSignature, DxError> {
         unsafe {
-            let res = self.0.CreateRootSignature(node_mask, blob).map_err(DxError::from)?;
+            let res = RootSignature(self.0.CreateRootSignature(node_mask, blob).map_err(DxError::from)?);
+            res.set_content_hash(blob);
 
-            Ok(RootSignature(res))
+            Ok(res)
         }
     }
 
@@ -795,3 +846,212 @@ impl_interface! {
         }
     }
 }
+
+impl_interface! {
+    Device,
+    Device1,
+    Device2,
+    Device4,
+    Device5,
+    Device10;
+
+    /// Queries [`Options7Feature`] for the level of mesh and amplification shader support.
+    pub fn mesh_shader_tier(&self) -> Result<MeshShaderTier, DxError> {
+        let mut feature = Options7Feature::default();
+        self.check_feature_support(&mut feature)?;
+
+        Ok(feature.mesh_shader_tier())
+    }
+
+    /// Queries [`Options5Feature`] for the level of raytracing support.
+    pub fn raytracing_tier(&self) -> Result<RaytracingTier, DxError> {
+        let mut feature = Options5Feature::default();
+        self.check_feature_support(&mut feature)?;
+
+        Ok(feature.raytracing_tier())
+    }
+
+    /// Queries [`Options21Feature`] for the level of work graphs support.
+    pub fn work_graphs_tier(&self) -> Result<WorkGraphsTier, DxError> {
+        let mut feature = Options21Feature::default();
+        self.check_feature_support(&mut feature)?;
+
+        Ok(feature.work_graphs_tier())
+    }
+
+    /// Queries [`Options12Feature`] for the support of enhanced barriers.
+    pub fn enhanced_barriers_supported(&self) -> Result<bool, DxError> {
+        let mut feature = Options12Feature::default();
+        self.check_feature_support(&mut feature)?;
+
+        Ok(feature.enhanced_barriers_supported())
+    }
+}
+
+impl_interface! {
+    Device1,
+    Device2,
+    Device4,
+    Device5,
+    Device10;
+
+    /// Creates a pipeline library from a blob previously returned by [`PipelineLibrary::serialize`].
+    /// An empty blob creates an empty library.
+    ///
+    /// Fails with [`DxError::AdapterNotFound`] or [`DxError::DriverVersionMismatch`] if the blob was created
+    /// on another adapter or driver.
+    ///
+    /// # Safety
+    ///
+    /// The library references `blob` without copying it, so `blob` must outlive the library and all its clones.
+    ///
+    /// For more information: [`ID3D12Device1::CreatePipelineLibrary method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12device1-createpipelinelibrary)
+    pub unsafe fn create_pipeline_library(&self, blob: &[u8]) -> Result<PipelineLibrary, DxError> {
+        unsafe {
+            let res = self.0.CreatePipelineLibrary(blob).map_err(DxError::from)?;
+
+            Ok(PipelineLibrary(res))
+        }
+    }
+}
+
+impl_interface! {
+    Device2,
+    Device4,
+    Device5,
+    Device10;
+
+    /// Creates a pipeline state object from a pipeline state stream description.
+    ///
+    /// For more information: [`ID3D12Device2::CreatePipelineState method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12device2-createpipelinestate)
+    pub fn create_pipeline_state(
+        &self,
+        desc: &PipelineStateStream<'_>,
+    ) -> Result<PipelineState, DxError> {
+        let (mut stream, size) = desc.encode();
+
+        let desc = D3D12_PIPELINE_STATE_STREAM_DESC {
+            SizeInBytes: size,
+            pPipelineStateSubobjectStream: stream.as_mut_ptr() as *mut _,
+        };
+
+        unsafe {
+            let res = self.0.CreatePipelineState(&desc).map_err(DxError::from)?;
+
+            Ok(PipelineState(res))
+        }
+    }
+}
+
+impl_interface! {
+    Device4,
+    Device5,
+    Device10;
+
+    /// Creates a session to monitor the validity of protected resources,
+    /// see [`GraphicsCommandList3::set_protected_resource_session`](crate::dx::GraphicsCommandList3::set_protected_resource_session).
+    ///
+    /// For more information: [`ID3D12Device4::CreateProtectedResourceSession method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12device4-createprotectedresourcesession)
+    pub fn create_protected_resource_session(
+        &self,
+        node_mask: u32,
+    ) -> Result<ProtectedResourceSession, DxError> {
+        let desc = D3D12_PROTECTED_RESOURCE_SESSION_DESC {
+            NodeMask: node_mask,
+            ..Default::default()
+        };
+
+        unsafe {
+            let res = self.0.CreateProtectedResourceSession(&desc).map_err(DxError::from)?;
+
+            Ok(ProtectedResourceSession(res))
+        }
+    }
+}
+
+impl_interface! {
+    Device5,
+    Device10;
+
+    /// Creates a state object, such as a raytracing pipeline or a collection of raytracing shaders.
+    ///
+    /// For more information: [`ID3D12Device5::CreateStateObject method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12device5-createstateobject)
+    pub fn create_state_object(&self, desc: &StateObjectDesc<'_>) -> Result<StateObject, DxError> {
+        let desc = desc.encode();
+
+        unsafe {
+            let res = self.0.CreateStateObject(desc.as_raw()).map_err(DxError::from)?;
+
+            Ok(StateObject(res))
+        }
+    }
+
+    /// Queries the sizes of the result and scratch buffers of an acceleration structure build.
+    ///
+    /// For more information: [`ID3D12Device5::GetRaytracingAccelerationStructurePrebuildInfo method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12device5-getraytracingaccelerationstructureprebuildinfo)
+    pub fn get_raytracing_acceleration_structure_prebuild_info(
+        &self,
+        inputs: &BuildAccelerationStructureInputs<'_>,
+    ) -> AccelerationStructurePrebuildInfo {
+        unsafe {
+            let mut info = Default::default();
+            self.0.GetRaytracingAccelerationStructurePrebuildInfo(&inputs.0, &mut info);
+
+            AccelerationStructurePrebuildInfo(info)
+        }
+    }
+}
+
+impl_interface! {
+    Device10;
+
+    /// Creates both a resource and an implicit heap, with the resource starting in `initial_layout`
+    /// instead of a legacy resource state.
+    ///
+    /// `castable_formats` lists the formats the resource can be viewed as, in addition to its own format.
+    ///
+    /// For more information: [`ID3D12Device10::CreateCommittedResource3 method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12device10-createcommittedresource3)
+    pub fn create_committed_resource3(
+        &self,
+        heap_properties: &HeapProperties,
+        heap_flags: HeapFlags,
+        desc: &ResourceDesc,
+        initial_layout: BarrierLayout,
+        optimized_clear_value: Option<&ClearValue>,
+        castable_formats: &[Format],
+    ) -> Result<Resource, DxError> {
+        unsafe {
+            let clear_value = optimized_clear_value.as_ref().map(|c| &c.0 as *const _);
+            let castable_formats = castable_formats.iter().map(|f| f.as_raw()).collect::<Vec<_>>();
+
+            let desc = D3D12_RESOURCE_DESC1 {
+                Dimension: desc.0.Dimension,
+                Alignment: desc.0.Alignment,
+                Width: desc.0.Width,
+                Height: desc.0.Height,
+                DepthOrArraySize: desc.0.DepthOrArraySize,
+                MipLevels: desc.0.MipLevels,
+                Format: desc.0.Format,
+                SampleDesc: desc.0.SampleDesc,
+                Layout: desc.0.Layout,
+                Flags: desc.0.Flags,
+                SamplerFeedbackMipRegion: Default::default(),
+            };
+
+            let mut resource: Option<ID3D12Resource> = None;
+
+            self.0.CreateCommittedResource3(
+                &heap_properties.0,
+                heap_flags.as_raw(),
+                &desc,
+                initial_layout.as_raw(),
+                clear_value,
+                None,
+                (!castable_formats.is_empty()).then_some(castable_formats.as_slice()),
+                &mut resource,
+            ).map_err(DxError::from)?;
+
+            Ok(Resource(resource.unwrap_unchecked()))
+        }
+    }
+}
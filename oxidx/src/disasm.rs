@@ -0,0 +1,1532 @@
+use std::fmt::Write;
+
+use crate::{dxbc::DxbcContainer, error::DxError};
+
+/// Instruction statistics of a shader program.
+///
+/// The counts are derived from the token stream the same way the compiler fills the `STAT` part,
+/// so they match [`ShaderReflection`](crate::reflection::ShaderReflection) getters
+/// such as [`get_mov_instruction_count`](crate::reflection::ShaderReflection::get_mov_instruction_count).
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct InstructionCounts {
+    pub instruction_count: u32,
+    pub temp_register_count: u32,
+    pub temp_array_count: u32,
+    pub dcl_count: u32,
+    pub mov_instruction_count: u32,
+    pub movc_instruction_count: u32,
+    pub conversion_instruction_count: u32,
+    pub bitwise_instruction_count: u32,
+    pub emit_instruction_count: u32,
+    pub cut_instruction_count: u32,
+    pub texture_normal_instructions: u32,
+    pub texture_load_instructions: u32,
+    pub texture_comp_instructions: u32,
+    pub texture_bias_instructions: u32,
+    pub texture_gradient_instructions: u32,
+}
+
+impl InstructionCounts {
+    /// Counts instructions of a DXBC container or of a raw `SHEX`/`SHDR` token stream.
+    pub fn from_bytecode(bytecode: &[u8]) -> Result<Self, DxError> {
+        Disassembler::run(bytecode).map(|d| d.counts)
+    }
+}
+
+/// Disassembles a DXBC container or a raw `SHEX`/`SHDR` token stream in the format of `fxc /dumpbin`.
+pub fn disassemble(bytecode: &[u8]) -> Result<String, DxError> {
+    Disassembler::run(bytecode).map(|d| d.out)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Value {
+    Float,
+    Int,
+    Uint,
+    Double,
+    Untyped,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Class {
+    Declaration,
+    Phase,
+    Arithmetic,
+    Mov,
+    Movc,
+    Conversion,
+    Bitwise,
+    Emit,
+    Cut,
+    EmitThenCut,
+    TextureNormal,
+    TextureLoad,
+    TextureComp,
+    TextureBias,
+    TextureGradient,
+}
+
+use Class::*;
+use Value::*;
+
+/// Mnemonics, statistics classes and immediate value types of opcodes, indexed by opcode.
+#[rustfmt::skip]
+const OPCODES: &[(&str, Class, Value)] = &[
+    ("add", Arithmetic, Float),
+    ("and", Bitwise, Uint),
+    ("break", Arithmetic, Untyped),
+    ("breakc", Arithmetic, Untyped),
+    ("call", Arithmetic, Untyped),
+    ("callc", Arithmetic, Untyped),
+    ("case", Arithmetic, Int),
+    ("continue", Arithmetic, Untyped),
+    ("continuec", Arithmetic, Untyped),
+    ("cut", Cut, Untyped),
+    ("default", Arithmetic, Untyped),
+    ("deriv_rtx", Arithmetic, Float),
+    ("deriv_rty", Arithmetic, Float),
+    ("discard", Arithmetic, Untyped),
+    ("div", Arithmetic, Float),
+    ("dp2", Arithmetic, Float),
+    ("dp3", Arithmetic, Float),
+    ("dp4", Arithmetic, Float),
+    ("else", Arithmetic, Untyped),
+    ("emit", Emit, Untyped),
+    ("emit_then_cut", EmitThenCut, Untyped),
+    ("endif", Arithmetic, Untyped),
+    ("endloop", Arithmetic, Untyped),
+    ("endswitch", Arithmetic, Untyped),
+    ("eq", Arithmetic, Float),
+    ("exp", Arithmetic, Float),
+    ("frc", Arithmetic, Float),
+    ("ftoi", Conversion, Float),
+    ("ftou", Conversion, Float),
+    ("ge", Arithmetic, Float),
+    ("iadd", Arithmetic, Int),
+    ("if", Arithmetic, Untyped),
+    ("ieq", Arithmetic, Int),
+    ("ige", Arithmetic, Int),
+    ("ilt", Arithmetic, Int),
+    ("imad", Arithmetic, Int),
+    ("imax", Arithmetic, Int),
+    ("imin", Arithmetic, Int),
+    ("imul", Arithmetic, Int),
+    ("ine", Arithmetic, Int),
+    ("ineg", Arithmetic, Int),
+    ("ishl", Bitwise, Int),
+    ("ishr", Bitwise, Int),
+    ("itof", Conversion, Int),
+    ("label", Phase, Untyped),
+    ("ld", TextureLoad, Int),
+    ("ld_ms", TextureLoad, Int),
+    ("log", Arithmetic, Float),
+    ("loop", Arithmetic, Untyped),
+    ("lt", Arithmetic, Float),
+    ("mad", Arithmetic, Float),
+    ("min", Arithmetic, Float),
+    ("max", Arithmetic, Float),
+    ("customdata", Phase, Untyped),
+    ("mov", Mov, Untyped),
+    ("movc", Movc, Untyped),
+    ("mul", Arithmetic, Float),
+    ("ne", Arithmetic, Float),
+    ("nop", Arithmetic, Untyped),
+    ("not", Bitwise, Uint),
+    ("or", Bitwise, Uint),
+    ("resinfo", Arithmetic, Uint),
+    ("ret", Arithmetic, Untyped),
+    ("retc", Arithmetic, Untyped),
+    ("round_ne", Arithmetic, Float),
+    ("round_ni", Arithmetic, Float),
+    ("round_pi", Arithmetic, Float),
+    ("round_z", Arithmetic, Float),
+    ("rsq", Arithmetic, Float),
+    ("sample", TextureNormal, Float),
+    ("sample_c", TextureComp, Float),
+    ("sample_c_lz", TextureComp, Float),
+    ("sample_l", TextureNormal, Float),
+    ("sample_d", TextureGradient, Float),
+    ("sample_b", TextureBias, Float),
+    ("sqrt", Arithmetic, Float),
+    ("switch", Arithmetic, Int),
+    ("sincos", Arithmetic, Float),
+    ("udiv", Arithmetic, Uint),
+    ("ult", Arithmetic, Uint),
+    ("uge", Arithmetic, Uint),
+    ("umul", Arithmetic, Uint),
+    ("umad", Arithmetic, Uint),
+    ("umax", Arithmetic, Uint),
+    ("umin", Arithmetic, Uint),
+    ("ushr", Bitwise, Uint),
+    ("utof", Conversion, Uint),
+    ("xor", Bitwise, Uint),
+    ("dcl_resource", Declaration, Untyped),
+    ("dcl_constantbuffer", Declaration, Untyped),
+    ("dcl_sampler", Declaration, Untyped),
+    ("dcl_indexrange", Declaration, Untyped),
+    ("dcl_outputtopology", Declaration, Untyped),
+    ("dcl_inputprimitive", Declaration, Untyped),
+    ("dcl_maxout", Declaration, Untyped),
+    ("dcl_input", Declaration, Untyped),
+    ("dcl_input_sgv", Declaration, Untyped),
+    ("dcl_input_siv", Declaration, Untyped),
+    ("dcl_input_ps", Declaration, Untyped),
+    ("dcl_input_ps_sgv", Declaration, Untyped),
+    ("dcl_input_ps_siv", Declaration, Untyped),
+    ("dcl_output", Declaration, Untyped),
+    ("dcl_output_sgv", Declaration, Untyped),
+    ("dcl_output_siv", Declaration, Untyped),
+    ("dcl_temps", Declaration, Untyped),
+    ("dcl_indexableTemp", Declaration, Untyped),
+    ("dcl_globalFlags", Declaration, Untyped),
+    ("reserved", Arithmetic, Untyped),
+    ("lod", Arithmetic, Float),
+    ("gather4", TextureNormal, Float),
+    ("sample_pos", Arithmetic, Uint),
+    ("sample_info", Arithmetic, Uint),
+    ("reserved", Arithmetic, Untyped),
+    ("hs_decls", Phase, Untyped),
+    ("hs_control_point_phase", Phase, Untyped),
+    ("hs_fork_phase", Phase, Untyped),
+    ("hs_join_phase", Phase, Untyped),
+    ("emit_stream", Emit, Untyped),
+    ("cut_stream", Cut, Untyped),
+    ("emit_then_cut_stream", EmitThenCut, Untyped),
+    ("fcall", Arithmetic, Untyped),
+    ("bufinfo", Arithmetic, Uint),
+    ("deriv_rtx_coarse", Arithmetic, Float),
+    ("deriv_rtx_fine", Arithmetic, Float),
+    ("deriv_rty_coarse", Arithmetic, Float),
+    ("deriv_rty_fine", Arithmetic, Float),
+    ("gather4_c", TextureComp, Float),
+    ("gather4_po", TextureNormal, Float),
+    ("gather4_po_c", TextureComp, Float),
+    ("rcp", Arithmetic, Float),
+    ("f32tof16", Conversion, Float),
+    ("f16tof32", Conversion, Uint),
+    ("uaddc", Arithmetic, Uint),
+    ("usubb", Arithmetic, Uint),
+    ("countbits", Bitwise, Uint),
+    ("firstbit_hi", Bitwise, Uint),
+    ("firstbit_lo", Bitwise, Uint),
+    ("firstbit_shi", Bitwise, Int),
+    ("ubfe", Bitwise, Uint),
+    ("ibfe", Bitwise, Int),
+    ("bfi", Bitwise, Uint),
+    ("bfrev", Bitwise, Uint),
+    ("swapc", Arithmetic, Untyped),
+    ("dcl_stream", Declaration, Untyped),
+    ("dcl_function_body", Declaration, Untyped),
+    ("dcl_function_table", Declaration, Untyped),
+    ("dcl_interface", Declaration, Untyped),
+    ("dcl_input_control_point_count", Declaration, Untyped),
+    ("dcl_output_control_point_count", Declaration, Untyped),
+    ("dcl_tessellator_domain", Declaration, Untyped),
+    ("dcl_tessellator_partitioning", Declaration, Untyped),
+    ("dcl_tessellator_output_primitive", Declaration, Untyped),
+    ("dcl_hs_max_tessfactor", Declaration, Float),
+    ("dcl_hs_fork_phase_instance_count", Declaration, Untyped),
+    ("dcl_hs_join_phase_instance_count", Declaration, Untyped),
+    ("dcl_thread_group", Declaration, Untyped),
+    ("dcl_uav_typed", Declaration, Untyped),
+    ("dcl_uav_raw", Declaration, Untyped),
+    ("dcl_uav_structured", Declaration, Untyped),
+    ("dcl_tgsm_raw", Declaration, Untyped),
+    ("dcl_tgsm_structured", Declaration, Untyped),
+    ("dcl_resource_raw", Declaration, Untyped),
+    ("dcl_resource_structured", Declaration, Untyped),
+    ("ld_uav_typed", TextureLoad, Uint),
+    ("store_uav_typed", Arithmetic, Untyped),
+    ("ld_raw", TextureLoad, Uint),
+    ("store_raw", Arithmetic, Untyped),
+    ("ld_structured", TextureLoad, Uint),
+    ("store_structured", Arithmetic, Untyped),
+    ("atomic_and", Arithmetic, Uint),
+    ("atomic_or", Arithmetic, Uint),
+    ("atomic_xor", Arithmetic, Uint),
+    ("atomic_cmp_store", Arithmetic, Uint),
+    ("atomic_iadd", Arithmetic, Int),
+    ("atomic_imax", Arithmetic, Int),
+    ("atomic_imin", Arithmetic, Int),
+    ("atomic_umax", Arithmetic, Uint),
+    ("atomic_umin", Arithmetic, Uint),
+    ("imm_atomic_alloc", Arithmetic, Uint),
+    ("imm_atomic_consume", Arithmetic, Uint),
+    ("imm_atomic_iadd", Arithmetic, Int),
+    ("imm_atomic_and", Arithmetic, Uint),
+    ("imm_atomic_or", Arithmetic, Uint),
+    ("imm_atomic_xor", Arithmetic, Uint),
+    ("imm_atomic_exch", Arithmetic, Uint),
+    ("imm_atomic_cmp_exch", Arithmetic, Uint),
+    ("imm_atomic_imax", Arithmetic, Int),
+    ("imm_atomic_imin", Arithmetic, Int),
+    ("imm_atomic_umax", Arithmetic, Uint),
+    ("imm_atomic_umin", Arithmetic, Uint),
+    ("sync", Arithmetic, Untyped),
+    ("dadd", Arithmetic, Double),
+    ("dmax", Arithmetic, Double),
+    ("dmin", Arithmetic, Double),
+    ("dmul", Arithmetic, Double),
+    ("deq", Arithmetic, Double),
+    ("dge", Arithmetic, Double),
+    ("dlt", Arithmetic, Double),
+    ("dne", Arithmetic, Double),
+    ("dmov", Mov, Double),
+    ("dmovc", Movc, Double),
+    ("dtof", Conversion, Double),
+    ("ftod", Conversion, Float),
+    ("eval_snapped", Arithmetic, Float),
+    ("eval_sample_index", Arithmetic, Float),
+    ("eval_centroid", Arithmetic, Float),
+    ("dcl_gsinstances", Declaration, Untyped),
+    ("abort", Arithmetic, Untyped),
+    ("debug_break", Arithmetic, Untyped),
+    ("reserved", Arithmetic, Untyped),
+    ("ddiv", Arithmetic, Double),
+    ("dfma", Arithmetic, Double),
+    ("drcp", Arithmetic, Double),
+    ("msad", Arithmetic, Uint),
+    ("dtoi", Conversion, Double),
+    ("dtou", Conversion, Double),
+    ("itod", Conversion, Int),
+    ("utod", Conversion, Uint),
+    ("reserved", Arithmetic, Untyped),
+    ("gather4_s", TextureNormal, Float),
+    ("gather4_c_s", TextureComp, Float),
+    ("gather4_po_s", TextureNormal, Float),
+    ("gather4_po_c_s", TextureComp, Float),
+    ("ld_s", TextureLoad, Int),
+    ("ld_ms_s", TextureLoad, Int),
+    ("ld_uav_typed_s", TextureLoad, Uint),
+    ("ld_raw_s", TextureLoad, Uint),
+    ("ld_structured_s", TextureLoad, Uint),
+    ("sample_l_s", TextureNormal, Float),
+    ("sample_c_lz_s", TextureComp, Float),
+    ("sample_cl_s", TextureNormal, Float),
+    ("sample_b_cl_s", TextureBias, Float),
+    ("sample_d_cl_s", TextureGradient, Float),
+    ("sample_c_cl_s", TextureComp, Float),
+    ("check_access_fully_mapped", Arithmetic, Uint),
+];
+
+mod opcode {
+    pub const BREAKC: u32 = 3;
+    pub const CALLC: u32 = 5;
+    pub const CONTINUEC: u32 = 8;
+    pub const DISCARD: u32 = 13;
+    pub const IF: u32 = 31;
+    pub const LABEL: u32 = 44;
+    pub const CUSTOMDATA: u32 = 53;
+    pub const RESINFO: u32 = 61;
+    pub const RETC: u32 = 63;
+    pub const DCL_RESOURCE: u32 = 88;
+    pub const DCL_CONSTANT_BUFFER: u32 = 89;
+    pub const DCL_SAMPLER: u32 = 90;
+    pub const DCL_INDEX_RANGE: u32 = 91;
+    pub const DCL_GS_OUTPUT_PRIMITIVE_TOPOLOGY: u32 = 92;
+    pub const DCL_GS_INPUT_PRIMITIVE: u32 = 93;
+    pub const DCL_MAX_OUTPUT_VERTEX_COUNT: u32 = 94;
+    pub const DCL_INPUT: u32 = 95;
+    pub const DCL_INPUT_SGV: u32 = 96;
+    pub const DCL_INPUT_SIV: u32 = 97;
+    pub const DCL_INPUT_PS: u32 = 98;
+    pub const DCL_INPUT_PS_SGV: u32 = 99;
+    pub const DCL_INPUT_PS_SIV: u32 = 100;
+    pub const DCL_OUTPUT: u32 = 101;
+    pub const DCL_OUTPUT_SGV: u32 = 102;
+    pub const DCL_OUTPUT_SIV: u32 = 103;
+    pub const DCL_TEMPS: u32 = 104;
+    pub const DCL_INDEXABLE_TEMP: u32 = 105;
+    pub const DCL_GLOBAL_FLAGS: u32 = 106;
+    pub const SAMPLE_INFO: u32 = 111;
+    pub const FCALL: u32 = 120;
+    pub const DCL_STREAM: u32 = 143;
+    pub const DCL_FUNCTION_BODY: u32 = 144;
+    pub const DCL_FUNCTION_TABLE: u32 = 145;
+    pub const DCL_INTERFACE: u32 = 146;
+    pub const DCL_INPUT_CONTROL_POINT_COUNT: u32 = 147;
+    pub const DCL_OUTPUT_CONTROL_POINT_COUNT: u32 = 148;
+    pub const DCL_TESS_DOMAIN: u32 = 149;
+    pub const DCL_TESS_PARTITIONING: u32 = 150;
+    pub const DCL_TESS_OUTPUT_PRIMITIVE: u32 = 151;
+    pub const DCL_HS_MAX_TESSFACTOR: u32 = 152;
+    pub const DCL_HS_FORK_PHASE_INSTANCE_COUNT: u32 = 153;
+    pub const DCL_HS_JOIN_PHASE_INSTANCE_COUNT: u32 = 154;
+    pub const DCL_THREAD_GROUP: u32 = 155;
+    pub const DCL_UAV_TYPED: u32 = 156;
+    pub const DCL_UAV_RAW: u32 = 157;
+    pub const DCL_UAV_STRUCTURED: u32 = 158;
+    pub const DCL_TGSM_RAW: u32 = 159;
+    pub const DCL_TGSM_STRUCTURED: u32 = 160;
+    pub const DCL_RESOURCE_RAW: u32 = 161;
+    pub const DCL_RESOURCE_STRUCTURED: u32 = 162;
+    pub const SYNC: u32 = 190;
+    pub const DCL_GS_INSTANCE_COUNT: u32 = 206;
+}
+
+mod operand {
+    pub const IMMEDIATE32: u32 = 4;
+    pub const IMMEDIATE64: u32 = 5;
+    pub const SAMPLER: u32 = 6;
+    pub const RESOURCE: u32 = 7;
+    pub const CONSTANT_BUFFER: u32 = 8;
+    pub const IMMEDIATE_CONSTANT_BUFFER: u32 = 9;
+    pub const INPUT: u32 = 1;
+    pub const INPUT_CONTROL_POINT: u32 = 25;
+    pub const OUTPUT_CONTROL_POINT: u32 = 26;
+    pub const UNORDERED_ACCESS_VIEW: u32 = 30;
+}
+
+const REGISTER_PREFIXES: &[&str] = &[
+    "r",
+    "v",
+    "o",
+    "x",
+    "l",
+    "d",
+    "s",
+    "t",
+    "cb",
+    "icb",
+    "label",
+    "vPrim",
+    "oDepth",
+    "null",
+    "rasterizer",
+    "oMask",
+    "m",
+    "fb",
+    "ft",
+    "fp",
+    "fi",
+    "fo",
+    "vOutputControlPointID",
+    "vForkInstanceID",
+    "vJoinInstanceID",
+    "vicp",
+    "vocp",
+    "vpc",
+    "vDomain",
+    "this",
+    "u",
+    "g",
+    "vThreadID",
+    "vThreadGroupID",
+    "vThreadIDInGroup",
+    "vCoverage",
+    "vThreadIDInGroupFlattened",
+    "vGSInstanceID",
+    "oDepthGE",
+    "oDepthLE",
+    "vCycleCounter",
+    "oStencilRef",
+    "vInnerCoverage",
+];
+
+const RESOURCE_DIMENSIONS: &[&str] = &[
+    "unknown",
+    "buffer",
+    "texture1d",
+    "texture2d",
+    "texture2dms",
+    "texture3d",
+    "texturecube",
+    "texture1darray",
+    "texture2darray",
+    "texture2dmsarray",
+    "texturecubearray",
+    "raw_buffer",
+    "structured_buffer",
+];
+
+const RETURN_TYPES: &[&str] = &[
+    "unknown",
+    "unorm",
+    "snorm",
+    "sint",
+    "uint",
+    "float",
+    "mixed",
+    "double",
+    "continued",
+    "unused",
+];
+
+const SYSTEM_VALUES: &[&str] = &[
+    "undefined",
+    "position",
+    "clip_distance",
+    "cull_distance",
+    "rendertarget_array_index",
+    "viewport_array_index",
+    "vertex_id",
+    "primitive_id",
+    "instance_id",
+    "is_front_face",
+    "sampleIndex",
+    "finalQuadUeq0EdgeTessFactor",
+    "finalQuadVeq0EdgeTessFactor",
+    "finalQuadUeq1EdgeTessFactor",
+    "finalQuadVeq1EdgeTessFactor",
+    "finalQuadUInsideTessFactor",
+    "finalQuadVInsideTessFactor",
+    "finalTriUeq0EdgeTessFactor",
+    "finalTriVeq0EdgeTessFactor",
+    "finalTriWeq0EdgeTessFactor",
+    "finalTriInsideTessFactor",
+    "finalLineDetailTessFactor",
+    "finalLineDensityTessFactor",
+];
+
+const INTERPOLATION_MODES: &[&str] = &[
+    "undefined",
+    "constant",
+    "linear",
+    "linear centroid",
+    "linear noperspective",
+    "linear noperspective centroid",
+    "linear sample",
+    "linear noperspective sample",
+];
+
+const GLOBAL_FLAGS: &[&str] = &[
+    "refactoringAllowed",
+    "enableDoublePrecisionFloatOps",
+    "forceEarlyDepthStencil",
+    "enableRawAndStructuredBuffers",
+    "skipOptimization",
+    "enableMinimumPrecision",
+    "enable11_1DoubleExtensions",
+    "enable11_1ShaderExtensions",
+];
+
+const PRIMITIVE_TOPOLOGIES: &[&str] = &[
+    "undefined",
+    "pointlist",
+    "linelist",
+    "linestrip",
+    "trianglelist",
+    "trianglestrip",
+    "",
+    "",
+    "",
+    "",
+    "linelist_adj",
+    "linestrip_adj",
+    "trianglelist_adj",
+    "trianglestrip_adj",
+];
+
+const PRIMITIVES: &[&str] = &[
+    "undefined",
+    "point",
+    "line",
+    "triangle",
+    "",
+    "",
+    "lineadj",
+    "triangleadj",
+];
+
+const SHADER_TYPES: &[&str] = &["ps", "vs", "gs", "hs", "ds", "cs"];
+
+#[derive(Clone, Debug)]
+enum Components {
+    None,
+    Scalar,
+    Mask(u32),
+    Swizzle(u32),
+    Select(u32),
+}
+
+#[derive(Clone, Debug)]
+enum Index {
+    Immediate(u64),
+    Relative(Box<Operand>, u64),
+}
+
+#[derive(Clone, Debug)]
+struct Operand {
+    ty: u32,
+    components: Components,
+    indices: Vec<Index>,
+    modifier: u32,
+    values: Vec<u32>,
+}
+
+struct Reader<'a> {
+    tokens: &'a [u32],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn next(&mut self) -> Result<u32, DxError> {
+        let token = self.tokens.get(self.pos).copied().ok_or_else(|| {
+            DxError::InvalidBytecode("unexpected end of an instruction".to_string())
+        })?;
+        self.pos += 1;
+
+        Ok(token)
+    }
+
+    fn next_u64(&mut self) -> Result<u64, DxError> {
+        let hi = self.next()? as u64;
+        let lo = self.next()? as u64;
+
+        Ok(hi << 32 | lo)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn operand(&mut self) -> Result<Operand, DxError> {
+        let token = self.next()?;
+
+        let components = match token & 3 {
+            0 => Components::None,
+            1 => Components::Scalar,
+            2 => match (token >> 2) & 3 {
+                0 => Components::Mask((token >> 4) & 0xf),
+                1 => Components::Swizzle((token >> 4) & 0xff),
+                2 => Components::Select((token >> 4) & 3),
+                mode => {
+                    return Err(DxError::InvalidBytecode(format!(
+                        "invalid component selection mode {}",
+                        mode
+                    )))
+                }
+            },
+            _ => {
+                return Err(DxError::InvalidBytecode(
+                    "operands with N components aren't supported".to_string(),
+                ))
+            }
+        };
+
+        let ty = (token >> 12) & 0xff;
+        let mut modifier = 0;
+        let mut extended = token & 0x8000_0000 != 0;
+
+        while extended {
+            let ext = self.next()?;
+
+            if ext & 0x3f == 1 {
+                modifier = (ext >> 6) & 0xff;
+            }
+
+            extended = ext & 0x8000_0000 != 0;
+        }
+
+        let count = match components {
+            Components::None => 0,
+            Components::Scalar => 1,
+            _ => 4,
+        };
+
+        let values = match ty {
+            operand::IMMEDIATE32 => (0..count).map(|_| self.next()).collect::<Result<_, _>>()?,
+            // A scalar is one double and a vector is two doubles.
+            operand::IMMEDIATE64 => (0..count.min(2) * 2)
+                .map(|_| self.next())
+                .collect::<Result<_, _>>()?,
+            _ => vec![],
+        };
+
+        let indices = (0..(token >> 20) & 3)
+            .map(|dim| match (token >> (22 + 3 * dim)) & 7 {
+                0 => self.next().map(|i| Index::Immediate(i as u64)),
+                1 => self.next_u64().map(Index::Immediate),
+                2 => Ok(Index::Relative(Box::new(self.operand()?), 0)),
+                3 => {
+                    let offset = self.next()? as u64;
+                    Ok(Index::Relative(Box::new(self.operand()?), offset))
+                }
+                4 => {
+                    let offset = self.next_u64()?;
+                    Ok(Index::Relative(Box::new(self.operand()?), offset))
+                }
+                repr => Err(DxError::InvalidBytecode(format!(
+                    "invalid index representation {}",
+                    repr
+                ))),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Operand {
+            ty,
+            components,
+            indices,
+            modifier,
+            values,
+        })
+    }
+}
+
+struct Disassembler {
+    out: String,
+    counts: InstructionCounts,
+    /// Shader model 5.1 uses ranges with register spaces for resource bindings.
+    sm51: bool,
+}
+
+impl Disassembler {
+    fn run(bytecode: &[u8]) -> Result<Self, DxError> {
+        let program = if DxbcContainer::is_dxbc(bytecode) {
+            DxbcContainer::parse(bytecode)?
+                .shader_program()
+                .ok_or_else(|| {
+                    DxError::InvalidBytecode("container has no SHEX or SHDR part".to_string())
+                })?
+        } else {
+            bytecode
+        };
+
+        if program.len() % 4 != 0 || program.len() < 8 {
+            return Err(DxError::InvalidBytecode(
+                "token stream size isn't a multiple of 4".to_string(),
+            ));
+        }
+
+        let tokens = program
+            .chunks_exact(4)
+            .map(|c| u32::from_le_bytes(c.try_into().expect("chunk has 4 bytes")))
+            .collect::<Vec<_>>();
+
+        let version = tokens[0];
+        let (minor, major) = (version & 0xf, (version >> 4) & 0xf);
+        let len = (tokens[1] as usize).min(tokens.len());
+
+        let mut this = Self {
+            out: String::new(),
+            counts: InstructionCounts::default(),
+            sm51: (major, minor) >= (5, 1),
+        };
+
+        let _ = writeln!(
+            this.out,
+            "{}_{}_{}",
+            SHADER_TYPES
+                .get((version >> 16) as usize)
+                .copied()
+                .unwrap_or("unknown"),
+            major,
+            minor
+        );
+
+        let mut pos = 2;
+
+        while pos < len {
+            let token = tokens[pos];
+            let opcode = token & 0x7ff;
+
+            let size = if opcode == opcode::CUSTOMDATA {
+                tokens.get(pos + 1).copied().unwrap_or(0) as usize
+            } else {
+                ((token >> 24) & 0x7f) as usize
+            };
+
+            if size == 0 || pos + size > len {
+                return Err(DxError::InvalidBytecode(format!(
+                    "invalid length of an instruction at token {}",
+                    pos
+                )));
+            }
+
+            let mut reader = Reader {
+                tokens: &tokens[pos + 1..pos + size],
+                pos: 0,
+            };
+
+            this.instruction(opcode, token, &mut reader)?;
+            this.out.push('\n');
+            pos += size;
+        }
+
+        let _ = writeln!(
+            this.out,
+            "// Approximately {} instruction slots used",
+            this.counts.instruction_count
+        );
+
+        Ok(this)
+    }
+
+    fn instruction(&mut self, opcode: u32, token: u32, r: &mut Reader<'_>) -> Result<(), DxError> {
+        let &(name, class, value) = OPCODES
+            .get(opcode as usize)
+            .ok_or_else(|| DxError::InvalidBytecode(format!("unknown opcode {}", opcode)))?;
+
+        self.count(class);
+
+        match class {
+            Declaration => self.declaration(opcode, name, token, r),
+            _ if opcode == opcode::CUSTOMDATA => self.custom_data(token, r),
+            _ => {
+                self.out.push_str(name);
+                self.instruction_modifiers(opcode, token, r)?;
+
+                let mut separator = " ";
+
+                if opcode == opcode::FCALL {
+                    let index = r.next()?;
+                    self.out.push(' ');
+                    self.operand(&r.operand()?, value, true);
+                    let _ = write!(self.out, ", {}", index);
+                    separator = ", ";
+                }
+
+                while !r.is_empty() {
+                    let operand = r.operand()?;
+                    self.out.push_str(separator);
+                    self.operand(&operand, value, true);
+                    separator = ", ";
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    fn count(&mut self, class: Class) {
+        let counts = &mut self.counts;
+
+        match class {
+            Declaration => {
+                counts.dcl_count += 1;
+                return;
+            }
+            Phase => return,
+            Mov => counts.mov_instruction_count += 1,
+            Movc => counts.movc_instruction_count += 1,
+            Conversion => counts.conversion_instruction_count += 1,
+            Bitwise => counts.bitwise_instruction_count += 1,
+            Emit => counts.emit_instruction_count += 1,
+            Cut => counts.cut_instruction_count += 1,
+            EmitThenCut => {
+                counts.emit_instruction_count += 1;
+                counts.cut_instruction_count += 1;
+            }
+            TextureNormal => counts.texture_normal_instructions += 1,
+            TextureLoad => counts.texture_load_instructions += 1,
+            TextureComp => counts.texture_comp_instructions += 1,
+            TextureBias => counts.texture_bias_instructions += 1,
+            TextureGradient => counts.texture_gradient_instructions += 1,
+            Arithmetic => {}
+        }
+
+        counts.instruction_count += 1;
+    }
+
+    fn instruction_modifiers(
+        &mut self,
+        opcode: u32,
+        token: u32,
+        r: &mut Reader<'_>,
+    ) -> Result<(), DxError> {
+        match opcode {
+            opcode::BREAKC
+            | opcode::CALLC
+            | opcode::CONTINUEC
+            | opcode::DISCARD
+            | opcode::IF
+            | opcode::RETC => {
+                self.out
+                    .push_str(if token & (1 << 18) != 0 { "_nz" } else { "_z" });
+            }
+            opcode::SYNC => {
+                for (bit, flag) in [(14, "_uglobal"), (13, "_ugroup"), (12, "_g"), (11, "_t")] {
+                    if token & (1 << bit) != 0 {
+                        self.out.push_str(flag);
+                    }
+                }
+            }
+            _ if token & (1 << 13) != 0 => self.out.push_str("_sat"),
+            _ => {}
+        }
+
+        let mut offsets = None;
+        let mut dimension = None;
+        let mut return_type = None;
+        let mut extended = token & 0x8000_0000 != 0;
+
+        while extended {
+            let ext = r.next()?;
+
+            match ext & 0x3f {
+                1 => {
+                    let offset = |shift: u32| (((ext >> shift) & 0xf) as i32) << 28 >> 28;
+                    offsets = Some([offset(9), offset(13), offset(17)]);
+                }
+                2 => dimension = Some(((ext >> 6) & 0x1f, (ext >> 11) & 0xfff)),
+                3 => return_type = Some(ext >> 6),
+                _ => {}
+            }
+
+            extended = ext & 0x8000_0000 != 0;
+        }
+
+        if offsets.is_some() {
+            self.out.push_str("_aoffimmi");
+        }
+
+        if dimension.is_some() {
+            self.out.push_str("_indexable");
+        }
+
+        if let Some([u, v, w]) = offsets {
+            let _ = write!(self.out, "({},{},{})", u, v, w);
+        }
+
+        if let Some((dim, stride)) = dimension {
+            let _ = write!(self.out, "({}", lookup(RESOURCE_DIMENSIONS, dim));
+            if stride != 0 {
+                let _ = write!(self.out, ", stride={}", stride);
+            }
+            self.out.push(')');
+        }
+
+        if let Some(ty) = return_type {
+            self.return_type(ty);
+        }
+
+        match opcode {
+            opcode::RESINFO => match (token >> 11) & 3 {
+                1 => self.out.push_str("_rcpFloat"),
+                2 => self.out.push_str("_uint"),
+                _ => {}
+            },
+            opcode::SAMPLE_INFO if token & (1 << 11) != 0 => self.out.push_str("_uint"),
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn declaration(
+        &mut self,
+        opcode: u32,
+        name: &str,
+        token: u32,
+        r: &mut Reader<'_>,
+    ) -> Result<(), DxError> {
+        let controls = token >> 11;
+
+        match opcode {
+            opcode::DCL_RESOURCE => {
+                let op = r.operand()?;
+                let ty = r.next()?;
+                let dim = controls & 0x1f;
+                let _ = write!(self.out, "{}_{}", name, lookup(RESOURCE_DIMENSIONS, dim));
+                if dim == 4 || dim == 9 {
+                    let samples = (controls >> 5) & 0x7f;
+                    if samples != 0 {
+                        let _ = write!(self.out, "({})", samples);
+                    }
+                }
+                self.out.push(' ');
+                self.return_type(ty);
+                self.out.push(' ');
+                let space = self.binding(&op, r)?;
+                self.space(space);
+            }
+            opcode::DCL_CONSTANT_BUFFER => {
+                let op = r.operand()?;
+                let access = if controls & 1 != 0 {
+                    "dynamicIndexed"
+                } else {
+                    "immediateIndexed"
+                };
+                let _ = write!(self.out, "{} ", name);
+
+                if self.sm51 {
+                    self.binding_range(&op);
+                    let size = r.next()?;
+                    let space = r.next()?;
+                    let _ = write!(self.out, "[{}], {}, space={}", size, access, space);
+                } else {
+                    self.operand(&op, Value::Untyped, false);
+                    let _ = write!(self.out, ", {}", access);
+                }
+            }
+            opcode::DCL_SAMPLER => {
+                let op = r.operand()?;
+                let mode = ["mode_default", "mode_comparison", "mode_mono"];
+                let _ = write!(self.out, "{} ", name);
+                let space = self.binding(&op, r)?;
+                let _ = write!(self.out, ", {}", lookup(&mode, controls & 0xf));
+                self.space(space);
+            }
+            opcode::DCL_INDEX_RANGE => {
+                let op = r.operand()?;
+                let _ = write!(self.out, "{} ", name);
+                self.operand(&op, Value::Untyped, true);
+                let _ = write!(self.out, " {}", r.next()?);
+            }
+            opcode::DCL_GS_OUTPUT_PRIMITIVE_TOPOLOGY => {
+                let topology = lookup(PRIMITIVE_TOPOLOGIES, controls & 0x7f);
+                let _ = write!(self.out, "{} {}", name, topology);
+            }
+            opcode::DCL_GS_INPUT_PRIMITIVE => {
+                let primitive = controls & 0x3f;
+                let _ = match primitive {
+                    8..=40 => write!(self.out, "{} patch{}", name, primitive - 7),
+                    _ => write!(self.out, "{} {}", name, lookup(PRIMITIVES, primitive)),
+                };
+            }
+            opcode::DCL_INPUT | opcode::DCL_OUTPUT | opcode::DCL_STREAM => {
+                let op = r.operand()?;
+                let _ = write!(self.out, "{} ", name);
+                self.operand(&op, Value::Untyped, true);
+            }
+            opcode::DCL_INPUT_SGV
+            | opcode::DCL_INPUT_SIV
+            | opcode::DCL_OUTPUT_SGV
+            | opcode::DCL_OUTPUT_SIV => {
+                let op = r.operand()?;
+                let _ = write!(self.out, "{} ", name);
+                self.operand(&op, Value::Untyped, true);
+                let _ = write!(self.out, ", {}", lookup(SYSTEM_VALUES, r.next()?));
+            }
+            opcode::DCL_INPUT_PS | opcode::DCL_INPUT_PS_SGV | opcode::DCL_INPUT_PS_SIV => {
+                let op = r.operand()?;
+                let mode = lookup(INTERPOLATION_MODES, controls & 0xf);
+                let _ = write!(self.out, "{} {} ", name, mode);
+                self.operand(&op, Value::Untyped, true);
+                if opcode != opcode::DCL_INPUT_PS {
+                    let _ = write!(self.out, ", {}", lookup(SYSTEM_VALUES, r.next()?));
+                }
+            }
+            opcode::DCL_TEMPS => {
+                let count = r.next()?;
+                self.counts.temp_register_count = self.counts.temp_register_count.max(count);
+                let _ = write!(self.out, "{} {}", name, count);
+            }
+            opcode::DCL_INDEXABLE_TEMP => {
+                let (register, count, components) = (r.next()?, r.next()?, r.next()?);
+                self.counts.temp_array_count += count;
+                let _ = write!(
+                    self.out,
+                    "{} x{}[{}], {}",
+                    name, register, count, components
+                );
+            }
+            opcode::DCL_GLOBAL_FLAGS => {
+                let flags = GLOBAL_FLAGS
+                    .iter()
+                    .enumerate()
+                    .filter(|(bit, _)| controls & (1 << bit) != 0)
+                    .map(|(_, flag)| *flag)
+                    .collect::<Vec<_>>();
+                let _ = write!(self.out, "{} {}", name, flags.join(" | "));
+            }
+            opcode::DCL_FUNCTION_BODY => {
+                let _ = write!(self.out, "{} fb{}", name, r.next()?);
+            }
+            opcode::DCL_FUNCTION_TABLE => {
+                let table = r.next()?;
+                let bodies = (0..r.next()?)
+                    .map(|_| r.next().map(|body| format!("fb{}", body)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let _ = write!(self.out, "{} ft{} = {{{}}}", name, table, bodies.join(", "));
+            }
+            opcode::DCL_INTERFACE => {
+                let interface = r.next()?;
+                let call_sites = r.next()?;
+                let table = r.next()?;
+                let tables = (0..table & 0xffff)
+                    .map(|_| r.next().map(|table| format!("ft{}", table)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let dynamic = if controls & 1 != 0 {
+                    "_dynamicindexed"
+                } else {
+                    ""
+                };
+                let _ = write!(
+                    self.out,
+                    "{}{} fp{}[{}][{}] = {{{}}}",
+                    name,
+                    dynamic,
+                    interface,
+                    call_sites,
+                    table >> 16,
+                    tables.join(", ")
+                );
+            }
+            opcode::DCL_INPUT_CONTROL_POINT_COUNT | opcode::DCL_OUTPUT_CONTROL_POINT_COUNT => {
+                let _ = write!(self.out, "{} {}", name, controls & 0x3f);
+            }
+            opcode::DCL_TESS_DOMAIN => {
+                let domain = [
+                    "domain_undefined",
+                    "domain_isoline",
+                    "domain_tri",
+                    "domain_quad",
+                ];
+                let _ = write!(self.out, "{} {}", name, lookup(&domain, controls & 3));
+            }
+            opcode::DCL_TESS_PARTITIONING => {
+                let partitioning = [
+                    "partitioning_undefined",
+                    "partitioning_integer",
+                    "partitioning_pow2",
+                    "partitioning_fractional_odd",
+                    "partitioning_fractional_even",
+                ];
+                let _ = write!(self.out, "{} {}", name, lookup(&partitioning, controls & 7));
+            }
+            opcode::DCL_TESS_OUTPUT_PRIMITIVE => {
+                let primitive = [
+                    "output_undefined",
+                    "output_point",
+                    "output_line",
+                    "output_triangle_cw",
+                    "output_triangle_ccw",
+                ];
+                let _ = write!(self.out, "{} {}", name, lookup(&primitive, controls & 7));
+            }
+            opcode::DCL_HS_MAX_TESSFACTOR => {
+                let factor = format_value(r.next()?, Value::Float);
+                let _ = write!(self.out, "{} l({})", name, factor);
+            }
+            opcode::DCL_MAX_OUTPUT_VERTEX_COUNT
+            | opcode::DCL_HS_FORK_PHASE_INSTANCE_COUNT
+            | opcode::DCL_HS_JOIN_PHASE_INSTANCE_COUNT
+            | opcode::DCL_GS_INSTANCE_COUNT => {
+                let _ = write!(self.out, "{} {}", name, r.next()?);
+            }
+            opcode::DCL_THREAD_GROUP => {
+                let (x, y, z) = (r.next()?, r.next()?, r.next()?);
+                let _ = write!(self.out, "{} {}, {}, {}", name, x, y, z);
+            }
+            opcode::DCL_UAV_TYPED => {
+                let op = r.operand()?;
+                let ty = r.next()?;
+                let _ = write!(
+                    self.out,
+                    "{}_{}",
+                    name,
+                    lookup(RESOURCE_DIMENSIONS, controls & 0x1f)
+                );
+                self.uav_flags(controls);
+                self.out.push(' ');
+                self.return_type(ty);
+                self.out.push(' ');
+                let space = self.binding(&op, r)?;
+                self.space(space);
+            }
+            opcode::DCL_UAV_RAW | opcode::DCL_RESOURCE_RAW => {
+                let op = r.operand()?;
+                self.out.push_str(name);
+                if opcode == opcode::DCL_UAV_RAW {
+                    self.uav_flags(controls);
+                }
+                self.out.push(' ');
+                let space = self.binding(&op, r)?;
+                self.space(space);
+            }
+            opcode::DCL_UAV_STRUCTURED | opcode::DCL_RESOURCE_STRUCTURED => {
+                let op = r.operand()?;
+                let stride = r.next()?;
+                self.out.push_str(name);
+                if opcode == opcode::DCL_UAV_STRUCTURED {
+                    self.uav_flags(controls);
+                    if controls & (1 << 12) != 0 {
+                        self.out.push_str("_opc");
+                    }
+                }
+                self.out.push(' ');
+                let space = self.binding(&op, r)?;
+                let _ = write!(self.out, ", {}", stride);
+                self.space(space);
+            }
+            opcode::DCL_TGSM_RAW => {
+                let op = r.operand()?;
+                let _ = write!(self.out, "{} ", name);
+                self.operand(&op, Value::Untyped, false);
+                let _ = write!(self.out, ", {}", r.next()?);
+            }
+            opcode::DCL_TGSM_STRUCTURED => {
+                let op = r.operand()?;
+                let (stride, count) = (r.next()?, r.next()?);
+                let _ = write!(self.out, "{} ", name);
+                self.operand(&op, Value::Untyped, false);
+                let _ = write!(self.out, ", {}, {}", stride, count);
+            }
+            _ => {
+                self.out.push_str(name);
+                while !r.is_empty() {
+                    let _ = write!(self.out, " 0x{:08x}", r.next()?);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn custom_data(&mut self, token: u32, r: &mut Reader<'_>) -> Result<(), DxError> {
+        let class = token >> 11;
+        let data = r.tokens.get(1..).ok_or_else(|| {
+            DxError::InvalidBytecode("custom data block without a length".to_string())
+        })?;
+
+        match class {
+            3 => {
+                const PREFIX: &str = "dcl_immediateConstantBuffer { ";
+
+                let rows = data
+                    .chunks(4)
+                    .map(|row| {
+                        let values = row
+                            .iter()
+                            .map(|v| format_value(*v, Value::Untyped))
+                            .collect::<Vec<_>>();
+                        format!("{{ {}}}", values.join(", "))
+                    })
+                    .collect::<Vec<_>>();
+
+                let separator = format!(",\n{}", " ".repeat(PREFIX.len()));
+                let _ = write!(self.out, "{}{} }}", PREFIX, rows.join(&separator));
+            }
+            _ => {
+                let classes = [
+                    "comment",
+                    "debuginfo",
+                    "opaque",
+                    "immediateConstantBuffer",
+                    "shader message",
+                    "shader clip plane constant mappings for dx9",
+                ];
+                let _ = write!(
+                    self.out,
+                    "// customdata {} ({} dwords)",
+                    lookup(&classes, class),
+                    data.len()
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn uav_flags(&mut self, controls: u32) {
+        if controls & (1 << 5) != 0 {
+            self.out.push_str("_glc");
+        }
+
+        if controls & (1 << 6) != 0 {
+            self.out.push_str("_rov");
+        }
+    }
+
+    fn return_type(&mut self, ty: u32) {
+        let types = (0..4)
+            .map(|i| lookup(RETURN_TYPES, (ty >> (i * 4)) & 0xf))
+            .collect::<Vec<_>>();
+        let _ = write!(self.out, "({})", types.join(","));
+    }
+
+    /// Writes a resource binding of a declaration and reads the register space of shader model 5.1.
+    fn binding(&mut self, op: &Operand, r: &mut Reader<'_>) -> Result<Option<u32>, DxError> {
+        if self.sm51 {
+            self.binding_range(op);
+            r.next().map(Some)
+        } else {
+            self.operand(op, Value::Untyped, false);
+            Ok(None)
+        }
+    }
+
+    fn space(&mut self, space: Option<u32>) {
+        if let Some(space) = space {
+            let _ = write!(self.out, ", space={}", space);
+        }
+    }
+
+    fn binding_range(&mut self, op: &Operand) {
+        let prefix = register_prefix(op.ty, true);
+        let index = |i: usize| match op.indices.get(i) {
+            Some(Index::Immediate(v)) => *v,
+            _ => 0,
+        };
+
+        let upper = match index(2) {
+            0xffff_ffff => "*".to_string(),
+            upper => upper.to_string(),
+        };
+
+        let _ = write!(self.out, "{}{}[{}:{}]", prefix, index(0), index(1), upper);
+    }
+
+    fn operand(&mut self, op: &Operand, value: Value, components: bool) {
+        let (prefix, suffix) = match op.modifier {
+            1 => ("-", ""),
+            2 => ("|", "|"),
+            3 => ("-|", "|"),
+            _ => ("", ""),
+        };
+
+        self.out.push_str(prefix);
+
+        match op.ty {
+            operand::IMMEDIATE32 => {
+                let values = op
+                    .values
+                    .iter()
+                    .map(|v| format_value(*v, value))
+                    .collect::<Vec<_>>();
+                let _ = write!(self.out, "l({})", values.join(", "));
+            }
+            operand::IMMEDIATE64 => {
+                let values = op
+                    .values
+                    .chunks_exact(2)
+                    .map(|v| format!("{:.6}", f64::from_bits((v[1] as u64) << 32 | v[0] as u64)))
+                    .collect::<Vec<_>>();
+                let _ = write!(self.out, "d({})", values.join(", "));
+            }
+            ty => {
+                self.out.push_str(register_prefix(ty, self.sm51));
+
+                let bracketed = ty == operand::IMMEDIATE_CONSTANT_BUFFER
+                    || ty == operand::INPUT_CONTROL_POINT
+                    || ty == operand::OUTPUT_CONTROL_POINT
+                    || (ty == operand::INPUT && op.indices.len() == 2);
+
+                for (i, index) in op.indices.iter().enumerate() {
+                    match index {
+                        Index::Immediate(v) if i == 0 && !bracketed => {
+                            let _ = write!(self.out, "{}", v);
+                        }
+                        Index::Immediate(v) => {
+                            let _ = write!(self.out, "[{}]", v);
+                        }
+                        Index::Relative(relative, offset) => {
+                            self.out.push('[');
+                            self.operand(relative, Value::Untyped, true);
+                            let _ = write!(self.out, " + {}]", offset);
+                        }
+                    }
+                }
+
+                if components {
+                    self.components(&op.components);
+                }
+            }
+        }
+
+        self.out.push_str(suffix);
+    }
+
+    fn components(&mut self, components: &Components) {
+        const NAMES: [char; 4] = ['x', 'y', 'z', 'w'];
+
+        match *components {
+            Components::Mask(mask) if mask != 0 => {
+                self.out.push('.');
+                self.out.extend(
+                    NAMES
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| mask & (1 << i) != 0)
+                        .map(|(_, c)| c),
+                );
+            }
+            Components::Swizzle(swizzle) => {
+                self.out.push('.');
+                self.out
+                    .extend((0..4).map(|i| NAMES[((swizzle >> (i * 2)) & 3) as usize]));
+            }
+            Components::Select(component) => {
+                self.out.push('.');
+                self.out.push(NAMES[component as usize]);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn register_prefix(ty: u32, sm51: bool) -> &'static str {
+    match ty {
+        operand::SAMPLER if sm51 => "S",
+        operand::RESOURCE if sm51 => "T",
+        operand::CONSTANT_BUFFER if sm51 => "CB",
+        operand::UNORDERED_ACCESS_VIEW if sm51 => "U",
+        _ => lookup(REGISTER_PREFIXES, ty),
+    }
+}
+
+fn lookup(names: &[&'static str], index: u32) -> &'static str {
+    names.get(index as usize).copied().unwrap_or("unknown")
+}
+
+/// Formats an immediate value. Untyped values are printed as floats when the bits look like a float of a sane magnitude.
+fn format_value(bits: u32, value: Value) -> String {
+    match value {
+        Value::Float | Value::Double => format!("{:.6}", f32::from_bits(bits)),
+        Value::Int => (bits as i32).to_string(),
+        Value::Uint => bits.to_string(),
+        Value::Untyped => {
+            let exponent = (bits >> 23) & 0xff;
+
+            if bits != 0 && (0x60..0xa0).contains(&exponent) {
+                format!("{:.6}", f32::from_bits(bits))
+            } else {
+                (bits as i32).to_string()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container(program: &[u32]) -> Vec<u8> {
+        let mut data = b"DXBC".to_vec();
+        data.extend_from_slice(&[0; 16]);
+        data.extend_from_slice(&1u32.to_le_bytes());
+        let size = 32 + 4 + 8 + program.len() * 4;
+        data.extend_from_slice(&(size as u32).to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&36u32.to_le_bytes());
+        data.extend_from_slice(b"SHEX");
+        data.extend_from_slice(&(program.len() as u32 * 4).to_le_bytes());
+        data.extend(program.iter().flat_map(|t| t.to_le_bytes()));
+        data
+    }
+
+    #[rustfmt::skip]
+    fn program() -> Vec<u32> {
+        let mut tokens = vec![
+            0x00000050, 0,
+            // dcl_globalFlags refactoringAllowed
+            0x0100086a,
+            // dcl_immediateConstantBuffer
+            0x00001835, 10, 0x3f800000, 0, 0, 0, 0, 0x3f800000, 0, 0,
+            // dcl_constantbuffer cb0[3], dynamicIndexed
+            0x04000859, 0x00208e46, 0, 3,
+            // dcl_sampler s0, mode_default
+            0x0300005a, 0x00106000, 0,
+            // dcl_resource_texture2d (float,float,float,float) t0
+            0x04001858, 0x00107000, 0, 0x5555,
+            // dcl_input_ps linear v1.xy
+            0x03001062, 0x00101032, 1,
+            // dcl_output o0.xyzw
+            0x03000065, 0x001020f2, 0,
+            // dcl_temps 1
+            0x02000068, 1,
+            // sample_indexable(texture2d)(float,float,float,float) r0.xyzw, v1.xyxx, t0.xyzw, s0
+            0x8b000045, 0x800000c2, 0x00155543,
+            0x001000f2, 0, 0x00101046, 1, 0x00107e46, 0, 0x00106000, 0,
+            // mul o0.xyzw, r0.xyzw, l(2.000000, 2.000000, 0.000000, 0.000000)
+            0x0a000038, 0x001020f2, 0, 0x00100e46, 0, 0x00004002, 0x40000000, 0x40000000, 0, 0,
+            // mov_sat r0.x, -|v1.y|
+            0x06002036, 0x00100012, 0, 0x8010101a, 0xc1, 1,
+            // mov r0.y, cb0[r0.x + 2].y
+            0x08000036, 0x00100022, 0, 0x0620801a, 0, 2, 0x0010000a, 0,
+            // if_nz r0.x
+            0x0304001f, 0x0010000a, 0,
+            // endif
+            0x01000015,
+            // ret
+            0x0100003e,
+        ];
+
+        tokens[1] = tokens.len() as u32;
+        tokens
+    }
+
+    #[test]
+    fn disassemble_test() {
+        let text = disassemble(&container(&program())).unwrap();
+
+        let expected = "\
+ps_5_0
+dcl_globalFlags refactoringAllowed
+dcl_immediateConstantBuffer { { 1.000000, 0, 0, 0},
+                              { 0, 1.000000, 0, 0} }
+dcl_constantbuffer cb0[3], dynamicIndexed
+dcl_sampler s0, mode_default
+dcl_resource_texture2d (float,float,float,float) t0
+dcl_input_ps linear v1.xy
+dcl_output o0.xyzw
+dcl_temps 1
+sample_indexable(texture2d)(float,float,float,float) r0.xyzw, v1.xyxx, t0.xyzw, s0
+mul o0.xyzw, r0.xyzw, l(2.000000, 2.000000, 0.000000, 0.000000)
+mov_sat r0.x, -|v1.y|
+mov r0.y, cb0[r0.x + 2].y
+if_nz r0.x
+endif
+ret
+// Approximately 7 instruction slots used
+";
+
+        assert_eq!(text, expected);
+    }
+
+    #[test]
+    fn instruction_counts_test() {
+        let counts = InstructionCounts::from_bytecode(&container(&program())).unwrap();
+
+        assert_eq!(counts.instruction_count, 7);
+        assert_eq!(counts.dcl_count, 7);
+        assert_eq!(counts.temp_register_count, 1);
+        assert_eq!(counts.mov_instruction_count, 2);
+        assert_eq!(counts.texture_normal_instructions, 1);
+        assert_eq!(counts.conversion_instruction_count, 0);
+
+        let mut truncated = program();
+        truncated.pop();
+        truncated.push(0x0200003e);
+        assert!(InstructionCounts::from_bytecode(&container(&truncated)).is_err());
+
+        let custom_data = [0x00000050, 5, 0x00001835, 1, 0x0100003e];
+        assert!(disassemble(&container(&custom_data)).is_err());
+    }
+
+    #[test]
+    fn reflection_counts_test() {
+        use crate::blob::{Blobby, CompileOptions};
+
+        let source = r#"
+            Texture2D<float4> tex : register(t0);
+            SamplerState smp : register(s0);
+
+            float4 main(float2 uv : TEXCOORD, uint id : ID) : SV_Target {
+                float4 color = tex.Sample(smp, uv);
+                uint bits = (id << 3) ^ (uint)color.x;
+                return id > 4 ? color : float4(bits, asfloat(bits & 7), color.y, (int)color.z);
+            }
+        "#;
+
+        let blob = CompileOptions::new("main", "ps_5_0")
+            .compile_from_source(source, "test.hlsl", None)
+            .unwrap()
+            .blob;
+
+        let reflection = blob.reflect().unwrap();
+        let counts = InstructionCounts::from_bytecode(&blob).unwrap();
+
+        assert_eq!(
+            counts.mov_instruction_count,
+            reflection.get_mov_instruction_count()
+        );
+        assert_eq!(
+            counts.movc_instruction_count,
+            reflection.get_movc_instruction_count()
+        );
+        assert_eq!(
+            counts.conversion_instruction_count,
+            reflection.get_conversion_instruction_count()
+        );
+        assert_eq!(
+            counts.bitwise_instruction_count,
+            reflection.get_bitwise_instruction_count()
+        );
+        assert_eq!(
+            counts.instruction_count,
+            reflection.get_desc().unwrap().get_instruction_count()
+        );
+    }
+}
@@ -1,27 +1,47 @@
+pub use crate::acceleration_structure::*;
 pub use crate::adapter::*;
+pub use crate::barrier_batch::*;
 pub use crate::blob::*;
 pub use crate::command_allocator::*;
 pub use crate::command_list::*;
 pub use crate::command_queue::*;
+pub use crate::command_recorder::*;
 pub use crate::command_signature::*;
 pub use crate::debug::*;
 pub use crate::descriptor_heap::*;
 pub use crate::device::*;
 pub use crate::device_child::*;
+pub use crate::disasm::*;
+pub use crate::dxbc::*;
+pub use crate::enhanced_barrier::*;
 pub use crate::entry::*;
 pub use crate::error::*;
 pub use crate::factory::*;
 pub use crate::heap::*;
+pub use crate::hot_reload::*;
+pub use crate::include::*;
+pub use crate::input_layout::*;
 
 #[cfg(feature = "callback")]
 pub use crate::info_queue::*;
 
 pub use crate::pageable::*;
+pub use crate::pipeline_cache::*;
+pub use crate::pipeline_stream::*;
+pub use crate::pipeline_validation::*;
+pub use crate::preprocessor::*;
+pub use crate::protected_session::*;
 pub use crate::pso::*;
 pub use crate::query_heap::*;
 pub use crate::reflection::*;
+pub use crate::render_pass::*;
 pub use crate::resources::*;
 pub use crate::root_signature::*;
+pub use crate::shader_cache::*;
+pub use crate::shader_table::*;
+pub use crate::state_object::*;
+pub use crate::state_tracker::*;
 pub use crate::swapchain::*;
 pub use crate::sync::*;
 pub use crate::types::*;
+pub use crate::work_graph::*;
@@ -0,0 +1,590 @@
+use std::fmt;
+
+use crate::{blob::Blob, error::DxError};
+
+const DXBC_MAGIC: [u8; 4] = *b"DXBC";
+const DXBC_VERSION: u32 = 1;
+const HEADER_SIZE: usize = 32;
+const PART_HEADER_SIZE: usize = 8;
+/// The checksum covers everything after the magic and the checksum itself.
+const CHECKSUM_START: usize = 20;
+
+/// Four-character code that identifies a part of a DXBC container.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FourCC(pub [u8; 4]);
+
+impl FourCC {
+    /// Shader model 5 token stream.
+    pub const SHEX: Self = Self(*b"SHEX");
+    /// Shader model 4 token stream.
+    pub const SHDR: Self = Self(*b"SHDR");
+    /// DXIL program.
+    pub const DXIL: Self = Self(*b"DXIL");
+    /// Input signature.
+    pub const ISGN: Self = Self(*b"ISGN");
+    /// Input signature with minimum precision.
+    pub const ISG1: Self = Self(*b"ISG1");
+    /// Output signature.
+    pub const OSGN: Self = Self(*b"OSGN");
+    /// Output signature with stream indices.
+    pub const OSG5: Self = Self(*b"OSG5");
+    /// Output signature with minimum precision.
+    pub const OSG1: Self = Self(*b"OSG1");
+    /// Patch constant signature.
+    pub const PCSG: Self = Self(*b"PCSG");
+    /// Patch constant signature with minimum precision.
+    pub const PSG1: Self = Self(*b"PSG1");
+    /// Resource definitions used by reflection.
+    pub const RDEF: Self = Self(*b"RDEF");
+    /// Statistics used by reflection.
+    pub const STAT: Self = Self(*b"STAT");
+    /// Shader feature info.
+    pub const SFI0: Self = Self(*b"SFI0");
+    /// Serialized root signature.
+    pub const RTS0: Self = Self(*b"RTS0");
+    /// Interface data used by reflection.
+    pub const IFCE: Self = Self(*b"IFCE");
+    /// Debug info.
+    pub const SDBG: Self = Self(*b"SDBG");
+    /// PDB debug info.
+    pub const SPDB: Self = Self(*b"SPDB");
+    /// Private data.
+    pub const PRIV: Self = Self(*b"PRIV");
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; 4] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for FourCC {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FourCC({})", self)
+    }
+}
+
+impl fmt::Display for FourCC {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            if byte.is_ascii_graphic() {
+                write!(f, "{}", byte as char)?;
+            } else {
+                write!(f, "\\x{:02x}", byte)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Part of a DXBC container.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DxbcPart<'a> {
+    pub fourcc: FourCC,
+    pub data: &'a [u8],
+}
+
+/// Read-only view of a DXBC container, the format of compiled shaders and serialized root signatures.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DxbcContainer<'a> {
+    data: &'a [u8],
+    parts: Vec<DxbcPart<'a>>,
+}
+
+impl<'a> DxbcContainer<'a> {
+    /// Parses the header and the part table of a container.
+    pub fn parse(data: &'a [u8]) -> Result<Self, DxError> {
+        if data.len() < HEADER_SIZE || data[0..4] != DXBC_MAGIC {
+            return Err(DxError::InvalidBytecode(
+                "missing DXBC container header".to_string(),
+            ));
+        }
+
+        let size = read_u32(data, 24)? as usize;
+        if size > data.len() || size < HEADER_SIZE {
+            return Err(DxError::InvalidBytecode(format!(
+                "container size {} doesn't match the data size {}",
+                size,
+                data.len()
+            )));
+        }
+
+        let data = &data[..size];
+        let count = read_u32(data, 28)? as usize;
+
+        let parts = (0..count)
+            .map(|i| {
+                let offset = read_u32(data, HEADER_SIZE + i * 4)? as usize;
+                let fourcc = FourCC(read_array(data, offset)?);
+                let len = read_u32(data, offset + 4)? as usize;
+
+                let data = offset
+                    .checked_add(PART_HEADER_SIZE)
+                    .and_then(|start| data.get(start..start.checked_add(len)?))
+                    .ok_or_else(|| {
+                        DxError::InvalidBytecode(format!("part {} is out of bounds", fourcc))
+                    })?;
+
+                Ok(DxbcPart { fourcc, data })
+            })
+            .collect::<Result<Vec<_>, DxError>>()?;
+
+        Ok(Self { data, parts })
+    }
+
+    /// Returns `true` if the data starts with the DXBC magic.
+    #[inline]
+    pub fn is_dxbc(data: &[u8]) -> bool {
+        data.starts_with(&DXBC_MAGIC)
+    }
+
+    /// Bytes of the whole container.
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Checksum stored in the header.
+    #[inline]
+    pub fn checksum(&self) -> [u8; 16] {
+        self.data[4..20].try_into().expect("header was validated")
+    }
+
+    /// Computes the checksum of the container with the modified MD5 used by the compiler.
+    #[inline]
+    pub fn compute_checksum(&self) -> [u8; 16] {
+        compute_checksum(&self.data[CHECKSUM_START..])
+    }
+
+    /// Returns `true` if the stored checksum matches the contents, so the runtime accepts the container.
+    #[inline]
+    pub fn verify_checksum(&self) -> bool {
+        self.checksum() == self.compute_checksum()
+    }
+
+    #[inline]
+    pub fn parts(&self) -> &[DxbcPart<'a>] {
+        &self.parts
+    }
+
+    /// Data of the first part with the code.
+    #[inline]
+    pub fn part(&self, fourcc: FourCC) -> Option<&'a [u8]> {
+        self.parts
+            .iter()
+            .find(|part| part.fourcc == fourcc)
+            .map(|part| part.data)
+    }
+
+    /// Token stream of the shader, either `SHEX` or `SHDR`.
+    #[inline]
+    pub fn shader_program(&self) -> Option<&'a [u8]> {
+        self.part(FourCC::SHEX).or_else(|| self.part(FourCC::SHDR))
+    }
+}
+
+/// Owned DXBC container for adding, replacing or removing parts.
+///
+/// [`DxbcBuilder::build`] serializes the parts into a new container with a recomputed checksum,
+/// e.g. to strip debug info or to embed an `RTS0` root signature into a shader.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DxbcBuilder {
+    parts: Vec<(FourCC, Vec<u8>)>,
+}
+
+impl DxbcBuilder {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copies the parts of an existing container.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, DxError> {
+        let container = DxbcContainer::parse(data)?;
+
+        Ok(Self {
+            parts: container
+                .parts()
+                .iter()
+                .map(|part| (part.fourcc, part.data.to_vec()))
+                .collect(),
+        })
+    }
+
+    /// Adds a part or replaces the data of an existing one.
+    #[inline]
+    pub fn with_part(mut self, fourcc: FourCC, data: impl Into<Vec<u8>>) -> Self {
+        self.set_part(fourcc, data);
+        self
+    }
+
+    /// Removes all parts with the code.
+    #[inline]
+    pub fn without_part(mut self, fourcc: FourCC) -> Self {
+        self.remove_part(fourcc);
+        self
+    }
+
+    #[inline]
+    pub fn parts(&self) -> impl Iterator<Item = DxbcPart<'_>> {
+        self.parts.iter().map(|(fourcc, data)| DxbcPart {
+            fourcc: *fourcc,
+            data,
+        })
+    }
+
+    #[inline]
+    pub fn part(&self, fourcc: FourCC) -> Option<&[u8]> {
+        self.parts
+            .iter()
+            .find(|(code, _)| *code == fourcc)
+            .map(|(_, data)| data.as_slice())
+    }
+
+    /// Appends a part, returns an error if a part with the code already exists.
+    pub fn add_part(&mut self, fourcc: FourCC, data: impl Into<Vec<u8>>) -> Result<(), DxError> {
+        if self.part(fourcc).is_some() {
+            return Err(DxError::InvalidBytecode(format!(
+                "part {} already exists",
+                fourcc
+            )));
+        }
+
+        self.parts.push((fourcc, data.into()));
+
+        Ok(())
+    }
+
+    /// Replaces the data of a part in place, returns the previous data if the part exists.
+    pub fn replace_part(&mut self, fourcc: FourCC, data: impl Into<Vec<u8>>) -> Option<Vec<u8>> {
+        self.parts
+            .iter_mut()
+            .find(|(code, _)| *code == fourcc)
+            .map(|(_, old)| std::mem::replace(old, data.into()))
+    }
+
+    /// Replaces the data of a part or appends a new one, returns the previous data.
+    pub fn set_part(&mut self, fourcc: FourCC, data: impl Into<Vec<u8>>) -> Option<Vec<u8>> {
+        let data = data.into();
+
+        match self.parts.iter_mut().find(|(code, _)| *code == fourcc) {
+            Some((_, old)) => Some(std::mem::replace(old, data)),
+            None => {
+                self.parts.push((fourcc, data));
+                None
+            }
+        }
+    }
+
+    /// Removes all parts with the code, returns the data of the first one.
+    pub fn remove_part(&mut self, fourcc: FourCC) -> Option<Vec<u8>> {
+        let index = self.parts.iter().position(|(code, _)| *code == fourcc)?;
+        let (_, data) = self.parts.remove(index);
+        self.parts.retain(|(code, _)| *code != fourcc);
+
+        Some(data)
+    }
+
+    /// Serializes the container and signs it. Parts are aligned to 4 bytes.
+    pub fn build(&self) -> Blob {
+        let table_size = HEADER_SIZE + self.parts.len() * 4;
+        let size = self.parts.iter().fold(table_size, |size, (_, data)| {
+            size + PART_HEADER_SIZE + data.len().next_multiple_of(4)
+        });
+
+        let mut out = Vec::with_capacity(size);
+        out.extend_from_slice(&DXBC_MAGIC);
+        out.extend_from_slice(&[0; 16]);
+        out.extend_from_slice(&DXBC_VERSION.to_le_bytes());
+        out.extend_from_slice(&(size as u32).to_le_bytes());
+        out.extend_from_slice(&(self.parts.len() as u32).to_le_bytes());
+
+        let mut offset = table_size;
+        for (_, data) in &self.parts {
+            out.extend_from_slice(&(offset as u32).to_le_bytes());
+            offset += PART_HEADER_SIZE + data.len().next_multiple_of(4);
+        }
+
+        for (fourcc, data) in &self.parts {
+            out.extend_from_slice(fourcc.as_bytes());
+            out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            out.extend_from_slice(data);
+            out.resize(out.len().next_multiple_of(4), 0);
+        }
+
+        let checksum = compute_checksum(&out[CHECKSUM_START..]);
+        out[4..CHECKSUM_START].copy_from_slice(&checksum);
+
+        out.into()
+    }
+}
+
+/// Recomputes the checksum of a container, e.g. after patching its parts in place.
+pub(crate) fn resign(data: &[u8]) -> Result<Blob, DxError> {
+    let container = DxbcContainer::parse(data)?;
+    let checksum = container.compute_checksum();
+
+    let mut out = container.as_bytes().to_vec();
+    out[4..CHECKSUM_START].copy_from_slice(&checksum);
+
+    Ok(out.into())
+}
+
+/// MD5 with the final block laid out the way the shader compiler does it:
+/// the bit count goes to the first dword and `(bits >> 2) | 1` to the last one.
+fn compute_checksum(data: &[u8]) -> [u8; 16] {
+    let bits = (data.len() as u32).wrapping_mul(8);
+    let full = data.len() - data.len() % 64;
+    let last = &data[full..];
+
+    let mut md5 = Md5::new();
+    md5.update(&data[..full]);
+
+    let mut padding = [0; 64];
+    padding[0] = 0x80;
+
+    if last.len() >= 56 {
+        md5.update(last);
+        md5.update(&padding[..64 - last.len()]);
+
+        let mut block = [0; 64];
+        block[..4].copy_from_slice(&bits.to_le_bytes());
+        block[60..].copy_from_slice(&((bits >> 2) | 1).to_le_bytes());
+        md5.update(&block);
+    } else {
+        md5.update(&bits.to_le_bytes());
+        md5.update(last);
+        md5.update(&padding[..56 - last.len()]);
+        md5.update(&((bits >> 2) | 1).to_le_bytes());
+    }
+
+    md5.digest()
+}
+
+struct Md5 {
+    state: [u32; 4],
+    buffer: [u8; 64],
+    len: usize,
+}
+
+impl Md5 {
+    #[rustfmt::skip]
+    const K: [u32; 64] = [
+        0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+        0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+        0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+        0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+        0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+        0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+        0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+        0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+    ];
+
+    const S: [u32; 16] = [7, 12, 17, 22, 5, 9, 14, 20, 4, 11, 16, 23, 6, 10, 15, 21];
+
+    fn new() -> Self {
+        Self {
+            state: [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476],
+            buffer: [0; 64],
+            len: 0,
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let n = (64 - self.len).min(data.len());
+            self.buffer[self.len..self.len + n].copy_from_slice(&data[..n]);
+            self.len += n;
+            data = &data[n..];
+
+            if self.len == 64 {
+                self.transform();
+                self.len = 0;
+            }
+        }
+    }
+
+    fn transform(&mut self) {
+        let m: [u32; 16] = std::array::from_fn(|i| {
+            u32::from_le_bytes(self.buffer[i * 4..i * 4 + 4].try_into().unwrap())
+        });
+        let [mut a, mut b, mut c, mut d] = self.state;
+
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+
+            let rotated = a
+                .wrapping_add(f)
+                .wrapping_add(Self::K[i])
+                .wrapping_add(m[g])
+                .rotate_left(Self::S[(i / 16) * 4 + i % 4]);
+
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(rotated);
+        }
+
+        for (state, value) in self.state.iter_mut().zip([a, b, c, d]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    /// The raw state, the caller is responsible for the final block.
+    fn digest(&self) -> [u8; 16] {
+        debug_assert_eq!(self.len, 0, "the last block isn't complete");
+
+        let mut out = [0; 16];
+        for (chunk, state) in out.chunks_exact_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&state.to_le_bytes());
+        }
+
+        out
+    }
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Result<u32, DxError> {
+    read_array(data, offset).map(u32::from_le_bytes)
+}
+
+fn read_array(data: &[u8], offset: usize) -> Result<[u8; 4], DxError> {
+    offset
+        .checked_add(4)
+        .and_then(|end| data.get(offset..end))
+        .map(|bytes| bytes.try_into().expect("slice has 4 bytes"))
+        .ok_or_else(|| DxError::InvalidBytecode(format!("unexpected end of data at {}", offset)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn md5(data: &[u8]) -> [u8; 16] {
+        let mut md5 = Md5::new();
+        md5.update(data);
+        md5.update(&[0x80]);
+        md5.update(&vec![0; (119 - data.len() % 64) % 64]);
+        md5.update(&((data.len() as u64) * 8).to_le_bytes());
+        md5.digest()
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn md5_test() {
+        assert_eq!(hex(&md5(b"")), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(hex(&md5(b"abc")), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(
+            hex(&md5(b"The quick brown fox jumps over the lazy dog")),
+            "9e107d9d372bb6826bd81d3542a419d6"
+        );
+    }
+
+    #[test]
+    fn build_and_edit_test() {
+        let blob = DxbcBuilder::new()
+            .with_part(FourCC::SHEX, vec![1, 2, 3, 4])
+            .with_part(FourCC::RDEF, vec![5, 6])
+            .with_part(FourCC::STAT, vec![7; 60])
+            .build();
+
+        let container = DxbcContainer::parse(&blob).unwrap();
+        assert!(container.verify_checksum());
+        assert_eq!(container.parts().len(), 3);
+        assert_eq!(container.part(FourCC::RDEF), Some(&[5, 6][..]));
+        assert_eq!(blob.len() % 4, 0);
+
+        let edited = DxbcBuilder::from_bytes(&blob)
+            .unwrap()
+            .without_part(FourCC::RDEF)
+            .with_part(FourCC::SHEX, vec![9; 8])
+            .with_part(FourCC::RTS0, vec![1; 12])
+            .build();
+
+        let container = DxbcContainer::parse(&edited).unwrap();
+        assert!(container.verify_checksum());
+        assert_eq!(
+            container
+                .parts()
+                .iter()
+                .map(|p| p.fourcc)
+                .collect::<Vec<_>>(),
+            [FourCC::SHEX, FourCC::STAT, FourCC::RTS0]
+        );
+        assert_eq!(container.part(FourCC::SHEX), Some(&[9; 8][..]));
+
+        let mut builder = DxbcBuilder::from_bytes(&edited).unwrap();
+        assert!(builder.add_part(FourCC::STAT, vec![]).is_err());
+        assert_eq!(builder.replace_part(FourCC::RDEF, vec![]), None);
+        assert_eq!(builder.remove_part(FourCC::RTS0), Some(vec![1; 12]));
+    }
+
+    #[test]
+    fn fxc_blob_test() {
+        use crate::blob::CompileOptions;
+
+        let source = r#"
+            cbuffer Constants : register(b0) { float4x4 transform; };
+
+            float4 main(float3 position : POSITION) : SV_Position {
+                return mul(transform, float4(position, 1.0));
+            }
+        "#;
+
+        let blob = CompileOptions::new("main", "vs_5_0")
+            .compile_from_source(source, "test.hlsl", None)
+            .unwrap()
+            .blob;
+
+        let container = DxbcContainer::parse(&blob).unwrap();
+        assert!(container.verify_checksum());
+        assert_eq!(container.as_bytes().len(), blob.len());
+        assert!(container.part(FourCC::RDEF).is_some());
+        assert!(container.part(FourCC::ISGN).is_some());
+        assert!(container.part(FourCC::OSGN).is_some());
+        assert!(container.shader_program().is_some());
+
+        // The builder lays out parts like the compiler does.
+        let rebuilt = DxbcBuilder::from_bytes(&blob).unwrap().build();
+        assert_eq!(&rebuilt[..], &blob[..]);
+
+        let stripped = DxbcBuilder::from_bytes(&blob)
+            .unwrap()
+            .without_part(FourCC::STAT)
+            .build();
+        let container = DxbcContainer::parse(&stripped).unwrap();
+        assert!(container.verify_checksum());
+        assert_eq!(container.part(FourCC::STAT), None);
+    }
+
+    #[test]
+    fn resign_test() {
+        let blob = DxbcBuilder::new()
+            .with_part(FourCC::SHEX, vec![1, 2, 3, 4])
+            .build();
+
+        let mut patched = blob.to_vec();
+        *patched.last_mut().unwrap() = 42;
+        assert!(!DxbcContainer::parse(&patched).unwrap().verify_checksum());
+
+        let resigned = resign(&patched).unwrap();
+        let container = DxbcContainer::parse(&resigned).unwrap();
+        assert!(container.verify_checksum());
+        assert_eq!(container.part(FourCC::SHEX), Some(&[1, 2, 3, 42][..]));
+
+        // Each branch of the final block layout.
+        for len in [0, 4, 28, 32, 36, 64, 100] {
+            let blob = DxbcBuilder::new()
+                .with_part(FourCC::PRIV, vec![3; len])
+                .build();
+            assert!(DxbcContainer::parse(&blob).unwrap().verify_checksum());
+        }
+    }
+}
@@ -0,0 +1,401 @@
+use std::{marker::PhantomData, ops::Range};
+
+use windows::Win32::Graphics::Direct3D12::*;
+
+use crate::{resources::Resource, types::*};
+
+/// Describes a range of texture subresources targeted by a [`TextureBarrier`].
+///
+/// For more information: [`D3D12_BARRIER_SUBRESOURCE_RANGE structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_barrier_subresource_range)
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct BarrierSubresourceRange(pub(crate) D3D12_BARRIER_SUBRESOURCE_RANGE);
+
+impl BarrierSubresourceRange {
+    /// Targets every subresource of the texture.
+    #[inline]
+    pub fn all() -> Self {
+        Self::subresource(BARRIER_ALL_SUBRESOURCES)
+    }
+
+    /// Targets a single subresource by its index.
+    #[inline]
+    pub fn subresource(index: u32) -> Self {
+        Self(D3D12_BARRIER_SUBRESOURCE_RANGE {
+            IndexOrFirstMipLevel: index,
+            ..Default::default()
+        })
+    }
+
+    #[inline]
+    pub fn new(mip_levels: Range<u32>, array_slices: Range<u32>, planes: Range<u32>) -> Self {
+        Self(D3D12_BARRIER_SUBRESOURCE_RANGE {
+            IndexOrFirstMipLevel: mip_levels.start,
+            NumMipLevels: mip_levels.len() as u32,
+            FirstArraySlice: array_slices.start,
+            NumArraySlices: array_slices.len() as u32,
+            FirstPlane: planes.start,
+            NumPlanes: planes.len() as u32,
+        })
+    }
+}
+
+impl Default for BarrierSubresourceRange {
+    #[inline]
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+/// Describes a barrier on all memory accesses, regardless of the resource.
+///
+/// By default the barrier doesn't synchronize and doesn't access memory.
+///
+/// For more information: [`D3D12_GLOBAL_BARRIER structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_global_barrier)
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct GlobalBarrier(pub(crate) D3D12_GLOBAL_BARRIER);
+
+impl Default for GlobalBarrier {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GlobalBarrier {
+    #[inline]
+    pub fn new() -> Self {
+        Self(D3D12_GLOBAL_BARRIER {
+            SyncBefore: D3D12_BARRIER_SYNC_NONE,
+            SyncAfter: D3D12_BARRIER_SYNC_NONE,
+            AccessBefore: D3D12_BARRIER_ACCESS_NO_ACCESS,
+            AccessAfter: D3D12_BARRIER_ACCESS_NO_ACCESS,
+        })
+    }
+
+    #[inline]
+    pub fn with_sync(mut self, before: BarrierSync, after: BarrierSync) -> Self {
+        self.0.SyncBefore = before.as_raw();
+        self.0.SyncAfter = after.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn with_access(mut self, before: BarrierAccess, after: BarrierAccess) -> Self {
+        self.0.AccessBefore = before.as_raw();
+        self.0.AccessAfter = after.as_raw();
+        self
+    }
+}
+
+/// Describes a barrier on texture subresources, which can also change their layout.
+///
+/// By default the barrier targets all subresources, doesn't synchronize and doesn't access them.
+///
+/// For more information: [`D3D12_TEXTURE_BARRIER structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_texture_barrier)
+#[derive(Debug, PartialEq)]
+#[repr(transparent)]
+pub struct TextureBarrier<'a>(pub(crate) D3D12_TEXTURE_BARRIER, PhantomData<&'a ()>);
+
+impl<'a> TextureBarrier<'a> {
+    #[inline]
+    pub fn new(resource: &'a Resource) -> Self {
+        Self(
+            D3D12_TEXTURE_BARRIER {
+                SyncBefore: D3D12_BARRIER_SYNC_NONE,
+                SyncAfter: D3D12_BARRIER_SYNC_NONE,
+                AccessBefore: D3D12_BARRIER_ACCESS_NO_ACCESS,
+                AccessAfter: D3D12_BARRIER_ACCESS_NO_ACCESS,
+                LayoutBefore: D3D12_BARRIER_LAYOUT_UNDEFINED,
+                LayoutAfter: D3D12_BARRIER_LAYOUT_UNDEFINED,
+                pResource: unsafe { std::mem::transmute_copy(&resource.0) },
+                Subresources: BarrierSubresourceRange::all().0,
+                Flags: D3D12_TEXTURE_BARRIER_FLAG_NONE,
+            },
+            PhantomData,
+        )
+    }
+
+    #[inline]
+    pub fn with_sync(mut self, before: BarrierSync, after: BarrierSync) -> Self {
+        self.0.SyncBefore = before.as_raw();
+        self.0.SyncAfter = after.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn with_access(mut self, before: BarrierAccess, after: BarrierAccess) -> Self {
+        self.0.AccessBefore = before.as_raw();
+        self.0.AccessAfter = after.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn with_layout(mut self, before: BarrierLayout, after: BarrierLayout) -> Self {
+        self.0.LayoutBefore = before.as_raw();
+        self.0.LayoutAfter = after.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn with_subresources(mut self, subresources: BarrierSubresourceRange) -> Self {
+        self.0.Subresources = subresources.0;
+        self
+    }
+
+    /// Discards the contents of the subresources, see [`TextureBarrierFlags::Discard`].
+    #[inline]
+    pub fn with_discard(mut self) -> Self {
+        self.0.Flags |= D3D12_TEXTURE_BARRIER_FLAG_DISCARD;
+        self
+    }
+
+    #[inline]
+    pub fn layout_before(&self) -> BarrierLayout {
+        self.0.LayoutBefore.into()
+    }
+
+    #[inline]
+    pub fn layout_after(&self) -> BarrierLayout {
+        self.0.LayoutAfter.into()
+    }
+
+    #[inline]
+    pub fn flags(&self) -> TextureBarrierFlags {
+        self.0.Flags.into()
+    }
+}
+
+impl Clone for TextureBarrier<'_> {
+    #[inline]
+    fn clone(&self) -> Self {
+        // The resource is borrowed, copying the pointer mustn't add a reference.
+        Self(unsafe { std::mem::transmute_copy(&self.0) }, PhantomData)
+    }
+}
+
+/// Describes a barrier on a whole buffer.
+///
+/// By default the barrier doesn't synchronize and doesn't access the buffer.
+///
+/// For more information: [`D3D12_BUFFER_BARRIER structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_buffer_barrier)
+#[derive(Debug, PartialEq)]
+#[repr(transparent)]
+pub struct BufferBarrier<'a>(pub(crate) D3D12_BUFFER_BARRIER, PhantomData<&'a ()>);
+
+impl<'a> BufferBarrier<'a> {
+    #[inline]
+    pub fn new(resource: &'a Resource) -> Self {
+        Self(
+            D3D12_BUFFER_BARRIER {
+                SyncBefore: D3D12_BARRIER_SYNC_NONE,
+                SyncAfter: D3D12_BARRIER_SYNC_NONE,
+                AccessBefore: D3D12_BARRIER_ACCESS_NO_ACCESS,
+                AccessAfter: D3D12_BARRIER_ACCESS_NO_ACCESS,
+                pResource: unsafe { std::mem::transmute_copy(&resource.0) },
+                Offset: 0,
+                Size: u64::MAX,
+            },
+            PhantomData,
+        )
+    }
+
+    #[inline]
+    pub fn with_sync(mut self, before: BarrierSync, after: BarrierSync) -> Self {
+        self.0.SyncBefore = before.as_raw();
+        self.0.SyncAfter = after.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn with_access(mut self, before: BarrierAccess, after: BarrierAccess) -> Self {
+        self.0.AccessBefore = before.as_raw();
+        self.0.AccessAfter = after.as_raw();
+        self
+    }
+}
+
+impl Clone for BufferBarrier<'_> {
+    #[inline]
+    fn clone(&self) -> Self {
+        // The resource is borrowed, copying the pointer mustn't add a reference.
+        Self(unsafe { std::mem::transmute_copy(&self.0) }, PhantomData)
+    }
+}
+
+/// Collects enhanced barriers to submit them with a single [`GraphicsCommandList7::barrier`](crate::command_list::GraphicsCommandList7::barrier) call.
+///
+/// For more information: [`D3D12_BARRIER_GROUP structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_barrier_group)
+#[derive(Clone, Debug, Default)]
+pub struct BarrierGroup<'a> {
+    globals: Vec<GlobalBarrier>,
+    textures: Vec<TextureBarrier<'a>>,
+    buffers: Vec<BufferBarrier<'a>>,
+}
+
+impl<'a> BarrierGroup<'a> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_global(mut self, barrier: GlobalBarrier) -> Self {
+        self.push_global(barrier);
+        self
+    }
+
+    #[inline]
+    pub fn with_texture(mut self, barrier: TextureBarrier<'a>) -> Self {
+        self.push_texture(barrier);
+        self
+    }
+
+    #[inline]
+    pub fn with_buffer(mut self, barrier: BufferBarrier<'a>) -> Self {
+        self.push_buffer(barrier);
+        self
+    }
+
+    #[inline]
+    pub fn push_global(&mut self, barrier: GlobalBarrier) {
+        self.globals.push(barrier);
+    }
+
+    #[inline]
+    pub fn push_texture(&mut self, barrier: TextureBarrier<'a>) {
+        self.textures.push(barrier);
+    }
+
+    #[inline]
+    pub fn push_buffer(&mut self, barrier: BufferBarrier<'a>) {
+        self.buffers.push(barrier);
+    }
+
+    #[inline]
+    pub fn globals(&self) -> &[GlobalBarrier] {
+        &self.globals
+    }
+
+    #[inline]
+    pub fn textures(&self) -> &[TextureBarrier<'a>] {
+        &self.textures
+    }
+
+    #[inline]
+    pub fn buffers(&self) -> &[BufferBarrier<'a>] {
+        &self.buffers
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.globals.len() + self.textures.len() + self.buffers.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.globals.clear();
+        self.textures.clear();
+        self.buffers.clear();
+    }
+
+    /// Builds one group per non-empty barrier type. The groups borrow the barriers of `self`.
+    pub(crate) fn as_raw(&self) -> ([D3D12_BARRIER_GROUP; 3], usize) {
+        let mut groups = [D3D12_BARRIER_GROUP::default(); 3];
+        let mut count = 0;
+
+        if !self.globals.is_empty() {
+            groups[count] = D3D12_BARRIER_GROUP {
+                Type: D3D12_BARRIER_TYPE_GLOBAL,
+                NumBarriers: self.globals.len() as u32,
+                Anonymous: D3D12_BARRIER_GROUP_0 {
+                    pGlobalBarriers: self.globals.as_ptr() as *const _,
+                },
+            };
+            count += 1;
+        }
+
+        if !self.textures.is_empty() {
+            groups[count] = D3D12_BARRIER_GROUP {
+                Type: D3D12_BARRIER_TYPE_TEXTURE,
+                NumBarriers: self.textures.len() as u32,
+                Anonymous: D3D12_BARRIER_GROUP_0 {
+                    pTextureBarriers: self.textures.as_ptr() as *const _,
+                },
+            };
+            count += 1;
+        }
+
+        if !self.buffers.is_empty() {
+            groups[count] = D3D12_BARRIER_GROUP {
+                Type: D3D12_BARRIER_TYPE_BUFFER,
+                NumBarriers: self.buffers.len() as u32,
+                Anonymous: D3D12_BARRIER_GROUP_0 {
+                    pBufferBarriers: self.buffers.as_ptr() as *const _,
+                },
+            };
+            count += 1;
+        }
+
+        (groups, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subresource_range_test() {
+        let all = BarrierSubresourceRange::all();
+        assert_eq!(all.0.IndexOrFirstMipLevel, u32::MAX);
+        assert_eq!(all.0.NumMipLevels, 0);
+
+        let range = BarrierSubresourceRange::new(1..3, 0..6, 0..1);
+        assert_eq!(range.0.IndexOrFirstMipLevel, 1);
+        assert_eq!(range.0.NumMipLevels, 2);
+        assert_eq!(range.0.NumArraySlices, 6);
+        assert_eq!(range.0.NumPlanes, 1);
+    }
+
+    #[test]
+    fn barrier_group_test() {
+        let group = BarrierGroup::new()
+            .with_global(
+                GlobalBarrier::new()
+                    .with_sync(BarrierSync::ComputeShading, BarrierSync::Draw)
+                    .with_access(
+                        BarrierAccess::UnorderedAccess,
+                        BarrierAccess::IndirectArgument,
+                    ),
+            )
+            .with_global(GlobalBarrier::new());
+
+        let (groups, count) = group.as_raw();
+        assert_eq!(count, 1);
+        assert_eq!(group.len(), 2);
+        assert_eq!(groups[0].Type, D3D12_BARRIER_TYPE_GLOBAL);
+        assert_eq!(groups[0].NumBarriers, 2);
+
+        unsafe {
+            let global = &*groups[0].Anonymous.pGlobalBarriers;
+            assert_eq!(global.SyncBefore, D3D12_BARRIER_SYNC_COMPUTE_SHADING);
+            assert_eq!(global.SyncAfter, D3D12_BARRIER_SYNC_DRAW);
+            assert_eq!(global.AccessBefore, D3D12_BARRIER_ACCESS_UNORDERED_ACCESS);
+            assert_eq!(global.AccessAfter, D3D12_BARRIER_ACCESS_INDIRECT_ARGUMENT);
+
+            let global = &*groups[0].Anonymous.pGlobalBarriers.add(1);
+            assert_eq!(global.SyncBefore, D3D12_BARRIER_SYNC_NONE);
+            assert_eq!(global.AccessBefore, D3D12_BARRIER_ACCESS_NO_ACCESS);
+            assert_eq!(global.AccessAfter, D3D12_BARRIER_ACCESS_NO_ACCESS);
+        }
+
+        assert!(BarrierGroup::new().as_raw().1 == 0);
+    }
+}
@@ -51,6 +51,26 @@ pub enum DxError {
     #[error("{0}")]
     ShaderCompilationError(String),
 
+    /// Rust constant buffer layout doesn't match the shader reflection data
+    #[error("Constant buffer layout mismatch: {0}")]
+    CbufferLayoutMismatch(String),
+
+    /// Input layout doesn't satisfy the vertex shader input signature
+    #[error("Input layout mismatch: {0}")]
+    InputLayoutMismatch(String),
+
+    /// Shader source preprocessing error
+    #[error("{0}")]
+    PreprocessError(String),
+
+    /// Malformed shader bytecode or DXBC container
+    #[error("Invalid shader bytecode: {0}")]
+    InvalidBytecode(String),
+
+    /// File system error
+    #[error("I/O error: {0}")]
+    Io(String),
+
     /// Unknown type of error
     #[error("{0}")]
     Other(String),
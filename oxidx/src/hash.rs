@@ -0,0 +1,637 @@
+use std::{ffi::CStr, hash::Hasher};
+
+use windows::{
+    core::{Interface, PCSTR},
+    Win32::Graphics::{Direct3D12::*, Dxgi::Common::DXGI_SAMPLE_DESC},
+};
+
+use crate::root_signature::RootSignature;
+
+const FNV_OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+const FNV_PRIME: u128 = 0x0000000001000000000000000000013b;
+
+/// 128-bit FNV-1a hasher.
+///
+/// Unlike `DefaultHasher` the result is stable between runs, processes and Rust versions,
+/// so it can be used for keys that are stored on disk.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub(crate) struct StableHasher(u128);
+
+impl Default for StableHasher {
+    #[inline]
+    fn default() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+}
+
+impl StableHasher {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes a length-prefixed byte string, so adjacent strings can't be confused.
+    #[inline]
+    pub(crate) fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+
+    #[inline]
+    pub(crate) fn finish128(&self) -> u128 {
+        self.0
+    }
+}
+
+impl Hasher for StableHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        (self.0 ^ (self.0 >> 64)) as u64
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u128;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.write(&[i]);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write(&(i as u64).to_le_bytes());
+    }
+}
+
+/// Hashes the contents of a description instead of the addresses it points to,
+/// so together with [`StableHasher`] the hash is stable between runs.
+///
+/// Pointers in the descriptions must be valid, which the borrowing wrappers guarantee.
+pub(crate) trait ContentHash {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H);
+}
+
+/// Writes the bits of a float, `-0.0` is written as `0.0` and all NaNs are written as [`f32::NAN`].
+#[inline]
+pub(crate) fn write_f32<H: Hasher>(hasher: &mut H, value: f32) {
+    let value = match value {
+        0.0 => 0.0,
+        value if value.is_nan() => f32::NAN,
+        value => value,
+    };
+
+    hasher.write_u32(value.to_bits());
+}
+
+/// Collects everything a [`ContentHash`] writes, so descriptions compare equal exactly when they hash the same.
+#[derive(Default)]
+pub(crate) struct ContentBytes(Vec<u8>);
+
+impl ContentBytes {
+    pub(crate) fn of<T: ContentHash>(value: &T) -> Self {
+        let mut bytes = Self::default();
+        value.hash_content(&mut bytes);
+        bytes
+    }
+}
+
+impl PartialEq for ContentBytes {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Hasher for ContentBytes {
+    #[inline]
+    fn finish(&self) -> u64 {
+        let mut hasher = StableHasher::new();
+        hasher.write(&self.0);
+        hasher.finish()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+}
+
+/// Writes a length-prefixed byte string, like [`StableHasher::write_bytes`].
+pub(crate) fn write_bytes<H: Hasher>(hasher: &mut H, bytes: &[u8]) {
+    hasher.write_usize(bytes.len());
+    hasher.write(bytes);
+}
+
+pub(crate) unsafe fn slice<'a, T>(ptr: *const T, len: usize) -> &'a [T] {
+    if ptr.is_null() || len == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(ptr, len)
+    }
+}
+
+pub(crate) unsafe fn cstr<'a>(ptr: PCSTR) -> &'a [u8] {
+    if ptr.is_null() {
+        &[]
+    } else {
+        CStr::from_ptr(ptr.0 as *const _).to_bytes()
+    }
+}
+
+impl<T: ContentHash> ContentHash for [T] {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_usize(self.len());
+
+        for item in self {
+            item.hash_content(hasher);
+        }
+    }
+}
+
+impl ContentHash for Option<ID3D12RootSignature> {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        hash_root_signature(self.as_ref(), hasher);
+    }
+}
+
+/// Returns `true` if the root signature is hashed by its content, so its hash is the same in every run.
+pub(crate) fn has_stable_hash(raw: &Option<ID3D12RootSignature>) -> bool {
+    raw.as_ref().is_none_or(|raw| {
+        let root_signature =
+            unsafe { &*(raw as *const ID3D12RootSignature as *const RootSignature) };
+        root_signature.content_hash().is_some()
+    })
+}
+
+/// Root signatures created from a blob are identified by its hash, others only by their address.
+pub(crate) fn hash_root_signature<H: Hasher>(raw: Option<&ID3D12RootSignature>, hasher: &mut H) {
+    let Some(raw) = raw else {
+        hasher.write_u8(0);
+        return;
+    };
+
+    let root_signature = unsafe { &*(raw as *const ID3D12RootSignature as *const RootSignature) };
+
+    match root_signature.content_hash() {
+        Some(hash) => {
+            hasher.write_u8(1);
+            hasher.write_u128(hash);
+        }
+        None => {
+            hasher.write_u8(2);
+            hasher.write_usize(raw.as_raw() as usize);
+        }
+    }
+}
+
+impl ContentHash for D3D12_SHADER_BYTECODE {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        write_bytes(hasher, unsafe {
+            slice(self.pShaderBytecode as *const u8, self.BytecodeLength)
+        });
+    }
+}
+
+impl ContentHash for D3D12_SO_DECLARATION_ENTRY {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_u32(self.Stream);
+        write_bytes(hasher, unsafe { cstr(self.SemanticName) });
+        hasher.write_u32(self.SemanticIndex);
+        hasher.write_u8(self.StartComponent);
+        hasher.write_u8(self.ComponentCount);
+        hasher.write_u8(self.OutputSlot);
+    }
+}
+
+impl ContentHash for D3D12_STREAM_OUTPUT_DESC {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        unsafe {
+            slice(self.pSODeclaration, self.NumEntries as usize).hash_content(hasher);
+
+            let strides = slice(self.pBufferStrides, self.NumStrides as usize);
+            hasher.write_usize(strides.len());
+            strides.iter().for_each(|stride| hasher.write_u32(*stride));
+        }
+
+        hasher.write_u32(self.RasterizedStream);
+    }
+}
+
+impl ContentHash for D3D12_RENDER_TARGET_BLEND_DESC {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_i32(self.BlendEnable.0);
+        hasher.write_i32(self.LogicOpEnable.0);
+        hasher.write_i32(self.SrcBlend.0);
+        hasher.write_i32(self.DestBlend.0);
+        hasher.write_i32(self.BlendOp.0);
+        hasher.write_i32(self.SrcBlendAlpha.0);
+        hasher.write_i32(self.DestBlendAlpha.0);
+        hasher.write_i32(self.BlendOpAlpha.0);
+        hasher.write_i32(self.LogicOp.0);
+        hasher.write_u8(self.RenderTargetWriteMask);
+    }
+}
+
+impl ContentHash for D3D12_BLEND_DESC {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_i32(self.AlphaToCoverageEnable.0);
+        hasher.write_i32(self.IndependentBlendEnable.0);
+        self.RenderTarget.hash_content(hasher);
+    }
+}
+
+impl ContentHash for D3D12_RASTERIZER_DESC {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_i32(self.FillMode.0);
+        hasher.write_i32(self.CullMode.0);
+        hasher.write_i32(self.FrontCounterClockwise.0);
+        hasher.write_i32(self.DepthBias);
+        write_f32(hasher, self.DepthBiasClamp);
+        write_f32(hasher, self.SlopeScaledDepthBias);
+        hasher.write_i32(self.DepthClipEnable.0);
+        hasher.write_i32(self.MultisampleEnable.0);
+        hasher.write_i32(self.AntialiasedLineEnable.0);
+        hasher.write_u32(self.ForcedSampleCount);
+        hasher.write_i32(self.ConservativeRaster.0);
+    }
+}
+
+impl ContentHash for D3D12_DEPTH_STENCILOP_DESC {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_i32(self.StencilFailOp.0);
+        hasher.write_i32(self.StencilDepthFailOp.0);
+        hasher.write_i32(self.StencilPassOp.0);
+        hasher.write_i32(self.StencilFunc.0);
+    }
+}
+
+impl ContentHash for D3D12_DEPTH_STENCIL_DESC {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_i32(self.DepthEnable.0);
+        hasher.write_i32(self.DepthWriteMask.0);
+        hasher.write_i32(self.DepthFunc.0);
+        hasher.write_i32(self.StencilEnable.0);
+        hasher.write_u8(self.StencilReadMask);
+        hasher.write_u8(self.StencilWriteMask);
+        self.FrontFace.hash_content(hasher);
+        self.BackFace.hash_content(hasher);
+    }
+}
+
+impl ContentHash for D3D12_RASTERIZER_DESC1 {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_i32(self.FillMode.0);
+        hasher.write_i32(self.CullMode.0);
+        hasher.write_i32(self.FrontCounterClockwise.0);
+        write_f32(hasher, self.DepthBias);
+        write_f32(hasher, self.DepthBiasClamp);
+        write_f32(hasher, self.SlopeScaledDepthBias);
+        hasher.write_i32(self.DepthClipEnable.0);
+        hasher.write_i32(self.MultisampleEnable.0);
+        hasher.write_i32(self.AntialiasedLineEnable.0);
+        hasher.write_u32(self.ForcedSampleCount);
+        hasher.write_i32(self.ConservativeRaster.0);
+    }
+}
+
+impl ContentHash for D3D12_RASTERIZER_DESC2 {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_i32(self.FillMode.0);
+        hasher.write_i32(self.CullMode.0);
+        hasher.write_i32(self.FrontCounterClockwise.0);
+        write_f32(hasher, self.DepthBias);
+        write_f32(hasher, self.DepthBiasClamp);
+        write_f32(hasher, self.SlopeScaledDepthBias);
+        hasher.write_i32(self.DepthClipEnable.0);
+        hasher.write_i32(self.LineRasterizationMode.0);
+        hasher.write_u32(self.ForcedSampleCount);
+        hasher.write_i32(self.ConservativeRaster.0);
+    }
+}
+
+impl ContentHash for D3D12_DEPTH_STENCILOP_DESC1 {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_i32(self.StencilFailOp.0);
+        hasher.write_i32(self.StencilDepthFailOp.0);
+        hasher.write_i32(self.StencilPassOp.0);
+        hasher.write_i32(self.StencilFunc.0);
+        hasher.write_u8(self.StencilReadMask);
+        hasher.write_u8(self.StencilWriteMask);
+    }
+}
+
+impl ContentHash for D3D12_DEPTH_STENCIL_DESC1 {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_i32(self.DepthEnable.0);
+        hasher.write_i32(self.DepthWriteMask.0);
+        hasher.write_i32(self.DepthFunc.0);
+        hasher.write_i32(self.StencilEnable.0);
+        hasher.write_u8(self.StencilReadMask);
+        hasher.write_u8(self.StencilWriteMask);
+        self.FrontFace.hash_content(hasher);
+        self.BackFace.hash_content(hasher);
+        hasher.write_i32(self.DepthBoundsTestEnable.0);
+    }
+}
+
+impl ContentHash for D3D12_DEPTH_STENCIL_DESC2 {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_i32(self.DepthEnable.0);
+        hasher.write_i32(self.DepthWriteMask.0);
+        hasher.write_i32(self.DepthFunc.0);
+        hasher.write_i32(self.StencilEnable.0);
+        self.FrontFace.hash_content(hasher);
+        self.BackFace.hash_content(hasher);
+        hasher.write_i32(self.DepthBoundsTestEnable.0);
+    }
+}
+
+impl ContentHash for D3D12_SAMPLER_DESC {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_i32(self.Filter.0);
+        hasher.write_i32(self.AddressU.0);
+        hasher.write_i32(self.AddressV.0);
+        hasher.write_i32(self.AddressW.0);
+        write_f32(hasher, self.MipLODBias);
+        hasher.write_u32(self.MaxAnisotropy);
+        hasher.write_i32(self.ComparisonFunc.0);
+        self.BorderColor.iter().for_each(|c| write_f32(hasher, *c));
+        write_f32(hasher, self.MinLOD);
+        write_f32(hasher, self.MaxLOD);
+    }
+}
+
+impl ContentHash for D3D12_STATIC_SAMPLER_DESC {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_i32(self.Filter.0);
+        hasher.write_i32(self.AddressU.0);
+        hasher.write_i32(self.AddressV.0);
+        hasher.write_i32(self.AddressW.0);
+        write_f32(hasher, self.MipLODBias);
+        hasher.write_u32(self.MaxAnisotropy);
+        hasher.write_i32(self.ComparisonFunc.0);
+        hasher.write_i32(self.BorderColor.0);
+        write_f32(hasher, self.MinLOD);
+        write_f32(hasher, self.MaxLOD);
+        hasher.write_u32(self.ShaderRegister);
+        hasher.write_u32(self.RegisterSpace);
+        hasher.write_i32(self.ShaderVisibility.0);
+    }
+}
+
+impl ContentHash for DXGI_SAMPLE_DESC {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_u32(self.Count);
+        hasher.write_u32(self.Quality);
+    }
+}
+
+impl ContentHash for D3D12_RT_FORMAT_ARRAY {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        self.RTFormats
+            .iter()
+            .for_each(|format| hasher.write_i32(format.0));
+        hasher.write_u32(self.NumRenderTargets);
+    }
+}
+
+impl ContentHash for D3D12_VIEW_INSTANCING_DESC {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        let locations =
+            unsafe { slice(self.pViewInstanceLocations, self.ViewInstanceCount as usize) };
+        hasher.write_usize(locations.len());
+
+        for location in locations {
+            hasher.write_u32(location.ViewportArrayIndex);
+            hasher.write_u32(location.RenderTargetArrayIndex);
+        }
+
+        hasher.write_i32(self.Flags.0);
+    }
+}
+
+impl ContentHash for D3D12_INPUT_ELEMENT_DESC {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        write_bytes(hasher, unsafe { cstr(self.SemanticName) });
+        hasher.write_u32(self.SemanticIndex);
+        hasher.write_i32(self.Format.0);
+        hasher.write_u32(self.InputSlot);
+        hasher.write_u32(self.AlignedByteOffset);
+        hasher.write_i32(self.InputSlotClass.0);
+        hasher.write_u32(self.InstanceDataStepRate);
+    }
+}
+
+impl ContentHash for D3D12_INPUT_LAYOUT_DESC {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        unsafe { slice(self.pInputElementDescs, self.NumElements as usize) }.hash_content(hasher);
+    }
+}
+
+impl ContentHash for D3D12_GRAPHICS_PIPELINE_STATE_DESC {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        write_bytes(hasher, b"graphics");
+        self.pRootSignature.hash_content(hasher);
+        self.VS.hash_content(hasher);
+        self.PS.hash_content(hasher);
+        self.DS.hash_content(hasher);
+        self.HS.hash_content(hasher);
+        self.GS.hash_content(hasher);
+        self.StreamOutput.hash_content(hasher);
+        self.BlendState.hash_content(hasher);
+        hasher.write_u32(self.SampleMask);
+        self.RasterizerState.hash_content(hasher);
+        self.DepthStencilState.hash_content(hasher);
+        self.InputLayout.hash_content(hasher);
+        hasher.write_i32(self.IBStripCutValue.0);
+        hasher.write_i32(self.PrimitiveTopologyType.0);
+        hasher.write_u32(self.NumRenderTargets);
+        self.RTVFormats
+            .iter()
+            .for_each(|format| hasher.write_i32(format.0));
+        hasher.write_i32(self.DSVFormat.0);
+        hasher.write_u32(self.SampleDesc.Count);
+        hasher.write_u32(self.SampleDesc.Quality);
+        hasher.write_u32(self.NodeMask);
+        hasher.write_i32(self.Flags.0);
+    }
+}
+
+impl ContentHash for D3D12_COMPUTE_PIPELINE_STATE_DESC {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        write_bytes(hasher, b"compute");
+        self.pRootSignature.hash_content(hasher);
+        self.CS.hash_content(hasher);
+        hasher.write_u32(self.NodeMask);
+        hasher.write_i32(self.Flags.0);
+    }
+}
+
+/// Implements `content_hash` for description wrappers.
+///
+/// * `hash_eq` also implements `Hash` and `Eq` for types without floats, the derived `PartialEq` is kept.
+/// * `float_eq` also implements `PartialEq`, `Eq` and `Hash` for types with floats. `-0.0` equals `0.0`
+///   and NaN equals NaN, like in [`write_f32`].
+/// * `content` only implements `content_hash` for types with pointers, their `PartialEq` compares addresses.
+macro_rules! impl_content_hash {
+    (hash_eq: $($t:ty),* $(,)?) => {
+        $(
+            impl Eq for $t {}
+
+            impl std::hash::Hash for $t {
+                #[inline]
+                fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                    $crate::hash::ContentHash::hash_content(&self.0, state);
+                }
+            }
+
+            impl_content_hash!(content: $t);
+        )*
+    };
+    (float_eq: $($t:ty),* $(,)?) => {
+        $(
+            impl PartialEq for $t {
+                #[inline]
+                fn eq(&self, other: &Self) -> bool {
+                    $crate::hash::ContentBytes::of(&self.0) == $crate::hash::ContentBytes::of(&other.0)
+                }
+            }
+
+            impl_content_hash!(hash_eq: $t);
+        )*
+    };
+    (content: $($t:ty),* $(,)?) => {
+        $(
+            impl $t {
+                /// Returns a hash of all fields, pointers are hashed by the contents they point to.
+                pub fn content_hash(&self) -> u128 {
+                    let mut hasher = $crate::hash::StableHasher::new();
+                    $crate::hash::ContentHash::hash_content(&self.0, &mut hasher);
+                    hasher.finish128()
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) use impl_content_hash;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{blob::Blob, pipeline_stream::PipelineStateStream, types::*};
+
+    #[test]
+    fn state_desc_key_test() {
+        let samplers: HashSet<_> = [
+            SamplerDesc::linear(),
+            SamplerDesc::point(),
+            SamplerDesc::linear(),
+            SamplerDesc::linear().with_mip_lod_bias(-0.5),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(samplers.len(), 3);
+        assert!(samplers.contains(&SamplerDesc::linear().with_mip_lod_bias(-0.5)));
+
+        // Equal descriptions have equal hashes, `-0.0` equals `0.0` and NaN equals NaN.
+        let rasterizer = RasterizerDesc::default().with_depth_bias_clamp(0.0);
+        assert_eq!(
+            rasterizer,
+            RasterizerDesc::default().with_depth_bias_clamp(-0.0)
+        );
+        assert_eq!(
+            rasterizer.content_hash(),
+            RasterizerDesc::default()
+                .with_depth_bias_clamp(-0.0)
+                .content_hash()
+        );
+        assert_ne!(
+            rasterizer,
+            RasterizerDesc::default().with_depth_bias_clamp(1.0)
+        );
+
+        let nan = RasterizerDesc::default().with_depth_bias_clamp(f32::NAN);
+        assert_eq!(nan, nan);
+        assert_eq!(
+            nan,
+            RasterizerDesc::default().with_depth_bias_clamp(-f32::NAN)
+        );
+        assert_eq!(
+            nan.content_hash(),
+            RasterizerDesc::default()
+                .with_depth_bias_clamp(-f32::NAN)
+                .content_hash()
+        );
+
+        assert_eq!(
+            BlendDesc::default().content_hash(),
+            BlendDesc::default().content_hash()
+        );
+        assert_ne!(
+            BlendDesc::default().content_hash(),
+            BlendDesc::default()
+                .enable_alpha_to_coverage()
+                .content_hash()
+        );
+    }
+
+    #[test]
+    fn stream_content_hash_test() {
+        let ms = Blob::copy_from_slice(b"mesh shader");
+        let same_ms = Blob::copy_from_slice(b"mesh shader");
+        let other_ms = Blob::copy_from_slice(b"other mesh shader");
+        let cache = Blob::copy_from_slice(b"cache");
+        let layout = [InputElementDesc::from_raw_per_vertex(
+            c"POSITION",
+            0,
+            Format::Rgb32Float,
+            0,
+        )];
+
+        let stream = |ms| {
+            PipelineStateStream::mesh(ms)
+                .with_render_targets([Format::Rgba8Unorm])
+                .unwrap()
+                .with_input_layout(&layout)
+        };
+
+        let hash = stream(&ms).content_hash();
+        assert_eq!(hash, stream(&same_ms).content_hash());
+        assert_eq!(hash, stream(&ms).with_cache(&cache).content_hash());
+        assert_ne!(hash, stream(&other_ms).content_hash());
+        assert_ne!(
+            hash,
+            stream(&ms)
+                .with_rasterizer_state1(RasterizerDesc1::default())
+                .content_hash()
+        );
+    }
+}
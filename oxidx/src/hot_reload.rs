@@ -0,0 +1,487 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Condvar, Mutex, RwLock,
+    },
+    thread::JoinHandle,
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    blob::Blob,
+    error::DxError,
+    include::FileIncludeHandler,
+    preprocessor::Preprocessor,
+    shader_cache::{ShaderCache, ShaderPermutation},
+};
+
+/// Callback that receives errors of failed recompilations.
+pub type ShaderErrorCallback = Box<dyn Fn(&ShaderPermutation, &DxError) + Send + Sync>;
+
+/// Result of [`ShaderCompiler::compile`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompiledPermutation {
+    pub blob: Blob,
+
+    /// Files the blob was compiled from: the source itself and all transitively included files.
+    pub dependencies: Vec<PathBuf>,
+}
+
+/// Compiles shader permutations for [`ShaderHotReload`].
+pub trait ShaderCompiler: Send + Sync {
+    fn compile(&self, permutation: &ShaderPermutation) -> Result<CompiledPermutation, DxError>;
+}
+
+/// [`ShaderCompiler`] that uses FXC, reads includes from disk and optionally goes through a [`ShaderCache`].
+#[derive(Clone, Debug, Default)]
+pub struct FxcShaderCompiler {
+    include: FileIncludeHandler,
+    cache: Option<ShaderCache>,
+}
+
+impl FxcShaderCompiler {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_include_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.include = self.include.with_include_dir(dir);
+        self
+    }
+
+    #[inline]
+    pub fn with_cache(mut self, cache: ShaderCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+}
+
+impl ShaderCompiler for FxcShaderCompiler {
+    fn compile(&self, permutation: &ShaderPermutation) -> Result<CompiledPermutation, DxError> {
+        let path = permutation.path.as_path();
+        let source =
+            fs::read(path).map_err(|err| DxError::Io(format!("{}: {}", path.display(), err)))?;
+        let source = String::from_utf8_lossy(&source);
+        let options = &permutation.options;
+
+        let expanded = Preprocessor::new(&self.include)
+            .with_defines(options.defines().iter().map(|(n, d)| (n, d)))
+            .preprocess(&source, path)?;
+
+        let blob = match &self.cache {
+            Some(cache) => cache.compile_preprocessed(
+                &expanded,
+                &*source,
+                path.to_string_lossy(),
+                options,
+                &self.include,
+            )?,
+            None => {
+                options
+                    .compile_from_source(
+                        source.as_bytes(),
+                        path.to_string_lossy(),
+                        Some(&self.include),
+                    )?
+                    .blob
+            }
+        };
+
+        Ok(CompiledPermutation {
+            blob,
+            dependencies: expanded.files().to_vec(),
+        })
+    }
+}
+
+/// Versioned handle to the latest successfully compiled blob of a permutation.
+///
+/// The renderer polls [`ShaderHandle::version`] and recreates pipelines when it changes.
+#[derive(Clone, Debug)]
+pub struct ShaderHandle(Arc<HandleState>);
+
+#[derive(Debug)]
+struct HandleState {
+    permutation: ShaderPermutation,
+    version: AtomicU64,
+    blob: RwLock<Blob>,
+}
+
+impl ShaderHandle {
+    fn new(permutation: ShaderPermutation, blob: Blob) -> Self {
+        Self(Arc::new(HandleState {
+            permutation,
+            version: AtomicU64::new(1),
+            blob: RwLock::new(blob),
+        }))
+    }
+
+    fn publish(&self, blob: Blob) {
+        *self.0.blob.write().unwrap_or_else(|e| e.into_inner()) = blob;
+        self.0.version.fetch_add(1, Ordering::Release);
+    }
+
+    #[inline]
+    pub fn permutation(&self) -> &ShaderPermutation {
+        &self.0.permutation
+    }
+
+    /// Version of the blob, starts at 1 and grows with every successful recompilation.
+    #[inline]
+    pub fn version(&self) -> u64 {
+        self.0.version.load(Ordering::Acquire)
+    }
+
+    /// The last successfully compiled blob.
+    #[inline]
+    pub fn blob(&self) -> Blob {
+        self.0
+            .blob
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// The last successfully compiled blob and its version.
+    #[inline]
+    pub fn get(&self) -> (u64, Blob) {
+        let blob = self.0.blob.read().unwrap_or_else(|e| e.into_inner());
+        (self.version(), blob.clone())
+    }
+}
+
+/// Modification stamp of a file, `None` if the file doesn't exist.
+type FileStamp = Option<(SystemTime, u64)>;
+
+struct Entry {
+    handle: ShaderHandle,
+    files: Vec<(PathBuf, FileStamp)>,
+}
+
+struct Shared {
+    compiler: Box<dyn ShaderCompiler>,
+    on_error: Option<ShaderErrorCallback>,
+    entries: Mutex<Vec<Entry>>,
+    stop: Mutex<bool>,
+    wake: Condvar,
+}
+
+/// Watches shader sources with their transitive includes and recompiles permutations when they change.
+///
+/// Changes are detected by polling file modification times, either with [`ShaderHotReload::poll`]
+/// or on a background thread started with [`ShaderHotReload::spawn`]. If a recompilation fails,
+/// the error is reported to the error callback and the last good blob stays active.
+pub struct ShaderHotReload {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ShaderHotReload {
+    #[inline]
+    pub fn new(compiler: impl ShaderCompiler + 'static) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                compiler: Box::new(compiler),
+                on_error: None,
+                entries: Mutex::new(vec![]),
+                stop: Mutex::new(false),
+                wake: Condvar::new(),
+            }),
+            thread: None,
+        }
+    }
+
+    /// Sets the callback for errors of recompilations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the background thread is already running.
+    #[inline]
+    pub fn with_error_callback(
+        mut self,
+        callback: impl Fn(&ShaderPermutation, &DxError) + Send + Sync + 'static,
+    ) -> Self {
+        Arc::get_mut(&mut self.shared)
+            .expect("the error callback must be set before spawning the watcher thread")
+            .on_error = Some(Box::new(callback));
+        self
+    }
+
+    /// Compiles a permutation and starts watching its files.
+    ///
+    /// The initial compilation must succeed, there is no good blob to fall back to otherwise.
+    pub fn watch(&self, permutation: ShaderPermutation) -> Result<ShaderHandle, DxError> {
+        let compiled = self.shared.compiler.compile(&permutation)?;
+        let files = stamp_files(&permutation, compiled.dependencies, &mut HashMap::new());
+        let handle = ShaderHandle::new(permutation, compiled.blob);
+
+        self.shared.lock_entries().push(Entry {
+            handle: handle.clone(),
+            files,
+        });
+
+        Ok(handle)
+    }
+
+    /// Checks watched files and recompiles affected permutations on the calling thread.
+    ///
+    /// Returns the number of successfully recompiled permutations.
+    #[inline]
+    pub fn poll(&self) -> usize {
+        self.shared.poll()
+    }
+
+    /// Starts polling on a background thread. The thread is stopped when the service is dropped.
+    pub fn spawn(&mut self, interval: Duration) -> Result<(), DxError> {
+        if self.thread.is_some() {
+            return Ok(());
+        }
+
+        let shared = Arc::clone(&self.shared);
+
+        let thread = std::thread::Builder::new()
+            .name("oxidx-shader-hot-reload".to_string())
+            .spawn(move || loop {
+                let stop = shared.stop.lock().unwrap_or_else(|e| e.into_inner());
+                let (stop, _) = shared
+                    .wake
+                    .wait_timeout_while(stop, interval, |stop| !*stop)
+                    .unwrap_or_else(|e| e.into_inner());
+
+                if *stop {
+                    break;
+                }
+
+                drop(stop);
+                shared.poll();
+            })
+            .map_err(|err| DxError::Other(err.to_string()))?;
+
+        self.thread = Some(thread);
+
+        Ok(())
+    }
+}
+
+impl Drop for ShaderHotReload {
+    fn drop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            *self.shared.stop.lock().unwrap_or_else(|e| e.into_inner()) = true;
+            self.shared.wake.notify_all();
+
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Shared {
+    fn lock_entries(&self) -> std::sync::MutexGuard<'_, Vec<Entry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn poll(&self) -> usize {
+        let mut stamps = HashMap::new();
+
+        // Compilation happens without holding the lock, so `watch` isn't blocked by it.
+        let dirty = self
+            .lock_entries()
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| {
+                entry
+                    .files
+                    .iter()
+                    .any(|(path, stamp)| file_stamp(path, &mut stamps) != *stamp)
+            })
+            .map(|(index, entry)| (index, entry.handle.clone()))
+            .collect::<Vec<_>>();
+
+        let mut recompiled = 0;
+
+        for (index, handle) in dirty {
+            let permutation = handle.permutation();
+
+            match self.compiler.compile(permutation) {
+                Ok(compiled) => {
+                    let files = stamp_files(permutation, compiled.dependencies, &mut stamps);
+                    handle.publish(compiled.blob);
+                    self.lock_entries()[index].files = files;
+                    recompiled += 1;
+                }
+                Err(err) => {
+                    if let Some(on_error) = &self.on_error {
+                        on_error(permutation, &err);
+                    }
+
+                    // Keep the old dependencies, but don't report the same error until the files change again.
+                    for (path, stamp) in &mut self.lock_entries()[index].files {
+                        *stamp = file_stamp(path, &mut stamps);
+                    }
+                }
+            }
+        }
+
+        recompiled
+    }
+}
+
+fn file_stamp(path: &PathBuf, stamps: &mut HashMap<PathBuf, FileStamp>) -> FileStamp {
+    *stamps.entry(path.clone()).or_insert_with(|| {
+        fs::metadata(path)
+            .ok()
+            .and_then(|m| Some((m.modified().ok()?, m.len())))
+    })
+}
+
+fn stamp_files(
+    permutation: &ShaderPermutation,
+    dependencies: Vec<PathBuf>,
+    stamps: &mut HashMap<PathBuf, FileStamp>,
+) -> Vec<(PathBuf, FileStamp)> {
+    let mut files = dependencies;
+
+    if !files.contains(&permutation.path) {
+        files.insert(0, permutation.path.clone());
+    }
+
+    files
+        .into_iter()
+        .map(|path| {
+            let stamp = file_stamp(&path, stamps);
+            (path, stamp)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::blob::CompileOptions;
+
+    /// Returns the source as the blob. `include <file>` lines are dependencies, `error` fails the compilation.
+    struct FakeCompiler;
+
+    impl ShaderCompiler for FakeCompiler {
+        fn compile(&self, permutation: &ShaderPermutation) -> Result<CompiledPermutation, DxError> {
+            let mut dependencies = vec![permutation.path.clone()];
+            let mut blob = vec![];
+            let mut i = 0;
+
+            while i < dependencies.len() {
+                let source = fs::read_to_string(&dependencies[i])
+                    .map_err(|err| DxError::Io(err.to_string()))?;
+
+                for line in source.lines() {
+                    if line == "error" {
+                        return Err(DxError::ShaderCompilationError("error".to_string()));
+                    }
+
+                    match line.strip_prefix("include ") {
+                        Some(name) => {
+                            let dir = permutation.path.parent().unwrap();
+                            dependencies.push(dir.join(name));
+                        }
+                        None => blob.extend_from_slice(line.as_bytes()),
+                    }
+                }
+
+                i += 1;
+            }
+
+            Ok(CompiledPermutation {
+                blob: blob.into(),
+                dependencies,
+            })
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("oxidx-hot-reload-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn recompile_on_include_change_test() {
+        let dir = temp_dir("poll");
+        fs::write(dir.join("main.hlsl"), "include common.hlsli\nmain\n").unwrap();
+        fs::write(dir.join("common.hlsli"), "include math.hlsli\ncommon\n").unwrap();
+        fs::write(dir.join("math.hlsli"), "math\n").unwrap();
+
+        let errors = Arc::new(Mutex::new(vec![]));
+        let sink = Arc::clone(&errors);
+
+        let service = ShaderHotReload::new(FakeCompiler).with_error_callback(move |_, err| {
+            sink.lock().unwrap().push(err.to_string());
+        });
+
+        let handle = service
+            .watch(ShaderPermutation::new(
+                dir.join("main.hlsl"),
+                CompileOptions::new("main", "ps_5_0"),
+            ))
+            .unwrap();
+
+        assert_eq!(handle.get(), (1, Blob::from_static(b"maincommonmath")));
+        assert_eq!(service.poll(), 0);
+
+        // A change in a transitive include triggers a recompilation.
+        fs::write(dir.join("math.hlsli"), "math2\n").unwrap();
+        assert_eq!(service.poll(), 1);
+        assert_eq!(handle.get(), (2, Blob::from_static(b"maincommonmath2")));
+
+        // Errors keep the last good blob and are reported once.
+        fs::write(dir.join("common.hlsli"), "error\n").unwrap();
+        assert_eq!(service.poll(), 0);
+        assert_eq!(service.poll(), 0);
+        assert_eq!(handle.get(), (2, Blob::from_static(b"maincommonmath2")));
+        assert_eq!(errors.lock().unwrap().len(), 1);
+
+        // The include was removed, `math.hlsli` isn't a dependency anymore.
+        fs::write(dir.join("common.hlsli"), "common2\n").unwrap();
+        assert_eq!(service.poll(), 1);
+        assert_eq!(handle.get(), (3, Blob::from_static(b"maincommon2")));
+
+        fs::write(dir.join("math.hlsli"), "math33\n").unwrap();
+        assert_eq!(service.poll(), 0);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn background_thread_test() {
+        let dir = temp_dir("thread");
+        fs::write(dir.join("main.hlsl"), "main\n").unwrap();
+
+        let mut service = ShaderHotReload::new(FakeCompiler);
+        let handle = service
+            .watch(ShaderPermutation::new(
+                dir.join("main.hlsl"),
+                CompileOptions::new("main", "ps_5_0"),
+            ))
+            .unwrap();
+
+        service.spawn(Duration::from_millis(5)).unwrap();
+        fs::write(dir.join("main.hlsl"), "main2\n").unwrap();
+
+        let start = Instant::now();
+
+        while handle.version() == 1 && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        assert_eq!(handle.get(), (2, Blob::from_static(b"main2")));
+
+        drop(service);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,368 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::c_void,
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use windows::{
+    core::PCSTR,
+    Win32::{
+        Foundation::{E_FAIL, E_INVALIDARG},
+        Graphics::Direct3D::{ID3DInclude_Impl, D3D_INCLUDE_TYPE},
+    },
+};
+
+use crate::types::IncludeType;
+
+/// Contents of a file opened by an [`IncludeHandler`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IncludeFile {
+    /// Resolved path of the file. It is passed back to the handler as the parent of nested includes.
+    pub path: PathBuf,
+
+    /// Source code of the file.
+    pub data: Vec<u8>,
+}
+
+impl IncludeFile {
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            path: path.into(),
+            data: data.into(),
+        }
+    }
+}
+
+/// A user-implemented handler the shader compiler calls back into to open `#include` files.
+///
+/// For more information: [`ID3DInclude interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3dcommon/nn-d3dcommon-id3dinclude)
+pub trait IncludeHandler {
+    /// Opens an include file.
+    ///
+    /// `name` is the path written in the `#include` directive and `parent` is the resolved path of the file
+    /// that contains the directive. `parent` is `None` when the includer is the root source and it has no path.
+    ///
+    /// For more information: [`ID3DInclude::Open method`](https://learn.microsoft.com/en-us/windows/win32/api/d3dcommon/nf-d3dcommon-id3dinclude-open)
+    fn open(
+        &self,
+        include_type: IncludeType,
+        name: &str,
+        parent: Option<&Path>,
+    ) -> io::Result<IncludeFile>;
+}
+
+impl<F> IncludeHandler for F
+where
+    F: Fn(IncludeType, &str, Option<&Path>) -> io::Result<IncludeFile>,
+{
+    #[inline]
+    fn open(
+        &self,
+        include_type: IncludeType,
+        name: &str,
+        parent: Option<&Path>,
+    ) -> io::Result<IncludeFile> {
+        self(include_type, name, parent)
+    }
+}
+
+/// Include handler that reads files from disk.
+///
+/// Local includes (`#include "file"`) are searched in the directory of the includer first,
+/// then in the include directories. System includes (`#include <file>`) are searched only in the include directories.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileIncludeHandler {
+    include_dirs: Vec<PathBuf>,
+}
+
+impl FileIncludeHandler {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_include_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.include_dirs.push(dir.into());
+        self
+    }
+
+    #[inline]
+    pub fn include_dirs(&self) -> &[PathBuf] {
+        &self.include_dirs
+    }
+}
+
+impl IncludeHandler for FileIncludeHandler {
+    fn open(
+        &self,
+        include_type: IncludeType,
+        name: &str,
+        parent: Option<&Path>,
+    ) -> io::Result<IncludeFile> {
+        for path in candidates(include_type, name, parent, &self.include_dirs) {
+            if path.is_file() {
+                let data = std::fs::read(&path)?;
+                return Ok(IncludeFile { path, data });
+            }
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("include file `{name}` not found"),
+        ))
+    }
+}
+
+/// Include handler backed by an in-memory virtual file system.
+///
+/// Paths are resolved the same way as in [`FileIncludeHandler`], but files are looked up in a map.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryIncludeHandler {
+    files: HashMap<PathBuf, Vec<u8>>,
+    include_dirs: Vec<PathBuf>,
+}
+
+impl MemoryIncludeHandler {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_file(mut self, path: impl AsRef<Path>, data: impl Into<Vec<u8>>) -> Self {
+        self.insert(path, data);
+        self
+    }
+
+    #[inline]
+    pub fn with_include_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.include_dirs.push(normalize_path(dir.as_ref()));
+        self
+    }
+
+    /// Adds or replaces a file of the virtual file system.
+    #[inline]
+    pub fn insert(&mut self, path: impl AsRef<Path>, data: impl Into<Vec<u8>>) {
+        self.files
+            .insert(normalize_path(path.as_ref()), data.into());
+    }
+
+    /// Removes a file from the virtual file system.
+    #[inline]
+    pub fn remove(&mut self, path: impl AsRef<Path>) -> Option<Vec<u8>> {
+        self.files.remove(&normalize_path(path.as_ref()))
+    }
+
+    #[inline]
+    pub fn get(&self, path: impl AsRef<Path>) -> Option<&[u8]> {
+        self.files
+            .get(&normalize_path(path.as_ref()))
+            .map(|data| data.as_slice())
+    }
+}
+
+impl IncludeHandler for MemoryIncludeHandler {
+    fn open(
+        &self,
+        include_type: IncludeType,
+        name: &str,
+        parent: Option<&Path>,
+    ) -> io::Result<IncludeFile> {
+        let mut candidates = candidates(include_type, name, parent, &self.include_dirs);
+
+        // The root of the virtual file system is an implicit include directory.
+        candidates.push(normalize_path(Path::new(name)));
+
+        for path in candidates {
+            if let Some(data) = self.files.get(&path) {
+                return Ok(IncludeFile {
+                    path,
+                    data: data.clone(),
+                });
+            }
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("include file `{name}` not found"),
+        ))
+    }
+}
+
+/// Returns the paths an include directive may refer to, in search order.
+fn candidates(
+    include_type: IncludeType,
+    name: &str,
+    parent: Option<&Path>,
+    include_dirs: &[PathBuf],
+) -> Vec<PathBuf> {
+    let name = Path::new(name);
+
+    if name.is_absolute() {
+        return vec![normalize_path(name)];
+    }
+
+    let mut paths = Vec::with_capacity(include_dirs.len() + 1);
+
+    if include_type == IncludeType::Local {
+        let dir = parent.and_then(Path::parent).unwrap_or(Path::new(""));
+        paths.push(normalize_path(&dir.join(name)));
+    }
+
+    paths.extend(
+        include_dirs
+            .iter()
+            .map(|dir| normalize_path(&dir.join(name))),
+    );
+    paths
+}
+
+/// Lexically removes `.` and `..` components and unifies separators.
+pub(crate) fn normalize_path(path: &Path) -> PathBuf {
+    let unified = path.to_string_lossy().replace('\\', "/");
+    let mut result = PathBuf::new();
+
+    for component in Path::new(&unified).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if matches!(
+                    result.components().next_back(),
+                    None | Some(Component::ParentDir)
+                ) {
+                    result.push("..");
+                } else {
+                    result.pop();
+                }
+            }
+            other => result.push(other),
+        }
+    }
+
+    result
+}
+
+/// File kept alive until the compiler closes it.
+struct OpenedFile {
+    path: PathBuf,
+    data: Box<[u8]>,
+}
+
+/// Adapts an [`IncludeHandler`] to the `ID3DInclude` interface.
+pub(crate) struct IncludeBridge<'a> {
+    handler: &'a dyn IncludeHandler,
+    root: Option<PathBuf>,
+    opened: RefCell<HashMap<usize, OpenedFile>>,
+}
+
+impl<'a> IncludeBridge<'a> {
+    pub(crate) fn new(handler: &'a dyn IncludeHandler, root: Option<&Path>) -> Self {
+        Self {
+            handler,
+            root: root.map(normalize_path),
+            opened: Default::default(),
+        }
+    }
+}
+
+impl ID3DInclude_Impl for IncludeBridge<'_> {
+    fn Open(
+        &self,
+        includetype: D3D_INCLUDE_TYPE,
+        pfilename: &PCSTR,
+        pparentdata: *const c_void,
+        ppdata: *mut *mut c_void,
+        pbytes: *mut u32,
+    ) -> windows::core::Result<()> {
+        let name = unsafe { pfilename.to_string() }.map_err(|_| E_INVALIDARG)?;
+
+        let parent = if pparentdata.is_null() {
+            self.root.clone()
+        } else {
+            self.opened
+                .borrow()
+                .get(&(pparentdata as usize))
+                .map(|file| file.path.clone())
+        };
+
+        let file = self
+            .handler
+            .open(includetype.into(), &name, parent.as_deref())
+            .map_err(|_| E_FAIL)?;
+
+        let len = u32::try_from(file.data.len()).map_err(|_| E_INVALIDARG)?;
+
+        // A trailing zero keeps the pointer unique even for empty files.
+        let mut data = file.data;
+        data.push(0);
+        let data = data.into_boxed_slice();
+
+        unsafe {
+            *ppdata = data.as_ptr() as *mut _;
+            *pbytes = len;
+        }
+
+        self.opened.borrow_mut().insert(
+            data.as_ptr() as usize,
+            OpenedFile {
+                path: file.path,
+                data,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn Close(&self, pdata: *const c_void) -> windows::core::Result<()> {
+        self.opened.borrow_mut().remove(&(pdata as usize));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_path_test() {
+        assert_eq!(
+            normalize_path(Path::new("shaders/./common/../lighting.hlsli")),
+            PathBuf::from("shaders/lighting.hlsli")
+        );
+        assert_eq!(
+            normalize_path(Path::new("..\\shared\\math.hlsli")),
+            PathBuf::from("../shared/math.hlsli")
+        );
+    }
+
+    #[test]
+    fn memory_include_handler_test() {
+        let handler = MemoryIncludeHandler::new()
+            .with_file("shaders/common.hlsli", "float4 Common;")
+            .with_file("shaders/lights/point.hlsli", "float3 Point;")
+            .with_file("include/math.hlsli", "float Pi;")
+            .with_include_dir("include");
+
+        let parent = Path::new("shaders/lights/point.hlsli");
+
+        let file = handler
+            .open(IncludeType::Local, "../common.hlsli", Some(parent))
+            .unwrap();
+        assert_eq!(file.path, PathBuf::from("shaders/common.hlsli"));
+        assert_eq!(file.data, b"float4 Common;");
+
+        let file = handler
+            .open(IncludeType::System, "math.hlsli", Some(parent))
+            .unwrap();
+        assert_eq!(file.path, PathBuf::from("include/math.hlsli"));
+
+        assert!(handler
+            .open(IncludeType::System, "../common.hlsli", Some(parent))
+            .is_err());
+    }
+}
@@ -0,0 +1,648 @@
+use std::ffi::{CStr, CString};
+
+use windows::Win32::Graphics::Direct3D12::D3D12_APPEND_ALIGNED_ELEMENT;
+
+use crate::{
+    dxbc::{read_u32, DxbcContainer, FourCC},
+    error::DxError,
+    reflection::ShaderReflection,
+    types::*,
+};
+
+/// Element of a shader input, output or patch constant signature.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct SignatureElement {
+    pub semantic_name: CString,
+    pub semantic_index: u32,
+    pub register: u32,
+    pub system_value: ShaderVarName,
+    pub component_type: RegisterComponentType,
+    /// Components declared by the shader.
+    pub mask: u8,
+    /// Components the shader reads (for inputs) or never writes (for outputs).
+    pub read_write_mask: u8,
+    pub stream: u32,
+    pub min_precision: MinPrecision,
+}
+
+impl SignatureElement {
+    /// Number of declared components.
+    #[inline]
+    pub fn component_count(&self) -> u32 {
+        self.mask.count_ones()
+    }
+
+    /// Number of components a vertex format must provide for the components the shader reads.
+    ///
+    /// The format fills the declared components in order, so the count includes the declared components
+    /// before the last read one.
+    #[inline]
+    pub fn required_component_count(&self) -> u32 {
+        let used = match self.read_write_mask {
+            0 => self.mask,
+            mask => mask,
+        } as u32;
+        let up_to_last_used = (1u32 << (u32::BITS - used.leading_zeros())) - 1;
+
+        (self.mask as u32 & up_to_last_used).count_ones()
+    }
+
+    /// Returns `true` for values generated by the system, such as `SV_VertexID`, which aren't fetched by the input assembler.
+    #[inline]
+    pub fn is_system_value(&self) -> bool {
+        self.system_value != ShaderVarName::Undefined
+    }
+
+    #[inline]
+    fn matches(&self, semantic_name: &CStr, semantic_index: u32) -> bool {
+        self.semantic_index == semantic_index
+            && self
+                .semantic_name
+                .to_bytes()
+                .eq_ignore_ascii_case(semantic_name.to_bytes())
+    }
+}
+
+impl From<SignatureParameterDesc> for SignatureElement {
+    fn from(desc: SignatureParameterDesc) -> Self {
+        Self {
+            semantic_name: desc.semantic_name().to_owned(),
+            semantic_index: desc.semantic_index(),
+            register: desc.register(),
+            system_value: desc.system_value_type(),
+            component_type: desc.component_type(),
+            mask: desc.mask(),
+            read_write_mask: desc.read_write_mask(),
+            stream: desc.stream(),
+            min_precision: desc.min_precision(),
+        }
+    }
+}
+
+/// Owned shader signature read from DXBC bytecode or from a reflection interface.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct ShaderSignature {
+    elements: Vec<SignatureElement>,
+}
+
+impl ShaderSignature {
+    /// Parses the input signature (`ISGN` or `ISG1`) of a DXBC container.
+    #[inline]
+    pub fn input_from_bytecode(bytecode: &[u8]) -> Result<Self, DxError> {
+        Self::from_bytecode(bytecode, &[FourCC::ISG1, FourCC::ISGN])
+    }
+
+    /// Parses the output signature (`OSGN`, `OSG5` or `OSG1`) of a DXBC container.
+    #[inline]
+    pub fn output_from_bytecode(bytecode: &[u8]) -> Result<Self, DxError> {
+        Self::from_bytecode(bytecode, &[FourCC::OSG1, FourCC::OSG5, FourCC::OSGN])
+    }
+
+    /// Parses the patch constant signature (`PCSG` or `PSG1`) of a DXBC container.
+    #[inline]
+    pub fn patch_constant_from_bytecode(bytecode: &[u8]) -> Result<Self, DxError> {
+        Self::from_bytecode(bytecode, &[FourCC::PSG1, FourCC::PCSG])
+    }
+
+    /// Reads the input signature with [`ShaderReflection::get_input_parameter_desc`].
+    pub fn input_from_reflection(reflection: &ShaderReflection) -> Result<Self, DxError> {
+        let count = reflection.get_desc()?.get_input_parameters() as usize;
+
+        let elements = (0..count)
+            .map(|i| reflection.get_input_parameter_desc(i).map(Into::into))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { elements })
+    }
+
+    /// Parses a signature part of a DXBC container, the layout of elements depends on the code of the part.
+    pub fn parse(fourcc: FourCC, data: &[u8]) -> Result<Self, DxError> {
+        // Size of an element, offsets of the stream and the min precision fields.
+        let (size, stream, min_precision) = match fourcc {
+            FourCC::ISGN | FourCC::OSGN | FourCC::PCSG => (24, None, None),
+            FourCC::OSG5 => (28, Some(0), None),
+            FourCC::ISG1 | FourCC::OSG1 | FourCC::PSG1 => (32, Some(0), Some(28)),
+            _ => {
+                return Err(DxError::InvalidBytecode(format!(
+                    "{} isn't a signature part",
+                    fourcc
+                )))
+            }
+        };
+
+        let count = read_u32(data, 0)? as usize;
+        let start = read_u32(data, 4)? as usize;
+        let base = if stream.is_some() { 4 } else { 0 };
+
+        let elements = (0..count)
+            .map(|i| {
+                let offset = start + i * size;
+                let field = |index: usize| read_u32(data, offset + base + index * 4);
+                let masks = field(5)?;
+
+                let semantic_name = data
+                    .get(field(0)? as usize..)
+                    .and_then(|name| CStr::from_bytes_until_nul(name).ok())
+                    .ok_or_else(|| {
+                        DxError::InvalidBytecode(format!("invalid semantic name in {}", fourcc))
+                    })?;
+
+                Ok(SignatureElement {
+                    semantic_name: semantic_name.to_owned(),
+                    semantic_index: field(1)?,
+                    system_value: ShaderVarName::from_repr(field(2)? as i32).unwrap_or_default(),
+                    component_type: RegisterComponentType::from_repr(field(3)? as i32)
+                        .unwrap_or_default(),
+                    register: field(4)?,
+                    mask: masks as u8,
+                    read_write_mask: (masks >> 8) as u8,
+                    stream: match stream {
+                        Some(stream) => read_u32(data, offset + stream)?,
+                        None => 0,
+                    },
+                    min_precision: match min_precision {
+                        Some(min_precision) => {
+                            MinPrecision::from_repr(read_u32(data, offset + min_precision)? as i32)
+                                .unwrap_or_default()
+                        }
+                        None => MinPrecision::Default,
+                    },
+                })
+            })
+            .collect::<Result<_, DxError>>()?;
+
+        Ok(Self { elements })
+    }
+
+    fn from_bytecode(bytecode: &[u8], parts: &[FourCC]) -> Result<Self, DxError> {
+        let container = DxbcContainer::parse(bytecode)?;
+
+        parts
+            .iter()
+            .find_map(|fourcc| {
+                container
+                    .part(*fourcc)
+                    .map(|data| Self::parse(*fourcc, data))
+            })
+            .unwrap_or_else(|| {
+                Err(DxError::InvalidBytecode(format!(
+                    "container has no {} part",
+                    parts[parts.len() - 1]
+                )))
+            })
+    }
+
+    #[inline]
+    pub fn elements(&self) -> &[SignatureElement] {
+        &self.elements
+    }
+
+    /// Finds an element by its semantic, semantic names are case-insensitive.
+    #[inline]
+    pub fn find(&self, semantic_name: &CStr, semantic_index: u32) -> Option<&SignatureElement> {
+        self.elements
+            .iter()
+            .find(|e| e.matches(semantic_name, semantic_index))
+    }
+
+    /// Generates an input layout for the signature, see [`InputLayout::from_signature`].
+    #[inline]
+    pub fn input_layout(&self) -> Result<InputLayout, DxError> {
+        InputLayout::from_signature(self)
+    }
+
+    /// Checks that an input layout provides every element of the input signature
+    /// with enough components of a compatible type.
+    pub fn validate_input_layout(&self, layout: &[InputElementDesc<'_>]) -> Result<(), DxError> {
+        let errors = self
+            .elements
+            .iter()
+            .filter(|e| !e.is_system_value())
+            .filter_map(|element| {
+                let name = element.semantic_name.to_string_lossy();
+                let index = element.semantic_index;
+
+                let Some(desc) = layout
+                    .iter()
+                    .find(|desc| element.matches(desc.semantic_name(), desc.semantic_index()))
+                else {
+                    return Some(format!("{}{} isn't provided by the layout", name, index));
+                };
+
+                let format = desc.format();
+                let Some((count, kind)) = format_components(format) else {
+                    return Some(format!(
+                        "{}{} has format {:?} that can't be used for vertex data",
+                        name, index, format
+                    ));
+                };
+
+                let needed = element.required_component_count();
+
+                if count < needed {
+                    return Some(format!(
+                        "{}{} reads {} components, but {:?} has {}",
+                        name, index, needed, format, count
+                    ));
+                }
+
+                match component_kind(element.component_type) {
+                    Some(expected) if expected != kind => Some(format!(
+                        "{}{} is {:?}, but {:?} is {:?}",
+                        name, index, element.component_type, format, kind
+                    )),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(DxError::InputLayoutMismatch(errors.join("; ")))
+        }
+    }
+}
+
+/// Owned element of an [`InputLayout`].
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct InputLayoutElement {
+    pub semantic_name: CString,
+    pub semantic_index: u32,
+    pub format: Format,
+    pub input_slot: u32,
+    /// Byte offset in the slot, `D3D12_APPEND_ALIGNED_ELEMENT` places the element right after the previous one.
+    pub offset: u32,
+    /// Step rate of per-instance data, `None` for per-vertex data.
+    pub instance_data_step_rate: Option<u32>,
+}
+
+impl InputLayoutElement {
+    #[inline]
+    pub fn as_desc(&self) -> InputElementDesc<'_> {
+        let desc = InputElementDesc::from_raw_per_vertex(
+            &self.semantic_name,
+            self.semantic_index,
+            self.format,
+            self.input_slot,
+        )
+        .with_offset(self.offset);
+
+        match self.instance_data_step_rate {
+            Some(step_rate) => desc.with_instance_data_step_rate(step_rate),
+            None => desc,
+        }
+    }
+}
+
+/// Owned input layout, usually generated from a vertex shader input signature.
+///
+/// ```ignore
+/// let layout = ShaderSignature::input_from_bytecode(&vs)?
+///     .input_layout()?
+///     .with_per_instance("INSTANCE_TRANSFORM", 1)
+///     .with_slot("INSTANCE_TRANSFORM", 1);
+/// let descs = layout.descs();
+/// let pso_desc = GraphicsPipelineDesc::new(&vs).with_input_layout(&descs);
+/// ```
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct InputLayout {
+    elements: Vec<InputLayoutElement>,
+}
+
+impl InputLayout {
+    /// Creates per-vertex elements in slot 0 for every non-system-value input of the signature.
+    ///
+    /// The formats are 32-bit (or 16-bit for 16-bit component types) with the declared number of components.
+    pub fn from_signature(signature: &ShaderSignature) -> Result<Self, DxError> {
+        let elements = signature
+            .elements()
+            .iter()
+            .filter(|e| !e.is_system_value())
+            .map(|element| {
+                let format = infer_format(element.component_type, element.component_count())
+                    .ok_or_else(|| {
+                        DxError::InputLayoutMismatch(format!(
+                            "can't infer a format for {}{} with {} {:?} components",
+                            element.semantic_name.to_string_lossy(),
+                            element.semantic_index,
+                            element.component_count(),
+                            element.component_type
+                        ))
+                    })?;
+
+                Ok(InputLayoutElement {
+                    semantic_name: element.semantic_name.clone(),
+                    semantic_index: element.semantic_index,
+                    format,
+                    input_slot: 0,
+                    offset: D3D12_APPEND_ALIGNED_ELEMENT,
+                    instance_data_step_rate: None,
+                })
+            })
+            .collect::<Result<_, DxError>>()?;
+
+        Ok(Self { elements })
+    }
+
+    /// Moves all elements with the semantic name to an input slot.
+    #[inline]
+    pub fn with_slot(mut self, semantic_name: &str, input_slot: u32) -> Self {
+        self.elements_mut(semantic_name)
+            .for_each(|e| e.input_slot = input_slot);
+        self
+    }
+
+    /// Makes all elements with the semantic name per-instance data.
+    #[inline]
+    pub fn with_per_instance(mut self, semantic_name: &str, step_rate: u32) -> Self {
+        self.elements_mut(semantic_name)
+            .for_each(|e| e.instance_data_step_rate = Some(step_rate));
+        self
+    }
+
+    /// Overrides the inferred format of an element, e.g. to fetch colors as `Rgba8Unorm`.
+    #[inline]
+    pub fn with_format(mut self, semantic_name: &str, semantic_index: u32, format: Format) -> Self {
+        self.elements_mut(semantic_name)
+            .filter(|e| e.semantic_index == semantic_index)
+            .for_each(|e| e.format = format);
+        self
+    }
+
+    #[inline]
+    pub fn elements(&self) -> &[InputLayoutElement] {
+        &self.elements
+    }
+
+    /// Descriptions for [`GraphicsPipelineDesc::with_input_layout`], they borrow semantic names from the layout.
+    #[inline]
+    pub fn descs(&self) -> Vec<InputElementDesc<'_>> {
+        self.elements.iter().map(|e| e.as_desc()).collect()
+    }
+
+    fn elements_mut<'a>(
+        &'a mut self,
+        semantic_name: &'a str,
+    ) -> impl Iterator<Item = &'a mut InputLayoutElement> + 'a {
+        self.elements.iter_mut().filter(move |e| {
+            e.semantic_name
+                .to_bytes()
+                .eq_ignore_ascii_case(semantic_name.as_bytes())
+        })
+    }
+}
+
+/// Type of values the shader sees after the input assembler converts a format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ComponentKind {
+    Float,
+    Uint,
+    Sint,
+}
+
+fn component_kind(component_type: RegisterComponentType) -> Option<ComponentKind> {
+    match component_type {
+        RegisterComponentType::Float32 | RegisterComponentType::Float16 => {
+            Some(ComponentKind::Float)
+        }
+        RegisterComponentType::Uint32 | RegisterComponentType::Uint16 => Some(ComponentKind::Uint),
+        RegisterComponentType::Sint32 | RegisterComponentType::Sint16 => Some(ComponentKind::Sint),
+        _ => None,
+    }
+}
+
+fn infer_format(component_type: RegisterComponentType, count: u32) -> Option<Format> {
+    use RegisterComponentType as T;
+
+    let formats = match component_type {
+        T::Float32 => [
+            Format::R32Float,
+            Format::Rg32Float,
+            Format::Rgb32Float,
+            Format::Rgba32Float,
+        ],
+        T::Uint32 => [
+            Format::R32Uint,
+            Format::Rg32Uint,
+            Format::Rgb32Uint,
+            Format::Rgba32Uint,
+        ],
+        T::Sint32 => [
+            Format::R32Sint,
+            Format::Rg32Sint,
+            Format::Rgb32Sint,
+            Format::Rgba32Sint,
+        ],
+        // There are no three-component 16-bit formats.
+        T::Float16 => [
+            Format::R16Float,
+            Format::Rg16Float,
+            Format::Rgba16Float,
+            Format::Rgba16Float,
+        ],
+        T::Uint16 => [
+            Format::R16Uint,
+            Format::Rg16Uint,
+            Format::Rgba16Uint,
+            Format::Rgba16Uint,
+        ],
+        T::Sint16 => [
+            Format::R16Sint,
+            Format::Rg16Sint,
+            Format::Rgba16Sint,
+            Format::Rgba16Sint,
+        ],
+        _ => return None,
+    };
+
+    formats.get(count.checked_sub(1)? as usize).copied()
+}
+
+/// Number of components and their type in the shader for formats usable as vertex data.
+fn format_components(format: Format) -> Option<(u32, ComponentKind)> {
+    use ComponentKind::*;
+
+    let components = match format {
+        Format::Rgba32Float
+        | Format::Rgba16Float
+        | Format::Rgba16Unorm
+        | Format::Rgba16Snorm
+        | Format::Rgba8Unorm
+        | Format::Rgba8UnormSrgb
+        | Format::Rgba8Snorm
+        | Format::Bgra8Unorm
+        | Format::Rgb10A2Unorm => (4, Float),
+        Format::Rgba32Uint | Format::Rgba16Uint | Format::Rgba8Uint | Format::Rgb10A2Uint => {
+            (4, Uint)
+        }
+        Format::Rgba32Sint | Format::Rgba16Sint | Format::Rgba8Sint => (4, Sint),
+        Format::Rgb32Float | Format::Rg11B10Float | Format::B5G6R5Unorm => (3, Float),
+        Format::Rgb32Uint => (3, Uint),
+        Format::Rgb32Sint => (3, Sint),
+        Format::Rg32Float
+        | Format::Rg16Float
+        | Format::Rg16Unorm
+        | Format::Rg16Snorm
+        | Format::Rg8Unorm
+        | Format::Rg8Snorm => (2, Float),
+        Format::Rg32Uint | Format::Rg16Uint | Format::Rg8Uint => (2, Uint),
+        Format::Rg32Sint | Format::Rg16Sint | Format::Rg8Sint => (2, Sint),
+        Format::R32Float
+        | Format::R16Float
+        | Format::R16Unorm
+        | Format::R16Snorm
+        | Format::R8Unorm
+        | Format::R8Snorm => (1, Float),
+        Format::R32Uint | Format::R16Uint | Format::R8Uint => (1, Uint),
+        Format::R32Sint | Format::R16Sint | Format::R8Sint => (1, Sint),
+        _ => return None,
+    };
+
+    Some(components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dxbc::DxbcBuilder;
+
+    fn isgn(elements: &[(&str, u32, u32, u32, u8)]) -> Vec<u8> {
+        let mut data = vec![];
+        data.extend_from_slice(&(elements.len() as u32).to_le_bytes());
+        data.extend_from_slice(&8u32.to_le_bytes());
+
+        let mut names = vec![];
+        let names_start = 8 + elements.len() * 24;
+
+        for (register, (name, index, sv, ty, mask)) in elements.iter().enumerate() {
+            let offset = (names_start + names.len()) as u32;
+            names.extend_from_slice(name.as_bytes());
+            names.push(0);
+
+            for field in [offset, *index, *sv, *ty, register as u32] {
+                data.extend_from_slice(&field.to_le_bytes());
+            }
+            data.extend_from_slice(&[*mask, *mask, 0, 0]);
+        }
+
+        data.extend(names);
+        data
+    }
+
+    fn bytecode() -> Vec<u8> {
+        DxbcBuilder::new()
+            .with_part(
+                FourCC::ISGN,
+                isgn(&[
+                    ("POSITION", 0, 0, 3, 0b0111),
+                    ("TEXCOORD", 0, 0, 3, 0b0011),
+                    ("BLENDINDICES", 0, 0, 1, 0b1111),
+                    ("SV_VertexID", 0, 6, 1, 0b0001),
+                ]),
+            )
+            .build()
+            .to_vec()
+    }
+
+    #[test]
+    fn parse_signature_test() {
+        let signature = ShaderSignature::input_from_bytecode(&bytecode()).unwrap();
+
+        assert_eq!(signature.elements().len(), 4);
+
+        let texcoord = signature.find(c"texcoord", 0).unwrap();
+        assert_eq!(texcoord.register, 1);
+        assert_eq!(texcoord.component_type, RegisterComponentType::Float32);
+        assert_eq!(texcoord.component_count(), 2);
+
+        let vertex_id = &signature.elements()[3];
+        assert_eq!(vertex_id.system_value, ShaderVarName::VertexId);
+        assert!(vertex_id.is_system_value());
+
+        let packed = SignatureElement {
+            mask: 0b0100,
+            ..vertex_id.clone()
+        };
+        assert_eq!(packed.component_count(), 1);
+
+        assert!(ShaderSignature::output_from_bytecode(&bytecode()).is_err());
+    }
+
+    #[test]
+    fn packed_mask_layout_test() {
+        let bytecode = DxbcBuilder::new()
+            .with_part(
+                FourCC::ISGN,
+                isgn(&[("TEXCOORD", 0, 0, 3, 0b1100), ("COLOR", 0, 0, 3, 0b1110)]),
+            )
+            .build();
+        let signature = ShaderSignature::input_from_bytecode(&bytecode).unwrap();
+
+        let texcoord = signature.find(c"TEXCOORD", 0).unwrap();
+        assert_eq!(texcoord.required_component_count(), 2);
+
+        let color = SignatureElement {
+            read_write_mask: 0b0100,
+            ..signature.find(c"COLOR", 0).unwrap().clone()
+        };
+        assert_eq!(color.required_component_count(), 2);
+
+        let layout = InputLayout::from_signature(&signature).unwrap();
+        let descs = layout.descs();
+        assert_eq!(descs[0].format(), Format::Rg32Float);
+        assert_eq!(descs[1].format(), Format::Rgb32Float);
+
+        assert!(signature.validate_input_layout(&descs).is_ok());
+    }
+
+    #[test]
+    fn input_layout_test() {
+        let signature = ShaderSignature::input_from_bytecode(&bytecode()).unwrap();
+        let layout = signature
+            .input_layout()
+            .unwrap()
+            .with_slot("blendindices", 1)
+            .with_per_instance("BLENDINDICES", 1)
+            .with_format("BLENDINDICES", 0, Format::Rgba8Uint);
+
+        let descs = layout.descs();
+        assert_eq!(descs.len(), 3);
+        assert_eq!(descs[0].semantic_name(), c"POSITION");
+        assert_eq!(descs[0].format(), Format::Rgb32Float);
+        assert_eq!(descs[1].format(), Format::Rg32Float);
+        assert_eq!(descs[2].format(), Format::Rgba8Uint);
+        assert_eq!(descs[2].input_slot(), 1);
+        assert!(descs[2].is_per_instance());
+        assert!(!descs[0].is_per_instance());
+
+        assert!(signature.validate_input_layout(&descs).is_ok());
+    }
+
+    #[test]
+    fn validate_input_layout_test() {
+        let signature = ShaderSignature::input_from_bytecode(&bytecode()).unwrap();
+
+        let layout = [
+            InputElementDesc::per_vertex(SemanticName::Position(0), Format::Rgba32Float, 0),
+            InputElementDesc::per_vertex(SemanticName::TexCoord(0), Format::R32Float, 0),
+            InputElementDesc::per_vertex(SemanticName::BlendIndices(0), Format::Rgba8Unorm, 0),
+        ];
+
+        let Err(DxError::InputLayoutMismatch(error)) = signature.validate_input_layout(&layout)
+        else {
+            panic!("layout must be rejected");
+        };
+
+        assert!(error.contains("TEXCOORD0 reads 2 components"));
+        assert!(error.contains("BLENDINDICES0 is Uint32"));
+
+        let Err(DxError::InputLayoutMismatch(error)) =
+            signature.validate_input_layout(&layout[..1])
+        else {
+            panic!("layout must be rejected");
+        };
+
+        assert!(error.contains("TEXCOORD0 isn't provided"));
+    }
+}
@@ -2,41 +2,66 @@
 #![allow(dead_code)]
 #![allow(clippy::too_many_arguments)]
 
+extern crate self as oxidx;
+
 pub mod dx;
 
+pub mod acceleration_structure;
 pub mod adapter;
+pub mod barrier_batch;
 pub mod blob;
+pub mod cbuffer;
 pub mod command_allocator;
 pub mod command_list;
 pub mod command_queue;
+pub mod command_recorder;
 pub mod command_signature;
 pub mod debug;
 pub mod descriptor_heap;
 pub mod device;
 pub mod device_child;
+pub mod disasm;
+pub mod dxbc;
+pub mod enhanced_barrier;
 pub mod entry;
 pub mod error;
 pub mod ext;
 pub mod factory;
 pub mod heap;
+pub mod hot_reload;
+pub mod include;
+pub mod input_layout;
 
 #[cfg(feature = "callback")]
 pub mod info_queue;
 
 pub mod pageable;
+pub mod pipeline_cache;
+pub mod pipeline_stream;
+pub mod pipeline_validation;
+pub mod preprocessor;
+pub mod protected_session;
 pub mod pso;
 pub mod query_heap;
 pub mod reflection;
+pub mod render_pass;
 pub mod resources;
 pub mod root_signature;
+pub mod shader_cache;
+pub mod shader_table;
+pub mod state_object;
+pub mod state_tracker;
 pub mod swapchain;
 pub mod sync;
 pub mod types;
+pub mod vertex;
+pub mod work_graph;
 
 #[cfg(feature = "pix")]
 pub(crate) mod pix;
 
 mod conv;
+mod hash;
 mod utils;
 
 pub trait FeatureObject: __Sealed {
@@ -0,0 +1,378 @@
+use std::{
+    fmt, fs,
+    hash::Hasher,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{
+    device::Device1,
+    error::DxError,
+    hash::{has_stable_hash, StableHasher},
+    pso::{PipelineLibrary, PipelineState},
+    types::{ComputePipelineStateDesc, GraphicsPipelineDesc},
+};
+
+/// Version of the on-disk format. Files of other versions are discarded.
+const CACHE_FORMAT_VERSION: u32 = 1;
+const CACHE_MAGIC: [u8; 4] = *b"OXPL";
+
+// magic + version + library size + checksum
+const HEADER_SIZE: usize = 4 + 4 + 8 + 8;
+
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Key of a pipeline state object in a [`PipelineCache`].
+///
+/// It is the content hash of the description, see [`GraphicsPipelineDesc::content_hash`].
+/// There is no key for a root signature that wasn't created by
+/// [`Device::create_root_signature`](crate::device::Device::create_root_signature), its hash differs between runs.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PipelineCacheKey(u128);
+
+impl PipelineCacheKey {
+    #[inline]
+    pub fn from_graphics(desc: &GraphicsPipelineDesc<'_>) -> Option<Self> {
+        has_stable_hash(&desc.0.pRootSignature).then(|| Self(desc.content_hash()))
+    }
+
+    #[inline]
+    pub fn from_compute(desc: &ComputePipelineStateDesc<'_>) -> Option<Self> {
+        has_stable_hash(&desc.0.pRootSignature).then(|| Self(desc.content_hash()))
+    }
+
+    #[inline]
+    pub fn as_u128(&self) -> u128 {
+        self.0
+    }
+}
+
+impl fmt::Display for PipelineCacheKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+/// Disk-backed cache of pipeline state objects built on top of a [`PipelineLibrary`].
+///
+/// Pipelines are stored under their [`PipelineCacheKey`]. A library created by another adapter or driver version
+/// is discarded on [`PipelineCache::open`], and the cache starts empty.
+pub struct PipelineCache {
+    // Declared before `data`, so it is released before the memory it references.
+    library: PipelineLibrary,
+    data: Box<[u8]>,
+    device: Device1,
+    path: PathBuf,
+    dirty: bool,
+}
+
+impl PipelineCache {
+    /// Opens the cache stored in `path`. A missing or corrupted file is treated as an empty cache.
+    pub fn open(device: &Device1, path: impl Into<PathBuf>) -> Result<Self, DxError> {
+        let path = path.into();
+        let data = read_library(&path)?;
+
+        // Safety: the library is released before the data, see the field order.
+        match unsafe { device.create_pipeline_library(&data) } {
+            Ok(library) => Ok(Self {
+                library,
+                data,
+                device: device.clone(),
+                path,
+                dirty: false,
+            }),
+            Err(err) if is_stale_library_error(&err) && !data.is_empty() => {
+                let _ = fs::remove_file(&path);
+
+                let data = Box::default();
+                let library = unsafe { device.create_pipeline_library(&data)? };
+
+                Ok(Self {
+                    library,
+                    data,
+                    device: device.clone(),
+                    path,
+                    dirty: false,
+                })
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns `true` if pipelines were added since the cache was opened or saved.
+    #[inline]
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Returns a cached graphics pipeline or creates it and adds it to the cache.
+    ///
+    /// Failing to add the pipeline isn't an error, the created pipeline is returned anyway.
+    /// Pipelines without a [`PipelineCacheKey`] are created without the cache.
+    pub fn load_or_create_graphics_pipeline(
+        &mut self,
+        desc: &GraphicsPipelineDesc<'_>,
+    ) -> Result<PipelineState, DxError> {
+        let Some(key) = PipelineCacheKey::from_graphics(desc) else {
+            return self.device.create_graphics_pipeline(desc);
+        };
+        let name = key.to_string();
+
+        if let Ok(pipeline) = self.library.load_graphics_pipeline(&name, desc) {
+            return Ok(pipeline);
+        }
+
+        let pipeline = self.device.create_graphics_pipeline(desc)?;
+        self.store(&name, &pipeline);
+
+        Ok(pipeline)
+    }
+
+    /// Returns a cached compute pipeline or creates it and adds it to the cache.
+    ///
+    /// Failing to add the pipeline isn't an error, the created pipeline is returned anyway.
+    /// Pipelines without a [`PipelineCacheKey`] are created without the cache.
+    pub fn load_or_create_compute_pipeline(
+        &mut self,
+        desc: &ComputePipelineStateDesc<'_>,
+    ) -> Result<PipelineState, DxError> {
+        let Some(key) = PipelineCacheKey::from_compute(desc) else {
+            return self.device.create_compute_pipeline_state(desc);
+        };
+        let name = key.to_string();
+
+        if let Ok(pipeline) = self.library.load_compute_pipeline(&name, desc) {
+            return Ok(pipeline);
+        }
+
+        let pipeline = self.device.create_compute_pipeline_state(desc)?;
+        self.store(&name, &pipeline);
+
+        Ok(pipeline)
+    }
+
+    /// Writes the library to disk if pipelines were added.
+    pub fn save(&mut self) -> Result<(), DxError> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        write_library(&self.path, &self.library.serialize()?)?;
+        self.dirty = false;
+
+        Ok(())
+    }
+
+    /// Drops all cached pipelines and removes the file.
+    pub fn clear(&mut self) -> Result<(), DxError> {
+        let data = Box::<[u8]>::default();
+        self.library = unsafe { self.device.create_pipeline_library(&data)? };
+        self.data = data;
+        self.dirty = false;
+
+        match fs::remove_file(&self.path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                Err(io_error(&self.path, err))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn store(&mut self, name: &str, pipeline: &PipelineState) {
+        // The name is taken if the stored pipeline failed to load with this description.
+        if self.library.store_pipeline(name, pipeline).is_ok() {
+            self.dirty = true;
+        }
+    }
+}
+
+impl fmt::Debug for PipelineCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PipelineCache")
+            .field("path", &self.path)
+            .field("size", &self.data.len())
+            .field("dirty", &self.dirty)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns `true` for errors of a library serialized by another adapter or driver version.
+/// Corrupted libraries are rejected with [`DxError::InvalidArgs`].
+fn is_stale_library_error(err: &DxError) -> bool {
+    matches!(
+        err,
+        DxError::AdapterNotFound | DxError::DriverVersionMismatch | DxError::InvalidArgs
+    )
+}
+
+fn read_library(path: &Path) -> Result<Box<[u8]>, DxError> {
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Box::default()),
+        Err(err) => return Err(io_error(path, err)),
+    };
+
+    match decode(&data) {
+        Some(library) => Ok(library.into()),
+        None => {
+            let _ = fs::remove_file(path);
+            Ok(Box::default())
+        }
+    }
+}
+
+fn write_library(path: &Path, library: &[u8]) -> Result<(), DxError> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|err| io_error(dir, err))?;
+    }
+
+    let mut data = Vec::with_capacity(HEADER_SIZE + library.len());
+    data.extend_from_slice(&CACHE_MAGIC);
+    data.extend_from_slice(&CACHE_FORMAT_VERSION.to_le_bytes());
+    data.extend_from_slice(&(library.len() as u64).to_le_bytes());
+    data.extend_from_slice(&checksum(library).to_le_bytes());
+    data.extend_from_slice(library);
+
+    // Readers never observe a partially written file, the last writer wins.
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let temp = PathBuf::from(temp);
+
+    fs::write(&temp, &data).map_err(|err| io_error(&temp, err))?;
+
+    fs::rename(&temp, path).map_err(|err| {
+        let _ = fs::remove_file(&temp);
+        io_error(path, err)
+    })
+}
+
+fn decode(data: &[u8]) -> Option<&[u8]> {
+    let header = data.get(..HEADER_SIZE)?;
+    let library = &data[HEADER_SIZE..];
+
+    let read_u32 =
+        |offset: usize| u32::from_le_bytes(header[offset..offset + 4].try_into().unwrap());
+    let read_u64 =
+        |offset: usize| u64::from_le_bytes(header[offset..offset + 8].try_into().unwrap());
+
+    let valid = header[..4] == CACHE_MAGIC
+        && read_u32(4) == CACHE_FORMAT_VERSION
+        && read_u64(8) == library.len() as u64
+        && read_u64(16) == checksum(library);
+
+    valid.then_some(library)
+}
+
+fn checksum(data: &[u8]) -> u64 {
+    let mut hasher = StableHasher::new();
+    hasher.write(data);
+    hasher.finish()
+}
+
+fn io_error(path: &Path, err: std::io::Error) -> DxError {
+    DxError::Io(format!("{}: {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{blob::Blob, types::*};
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "oxidx-pipeline-cache-{}-{}",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn key_test() {
+        let vs = Blob::copy_from_slice(b"DXBC vertex shader");
+        let same_vs = Blob::copy_from_slice(b"DXBC vertex shader");
+        let ps = Blob::copy_from_slice(b"DXBC pixel shader");
+        let cache = Blob::copy_from_slice(b"cached");
+        let layout = [
+            InputElementDesc::from_raw_per_vertex(c"POSITION", 0, Format::Rgb32Float, 0),
+            InputElementDesc::from_raw_per_vertex(c"TEXCOORD", 0, Format::Rg32Float, 0)
+                .with_offset(12),
+        ];
+
+        let desc = |vs| {
+            GraphicsPipelineDesc::new(vs)
+                .with_ps(&ps)
+                .with_input_layout(&layout)
+                .with_render_targets([Format::Rgba8Unorm])
+        };
+
+        // Only the contents of the shaders matter, not their addresses.
+        let key = PipelineCacheKey::from_graphics(&desc(&vs)).unwrap();
+        assert_eq!(
+            key,
+            PipelineCacheKey::from_graphics(&desc(&same_vs)).unwrap()
+        );
+        assert_eq!(
+            key,
+            PipelineCacheKey::from_graphics(&desc(&vs).with_cache(&cache)).unwrap()
+        );
+
+        assert_ne!(
+            key,
+            PipelineCacheKey::from_graphics(&desc(&vs).with_render_targets([Format::Rgba16Float]))
+                .unwrap()
+        );
+        assert_ne!(
+            key,
+            PipelineCacheKey::from_graphics(&desc(&vs).with_input_layout(&layout[..1])).unwrap()
+        );
+        assert_ne!(
+            key,
+            PipelineCacheKey::from_graphics(
+                &desc(&vs).with_rasterizer_state(RasterizerDesc::default().with_depth_bias(1))
+            )
+            .unwrap()
+        );
+        assert_ne!(
+            key.as_u128(),
+            ComputePipelineStateDesc::new(&vs).content_hash()
+        );
+        assert_eq!(
+            PipelineCacheKey::from_compute(&ComputePipelineStateDesc::new(&vs)),
+            Some(PipelineCacheKey(
+                ComputePipelineStateDesc::new(&vs).content_hash()
+            ))
+        );
+    }
+
+    #[test]
+    fn read_and_write_test() {
+        let path = temp_path("file");
+        let _ = fs::remove_file(&path);
+
+        assert!(read_library(&path).unwrap().is_empty());
+
+        write_library(&path, b"library").unwrap();
+        assert_eq!(&*read_library(&path).unwrap(), b"library");
+
+        // Corrupted files are dropped.
+        let mut data = fs::read(&path).unwrap();
+        *data.last_mut().unwrap() ^= 0xff;
+        fs::write(&path, data).unwrap();
+
+        assert!(read_library(&path).unwrap().is_empty());
+        assert!(!path.exists());
+
+        assert!(is_stale_library_error(&DxError::AdapterNotFound));
+        assert!(is_stale_library_error(&DxError::DriverVersionMismatch));
+        assert!(!is_stale_library_error(&DxError::Oom));
+    }
+}
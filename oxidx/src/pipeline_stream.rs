@@ -0,0 +1,971 @@
+use std::{collections::BTreeMap, ffi::c_void, hash::Hasher, marker::PhantomData, mem::offset_of};
+
+use windows::{
+    core::Interface,
+    Win32::Graphics::{Direct3D12::*, Dxgi::Common::*},
+};
+
+use crate::{
+    blob::Blob,
+    dx::{BlobbyInternal, RootSignature},
+    error::DxError,
+    hash::{hash_root_signature, ContentHash, StableHasher},
+    types::*,
+};
+
+/// Alignment of every subobject in a stream, `alignas(void*)` in `d3dx12`.
+const SUBOBJECT_ALIGNMENT: usize = align_of::<*const c_void>();
+
+#[derive(Clone, Debug)]
+enum Subobject {
+    RootSignature(*mut c_void),
+    Shader(D3D12_SHADER_BYTECODE),
+    StreamOutput(D3D12_STREAM_OUTPUT_DESC),
+    Blend(Box<D3D12_BLEND_DESC>),
+    SampleMask(u32),
+    Rasterizer(D3D12_RASTERIZER_DESC),
+    Rasterizer1(D3D12_RASTERIZER_DESC1),
+    Rasterizer2(D3D12_RASTERIZER_DESC2),
+    DepthStencil(D3D12_DEPTH_STENCIL_DESC),
+    DepthStencil1(D3D12_DEPTH_STENCIL_DESC1),
+    DepthStencil2(D3D12_DEPTH_STENCIL_DESC2),
+    InputLayout(D3D12_INPUT_LAYOUT_DESC),
+    IbStripCutValue(D3D12_INDEX_BUFFER_STRIP_CUT_VALUE),
+    PrimitiveTopology(D3D12_PRIMITIVE_TOPOLOGY_TYPE),
+    RenderTargetFormats(D3D12_RT_FORMAT_ARRAY),
+    DepthStencilFormat(DXGI_FORMAT),
+    SampleDesc(DXGI_SAMPLE_DESC),
+    NodeMask(u32),
+    CachedPso(D3D12_CACHED_PIPELINE_STATE),
+    Flags(D3D12_PIPELINE_STATE_FLAGS),
+    ViewInstancing(D3D12_VIEW_INSTANCING_DESC),
+}
+
+impl ContentHash for Subobject {
+    fn hash_content<H: Hasher>(&self, hasher: &mut H) {
+        match self {
+            Subobject::RootSignature(root_signature) => unsafe {
+                hash_root_signature(
+                    ID3D12RootSignature::from_raw_borrowed(root_signature),
+                    hasher,
+                )
+            },
+            Subobject::Shader(shader) => shader.hash_content(hasher),
+            Subobject::StreamOutput(desc) => desc.hash_content(hasher),
+            Subobject::Blend(desc) => desc.hash_content(hasher),
+            Subobject::SampleMask(mask) | Subobject::NodeMask(mask) => hasher.write_u32(*mask),
+            Subobject::Rasterizer(desc) => desc.hash_content(hasher),
+            Subobject::Rasterizer1(desc) => desc.hash_content(hasher),
+            Subobject::Rasterizer2(desc) => desc.hash_content(hasher),
+            Subobject::DepthStencil(desc) => desc.hash_content(hasher),
+            Subobject::DepthStencil1(desc) => desc.hash_content(hasher),
+            Subobject::DepthStencil2(desc) => desc.hash_content(hasher),
+            Subobject::InputLayout(desc) => desc.hash_content(hasher),
+            Subobject::IbStripCutValue(value) => hasher.write_i32(value.0),
+            Subobject::PrimitiveTopology(topology) => hasher.write_i32(topology.0),
+            Subobject::RenderTargetFormats(formats) => formats.hash_content(hasher),
+            Subobject::DepthStencilFormat(format) => hasher.write_i32(format.0),
+            Subobject::SampleDesc(desc) => desc.hash_content(hasher),
+            Subobject::CachedPso(_) => {}
+            Subobject::Flags(flags) => hasher.write_i32(flags.0),
+            Subobject::ViewInstancing(desc) => desc.hash_content(hasher),
+        }
+    }
+}
+
+/// Describes a pipeline state object as a stream of subobjects.
+///
+/// Unlike [`GraphicsPipelineDesc`] and [`ComputePipelineStateDesc`] the stream supports subobjects added in newer
+/// versions of D3D12: depth bounds, view instancing, mesh and amplification shaders, and the extended rasterizer and
+/// depth-stencil states. Every subobject type is stored at most once, setting one of the rasterizer or depth-stencil
+/// variants replaces the others.
+///
+/// For more information: [`D3D12_PIPELINE_STATE_STREAM_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_pipeline_state_stream_desc)
+#[derive(Clone, Debug, Default)]
+pub struct PipelineStateStream<'a> {
+    subobjects: BTreeMap<PipelineStateSubobjectType, Subobject>,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> PipelineStateStream<'a> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a stream for a mesh shader pipeline, subobjects that aren't set use the D3D12 defaults.
+    ///
+    /// Check [`Device::mesh_shader_tier`](crate::device::Device::mesh_shader_tier) before creating mesh shader pipelines.
+    #[inline]
+    pub fn mesh(ms: &'a Blob) -> Self {
+        Self::new().with_ms(ms)
+    }
+
+    #[inline]
+    pub fn with_root_signature(self, root_signature: &'a RootSignature) -> Self {
+        self.with(
+            PipelineStateSubobjectType::RootSignature,
+            Subobject::RootSignature(root_signature.0.as_raw()),
+        )
+    }
+
+    #[inline]
+    pub fn with_vs(self, vs: &'a Blob) -> Self {
+        self.with_shader(PipelineStateSubobjectType::Vs, vs)
+    }
+
+    #[inline]
+    pub fn with_ps(self, ps: &'a Blob) -> Self {
+        self.with_shader(PipelineStateSubobjectType::Ps, ps)
+    }
+
+    #[inline]
+    pub fn with_ds(self, ds: &'a Blob) -> Self {
+        self.with_shader(PipelineStateSubobjectType::Ds, ds)
+    }
+
+    #[inline]
+    pub fn with_hs(self, hs: &'a Blob) -> Self {
+        self.with_shader(PipelineStateSubobjectType::Hs, hs)
+    }
+
+    #[inline]
+    pub fn with_gs(self, gs: &'a Blob) -> Self {
+        self.with_shader(PipelineStateSubobjectType::Gs, gs)
+    }
+
+    #[inline]
+    pub fn with_cs(self, cs: &'a Blob) -> Self {
+        self.with_shader(PipelineStateSubobjectType::Cs, cs)
+    }
+
+    /// Sets the amplification shader of a mesh shader pipeline.
+    #[inline]
+    pub fn with_as(self, r#as: &'a Blob) -> Self {
+        self.with_shader(PipelineStateSubobjectType::As, r#as)
+    }
+
+    /// Sets the mesh shader, mesh shader pipelines have no input layout and vertex shader.
+    #[inline]
+    pub fn with_ms(self, ms: &'a Blob) -> Self {
+        self.with_shader(PipelineStateSubobjectType::Ms, ms)
+    }
+
+    #[inline]
+    pub fn with_stream_output(self, stream_output: StreamOutputDesc<'a>) -> Self {
+        self.with(
+            PipelineStateSubobjectType::StreamOutput,
+            Subobject::StreamOutput(stream_output.0),
+        )
+    }
+
+    #[inline]
+    pub fn with_blend_desc(self, blend_desc: BlendDesc) -> Self {
+        self.with(
+            PipelineStateSubobjectType::Blend,
+            Subobject::Blend(Box::new(blend_desc.0)),
+        )
+    }
+
+    #[inline]
+    pub fn with_sample_mask(self, sample_mask: u32) -> Self {
+        self.with(
+            PipelineStateSubobjectType::SampleMask,
+            Subobject::SampleMask(sample_mask),
+        )
+    }
+
+    #[inline]
+    pub fn with_rasterizer_state(self, rasterizer_state: RasterizerDesc) -> Self {
+        self.without_rasterizer().with(
+            PipelineStateSubobjectType::Rasterizer,
+            Subobject::Rasterizer(rasterizer_state.0),
+        )
+    }
+
+    #[inline]
+    pub fn with_rasterizer_state1(self, rasterizer_state: RasterizerDesc1) -> Self {
+        self.without_rasterizer().with(
+            PipelineStateSubobjectType::Rasterizer1,
+            Subobject::Rasterizer1(rasterizer_state.0),
+        )
+    }
+
+    #[inline]
+    pub fn with_rasterizer_state2(self, rasterizer_state: RasterizerDesc2) -> Self {
+        self.without_rasterizer().with(
+            PipelineStateSubobjectType::Rasterizer2,
+            Subobject::Rasterizer2(rasterizer_state.0),
+        )
+    }
+
+    #[inline]
+    pub fn with_depth_stencil(self, depth_stencil: DepthStencilDesc, format: Format) -> Self {
+        self.without_depth_stencil()
+            .with(
+                PipelineStateSubobjectType::DepthStencil,
+                Subobject::DepthStencil(depth_stencil.0),
+            )
+            .with_depth_stencil_format(format)
+    }
+
+    /// Sets depth-stencil state that can enable depth bounds testing.
+    #[inline]
+    pub fn with_depth_stencil1(self, depth_stencil: DepthStencilDesc1, format: Format) -> Self {
+        self.without_depth_stencil()
+            .with(
+                PipelineStateSubobjectType::DepthStencil1,
+                Subobject::DepthStencil1(depth_stencil.0),
+            )
+            .with_depth_stencil_format(format)
+    }
+
+    /// Sets depth-stencil state with separate stencil masks for front and back faces.
+    #[inline]
+    pub fn with_depth_stencil2(self, depth_stencil: DepthStencilDesc2, format: Format) -> Self {
+        self.without_depth_stencil()
+            .with(
+                PipelineStateSubobjectType::DepthStencil2,
+                Subobject::DepthStencil2(depth_stencil.0),
+            )
+            .with_depth_stencil_format(format)
+    }
+
+    #[inline]
+    pub fn with_depth_stencil_format(self, format: Format) -> Self {
+        self.with(
+            PipelineStateSubobjectType::DepthStencilFormat,
+            Subobject::DepthStencilFormat(format.as_raw()),
+        )
+    }
+
+    #[inline]
+    pub fn with_input_layout(self, input_layout: &'a [InputElementDesc]) -> Self {
+        self.with(
+            PipelineStateSubobjectType::InputLayout,
+            Subobject::InputLayout(D3D12_INPUT_LAYOUT_DESC {
+                pInputElementDescs: input_layout.as_ptr() as *const _,
+                NumElements: input_layout.len() as u32,
+            }),
+        )
+    }
+
+    #[inline]
+    pub fn with_ib_strip_cut_value(self, ib_strip_cut_value: IndexBufferStripCutValue) -> Self {
+        self.with(
+            PipelineStateSubobjectType::IbStripCutValue,
+            Subobject::IbStripCutValue(ib_strip_cut_value.as_raw()),
+        )
+    }
+
+    #[inline]
+    pub fn with_primitive_topology(self, primitive_topology: PipelinePrimitiveTopology) -> Self {
+        self.with(
+            PipelineStateSubobjectType::PrimitiveTopology,
+            Subobject::PrimitiveTopology(primitive_topology.as_raw()),
+        )
+    }
+
+    /// Sets the render target formats.
+    ///
+    /// Fails with [`DxError::InvalidArgs`] if more than 8 formats are passed.
+    #[inline]
+    pub fn with_render_targets(
+        self,
+        render_targets: impl IntoIterator<Item = Format>,
+    ) -> Result<Self, DxError> {
+        let mut formats = D3D12_RT_FORMAT_ARRAY::default();
+
+        for (i, format) in render_targets.into_iter().enumerate() {
+            if i == formats.RTFormats.len() {
+                return Err(DxError::InvalidArgs);
+            }
+
+            formats.RTFormats[i] = format.as_raw();
+            formats.NumRenderTargets = i as u32 + 1;
+        }
+
+        Ok(self.with(
+            PipelineStateSubobjectType::RenderTargetFormats,
+            Subobject::RenderTargetFormats(formats),
+        ))
+    }
+
+    #[inline]
+    pub fn with_sample_desc(self, sample_desc: SampleDesc) -> Self {
+        self.with(
+            PipelineStateSubobjectType::SampleDesc,
+            Subobject::SampleDesc(sample_desc.0),
+        )
+    }
+
+    #[inline]
+    pub fn with_node_mask(self, node_mask: u32) -> Self {
+        self.with(
+            PipelineStateSubobjectType::NodeMask,
+            Subobject::NodeMask(node_mask),
+        )
+    }
+
+    #[inline]
+    pub fn with_cache(self, cache: &'a Blob) -> Self {
+        self.with(
+            PipelineStateSubobjectType::CachedPso,
+            Subobject::CachedPso(cache.as_cached_pipeline_state()),
+        )
+    }
+
+    #[inline]
+    pub fn with_flags(self, flags: PipelineStateFlags) -> Self {
+        self.with(
+            PipelineStateSubobjectType::Flags,
+            Subobject::Flags(flags.as_raw()),
+        )
+    }
+
+    #[inline]
+    pub fn with_view_instancing(self, view_instancing: ViewInstancingDesc<'a>) -> Self {
+        self.with(
+            PipelineStateSubobjectType::ViewInstancing,
+            Subobject::ViewInstancing(view_instancing.0),
+        )
+    }
+
+    /// Removes a subobject, so the runtime uses its default value.
+    #[inline]
+    pub fn without(mut self, r#type: PipelineStateSubobjectType) -> Self {
+        self.subobjects.remove(&r#type);
+        self
+    }
+
+    #[inline]
+    pub fn contains(&self, r#type: PipelineStateSubobjectType) -> bool {
+        self.subobjects.contains_key(&r#type)
+    }
+
+    /// Types of the subobjects in the order they are encoded.
+    #[inline]
+    pub fn subobject_types(&self) -> impl Iterator<Item = PipelineStateSubobjectType> + '_ {
+        self.subobjects.keys().copied()
+    }
+
+    /// Encodes the stream as it is passed to [`ID3D12Device2::CreatePipelineState`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12device2-createpipelinestate).
+    ///
+    /// Every subobject starts at a pointer-aligned offset with its type, the payload follows at its natural alignment
+    /// and padding bytes are zero.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = StreamWriter::default();
+
+        for (ty, subobject) in &self.subobjects {
+            writer.write_subobject(*ty, subobject);
+        }
+
+        writer.bytes
+    }
+
+    /// Encoded stream in pointer-aligned storage and its size in bytes, which excludes the padding of the storage.
+    pub(crate) fn encode(&self) -> (Vec<u64>, usize) {
+        let mut bytes = self.to_bytes();
+        let size = bytes.len();
+        bytes.resize(size.next_multiple_of(size_of::<u64>()), 0);
+
+        let stream = bytes
+            .chunks_exact(size_of::<u64>())
+            .map(|chunk| u64::from_ne_bytes(chunk.try_into().unwrap()))
+            .collect();
+
+        (stream, size)
+    }
+
+    /// Returns a hash of all subobjects. Shaders are hashed by their bytecode, the cached blob isn't part of the hash.
+    pub fn content_hash(&self) -> u128 {
+        let mut hasher = StableHasher::new();
+
+        for (ty, subobject) in &self.subobjects {
+            if !matches!(subobject, Subobject::CachedPso(_)) {
+                hasher.write_i32(ty.as_raw().0);
+                subobject.hash_content(&mut hasher);
+            }
+        }
+
+        hasher.finish128()
+    }
+
+    #[inline]
+    fn with(mut self, r#type: PipelineStateSubobjectType, subobject: Subobject) -> Self {
+        self.subobjects.insert(r#type, subobject);
+        self
+    }
+
+    #[inline]
+    fn with_shader(self, r#type: PipelineStateSubobjectType, shader: &'a Blob) -> Self {
+        self.with(r#type, Subobject::Shader(shader.as_shader_bytecode()))
+    }
+
+    #[inline]
+    fn without_rasterizer(self) -> Self {
+        self.without(PipelineStateSubobjectType::Rasterizer)
+            .without(PipelineStateSubobjectType::Rasterizer1)
+            .without(PipelineStateSubobjectType::Rasterizer2)
+    }
+
+    #[inline]
+    fn without_depth_stencil(self) -> Self {
+        self.without(PipelineStateSubobjectType::DepthStencil)
+            .without(PipelineStateSubobjectType::DepthStencil1)
+            .without(PipelineStateSubobjectType::DepthStencil2)
+    }
+}
+
+impl<'a> From<GraphicsPipelineDesc<'a>> for PipelineStateStream<'a> {
+    fn from(desc: GraphicsPipelineDesc<'a>) -> Self {
+        let desc = &desc.0;
+        let mut stream = Self::new();
+
+        if let Some(root_signature) = desc.pRootSignature.as_ref() {
+            stream = stream.with(
+                PipelineStateSubobjectType::RootSignature,
+                Subobject::RootSignature(root_signature.as_raw()),
+            );
+        }
+
+        for (ty, shader) in [
+            (PipelineStateSubobjectType::Vs, desc.VS),
+            (PipelineStateSubobjectType::Ps, desc.PS),
+            (PipelineStateSubobjectType::Ds, desc.DS),
+            (PipelineStateSubobjectType::Hs, desc.HS),
+            (PipelineStateSubobjectType::Gs, desc.GS),
+        ] {
+            if shader.BytecodeLength > 0 {
+                stream = stream.with(ty, Subobject::Shader(shader));
+            }
+        }
+
+        if desc.StreamOutput.NumEntries > 0 {
+            stream = stream.with(
+                PipelineStateSubobjectType::StreamOutput,
+                Subobject::StreamOutput(desc.StreamOutput),
+            );
+        }
+
+        if desc.InputLayout.NumElements > 0 {
+            stream = stream.with(
+                PipelineStateSubobjectType::InputLayout,
+                Subobject::InputLayout(desc.InputLayout),
+            );
+        }
+
+        if !desc.CachedPSO.pCachedBlob.is_null() {
+            stream = stream.with(
+                PipelineStateSubobjectType::CachedPso,
+                Subobject::CachedPso(desc.CachedPSO),
+            );
+        }
+
+        stream
+            .with(
+                PipelineStateSubobjectType::Blend,
+                Subobject::Blend(Box::new(desc.BlendState)),
+            )
+            .with(
+                PipelineStateSubobjectType::SampleMask,
+                Subobject::SampleMask(desc.SampleMask),
+            )
+            .with(
+                PipelineStateSubobjectType::Rasterizer,
+                Subobject::Rasterizer(desc.RasterizerState),
+            )
+            .with(
+                PipelineStateSubobjectType::DepthStencil,
+                Subobject::DepthStencil(desc.DepthStencilState),
+            )
+            .with(
+                PipelineStateSubobjectType::IbStripCutValue,
+                Subobject::IbStripCutValue(desc.IBStripCutValue),
+            )
+            .with(
+                PipelineStateSubobjectType::PrimitiveTopology,
+                Subobject::PrimitiveTopology(desc.PrimitiveTopologyType),
+            )
+            .with(
+                PipelineStateSubobjectType::RenderTargetFormats,
+                Subobject::RenderTargetFormats(D3D12_RT_FORMAT_ARRAY {
+                    RTFormats: desc.RTVFormats,
+                    NumRenderTargets: desc.NumRenderTargets,
+                }),
+            )
+            .with(
+                PipelineStateSubobjectType::DepthStencilFormat,
+                Subobject::DepthStencilFormat(desc.DSVFormat),
+            )
+            .with(
+                PipelineStateSubobjectType::SampleDesc,
+                Subobject::SampleDesc(desc.SampleDesc),
+            )
+            .with(
+                PipelineStateSubobjectType::NodeMask,
+                Subobject::NodeMask(desc.NodeMask),
+            )
+            .with(
+                PipelineStateSubobjectType::Flags,
+                Subobject::Flags(desc.Flags),
+            )
+    }
+}
+
+impl<'a> From<ComputePipelineStateDesc<'a>> for PipelineStateStream<'a> {
+    fn from(desc: ComputePipelineStateDesc<'a>) -> Self {
+        let desc = &desc.0;
+        let mut stream =
+            Self::new().with(PipelineStateSubobjectType::Cs, Subobject::Shader(desc.CS));
+
+        if let Some(root_signature) = desc.pRootSignature.as_ref() {
+            stream = stream.with(
+                PipelineStateSubobjectType::RootSignature,
+                Subobject::RootSignature(root_signature.as_raw()),
+            );
+        }
+
+        if !desc.CachedPSO.pCachedBlob.is_null() {
+            stream = stream.with(
+                PipelineStateSubobjectType::CachedPso,
+                Subobject::CachedPso(desc.CachedPSO),
+            );
+        }
+
+        stream
+            .with(
+                PipelineStateSubobjectType::NodeMask,
+                Subobject::NodeMask(desc.NodeMask),
+            )
+            .with(
+                PipelineStateSubobjectType::Flags,
+                Subobject::Flags(desc.Flags),
+            )
+    }
+}
+
+/// Writes subobjects field by field, so the padding inside of D3D12 structs is always zero.
+#[derive(Default)]
+struct StreamWriter {
+    bytes: Vec<u8>,
+}
+
+macro_rules! put_fields {
+    ($writer:ident, $base:expr, $value:expr, $ty:ty, [$($field:ident),* $(,)?]) => {
+        $( $writer.put($base + offset_of!($ty, $field), $value.$field); )*
+    };
+}
+
+impl StreamWriter {
+    fn write_subobject(&mut self, r#type: PipelineStateSubobjectType, subobject: &Subobject) {
+        match *subobject {
+            Subobject::RootSignature(root_signature) => {
+                let base = self.begin(r#type, align_of::<*mut c_void>());
+                self.put(base, root_signature);
+            }
+            Subobject::Shader(shader) => {
+                let base = self.begin(r#type, align_of::<D3D12_SHADER_BYTECODE>());
+                self.put(base, shader);
+            }
+            Subobject::StreamOutput(desc) => {
+                let base = self.begin(r#type, align_of::<D3D12_STREAM_OUTPUT_DESC>());
+                self.reserve(base, size_of::<D3D12_STREAM_OUTPUT_DESC>());
+                put_fields!(
+                    self,
+                    base,
+                    desc,
+                    D3D12_STREAM_OUTPUT_DESC,
+                    [
+                        pSODeclaration,
+                        NumEntries,
+                        pBufferStrides,
+                        NumStrides,
+                        RasterizedStream
+                    ]
+                );
+            }
+            Subobject::Blend(ref desc) => {
+                let base = self.begin(r#type, align_of::<D3D12_BLEND_DESC>());
+                self.reserve(base, size_of::<D3D12_BLEND_DESC>());
+                put_fields!(
+                    self,
+                    base,
+                    desc,
+                    D3D12_BLEND_DESC,
+                    [AlphaToCoverageEnable, IndependentBlendEnable]
+                );
+
+                for (i, rt) in desc.RenderTarget.iter().enumerate() {
+                    let rt_base = base
+                        + offset_of!(D3D12_BLEND_DESC, RenderTarget)
+                        + i * size_of::<D3D12_RENDER_TARGET_BLEND_DESC>();
+
+                    put_fields!(
+                        self,
+                        rt_base,
+                        rt,
+                        D3D12_RENDER_TARGET_BLEND_DESC,
+                        [
+                            BlendEnable,
+                            LogicOpEnable,
+                            SrcBlend,
+                            DestBlend,
+                            BlendOp,
+                            SrcBlendAlpha,
+                            DestBlendAlpha,
+                            BlendOpAlpha,
+                            LogicOp,
+                            RenderTargetWriteMask,
+                        ]
+                    );
+                }
+            }
+            Subobject::SampleMask(mask) | Subobject::NodeMask(mask) => {
+                let base = self.begin(r#type, align_of::<u32>());
+                self.put(base, mask);
+            }
+            Subobject::Rasterizer(desc) => {
+                let base = self.begin(r#type, align_of::<D3D12_RASTERIZER_DESC>());
+                self.put(base, desc);
+            }
+            Subobject::Rasterizer1(desc) => {
+                let base = self.begin(r#type, align_of::<D3D12_RASTERIZER_DESC1>());
+                self.put(base, desc);
+            }
+            Subobject::Rasterizer2(desc) => {
+                let base = self.begin(r#type, align_of::<D3D12_RASTERIZER_DESC2>());
+                self.put(base, desc);
+            }
+            Subobject::DepthStencil(desc) => {
+                let base = self.begin(r#type, align_of::<D3D12_DEPTH_STENCIL_DESC>());
+                self.reserve(base, size_of::<D3D12_DEPTH_STENCIL_DESC>());
+                put_fields!(
+                    self,
+                    base,
+                    desc,
+                    D3D12_DEPTH_STENCIL_DESC,
+                    [
+                        DepthEnable,
+                        DepthWriteMask,
+                        DepthFunc,
+                        StencilEnable,
+                        StencilReadMask,
+                        StencilWriteMask,
+                        FrontFace,
+                        BackFace,
+                    ]
+                );
+            }
+            Subobject::DepthStencil1(desc) => {
+                let base = self.begin(r#type, align_of::<D3D12_DEPTH_STENCIL_DESC1>());
+                self.reserve(base, size_of::<D3D12_DEPTH_STENCIL_DESC1>());
+                put_fields!(
+                    self,
+                    base,
+                    desc,
+                    D3D12_DEPTH_STENCIL_DESC1,
+                    [
+                        DepthEnable,
+                        DepthWriteMask,
+                        DepthFunc,
+                        StencilEnable,
+                        StencilReadMask,
+                        StencilWriteMask,
+                        FrontFace,
+                        BackFace,
+                        DepthBoundsTestEnable,
+                    ]
+                );
+            }
+            Subobject::DepthStencil2(desc) => {
+                let base = self.begin(r#type, align_of::<D3D12_DEPTH_STENCIL_DESC2>());
+                self.reserve(base, size_of::<D3D12_DEPTH_STENCIL_DESC2>());
+                put_fields!(
+                    self,
+                    base,
+                    desc,
+                    D3D12_DEPTH_STENCIL_DESC2,
+                    [
+                        DepthEnable,
+                        DepthWriteMask,
+                        DepthFunc,
+                        StencilEnable,
+                        DepthBoundsTestEnable
+                    ]
+                );
+
+                for (offset, face) in [
+                    (
+                        offset_of!(D3D12_DEPTH_STENCIL_DESC2, FrontFace),
+                        desc.FrontFace,
+                    ),
+                    (
+                        offset_of!(D3D12_DEPTH_STENCIL_DESC2, BackFace),
+                        desc.BackFace,
+                    ),
+                ] {
+                    put_fields!(
+                        self,
+                        base + offset,
+                        face,
+                        D3D12_DEPTH_STENCILOP_DESC1,
+                        [
+                            StencilFailOp,
+                            StencilDepthFailOp,
+                            StencilPassOp,
+                            StencilFunc,
+                            StencilReadMask,
+                            StencilWriteMask,
+                        ]
+                    );
+                }
+            }
+            Subobject::InputLayout(desc) => {
+                let base = self.begin(r#type, align_of::<D3D12_INPUT_LAYOUT_DESC>());
+                self.reserve(base, size_of::<D3D12_INPUT_LAYOUT_DESC>());
+                put_fields!(
+                    self,
+                    base,
+                    desc,
+                    D3D12_INPUT_LAYOUT_DESC,
+                    [pInputElementDescs, NumElements]
+                );
+            }
+            Subobject::IbStripCutValue(value) => {
+                let base = self.begin(r#type, align_of::<D3D12_INDEX_BUFFER_STRIP_CUT_VALUE>());
+                self.put(base, value);
+            }
+            Subobject::PrimitiveTopology(topology) => {
+                let base = self.begin(r#type, align_of::<D3D12_PRIMITIVE_TOPOLOGY_TYPE>());
+                self.put(base, topology);
+            }
+            Subobject::RenderTargetFormats(formats) => {
+                let base = self.begin(r#type, align_of::<D3D12_RT_FORMAT_ARRAY>());
+                self.put(base, formats);
+            }
+            Subobject::DepthStencilFormat(format) => {
+                let base = self.begin(r#type, align_of::<DXGI_FORMAT>());
+                self.put(base, format);
+            }
+            Subobject::SampleDesc(desc) => {
+                let base = self.begin(r#type, align_of::<DXGI_SAMPLE_DESC>());
+                self.put(base, desc);
+            }
+            Subobject::CachedPso(cache) => {
+                let base = self.begin(r#type, align_of::<D3D12_CACHED_PIPELINE_STATE>());
+                self.put(base, cache);
+            }
+            Subobject::Flags(flags) => {
+                let base = self.begin(r#type, align_of::<D3D12_PIPELINE_STATE_FLAGS>());
+                self.put(base, flags);
+            }
+            Subobject::ViewInstancing(desc) => {
+                let base = self.begin(r#type, align_of::<D3D12_VIEW_INSTANCING_DESC>());
+                self.reserve(base, size_of::<D3D12_VIEW_INSTANCING_DESC>());
+                put_fields!(
+                    self,
+                    base,
+                    desc,
+                    D3D12_VIEW_INSTANCING_DESC,
+                    [ViewInstanceCount, pViewInstanceLocations, Flags]
+                );
+            }
+        }
+
+        self.bytes
+            .resize(self.bytes.len().next_multiple_of(SUBOBJECT_ALIGNMENT), 0);
+    }
+
+    /// Writes the type of a subobject and returns the offset of its payload.
+    #[inline]
+    fn begin(&mut self, r#type: PipelineStateSubobjectType, alignment: usize) -> usize {
+        let start = self.bytes.len();
+        self.put(start, r#type.as_raw());
+
+        (start + size_of::<D3D12_PIPELINE_STATE_SUBOBJECT_TYPE>()).next_multiple_of(alignment)
+    }
+
+    #[inline]
+    fn reserve(&mut self, offset: usize, size: usize) {
+        if self.bytes.len() < offset + size {
+            self.bytes.resize(offset + size, 0);
+        }
+    }
+
+    /// Copies a value that has no padding bytes.
+    #[inline]
+    fn put<T: Copy>(&mut self, offset: usize, value: T) {
+        self.reserve(offset, size_of::<T>());
+
+        unsafe {
+            std::ptr::write_unaligned(self.bytes.as_mut_ptr().add(offset) as *mut T, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+        u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+    }
+
+    #[test]
+    fn small_subobjects_test() {
+        let bytes = PipelineStateStream::new()
+            .with_flags(PipelineStateFlags::DynamicDepthBias)
+            .with_sample_mask(0xFFFF_FFFF)
+            .with_node_mask(1)
+            .to_bytes();
+
+        let mut expected = vec![];
+        for (ty, value) in [
+            (9u32, 0xFFFF_FFFFu32),
+            (18, 1),
+            (20, D3D12_PIPELINE_STATE_FLAG_DYNAMIC_DEPTH_BIAS.0 as u32),
+        ] {
+            expected.extend_from_slice(&ty.to_le_bytes());
+            expected.extend_from_slice(&value.to_le_bytes());
+        }
+
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn pointer_alignment_test() {
+        let vs = Blob::from_static(b"vertex shader");
+        let bytes = PipelineStateStream::new()
+            .with_sample_mask(0xF)
+            .with_vs(&vs)
+            .with_render_targets([Format::Rgba8Unorm, Format::R32Float])
+            .unwrap()
+            .to_bytes();
+
+        // VS (1): type, 4 bytes of padding, pointer and length.
+        assert_eq!(u32_at(&bytes, 0), 1);
+        assert_eq!(u32_at(&bytes, 4), 0);
+        assert_eq!(u64_at(&bytes, 8), vs.as_ptr() as u64);
+        assert_eq!(u64_at(&bytes, 16), vs.len() as u64);
+
+        // SAMPLE_MASK (9) follows immediately.
+        assert_eq!(u32_at(&bytes, 24), 9);
+        assert_eq!(u32_at(&bytes, 28), 0xF);
+
+        // RENDER_TARGET_FORMATS (15): 8 formats and the count, padded to 40 bytes.
+        assert_eq!(u32_at(&bytes, 32), 15);
+        assert_eq!(u32_at(&bytes, 36), DXGI_FORMAT_R8G8B8A8_UNORM.0 as u32);
+        assert_eq!(u32_at(&bytes, 40), DXGI_FORMAT_R32_FLOAT.0 as u32);
+        assert_eq!(u32_at(&bytes, 68), 2);
+        assert_eq!(bytes.len(), 72);
+    }
+
+    #[test]
+    fn too_many_render_targets_test() {
+        assert!(matches!(
+            PipelineStateStream::new().with_render_targets([Format::Rgba8Unorm; 9]),
+            Err(DxError::InvalidArgs)
+        ));
+        assert!(PipelineStateStream::new()
+            .with_render_targets([Format::Rgba8Unorm; 8])
+            .is_ok());
+    }
+
+    #[test]
+    fn depth_stencil2_test() {
+        let face = DepthStencilOpDesc1::default()
+            .with_stencil_read_mask(0x0F)
+            .with_stencil_write_mask(0xF0);
+        let bytes = PipelineStateStream::new()
+            .with_depth_stencil(DepthStencilDesc::default(), Format::D32Float)
+            .with_depth_stencil2(
+                DepthStencilDesc2::default()
+                    .enable_depth(ComparisonFunc::Less)
+                    .enable_stencil()
+                    .with_front_face(face)
+                    .with_back_face(face)
+                    .enable_depth_bounds_test(),
+                Format::D24UnormS8Uint,
+            )
+            .to_bytes();
+
+        let mut expected = vec![];
+        let mut push = |v: u32| expected.extend_from_slice(&v.to_le_bytes());
+
+        push(D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_DEPTH_STENCIL_FORMAT.0 as u32);
+        push(DXGI_FORMAT_D24_UNORM_S8_UINT.0 as u32);
+
+        push(D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_DEPTH_STENCIL2.0 as u32);
+        push(1);
+        push(D3D12_DEPTH_WRITE_MASK_ALL.0 as u32);
+        push(D3D12_COMPARISON_FUNC_LESS.0 as u32);
+        push(1);
+        for _ in 0..2 {
+            push(D3D12_STENCIL_OP_KEEP.0 as u32);
+            push(D3D12_STENCIL_OP_KEEP.0 as u32);
+            push(D3D12_STENCIL_OP_KEEP.0 as u32);
+            push(D3D12_COMPARISON_FUNC_ALWAYS.0 as u32);
+            push(0x0000_F00F);
+        }
+        push(1);
+
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn mesh_pipeline_test() {
+        let r#as = Blob::from_static(b"amplification");
+        let ms = Blob::from_static(b"mesh");
+        let stream = PipelineStateStream::mesh(&ms)
+            .with_as(&r#as)
+            .with_render_targets([Format::Rgba8Unorm])
+            .unwrap();
+
+        assert_eq!(
+            stream.subobject_types().collect::<Vec<_>>(),
+            [
+                PipelineStateSubobjectType::RenderTargetFormats,
+                PipelineStateSubobjectType::As,
+                PipelineStateSubobjectType::Ms
+            ]
+        );
+
+        let bytes = stream.to_bytes();
+        assert_eq!(u32_at(&bytes, 40), 24);
+        assert_eq!(u64_at(&bytes, 48), r#as.as_ptr() as u64);
+        assert_eq!(u32_at(&bytes, 64), 25);
+        assert_eq!(u64_at(&bytes, 72), ms.as_ptr() as u64);
+        assert_eq!(u64_at(&bytes, 80), ms.len() as u64);
+        assert_eq!(bytes.len(), 88);
+    }
+
+    #[test]
+    fn graphics_desc_conversion_test() {
+        let vs = Blob::from_static(b"vs");
+        let ps = Blob::from_static(b"ps");
+        let stream = PipelineStateStream::from(
+            GraphicsPipelineDesc::new(&vs)
+                .with_ps(&ps)
+                .with_render_targets([Format::Bgra8Unorm]),
+        );
+
+        assert!(stream.contains(PipelineStateSubobjectType::Vs));
+        assert!(stream.contains(PipelineStateSubobjectType::Ps));
+        assert!(!stream.contains(PipelineStateSubobjectType::Gs));
+        assert!(!stream.contains(PipelineStateSubobjectType::RootSignature));
+
+        let stream = stream.with_rasterizer_state1(RasterizerDesc1::default());
+        assert!(!stream.contains(PipelineStateSubobjectType::Rasterizer));
+
+        let bytes = stream.to_bytes();
+        assert_eq!(bytes.len() % SUBOBJECT_ALIGNMENT, 0);
+        let (encoded, size) = stream.encode();
+        assert_eq!(size, bytes.len());
+        assert_eq!(encoded.len(), bytes.len().div_ceil(8));
+    }
+}
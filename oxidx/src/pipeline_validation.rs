@@ -0,0 +1,419 @@
+use windows::Win32::Graphics::{Direct3D12::*, Dxgi::Common::DXGI_FORMAT_UNKNOWN};
+
+use crate::types::{
+    Format, GraphicsPipelineDesc, PipelinePrimitiveTopology, RenderTargetBlendDesc,
+};
+
+/// A problem in a graphics pipeline description found by [`GraphicsPipelineDesc::validate`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, thiserror::Error)]
+pub enum PipelineDiagnostic {
+    /// `NumRenderTargets` is greater than 8.
+    #[error("NumRenderTargets is {0}, but at most 8 render targets can be bound.")]
+    TooManyRenderTargets(u32),
+
+    /// A render target format is set in a slot past `NumRenderTargets`.
+    #[error("Render target {index} has the format {format:?}, but NumRenderTargets is {count}.")]
+    RenderTargetCountMismatch {
+        index: u32,
+        format: Format,
+        count: u32,
+    },
+
+    /// A render target format is typeless.
+    #[error("Render target {index} has the typeless format {format:?}.")]
+    TypelessRenderTarget { index: u32, format: Format },
+
+    /// A render target uses a depth-stencil format.
+    #[error("Render target {index} has the depth-stencil format {format:?}.")]
+    DepthStencilRenderTarget { index: u32, format: Format },
+
+    /// The depth-stencil format isn't a depth format.
+    #[error("{0:?} isn't a depth-stencil format.")]
+    InvalidDepthStencilFormat(Format),
+
+    /// Depth or stencil testing is enabled, but the depth-stencil format is unknown.
+    #[error("Depth or stencil testing is enabled, but the depth-stencil format is unknown.")]
+    DepthStencilWithoutFormat,
+
+    /// Stencil testing is enabled with a format without a stencil component.
+    #[error("Stencil testing is enabled, but {0:?} has no stencil component.")]
+    StencilWithoutStencilFormat(Format),
+
+    /// A depth-stencil format is set, but depth and stencil testing are disabled.
+    #[error("The depth-stencil format is {0:?}, but depth and stencil testing are disabled.")]
+    DepthFormatWithoutDepthStencil(Format),
+
+    /// Blending is enabled for a render target with an integer format.
+    #[error("Blending is enabled for render target {index}, but {format:?} is an integer format.")]
+    BlendOnIntegerFormat { index: u32, format: Format },
+
+    /// Blending and a logic operation are both enabled for a render target.
+    #[error("Blending and a logic operation are both enabled for render target {index}.")]
+    BlendWithLogicOp { index: u32 },
+
+    /// A logic operation is enabled for a render target that doesn't have an integer format.
+    #[error("A logic operation is enabled for render target {index}, but {format:?} isn't an integer format.")]
+    LogicOpOnNonIntegerFormat { index: u32, format: Format },
+
+    /// A logic operation is enabled together with `IndependentBlendEnable`.
+    #[error("Logic operations require IndependentBlendEnable to be false.")]
+    LogicOpWithIndependentBlend,
+
+    /// `IndependentBlendEnable` is false, but a render target has its own blend state, which is ignored.
+    #[error("Render target {index} has its own blend state, but IndependentBlendEnable is false.")]
+    IndependentBlendIgnored { index: u32 },
+
+    /// The sample count is 0 or greater than 32.
+    #[error("The sample count {0} is invalid.")]
+    InvalidSampleCount(u32),
+
+    /// Multisampling is enabled for a format that can't be multisampled.
+    #[error("{format:?} can't be used with {count} samples.")]
+    UnsupportedMsaaFormat { format: Format, count: u32 },
+
+    /// Multisampling is enabled for a format whose multisampling support is optional.
+    #[error("{format:?} can be used with {count} samples only if the device supports it.")]
+    OptionalMsaaFormat { format: Format, count: u32 },
+
+    /// A hull shader is set without a domain shader.
+    #[error("A hull shader is set without a domain shader.")]
+    HullShaderWithoutDomainShader,
+
+    /// A domain shader is set without a hull shader.
+    #[error("A domain shader is set without a hull shader.")]
+    DomainShaderWithoutHullShader,
+
+    /// Tessellation is enabled, but the primitive topology type isn't a patch.
+    #[error("Tessellation requires the patch primitive topology type, but it is {0:?}.")]
+    TessellationWithoutPatchTopology(PipelinePrimitiveTopology),
+}
+
+impl PipelineDiagnostic {
+    /// Returns `true` for descriptions that D3D12 accepts, but that are most likely mistakes.
+    #[inline]
+    pub fn is_warning(&self) -> bool {
+        matches!(
+            self,
+            PipelineDiagnostic::DepthFormatWithoutDepthStencil(_)
+                | PipelineDiagnostic::IndependentBlendIgnored { .. }
+                | PipelineDiagnostic::OptionalMsaaFormat { .. }
+        )
+    }
+}
+
+impl GraphicsPipelineDesc<'_> {
+    /// Checks the description for common causes of `E_INVALIDARG` without calling D3D12.
+    ///
+    /// Multisampling support depends on the device, only formats that can't be multisampled at all are reported.
+    /// Use [`MultisampleQualityLevelsFeature`](crate::types::features::MultisampleQualityLevelsFeature) for exact checks.
+    pub fn validate(&self) -> Vec<PipelineDiagnostic> {
+        let desc = &self.0;
+        let mut diagnostics = vec![];
+
+        let count = desc.NumRenderTargets;
+        if count > 8 {
+            diagnostics.push(PipelineDiagnostic::TooManyRenderTargets(count));
+        }
+
+        let render_targets = desc
+            .RTVFormats
+            .iter()
+            .enumerate()
+            .filter(|(_, format)| **format != DXGI_FORMAT_UNKNOWN)
+            .map(|(index, format)| (index as u32, Format::from(*format)))
+            .collect::<Vec<_>>();
+
+        for &(index, format) in &render_targets {
+            if index >= count {
+                diagnostics.push(PipelineDiagnostic::RenderTargetCountMismatch {
+                    index,
+                    format,
+                    count,
+                });
+            } else if format.is_typeless() {
+                diagnostics.push(PipelineDiagnostic::TypelessRenderTarget { index, format });
+            } else if format.is_depth_stencil() {
+                diagnostics.push(PipelineDiagnostic::DepthStencilRenderTarget { index, format });
+            }
+        }
+
+        let render_targets = render_targets
+            .into_iter()
+            .filter(|(index, _)| *index < count)
+            .collect::<Vec<_>>();
+
+        validate_depth_stencil(desc, &mut diagnostics);
+        validate_blend(desc, &render_targets, &mut diagnostics);
+
+        let samples = desc.SampleDesc.Count;
+        if samples == 0 || samples > 32 {
+            diagnostics.push(PipelineDiagnostic::InvalidSampleCount(samples));
+        } else if samples > 1 {
+            let dsv = Format::from(desc.DSVFormat);
+
+            for format in render_targets
+                .iter()
+                .map(|(_, format)| *format)
+                .chain(Some(dsv).filter(|f| *f != Format::Unknown))
+            {
+                if !can_multisample(format) {
+                    diagnostics.push(PipelineDiagnostic::UnsupportedMsaaFormat {
+                        format,
+                        count: samples,
+                    });
+                } else if matches!(
+                    format,
+                    Format::Rgb32Float | Format::Rgb32Uint | Format::Rgb32Sint
+                ) {
+                    diagnostics.push(PipelineDiagnostic::OptionalMsaaFormat {
+                        format,
+                        count: samples,
+                    });
+                }
+            }
+        }
+
+        let has_hs = desc.HS.BytecodeLength > 0;
+        let has_ds = desc.DS.BytecodeLength > 0;
+
+        match (has_hs, has_ds) {
+            (true, false) => diagnostics.push(PipelineDiagnostic::HullShaderWithoutDomainShader),
+            (false, true) => diagnostics.push(PipelineDiagnostic::DomainShaderWithoutHullShader),
+            _ => {}
+        }
+
+        let topology = PipelinePrimitiveTopology::from(desc.PrimitiveTopologyType);
+        if has_hs && topology != PipelinePrimitiveTopology::Patch {
+            diagnostics.push(PipelineDiagnostic::TessellationWithoutPatchTopology(
+                topology,
+            ));
+        }
+
+        diagnostics
+    }
+}
+
+fn validate_depth_stencil(
+    desc: &D3D12_GRAPHICS_PIPELINE_STATE_DESC,
+    diagnostics: &mut Vec<PipelineDiagnostic>,
+) {
+    let format = Format::from(desc.DSVFormat);
+    let depth = desc.DepthStencilState.DepthEnable.as_bool();
+    let stencil = desc.DepthStencilState.StencilEnable.as_bool();
+
+    if format == Format::Unknown {
+        if depth || stencil {
+            diagnostics.push(PipelineDiagnostic::DepthStencilWithoutFormat);
+        }
+    } else if !format.is_depth_stencil() {
+        diagnostics.push(PipelineDiagnostic::InvalidDepthStencilFormat(format));
+    } else if stencil && !format.has_stencil() {
+        diagnostics.push(PipelineDiagnostic::StencilWithoutStencilFormat(format));
+    } else if !depth && !stencil {
+        diagnostics.push(PipelineDiagnostic::DepthFormatWithoutDepthStencil(format));
+    }
+}
+
+fn validate_blend(
+    desc: &D3D12_GRAPHICS_PIPELINE_STATE_DESC,
+    render_targets: &[(u32, Format)],
+    diagnostics: &mut Vec<PipelineDiagnostic>,
+) {
+    let blend = &desc.BlendState;
+    let independent = blend.IndependentBlendEnable.as_bool();
+
+    // Without independent blending the first blend state applies to every render target.
+    let blend_state = |index: u32| {
+        if independent {
+            &blend.RenderTarget[index as usize]
+        } else {
+            &blend.RenderTarget[0]
+        }
+    };
+
+    if independent
+        && blend
+            .RenderTarget
+            .iter()
+            .any(|rt| rt.LogicOpEnable.as_bool())
+    {
+        diagnostics.push(PipelineDiagnostic::LogicOpWithIndependentBlend);
+    }
+
+    for &(index, format) in render_targets {
+        let state = blend_state(index);
+
+        if state.BlendEnable.as_bool() && state.LogicOpEnable.as_bool() {
+            diagnostics.push(PipelineDiagnostic::BlendWithLogicOp { index });
+        } else if state.BlendEnable.as_bool() && format.is_integer() {
+            diagnostics.push(PipelineDiagnostic::BlendOnIntegerFormat { index, format });
+        } else if state.LogicOpEnable.as_bool() && !format.is_integer() {
+            diagnostics.push(PipelineDiagnostic::LogicOpOnNonIntegerFormat { index, format });
+        }
+    }
+
+    if !independent {
+        let first = &blend.RenderTarget[0];
+        let zeroed = D3D12_RENDER_TARGET_BLEND_DESC::default();
+        let default = RenderTargetBlendDesc::default().0;
+
+        for (index, state) in blend.RenderTarget.iter().enumerate().skip(1) {
+            if state != first && *state != zeroed && *state != default {
+                diagnostics.push(PipelineDiagnostic::IndependentBlendIgnored {
+                    index: index as u32,
+                });
+            }
+        }
+    }
+}
+
+/// Returns `false` for formats that no device can multisample.
+fn can_multisample(format: Format) -> bool {
+    !(format.is_block_compressed()
+        || format.is_typeless()
+        || matches!(
+            format,
+            Format::Unknown
+                | Format::Rgb9E5
+                | Format::R1Unorm
+                | Format::Rg8Bg8Unorm
+                | Format::Gr8Gb8Unorm
+                | Format::Ayuv
+                | Format::Y410
+                | Format::Y416
+                | Format::Nv12
+                | Format::P010
+                | Format::P016
+                | Format::Opaque420
+                | Format::Yuy2
+                | Format::Y210
+                | Format::Y216
+                | Format::Nv11
+                | Format::Ai44
+                | Format::Ia44
+                | Format::P8
+                | Format::A8P8
+                | Format::P208
+                | Format::V208
+                | Format::V408
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{blob::Blob, types::*};
+
+    #[test]
+    fn valid_desc_test() {
+        let vs = Blob::from_static(b"vs");
+        let ps = Blob::from_static(b"ps");
+
+        let desc = GraphicsPipelineDesc::new(&vs)
+            .with_ps(&ps)
+            .with_primitive_topology(PipelinePrimitiveTopology::Triangle)
+            .with_render_targets([Format::Rgba8Unorm, Format::R32Uint])
+            .with_depth_stencil(
+                DepthStencilDesc::default().enable_depth(ComparisonFunc::Less),
+                Format::D32Float,
+            );
+
+        assert_eq!(desc.validate(), vec![]);
+    }
+
+    #[test]
+    fn typeless_formats_test() {
+        for format in [
+            Format::Rgba8Typeless,
+            Format::R24G8Typeless,
+            Format::R32G8X24Typeless,
+        ] {
+            assert!(format.is_typeless(), "{format:?}");
+        }
+
+        for format in [
+            Format::R24UnormX8Typeless,
+            Format::X24TypelessG8Uint,
+            Format::R32FloatX8X24Typeless,
+            Format::X32TypelessG8X24Uint,
+            Format::Rgba8Unorm,
+        ] {
+            assert!(!format.is_typeless(), "{format:?}");
+        }
+    }
+
+    #[test]
+    fn render_target_and_depth_test() {
+        let vs = Blob::from_static(b"vs");
+
+        let mut desc = GraphicsPipelineDesc::new(&vs)
+            .with_render_targets([Format::Rgba8Typeless, Format::D24UnormS8Uint])
+            .with_depth_stencil(
+                DepthStencilDesc::default().enable_stencil(0xFF, 0xFF),
+                Format::D32Float,
+            );
+        desc.0.NumRenderTargets = 1;
+
+        assert_eq!(
+            desc.validate(),
+            vec![
+                PipelineDiagnostic::TypelessRenderTarget {
+                    index: 0,
+                    format: Format::Rgba8Typeless
+                },
+                PipelineDiagnostic::RenderTargetCountMismatch {
+                    index: 1,
+                    format: Format::D24UnormS8Uint,
+                    count: 1
+                },
+                PipelineDiagnostic::StencilWithoutStencilFormat(Format::D32Float),
+            ]
+        );
+    }
+
+    #[test]
+    fn blend_and_sample_test() {
+        let vs = Blob::from_static(b"vs");
+        let hs = Blob::from_static(b"hs");
+
+        let additive = RenderTargetBlendDesc::blend(
+            Blend::One,
+            Blend::One,
+            BlendOp::Add,
+            ColorWriteEnable::all(),
+        );
+
+        let desc = GraphicsPipelineDesc::new(&vs)
+            .with_hs(&hs)
+            .with_primitive_topology(PipelinePrimitiveTopology::Triangle)
+            .with_render_targets([Format::R32Uint, Format::Rgb32Float])
+            .with_blend_desc(BlendDesc::default().with_render_targets([
+                additive,
+                RenderTargetBlendDesc::logic(LogicOp::Xor, ColorWriteEnable::all()),
+            ]))
+            .with_sample_desc(SampleDesc::new(4, 0));
+
+        let diagnostics = desc.validate();
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                PipelineDiagnostic::BlendOnIntegerFormat {
+                    index: 0,
+                    format: Format::R32Uint
+                },
+                PipelineDiagnostic::IndependentBlendIgnored { index: 1 },
+                PipelineDiagnostic::OptionalMsaaFormat {
+                    format: Format::Rgb32Float,
+                    count: 4
+                },
+                PipelineDiagnostic::HullShaderWithoutDomainShader,
+                PipelineDiagnostic::TessellationWithoutPatchTopology(
+                    PipelinePrimitiveTopology::Triangle
+                ),
+            ]
+        );
+        assert!(diagnostics[1].is_warning());
+        assert!(diagnostics[2].is_warning());
+    }
+}
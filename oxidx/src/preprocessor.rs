@@ -0,0 +1,1355 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::DxError,
+    include::{normalize_path, IncludeHandler},
+    types::{IncludeType, ShaderMacro},
+};
+
+/// Maximum depth of nested includes.
+const MAX_INCLUDE_DEPTH: usize = 64;
+
+/// Pure-Rust HLSL preprocessor.
+///
+/// Supports `#include`, object-like and function-like `#define` (including `#` and `##`), `#undef`,
+/// `#if`/`#elif` with expression evaluation, `#ifdef`/`#ifndef`/`#else`/`#endif`, `#error`, `#line` and `#pragma once`.
+/// Other directives, like `#pragma pack_matrix`, are passed through to the output.
+pub struct Preprocessor<'a> {
+    include: &'a dyn IncludeHandler,
+    macros: HashMap<String, Macro>,
+}
+
+impl<'a> Preprocessor<'a> {
+    #[inline]
+    pub fn new(include: &'a dyn IncludeHandler) -> Self {
+        Self {
+            include,
+            macros: HashMap::new(),
+        }
+    }
+
+    /// Defines an object-like macro, the same as `D3D_SHADER_MACRO` does for the compiler.
+    #[inline]
+    pub fn with_define(mut self, name: impl AsRef<str>, definition: impl AsRef<str>) -> Self {
+        self.macros.insert(
+            name.as_ref().to_string(),
+            Macro {
+                params: None,
+                body: trim_spaces(tokenize(definition.as_ref())),
+            },
+        );
+        self
+    }
+
+    #[inline]
+    pub fn with_defines<N: AsRef<str>, D: AsRef<str>>(
+        self,
+        defines: impl IntoIterator<Item = (N, D)>,
+    ) -> Self {
+        defines.into_iter().fold(self, |this, (name, definition)| {
+            this.with_define(name, definition)
+        })
+    }
+
+    /// Uses the same defines that are passed to the compiler.
+    #[inline]
+    pub fn with_shader_macros(self, defines: &[ShaderMacro]) -> Self {
+        self.with_defines(
+            defines
+                .iter()
+                .map(|d| (d.name().to_string_lossy(), d.definition().to_string_lossy())),
+        )
+    }
+
+    /// Preprocesses a source file read from disk.
+    pub fn preprocess_file(&self, path: impl AsRef<Path>) -> Result<PreprocessedSource, DxError> {
+        let path = path.as_ref();
+        let source = std::fs::read(path)
+            .map_err(|err| DxError::PreprocessError(format!("{}: {}", path.display(), err)))?;
+
+        self.preprocess(String::from_utf8_lossy(&source), path)
+    }
+
+    /// Preprocesses source code. `source_name` is the path of the root source that is passed to the include handler.
+    pub fn preprocess(
+        &self,
+        source: impl AsRef<str>,
+        source_name: impl AsRef<Path>,
+    ) -> Result<PreprocessedSource, DxError> {
+        let root = normalize_path(source_name.as_ref());
+
+        let mut context = Context {
+            include: self.include,
+            macros: self.macros.clone(),
+            files: vec![root.clone()],
+            names: vec![root.clone()],
+            once: HashSet::new(),
+            output: String::new(),
+            lines: vec![],
+        };
+
+        context.process_file(&root, source.as_ref(), 0)?;
+
+        Ok(PreprocessedSource {
+            source: context.output,
+            files: context.files,
+            names: context.names,
+            lines: context.lines,
+        })
+    }
+}
+
+/// Result of [`Preprocessor::preprocess`].
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct PreprocessedSource {
+    source: String,
+    files: Vec<PathBuf>,
+    names: Vec<PathBuf>,
+    lines: Vec<(usize, u32)>,
+}
+
+impl PreprocessedSource {
+    /// Fully expanded source code.
+    #[inline]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The root source followed by all transitively included files, in the order they were first included.
+    #[inline]
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// All transitively included files, in the order they were first included.
+    #[inline]
+    pub fn dependencies(&self) -> &[PathBuf] {
+        &self.files[1..]
+    }
+
+    /// Maps a one-based line of the expanded source to the file and the one-based line it came from.
+    /// Both are changed by `#line` directives.
+    #[inline]
+    pub fn location(&self, line: u32) -> Option<(&Path, u32)> {
+        let index = (line as usize).checked_sub(1)?;
+        let (name, line) = self.lines.get(index)?;
+
+        Some((&self.names[*name], *line))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Macro {
+    params: Option<Vec<String>>,
+    body: Vec<Token>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Number(String),
+    Literal(String),
+    Punct(String),
+    Space(String),
+}
+
+impl Token {
+    fn text(&self) -> &str {
+        match self {
+            Token::Ident(s)
+            | Token::Number(s)
+            | Token::Literal(s)
+            | Token::Punct(s)
+            | Token::Space(s) => s,
+        }
+    }
+
+    fn is_space(&self) -> bool {
+        matches!(self, Token::Space(_))
+    }
+
+    fn is_punct(&self, punct: &str) -> bool {
+        matches!(self, Token::Punct(p) if p == punct)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Conditional {
+    parent_active: bool,
+    active: bool,
+    taken: bool,
+    seen_else: bool,
+}
+
+struct Context<'a> {
+    include: &'a dyn IncludeHandler,
+    macros: HashMap<String, Macro>,
+    files: Vec<PathBuf>,
+    /// Files and names set by `#line`, indexed by `lines`.
+    names: Vec<PathBuf>,
+    once: HashSet<PathBuf>,
+    output: String,
+    lines: Vec<(usize, u32)>,
+}
+
+impl Context<'_> {
+    fn process_file(&mut self, path: &Path, source: &str, depth: usize) -> Result<(), DxError> {
+        let mut name_index = self.name_index(path);
+        let mut line_offset = 0i64;
+        let mut conditionals: Vec<Conditional> = vec![];
+        let mut last_line = 1;
+
+        let mut lines = logical_lines(source).into_iter().peekable();
+
+        while let Some((line_number, line)) = lines.next() {
+            let err = |message: String| error(path, line_number, message);
+            let active = !matches!(conditionals.last(), Some(c) if !c.active);
+            let mapped_line = (line_number as i64 + line_offset) as u32;
+            last_line = line_number;
+
+            let Some(directive) = line.trim_start().strip_prefix('#') else {
+                if !active {
+                    continue;
+                }
+
+                let mut tokens = tokenize(&line);
+
+                // Arguments of a macro call can continue on the following lines,
+                // the whole call is emitted on the line where it starts.
+                loop {
+                    match self.expand_partial(&tokens, &[], path, line_number)? {
+                        Expansion::Complete(expanded) => {
+                            self.emit(&join(&expanded), name_index, mapped_line);
+                            break;
+                        }
+                        Expansion::Unterminated(name) => {
+                            let Some((next_number, next)) =
+                                lines.next_if(|(_, next)| !next.trim_start().starts_with('#'))
+                            else {
+                                return Err(err(format!("unterminated argument list of `{name}`")));
+                            };
+
+                            last_line = next_number;
+                            tokens.push(Token::Space(" ".to_string()));
+                            tokens.extend(tokenize(&next));
+                        }
+                    }
+                }
+
+                continue;
+            };
+
+            let directive = directive.trim_start();
+            let name_len = directive
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(directive.len());
+            let (name, rest) = directive.split_at(name_len);
+
+            match name {
+                "if" | "ifdef" | "ifndef" => {
+                    let condition = if !active {
+                        false
+                    } else if name == "if" {
+                        self.evaluate(rest, path, line_number)?
+                    } else {
+                        let macro_name = rest.trim();
+
+                        if macro_name.is_empty() {
+                            return Err(err(format!("#{name} requires a macro name")));
+                        }
+
+                        self.macros.contains_key(macro_name) == (name == "ifdef")
+                    };
+
+                    conditionals.push(Conditional {
+                        parent_active: active,
+                        active: condition,
+                        taken: condition,
+                        seen_else: false,
+                    });
+                }
+                "elif" => {
+                    let Some(mut current) = conditionals.pop() else {
+                        return Err(err("#elif without #if".to_string()));
+                    };
+
+                    if current.seen_else {
+                        return Err(err("#elif after #else".to_string()));
+                    }
+
+                    current.active = current.parent_active
+                        && !current.taken
+                        && self.evaluate(rest, path, line_number)?;
+                    current.taken |= current.active;
+                    conditionals.push(current);
+                }
+                "else" => {
+                    let Some(current) = conditionals.last_mut() else {
+                        return Err(err("#else without #if".to_string()));
+                    };
+
+                    if current.seen_else {
+                        return Err(err("#else after #else".to_string()));
+                    }
+
+                    current.active = current.parent_active && !current.taken;
+                    current.taken = true;
+                    current.seen_else = true;
+                }
+                "endif" => {
+                    if conditionals.pop().is_none() {
+                        return Err(err("#endif without #if".to_string()));
+                    }
+                }
+                _ if !active => {}
+                "" => {}
+                "include" => self.include(rest, path, line_number, depth)?,
+                "define" => self.define(rest, path, line_number)?,
+                "undef" => {
+                    self.macros.remove(rest.trim());
+                }
+                "pragma" if rest.trim() == "once" => {
+                    self.once.insert(path.to_path_buf());
+                }
+                "error" => return Err(err(format!("#error {}", rest.trim()))),
+                "line" => {
+                    let (line, name) = self.line(rest, path, line_number)?;
+
+                    // The line after the directive gets the given number.
+                    line_offset = line as i64 - line_number as i64 - 1;
+
+                    if let Some(name) = name {
+                        name_index = self.name_index(&name);
+                    }
+                }
+                _ => self.emit(&line, name_index, mapped_line),
+            }
+        }
+
+        if !conditionals.is_empty() {
+            return Err(error(path, last_line, "unterminated #if".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn emit(&mut self, line: &str, name_index: usize, line_number: u32) {
+        self.output.push_str(line.trim_end());
+        self.output.push('\n');
+        self.lines.push((name_index, line_number));
+    }
+
+    fn name_index(&mut self, name: &Path) -> usize {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .unwrap_or_else(|| {
+                self.names.push(name.to_path_buf());
+                self.names.len() - 1
+            })
+    }
+
+    /// Parses `#line number "name"`, the name is optional.
+    fn line(
+        &self,
+        rest: &str,
+        path: &Path,
+        line_number: u32,
+    ) -> Result<(u32, Option<PathBuf>), DxError> {
+        let expanded = self
+            .expand(&tokenize(rest), &[], path, line_number)?
+            .into_iter()
+            .filter(|t| !t.is_space())
+            .collect::<Vec<_>>();
+
+        let (line, name) = match expanded.as_slice() {
+            [Token::Number(line)] => (line.parse::<u32>().ok(), None),
+            [Token::Number(line), Token::Literal(name)] if name.starts_with('"') => (
+                line.parse::<u32>().ok(),
+                Some(PathBuf::from(name.trim_matches('"'))),
+            ),
+            _ => (None, None),
+        };
+
+        match line {
+            Some(line) if line > 0 => Ok((line, name)),
+            _ => Err(error(
+                path,
+                line_number,
+                format!("malformed #line `{}`", rest.trim()),
+            )),
+        }
+    }
+
+    fn include(
+        &mut self,
+        rest: &str,
+        path: &Path,
+        line_number: u32,
+        depth: usize,
+    ) -> Result<(), DxError> {
+        let err = |message: String| error(path, line_number, message);
+
+        let mut rest = rest.trim().to_string();
+
+        if !rest.starts_with('"') && !rest.starts_with('<') {
+            let expanded = self.expand(&tokenize(&rest), &[], path, line_number)?;
+            rest = join(&expanded);
+        }
+
+        let (include_type, name) = if let Some(name) = rest.strip_prefix('"') {
+            (IncludeType::Local, name.split_once('"').map(|(n, _)| n))
+        } else if let Some(name) = rest.strip_prefix('<') {
+            (IncludeType::System, name.split_once('>').map(|(n, _)| n))
+        } else {
+            (IncludeType::Local, None)
+        };
+
+        let Some(name) = name else {
+            return Err(err(format!("malformed #include `{rest}`")));
+        };
+
+        if depth >= MAX_INCLUDE_DEPTH {
+            return Err(err(format!("#include nested too deeply in `{name}`")));
+        }
+
+        let file = self
+            .include
+            .open(include_type, name, Some(path))
+            .map_err(|e| err(format!("can't open `{name}`: {e}")))?;
+
+        if !self.files.contains(&file.path) {
+            self.files.push(file.path.clone());
+        }
+
+        if self.once.contains(&file.path) {
+            return Ok(());
+        }
+
+        let source = String::from_utf8_lossy(&file.data);
+        self.process_file(&file.path, &source, depth + 1)
+    }
+
+    fn define(&mut self, rest: &str, path: &Path, line_number: u32) -> Result<(), DxError> {
+        let err = |message: String| error(path, line_number, message);
+
+        let tokens = tokenize(rest.trim_start());
+
+        let Some(Token::Ident(name)) = tokens.first() else {
+            return Err(err("#define requires a macro name".to_string()));
+        };
+
+        let mut i = 1;
+        let mut params = None;
+
+        if tokens.get(1).is_some_and(|t| t.is_punct("(")) {
+            let mut list = vec![];
+            i = 2;
+
+            loop {
+                match tokens.get(i) {
+                    Some(Token::Space(_)) => {}
+                    Some(Token::Ident(param)) => list.push(param.clone()),
+                    Some(t) if t.is_punct(",") => {}
+                    Some(t) if t.is_punct(")") => break,
+                    Some(t) if t.is_punct(".") => {
+                        if !list.contains(&"__VA_ARGS__".to_string()) {
+                            list.push("__VA_ARGS__".to_string());
+                        }
+                    }
+                    _ => return Err(err(format!("malformed parameter list of `{name}`"))),
+                }
+
+                i += 1;
+            }
+
+            params = Some(list);
+            i += 1;
+        }
+
+        let body = trim_spaces(tokens[i.min(tokens.len())..].to_vec());
+        self.macros.insert(name.clone(), Macro { params, body });
+
+        Ok(())
+    }
+
+    fn evaluate(&self, expression: &str, path: &Path, line_number: u32) -> Result<bool, DxError> {
+        let err = |message: String| error(path, line_number, message);
+
+        let tokens = tokenize(expression);
+        let mut resolved = vec![];
+        let mut i = 0;
+
+        // `defined` has to be resolved before macro expansion.
+        while i < tokens.len() {
+            if matches!(&tokens[i], Token::Ident(name) if name == "defined") {
+                let mut j = next_non_space(&tokens, i + 1);
+                let parenthesized = tokens.get(j).is_some_and(|t| t.is_punct("("));
+
+                if parenthesized {
+                    j = next_non_space(&tokens, j + 1);
+                }
+
+                let Some(Token::Ident(name)) = tokens.get(j) else {
+                    return Err(err("`defined` requires a macro name".to_string()));
+                };
+
+                if parenthesized {
+                    j = next_non_space(&tokens, j + 1);
+
+                    if !tokens.get(j).is_some_and(|t| t.is_punct(")")) {
+                        return Err(err("missing `)` after `defined`".to_string()));
+                    }
+                }
+
+                let value = if self.macros.contains_key(name) {
+                    "1"
+                } else {
+                    "0"
+                };
+                resolved.push(Token::Number(value.to_string()));
+                i = j + 1;
+            } else {
+                resolved.push(tokens[i].clone());
+                i += 1;
+            }
+        }
+
+        let expanded = self
+            .expand(&resolved, &[], path, line_number)?
+            .into_iter()
+            .filter(|t| !t.is_space())
+            .collect::<Vec<_>>();
+
+        if expanded.is_empty() {
+            return Err(err("#if with no expression".to_string()));
+        }
+
+        let mut parser = ExpressionParser {
+            tokens: &expanded,
+            pos: 0,
+        };
+
+        let value = parser.ternary(true).map_err(&err)?;
+
+        if parser.pos != expanded.len() {
+            return Err(err(format!(
+                "unexpected `{}` in #if expression",
+                expanded[parser.pos].text()
+            )));
+        }
+
+        Ok(value != 0)
+    }
+
+    /// Expands macros in `tokens`, names in `hidden` aren't expanded.
+    fn expand(
+        &self,
+        tokens: &[Token],
+        hidden: &[String],
+        path: &Path,
+        line_number: u32,
+    ) -> Result<Vec<Token>, DxError> {
+        match self.expand_partial(tokens, hidden, path, line_number)? {
+            Expansion::Complete(tokens) => Ok(tokens),
+            Expansion::Unterminated(name) => Err(error(
+                path,
+                line_number,
+                format!("unterminated argument list of `{name}`"),
+            )),
+        }
+    }
+
+    /// Expands macros in `tokens`, which may end inside the argument list of a macro call.
+    ///
+    /// An expanded macro body is rescanned together with the remaining tokens, so it can be followed by arguments.
+    /// Every token remembers the macros it was expanded from, they aren't expanded again in it.
+    fn expand_partial(
+        &self,
+        tokens: &[Token],
+        hidden: &[String],
+        path: &Path,
+        line_number: u32,
+    ) -> Result<Expansion, DxError> {
+        let mut tokens = tokens.to_vec();
+        let mut hidden = vec![hidden.to_vec(); tokens.len()];
+        let mut result = vec![];
+        let mut i = 0;
+
+        while i < tokens.len() {
+            let Token::Ident(name) = &tokens[i] else {
+                result.push(tokens[i].clone());
+                i += 1;
+                continue;
+            };
+
+            if name == "__LINE__" {
+                result.push(Token::Number(line_number.to_string()));
+                i += 1;
+                continue;
+            }
+
+            if name == "__FILE__" {
+                result.push(Token::Literal(format!("{:?}", path.display().to_string())));
+                i += 1;
+                continue;
+            }
+
+            let Some(m) = self.macros.get(name).filter(|_| !hidden[i].contains(name)) else {
+                result.push(tokens[i].clone());
+                i += 1;
+                continue;
+            };
+
+            let (body, end) = match &m.params {
+                None => (m.body.clone(), i + 1),
+                Some(params) => {
+                    let open = next_non_space(&tokens, i + 1);
+
+                    if !tokens.get(open).is_some_and(|t| t.is_punct("(")) {
+                        result.push(tokens[i].clone());
+                        i += 1;
+                        continue;
+                    }
+
+                    let Some((args, end)) = collect_args(&tokens, open + 1) else {
+                        return Ok(Expansion::Unterminated(name.clone()));
+                    };
+
+                    let args = match_args(params, args).ok_or_else(|| {
+                        error(
+                            path,
+                            line_number,
+                            format!("wrong number of arguments of `{name}`"),
+                        )
+                    })?;
+
+                    let expanded = args
+                        .iter()
+                        .map(|arg| self.expand(arg, &hidden[i], path, line_number))
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    (substitute(&m.body, params, &args, &expanded), end)
+                }
+            };
+
+            let body = paste(body);
+            let mut body_hidden = hidden[i].clone();
+            body_hidden.push(name.clone());
+
+            hidden.splice(i..end, vec![body_hidden; body.len()]);
+            tokens.splice(i..end, body);
+        }
+
+        Ok(Expansion::Complete(result))
+    }
+}
+
+/// Result of [`Context::expand_partial`].
+enum Expansion {
+    Complete(Vec<Token>),
+    /// The tokens end inside the argument list of the macro.
+    Unterminated(String),
+}
+
+fn error(path: &Path, line_number: u32, message: String) -> DxError {
+    DxError::PreprocessError(format!("{}({}): {}", path.display(), line_number, message))
+}
+
+/// Splits the source into logical lines: joins line continuations and replaces comments with spaces.
+/// Each line is returned with the one-based number of the physical line it starts on.
+fn logical_lines(source: &str) -> Vec<(u32, String)> {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut lines = vec![];
+    let mut current = String::new();
+    let mut line_number = 1;
+    let mut start = 1;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        match c {
+            '\r' => i += 1,
+            '\\' if next == Some('\n')
+                || (next == Some('\r') && chars.get(i + 2) == Some(&'\n')) =>
+            {
+                i += if next == Some('\n') { 2 } else { 3 };
+                line_number += 1;
+            }
+            '\n' => {
+                lines.push((start, std::mem::take(&mut current)));
+                line_number += 1;
+                start = line_number;
+                i += 1;
+            }
+            '"' | '\'' => {
+                current.push(c);
+                i += 1;
+
+                while i < chars.len() && chars[i] != '\n' {
+                    current.push(chars[i]);
+                    i += 1;
+
+                    if chars[i - 1] == '\\' && i < chars.len() && chars[i] != '\n' {
+                        current.push(chars[i]);
+                        i += 1;
+                    } else if chars[i - 1] == c {
+                        break;
+                    }
+                }
+            }
+            '/' if next == Some('/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if next == Some('*') => {
+                i += 2;
+
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    if chars[i] == '\n' {
+                        line_number += 1;
+                    }
+
+                    i += 1;
+                }
+
+                i += 2;
+                current.push(' ');
+            }
+            _ => {
+                current.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    if !current.is_empty() {
+        lines.push((start, current));
+    }
+
+    lines
+}
+
+fn tokenize(line: &str) -> Vec<Token> {
+    const PUNCTS: [&str; 9] = ["##", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||"];
+
+    let chars = line.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+
+        if c.is_whitespace() {
+            while i < chars.len() && chars[i].is_whitespace() {
+                i += 1;
+            }
+
+            tokens.push(Token::Space(chars[start..i].iter().collect()));
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if c.is_ascii_digit()
+            || (c == '.' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()))
+        {
+            let hex = c == '0' && matches!(chars.get(i + 1), Some('x' | 'X'));
+
+            while i < chars.len()
+                && (chars[i].is_alphanumeric()
+                    || chars[i] == '.'
+                    || (!hex && matches!(chars[i], '+' | '-') && matches!(chars[i - 1], 'e' | 'E')))
+            {
+                i += 1;
+            }
+
+            tokens.push(Token::Number(chars[start..i].iter().collect()));
+        } else if c == '"' || c == '\'' {
+            i += 1;
+
+            while i < chars.len() && chars[i] != c {
+                i += if chars[i] == '\\' { 2 } else { 1 };
+            }
+
+            i = (i + 1).min(chars.len());
+            tokens.push(Token::Literal(chars[start..i].iter().collect()));
+        } else {
+            let pair = chars[i..chars.len().min(i + 2)].iter().collect::<String>();
+
+            if PUNCTS.contains(&pair.as_str()) {
+                i += 2;
+                tokens.push(Token::Punct(pair));
+            } else {
+                i += 1;
+                tokens.push(Token::Punct(c.to_string()));
+            }
+        }
+    }
+
+    tokens
+}
+
+fn join(tokens: &[Token]) -> String {
+    tokens.iter().map(Token::text).collect()
+}
+
+fn trim_spaces(mut tokens: Vec<Token>) -> Vec<Token> {
+    while tokens.last().is_some_and(Token::is_space) {
+        tokens.pop();
+    }
+
+    let start = tokens
+        .iter()
+        .position(|t| !t.is_space())
+        .unwrap_or(tokens.len());
+    tokens.drain(..start);
+
+    tokens
+}
+
+fn next_non_space(tokens: &[Token], from: usize) -> usize {
+    tokens[from.min(tokens.len())..]
+        .iter()
+        .position(|t| !t.is_space())
+        .map_or(tokens.len(), |p| from + p)
+}
+
+/// Collects the arguments of a macro invocation starting right after `(`.
+/// Returns the arguments and the index after the closing `)`.
+fn collect_args(tokens: &[Token], from: usize) -> Option<(Vec<Vec<Token>>, usize)> {
+    let mut args = vec![vec![]];
+    let mut depth = 0;
+
+    for (i, token) in tokens.iter().enumerate().skip(from) {
+        if token.is_punct("(") {
+            depth += 1;
+        } else if token.is_punct(")") {
+            if depth == 0 {
+                let args = args.into_iter().map(trim_spaces).collect();
+                return Some((args, i + 1));
+            }
+
+            depth -= 1;
+        } else if token.is_punct(",") && depth == 0 {
+            args.push(vec![]);
+            continue;
+        }
+
+        args.last_mut()?.push(token.clone());
+    }
+
+    None
+}
+
+fn match_args(params: &[String], mut args: Vec<Vec<Token>>) -> Option<Vec<Vec<Token>>> {
+    let variadic = params.last().is_some_and(|p| p == "__VA_ARGS__");
+
+    if params.is_empty() && args.len() == 1 && args[0].is_empty() {
+        return Some(vec![]);
+    }
+
+    if variadic && args.len() >= params.len() {
+        let rest = args.split_off(params.len() - 1);
+        let mut joined = vec![];
+
+        for (i, arg) in rest.into_iter().enumerate() {
+            if i > 0 {
+                joined.push(Token::Punct(",".to_string()));
+            }
+
+            joined.extend(arg);
+        }
+
+        args.push(joined);
+    } else if variadic && args.len() + 1 == params.len() {
+        args.push(vec![]);
+    }
+
+    (args.len() == params.len()).then_some(args)
+}
+
+/// Replaces parameters in the macro body. `##` is kept as a token and handled by [`paste`].
+fn substitute(
+    body: &[Token],
+    params: &[String],
+    args: &[Vec<Token>],
+    expanded: &[Vec<Token>],
+) -> Vec<Token> {
+    let param_index = |token: &Token| match token {
+        Token::Ident(name) => params.iter().position(|p| p == name),
+        _ => None,
+    };
+
+    let mut result = vec![];
+    let mut i = 0;
+
+    while i < body.len() {
+        let token = &body[i];
+
+        if token.is_punct("#") {
+            let next = next_non_space(body, i + 1);
+
+            if let Some(index) = body.get(next).and_then(param_index) {
+                let text = join(&args[index])
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"");
+                result.push(Token::Literal(format!("\"{text}\"")));
+                i = next + 1;
+                continue;
+            }
+        }
+
+        if let Some(index) = param_index(token) {
+            let before_paste = body
+                .get(next_non_space(body, i + 1))
+                .is_some_and(|t| t.is_punct("##"));
+            let after_paste = body[..i]
+                .iter()
+                .rev()
+                .find(|t| !t.is_space())
+                .is_some_and(|t| t.is_punct("##"));
+
+            if before_paste || after_paste {
+                result.extend(args[index].iter().cloned());
+            } else {
+                result.extend(expanded[index].iter().cloned());
+            }
+        } else {
+            result.push(token.clone());
+        }
+
+        i += 1;
+    }
+
+    result
+}
+
+/// Concatenates tokens around `##`.
+fn paste(tokens: Vec<Token>) -> Vec<Token> {
+    if !tokens.iter().any(|t| t.is_punct("##")) {
+        return tokens;
+    }
+
+    let mut result: Vec<Token> = vec![];
+    let mut iter = tokens.into_iter().peekable();
+
+    while let Some(token) = iter.next() {
+        if !token.is_punct("##") {
+            result.push(token);
+            continue;
+        }
+
+        while result.last().is_some_and(Token::is_space) {
+            result.pop();
+        }
+
+        while iter.peek().is_some_and(Token::is_space) {
+            iter.next();
+        }
+
+        let left = result
+            .pop()
+            .map(|t| t.text().to_string())
+            .unwrap_or_default();
+        let right = iter
+            .next()
+            .map(|t| t.text().to_string())
+            .unwrap_or_default();
+
+        result.extend(tokenize(&format!("{left}{right}")));
+    }
+
+    result
+}
+
+struct ExpressionParser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl ExpressionParser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<(), String> {
+        if self.peek().is_some_and(|t| t.is_punct(punct)) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!("expected `{punct}` in #if expression"))
+        }
+    }
+
+    fn ternary(&mut self, eval: bool) -> Result<i64, String> {
+        let condition = self.binary(0, eval)?;
+
+        if !self.peek().is_some_and(|t| t.is_punct("?")) {
+            return Ok(condition);
+        }
+
+        self.pos += 1;
+        let a = self.ternary(eval && condition != 0)?;
+        self.expect(":")?;
+        let b = self.ternary(eval && condition == 0)?;
+
+        Ok(if condition != 0 { a } else { b })
+    }
+
+    fn binary(&mut self, min_precedence: u8, eval: bool) -> Result<i64, String> {
+        let mut lhs = self.unary(eval)?;
+
+        while let Some(Token::Punct(op)) = self.peek() {
+            let precedence = match op.as_str() {
+                "||" => 1,
+                "&&" => 2,
+                "|" => 3,
+                "^" => 4,
+                "&" => 5,
+                "==" | "!=" => 6,
+                "<" | ">" | "<=" | ">=" => 7,
+                "<<" | ">>" => 8,
+                "+" | "-" => 9,
+                "*" | "/" | "%" => 10,
+                _ => break,
+            };
+
+            if precedence < min_precedence {
+                break;
+            }
+
+            let op = op.clone();
+            self.pos += 1;
+
+            let eval_rhs = match op.as_str() {
+                "||" => eval && lhs == 0,
+                "&&" => eval && lhs != 0,
+                _ => eval,
+            };
+            let rhs = self.binary(precedence + 1, eval_rhs)?;
+
+            lhs = match op.as_str() {
+                "||" => (lhs != 0 || rhs != 0) as i64,
+                "&&" => (lhs != 0 && rhs != 0) as i64,
+                "|" => lhs | rhs,
+                "^" => lhs ^ rhs,
+                "&" => lhs & rhs,
+                "==" => (lhs == rhs) as i64,
+                "!=" => (lhs != rhs) as i64,
+                "<" => (lhs < rhs) as i64,
+                ">" => (lhs > rhs) as i64,
+                "<=" => (lhs <= rhs) as i64,
+                ">=" => (lhs >= rhs) as i64,
+                "<<" => lhs.wrapping_shl(rhs as u32),
+                ">>" => lhs.wrapping_shr(rhs as u32),
+                "+" => lhs.wrapping_add(rhs),
+                "-" => lhs.wrapping_sub(rhs),
+                "*" => lhs.wrapping_mul(rhs),
+                "/" | "%" if rhs == 0 => {
+                    if eval {
+                        return Err("division by zero in #if expression".to_string());
+                    }
+
+                    0
+                }
+                "/" => lhs.wrapping_div(rhs),
+                _ => lhs.wrapping_rem(rhs),
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    fn unary(&mut self, eval: bool) -> Result<i64, String> {
+        let Some(token) = self.peek().cloned() else {
+            return Err("unexpected end of #if expression".to_string());
+        };
+
+        self.pos += 1;
+
+        match token {
+            Token::Punct(op) if op == "!" => Ok((self.unary(eval)? == 0) as i64),
+            Token::Punct(op) if op == "~" => Ok(!self.unary(eval)?),
+            Token::Punct(op) if op == "-" => Ok(self.unary(eval)?.wrapping_neg()),
+            Token::Punct(op) if op == "+" => self.unary(eval),
+            Token::Punct(op) if op == "(" => {
+                let value = self.ternary(eval)?;
+                self.expect(")")?;
+                Ok(value)
+            }
+            Token::Number(number) => parse_number(&number),
+            Token::Literal(literal) if literal.starts_with('\'') => {
+                let value = literal.trim_matches('\'');
+                let mut chars = value.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some('\\'), Some(c)) => Ok(match c {
+                        'n' => 10,
+                        't' => 9,
+                        'r' => 13,
+                        '0' => 0,
+                        c => c as i64,
+                    }),
+                    (Some(c), _) => Ok(c as i64),
+                    _ => Err(format!("invalid character literal {literal}")),
+                }
+            }
+            // Identifiers that are not macros evaluate to zero.
+            Token::Ident(_) => Ok(0),
+            token => Err(format!("unexpected `{}` in #if expression", token.text())),
+        }
+    }
+}
+
+fn parse_number(number: &str) -> Result<i64, String> {
+    let digits = number.trim_end_matches(['u', 'U', 'l', 'L']);
+
+    let value = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        u64::from_str_radix(hex, 16)
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        u64::from_str_radix(&digits[1..], 8)
+    } else {
+        digits.parse::<u64>()
+    };
+
+    value
+        .map(|v| v as i64)
+        .map_err(|_| format!("invalid integer `{number}` in #if expression"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::include::MemoryIncludeHandler;
+
+    #[test]
+    fn include_and_pragma_once_test() {
+        let include = MemoryIncludeHandler::new()
+            .with_file("shaders/common.hlsli", "#pragma once\nfloat4 Common;\n")
+            .with_file(
+                "shaders/lighting.hlsli",
+                "#include \"common.hlsli\"\nfloat3 Light;\n",
+            );
+
+        let source = "#include \"common.hlsli\"\n#include \"lighting.hlsli\"\nfloat4 main() : SV_Target { return Common; }\n";
+
+        let result = Preprocessor::new(&include)
+            .preprocess(source, "shaders/main.hlsl")
+            .unwrap();
+
+        assert_eq!(
+            result.source(),
+            "float4 Common;\nfloat3 Light;\nfloat4 main() : SV_Target { return Common; }\n"
+        );
+        assert_eq!(
+            result.dependencies(),
+            [
+                PathBuf::from("shaders/common.hlsli"),
+                PathBuf::from("shaders/lighting.hlsli")
+            ]
+        );
+        assert_eq!(
+            result.location(1),
+            Some((Path::new("shaders/common.hlsli"), 2))
+        );
+        assert_eq!(
+            result.location(2),
+            Some((Path::new("shaders/lighting.hlsli"), 2))
+        );
+        assert_eq!(
+            result.location(3),
+            Some((Path::new("shaders/main.hlsl"), 3))
+        );
+    }
+
+    #[test]
+    fn conditionals_test() {
+        let include = MemoryIncludeHandler::new();
+        let source = "\
+#if defined(USE_SHADOWS) && SHADOW_CASCADES > 2
+cascades
+#elif defined USE_SHADOWS
+shadows
+#else
+no_shadows
+#endif
+#ifndef QUALITY
+#error QUALITY is required
+#endif
+#if QUALITY == HIGH ? 1 : (1 / 0)
+high
+#endif
+";
+
+        let result = Preprocessor::new(&include)
+            .with_define("USE_SHADOWS", "")
+            .with_define("SHADOW_CASCADES", "2")
+            .with_define("HIGH", "3")
+            .with_define("QUALITY", "HIGH")
+            .preprocess(source, "main.hlsl")
+            .unwrap();
+
+        assert_eq!(result.source(), "shadows\nhigh\n");
+        assert_eq!(result.location(2), Some((Path::new("main.hlsl"), 12)));
+
+        let err = Preprocessor::new(&include)
+            .preprocess(source, "main.hlsl")
+            .unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("main.hlsl(9): #error QUALITY is required"));
+    }
+
+    #[test]
+    fn macro_expansion_test() {
+        let include = MemoryIncludeHandler::new();
+        let source = "\
+#define SQUARE(x) ((x) * (x))
+#define REGISTER(type, slot) register(type##slot)
+#define STR(x) #x
+#define SELF SELF + 1
+/* multi-line
+   comment */ float a = SQUARE(2 + 1); // comment
+Texture2D t : REGISTER(t, 3);
+string s = STR(a \"b\");
+int b = SELF;
+float c = 1.0 + \\
+    2.0;
+";
+
+        let result = Preprocessor::new(&include)
+            .preprocess(source, "main.hlsl")
+            .unwrap();
+
+        assert_eq!(
+            result.source(),
+            "  float a = ((2 + 1) * (2 + 1));\n\
+             Texture2D t : register(t3);\n\
+             string s = \"a \\\"b\\\"\";\n\
+             int b = SELF + 1;\n\
+             float c = 1.0 +     2.0;\n"
+        );
+        assert_eq!(result.location(1), Some((Path::new("main.hlsl"), 5)));
+        assert_eq!(result.location(5), Some((Path::new("main.hlsl"), 10)));
+    }
+
+    #[test]
+    fn rescan_test() {
+        let include = MemoryIncludeHandler::new();
+        let source = "\
+#define F G
+#define G(x) x
+#define ID(x) x
+#define A ID(A)
+#define CALL(f) f(2)
+int a = F(1);
+int b = ID(ID(3));
+int c = A;
+int d = CALL(G);
+";
+
+        let result = Preprocessor::new(&include)
+            .preprocess(source, "main.hlsl")
+            .unwrap();
+
+        assert_eq!(
+            result.source(),
+            "int a = 1;\nint b = 3;\nint c = A;\nint d = 2;\n"
+        );
+    }
+
+    #[test]
+    fn multiline_call_test() {
+        let include = MemoryIncludeHandler::new();
+        let source = "\
+#define ADD(a, b) ((a) + (b))
+float a = ADD(1,
+              2);
+float b = ADD(3,
+#endif
+";
+
+        let result = Preprocessor::new(&include)
+            .preprocess(
+                &source[..source.len() - "float b = ADD(3,\n#endif\n".len()],
+                "main.hlsl",
+            )
+            .unwrap();
+
+        assert_eq!(result.source(), "float a = ((1) + (2));\n");
+        assert_eq!(result.location(1), Some((Path::new("main.hlsl"), 2)));
+
+        let err = Preprocessor::new(&include)
+            .preprocess(source, "main.hlsl")
+            .unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("main.hlsl(4): unterminated argument list of `ADD`"));
+    }
+
+    #[test]
+    fn line_directive_test() {
+        let include =
+            MemoryIncludeHandler::new().with_file("common.hlsli", "#line 10\nfloat4 Common;\n");
+        let source = "\
+float a;
+#line 100 \"generated.hlsl\"
+float b;
+#include \"common.hlsli\"
+float c;
+#line 0
+";
+
+        let err = Preprocessor::new(&include)
+            .preprocess(source, "main.hlsl")
+            .unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("main.hlsl(6): malformed #line `0`"));
+
+        let result = Preprocessor::new(&include)
+            .preprocess(&source[..source.len() - "#line 0\n".len()], "main.hlsl")
+            .unwrap();
+
+        assert_eq!(
+            result.source(),
+            "float a;\nfloat b;\nfloat4 Common;\nfloat c;\n"
+        );
+        assert_eq!(result.dependencies(), [PathBuf::from("common.hlsli")]);
+        assert_eq!(result.location(1), Some((Path::new("main.hlsl"), 1)));
+        assert_eq!(result.location(2), Some((Path::new("generated.hlsl"), 100)));
+        assert_eq!(result.location(3), Some((Path::new("common.hlsli"), 10)));
+        assert_eq!(result.location(4), Some((Path::new("generated.hlsl"), 102)));
+    }
+
+    #[test]
+    fn shader_macros_test() {
+        let include = MemoryIncludeHandler::new();
+        let defines = [ShaderMacro::new(c"MAX_LIGHTS", c"16")];
+
+        let result = Preprocessor::new(&include)
+            .with_shader_macros(&defines)
+            .preprocess(
+                "#if MAX_LIGHTS >= 0x10\nLight lights[MAX_LIGHTS];\n#endif\n",
+                "main.hlsl",
+            )
+            .unwrap();
+
+        assert_eq!(result.source(), "Light lights[16];\n");
+    }
+}
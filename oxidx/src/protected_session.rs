@@ -0,0 +1,23 @@
+use windows::Win32::Graphics::Direct3D12::*;
+
+use crate::{create_type, impl_interface, types::*};
+
+create_type! {
+    /// Monitors the validity of a protected resource session, which restricts access to the resources it protects.
+    ///
+    /// For more information: [`ID3D12ProtectedResourceSession interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12protectedresourcesession)
+    ProtectedResourceSession wrap ID3D12ProtectedResourceSession
+}
+
+impl_interface! {
+    ProtectedResourceSession;
+
+    /// Gets the status of the protected session.
+    ///
+    /// For more information: [`ID3D12ProtectedSession::GetSessionStatus method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12protectedsession-getsessionstatus)
+    pub fn get_session_status(&self) -> ProtectedSessionStatus {
+        unsafe {
+            self.0.GetSessionStatus().into()
+        }
+    }
+}
@@ -1,6 +1,12 @@
-use windows::Win32::Graphics::Direct3D12::*;
+use windows::{core::HSTRING, Win32::Graphics::Direct3D12::*};
 
-use crate::{blob::Blob, create_type, error::DxError, impl_interface};
+use crate::{
+    blob::Blob,
+    create_type,
+    error::DxError,
+    impl_interface,
+    types::{ComputePipelineStateDesc, GraphicsPipelineDesc},
+};
 
 create_type! {
     /// Represents the state of all currently set shaders as well as certain fixed function state objects.
@@ -9,6 +15,14 @@ create_type! {
     PipelineState wrap ID3D12PipelineState
 }
 
+create_type! {
+    /// Manages a set of named pipeline state objects, which can be serialized and loaded again in later runs
+    /// without recompiling them.
+    ///
+    /// For more information: [`ID3D12PipelineLibrary interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12pipelinelibrary)
+    PipelineLibrary wrap ID3D12PipelineLibrary
+}
+
 impl_interface! {
     PipelineState;
 
@@ -28,3 +42,69 @@ impl_interface! {
         }
     }
 }
+
+impl_interface! {
+    PipelineLibrary;
+
+    /// Adds the pipeline state object to the library under the name.
+    /// Fails with [`DxError::InvalidArgs`] if the name is already in use.
+    ///
+    /// For more information: [`ID3D12PipelineLibrary::StorePipeline method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12pipelinelibrary-storepipeline)
+    pub fn store_pipeline(&self, name: &str, pipeline: &PipelineState) -> Result<(), DxError> {
+        unsafe {
+            self.0.StorePipeline(&HSTRING::from(name), &pipeline.0).map_err(DxError::from)
+        }
+    }
+
+    /// Retrieves a graphics pipeline state object from the library.
+    /// Fails with [`DxError::InvalidArgs`] if the name isn't found or the description doesn't match the stored one.
+    ///
+    /// For more information: [`ID3D12PipelineLibrary::LoadGraphicsPipeline method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12pipelinelibrary-loadgraphicspipeline)
+    pub fn load_graphics_pipeline(
+        &self,
+        name: &str,
+        desc: &GraphicsPipelineDesc<'_>,
+    ) -> Result<PipelineState, DxError> {
+        unsafe {
+            let res = self.0.LoadGraphicsPipeline(&HSTRING::from(name), &desc.0).map_err(DxError::from)?;
+
+            Ok(PipelineState(res))
+        }
+    }
+
+    /// Retrieves a compute pipeline state object from the library.
+    /// Fails with [`DxError::InvalidArgs`] if the name isn't found or the description doesn't match the stored one.
+    ///
+    /// For more information: [`ID3D12PipelineLibrary::LoadComputePipeline method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12pipelinelibrary-loadcomputepipeline)
+    pub fn load_compute_pipeline(
+        &self,
+        name: &str,
+        desc: &ComputePipelineStateDesc<'_>,
+    ) -> Result<PipelineState, DxError> {
+        unsafe {
+            let res = self.0.LoadComputePipeline(&HSTRING::from(name), &desc.0).map_err(DxError::from)?;
+
+            Ok(PipelineState(res))
+        }
+    }
+
+    /// Returns the amount of memory required to serialize the current contents of the library.
+    ///
+    /// For more information: [`ID3D12PipelineLibrary::GetSerializedSize method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12pipelinelibrary-getserializedsize)
+    pub fn get_serialized_size(&self) -> usize {
+        unsafe { self.0.GetSerializedSize() }
+    }
+
+    /// Serializes the library, so it can be passed to [`Device1::create_pipeline_library`](crate::device::Device1::create_pipeline_library)
+    /// in later runs.
+    ///
+    /// For more information: [`ID3D12PipelineLibrary::Serialize method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12pipelinelibrary-serialize)
+    pub fn serialize(&self) -> Result<Blob, DxError> {
+        unsafe {
+            let mut bytes = vec![0u8; self.0.GetSerializedSize()];
+            self.0.Serialize(&mut bytes).map_err(DxError::from)?;
+
+            Ok(bytes.into())
+        }
+    }
+}
@@ -0,0 +1,365 @@
+use std::{marker::PhantomData, mem::ManuallyDrop};
+
+use windows::Win32::Graphics::Direct3D12::*;
+
+use crate::{resources::Resource, types::*};
+
+/// Specifies what happens to a render target or a depth-stencil plane at the beginning of a render pass.
+///
+/// For more information: [`D3D12_RENDER_PASS_BEGINNING_ACCESS structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_render_pass_beginning_access)
+#[derive(Clone, Copy)]
+pub enum RenderPassBeginningAccess {
+    /// The previous contents aren't needed, the render pass overwrites them.
+    Discard,
+
+    /// The previous contents are loaded for the render pass.
+    Preserve,
+
+    /// The resource is cleared with the value at the beginning of the render pass.
+    Clear(ClearValue),
+
+    /// The resource isn't accessed during the render pass.
+    NoAccess,
+}
+
+impl RenderPassBeginningAccess {
+    #[inline]
+    pub(crate) fn as_raw(&self) -> D3D12_RENDER_PASS_BEGINNING_ACCESS {
+        let (r#type, clear) = match self {
+            RenderPassBeginningAccess::Discard => {
+                (D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_DISCARD, None)
+            }
+            RenderPassBeginningAccess::Preserve => {
+                (D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_PRESERVE, None)
+            }
+            RenderPassBeginningAccess::Clear(value) => {
+                (D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_CLEAR, Some(value.0))
+            }
+            RenderPassBeginningAccess::NoAccess => {
+                (D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_NO_ACCESS, None)
+            }
+        };
+
+        D3D12_RENDER_PASS_BEGINNING_ACCESS {
+            Type: r#type,
+            Anonymous: D3D12_RENDER_PASS_BEGINNING_ACCESS_0 {
+                Clear: D3D12_RENDER_PASS_BEGINNING_ACCESS_CLEAR_PARAMETERS {
+                    ClearValue: clear.unwrap_or_default(),
+                },
+            },
+        }
+    }
+}
+
+/// Specifies what happens to a render target or a depth-stencil plane at the end of a render pass.
+///
+/// For more information: [`D3D12_RENDER_PASS_ENDING_ACCESS structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_render_pass_ending_access)
+#[derive(Clone)]
+pub enum RenderPassEndingAccess<'a> {
+    /// The contents aren't needed after the render pass.
+    Discard,
+
+    /// The contents are written back to be used after the render pass.
+    Preserve,
+
+    /// The multi-sampled contents are resolved into another resource.
+    Resolve(RenderPassResolveParameters<'a>),
+
+    /// The resource isn't accessed during the render pass.
+    NoAccess,
+}
+
+impl RenderPassEndingAccess<'_> {
+    #[inline]
+    pub(crate) fn as_raw(&self) -> D3D12_RENDER_PASS_ENDING_ACCESS {
+        let (r#type, resolve) = match self {
+            RenderPassEndingAccess::Discard => (D3D12_RENDER_PASS_ENDING_ACCESS_TYPE_DISCARD, None),
+            RenderPassEndingAccess::Preserve => {
+                (D3D12_RENDER_PASS_ENDING_ACCESS_TYPE_PRESERVE, None)
+            }
+            RenderPassEndingAccess::Resolve(parameters) => (
+                D3D12_RENDER_PASS_ENDING_ACCESS_TYPE_RESOLVE,
+                Some(parameters.borrow_raw()),
+            ),
+            RenderPassEndingAccess::NoAccess => {
+                (D3D12_RENDER_PASS_ENDING_ACCESS_TYPE_NO_ACCESS, None)
+            }
+        };
+
+        D3D12_RENDER_PASS_ENDING_ACCESS {
+            Type: r#type,
+            Anonymous: D3D12_RENDER_PASS_ENDING_ACCESS_0 {
+                Resolve: ManuallyDrop::new(resolve.unwrap_or_default()),
+            },
+        }
+    }
+}
+
+/// Describes a resolve at the end of a render pass.
+///
+/// For more information: [`D3D12_RENDER_PASS_ENDING_ACCESS_RESOLVE_PARAMETERS structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_render_pass_ending_access_resolve_parameters)
+#[derive(Debug, PartialEq)]
+#[repr(transparent)]
+pub struct RenderPassResolveParameters<'a>(
+    pub(crate) D3D12_RENDER_PASS_ENDING_ACCESS_RESOLVE_PARAMETERS,
+    PhantomData<&'a ()>,
+);
+
+impl<'a> RenderPassResolveParameters<'a> {
+    #[inline]
+    pub fn new(
+        src_resource: &'a Resource,
+        dst_resource: &'a Resource,
+        subresource_parameters: &'a [RenderPassResolveSubresourceParameters],
+        format: Format,
+        resolve_mode: ResolveMode,
+    ) -> Self {
+        Self(
+            D3D12_RENDER_PASS_ENDING_ACCESS_RESOLVE_PARAMETERS {
+                pSrcResource: unsafe { std::mem::transmute_copy(&src_resource.0) },
+                pDstResource: unsafe { std::mem::transmute_copy(&dst_resource.0) },
+                SubresourceCount: subresource_parameters.len() as u32,
+                pSubresourceParameters: subresource_parameters.as_ptr() as *const _,
+                Format: format.as_raw(),
+                ResolveMode: resolve_mode.as_raw(),
+                PreserveResolveSource: false.into(),
+            },
+            PhantomData,
+        )
+    }
+
+    /// Keeps the contents of the source resource after the resolve.
+    #[inline]
+    pub fn with_preserve_resolve_source(mut self, preserve: bool) -> Self {
+        self.0.PreserveResolveSource = preserve.into();
+        self
+    }
+
+    #[inline]
+    pub fn subresource_count(&self) -> u32 {
+        self.0.SubresourceCount
+    }
+
+    #[inline]
+    pub fn format(&self) -> Format {
+        self.0.Format.into()
+    }
+
+    #[inline]
+    pub fn resolve_mode(&self) -> ResolveMode {
+        self.0.ResolveMode.into()
+    }
+
+    #[inline]
+    pub fn preserve_resolve_source(&self) -> bool {
+        self.0.PreserveResolveSource.into()
+    }
+
+    /// Copies the parameters without adding references to the borrowed resources.
+    #[inline]
+    fn borrow_raw(&self) -> D3D12_RENDER_PASS_ENDING_ACCESS_RESOLVE_PARAMETERS {
+        unsafe { std::mem::transmute_copy(&self.0) }
+    }
+}
+
+impl Clone for RenderPassResolveParameters<'_> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(self.borrow_raw(), PhantomData)
+    }
+}
+
+/// Describes the subresources to resolve at the end of a render pass.
+///
+/// For more information: [`D3D12_RENDER_PASS_ENDING_ACCESS_RESOLVE_SUBRESOURCE_PARAMETERS structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_render_pass_ending_access_resolve_subresource_parameters)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct RenderPassResolveSubresourceParameters(
+    pub(crate) D3D12_RENDER_PASS_ENDING_ACCESS_RESOLVE_SUBRESOURCE_PARAMETERS,
+);
+
+impl RenderPassResolveSubresourceParameters {
+    /// Resolves `src_rect` of the source subresource to (`dst_x`, `dst_y`) in the destination subresource.
+    #[inline]
+    pub fn new(
+        src_subresource: u32,
+        dst_subresource: u32,
+        dst_x: u32,
+        dst_y: u32,
+        src_rect: Rect,
+    ) -> Self {
+        Self(
+            D3D12_RENDER_PASS_ENDING_ACCESS_RESOLVE_SUBRESOURCE_PARAMETERS {
+                SrcSubresource: src_subresource,
+                DstSubresource: dst_subresource,
+                DstX: dst_x,
+                DstY: dst_y,
+                SrcRect: src_rect.0,
+            },
+        )
+    }
+}
+
+/// Describes a binding (fixed for the duration of the render pass) to a render target.
+///
+/// For more information: [`D3D12_RENDER_PASS_RENDER_TARGET_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_render_pass_render_target_desc)
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct RenderPassRenderTargetDesc<'a>(
+    pub(crate) D3D12_RENDER_PASS_RENDER_TARGET_DESC,
+    PhantomData<&'a ()>,
+);
+
+impl<'a> RenderPassRenderTargetDesc<'a> {
+    #[inline]
+    pub fn new(
+        descriptor: CpuDescriptorHandle,
+        beginning_access: RenderPassBeginningAccess,
+        ending_access: RenderPassEndingAccess<'a>,
+    ) -> Self {
+        Self(
+            D3D12_RENDER_PASS_RENDER_TARGET_DESC {
+                cpuDescriptor: descriptor.0,
+                BeginningAccess: beginning_access.as_raw(),
+                EndingAccess: ending_access.as_raw(),
+            },
+            PhantomData,
+        )
+    }
+}
+
+/// Describes a binding (fixed for the duration of the render pass) to a depth stencil view.
+///
+/// Both planes aren't accessed unless set by [`RenderPassDepthStencilDesc::with_depth`] and [`RenderPassDepthStencilDesc::with_stencil`].
+///
+/// For more information: [`D3D12_RENDER_PASS_DEPTH_STENCIL_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_render_pass_depth_stencil_desc)
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct RenderPassDepthStencilDesc<'a>(
+    pub(crate) D3D12_RENDER_PASS_DEPTH_STENCIL_DESC,
+    PhantomData<&'a ()>,
+);
+
+impl<'a> RenderPassDepthStencilDesc<'a> {
+    #[inline]
+    pub fn new(descriptor: CpuDescriptorHandle) -> Self {
+        Self(
+            D3D12_RENDER_PASS_DEPTH_STENCIL_DESC {
+                cpuDescriptor: descriptor.0,
+                DepthBeginningAccess: RenderPassBeginningAccess::NoAccess.as_raw(),
+                StencilBeginningAccess: RenderPassBeginningAccess::NoAccess.as_raw(),
+                DepthEndingAccess: RenderPassEndingAccess::NoAccess.as_raw(),
+                StencilEndingAccess: RenderPassEndingAccess::NoAccess.as_raw(),
+            },
+            PhantomData,
+        )
+    }
+
+    #[inline]
+    pub fn with_depth(
+        mut self,
+        beginning_access: RenderPassBeginningAccess,
+        ending_access: RenderPassEndingAccess<'a>,
+    ) -> Self {
+        self.0.DepthBeginningAccess = beginning_access.as_raw();
+        self.0.DepthEndingAccess = ending_access.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn with_stencil(
+        mut self,
+        beginning_access: RenderPassBeginningAccess,
+        ending_access: RenderPassEndingAccess<'a>,
+    ) -> Self {
+        self.0.StencilBeginningAccess = beginning_access.as_raw();
+        self.0.StencilEndingAccess = ending_access.as_raw();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_target_desc_test() {
+        let desc = RenderPassRenderTargetDesc::new(
+            CpuDescriptorHandle::default(),
+            RenderPassBeginningAccess::Clear(ClearValue::color(
+                Format::Rgba8Unorm,
+                [0.0, 0.5, 1.0, 1.0],
+            )),
+            RenderPassEndingAccess::Preserve,
+        );
+
+        assert_eq!(
+            desc.0.BeginningAccess.Type,
+            D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_CLEAR
+        );
+        unsafe {
+            let clear = desc.0.BeginningAccess.Anonymous.Clear.ClearValue;
+            assert_eq!(clear.Format, Format::Rgba8Unorm.as_raw());
+            assert_eq!(clear.Anonymous.Color, [0.0, 0.5, 1.0, 1.0]);
+        }
+        assert_eq!(
+            desc.0.EndingAccess.Type,
+            D3D12_RENDER_PASS_ENDING_ACCESS_TYPE_PRESERVE
+        );
+    }
+
+    #[test]
+    fn depth_stencil_desc_test() {
+        let desc = RenderPassDepthStencilDesc::new(CpuDescriptorHandle::default()).with_depth(
+            RenderPassBeginningAccess::Clear(ClearValue::depth(Format::D32Float, 1.0, 0)),
+            RenderPassEndingAccess::Discard,
+        );
+
+        assert_eq!(
+            desc.0.DepthBeginningAccess.Type,
+            D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_CLEAR
+        );
+        assert_eq!(
+            desc.0.DepthEndingAccess.Type,
+            D3D12_RENDER_PASS_ENDING_ACCESS_TYPE_DISCARD
+        );
+        assert_eq!(
+            desc.0.StencilBeginningAccess.Type,
+            D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_NO_ACCESS
+        );
+        assert_eq!(
+            desc.0.StencilEndingAccess.Type,
+            D3D12_RENDER_PASS_ENDING_ACCESS_TYPE_NO_ACCESS
+        );
+        unsafe {
+            assert_eq!(
+                desc.0
+                    .DepthBeginningAccess
+                    .Anonymous
+                    .Clear
+                    .ClearValue
+                    .Anonymous
+                    .DepthStencil
+                    .Depth,
+                1.0
+            );
+        }
+    }
+
+    #[test]
+    fn resolve_subresource_parameters_test() {
+        let parameters = [RenderPassResolveSubresourceParameters::new(
+            0,
+            2,
+            8,
+            16,
+            Rect::default().with_size((64, 32)),
+        )];
+
+        assert_eq!(parameters[0].0.DstSubresource, 2);
+        assert_eq!(parameters[0].0.DstX, 8);
+        assert_eq!(parameters[0].0.DstY, 16);
+        assert_eq!(parameters[0].0.SrcRect.right, 64);
+        assert_eq!(parameters[0].0.SrcRect.bottom, 32);
+    }
+}
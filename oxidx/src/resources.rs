@@ -1,6 +1,6 @@
 use std::ops::Range;
 
-use windows::Win32::Graphics::Direct3D12::*;
+use windows::{core::Interface, Win32::Graphics::Direct3D12::*};
 
 use crate::{
     create_type,
@@ -17,6 +17,13 @@ create_type! {
     Resource wrap ID3D12Resource
 }
 
+impl std::hash::Hash for Resource {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.as_raw().hash(state);
+    }
+}
+
 impl_interface! {
     Resource;
 
@@ -1,6 +1,13 @@
-use windows::Win32::Graphics::Direct3D12::*;
+use std::hash::Hasher;
 
-use crate::{blob::Blob, create_type, error::DxError, impl_interface, types::*};
+use windows::{core::GUID, Win32::Graphics::Direct3D12::*};
+
+use crate::{
+    blob::Blob, create_type, error::DxError, hash::StableHasher, impl_interface, types::*,
+};
+
+/// Private data under which the hash of the serialized root signature is stored.
+const CONTENT_HASH_GUID: GUID = GUID::from_u128(0x6a1f3c2e_94b7_4d05_8e61_c2d7b05f9a13);
 
 create_type! {
     /// The root signature defines what resources are bound to the graphics pipeline.
@@ -41,3 +48,39 @@ impl_interface! {
         Ok(bytes.into())
     }
 }
+
+impl RootSignature {
+    /// Remembers the hash of the serialized root signature, so pipeline descriptions referencing it
+    /// hash the same way in every run.
+    pub(crate) fn set_content_hash(&self, blob: &[u8]) {
+        let mut hasher = StableHasher::new();
+        hasher.write(blob);
+        let hash = hasher.finish128().to_le_bytes();
+
+        unsafe {
+            let _ = self.0.SetPrivateData(
+                &CONTENT_HASH_GUID,
+                hash.len() as u32,
+                Some(hash.as_ptr() as *const _),
+            );
+        }
+    }
+
+    /// Returns the hash stored by [`RootSignature::set_content_hash`].
+    pub(crate) fn content_hash(&self) -> Option<u128> {
+        let mut hash = [0u8; 16];
+        let mut size = hash.len() as u32;
+
+        unsafe {
+            self.0
+                .GetPrivateData(
+                    &CONTENT_HASH_GUID,
+                    &mut size,
+                    Some(hash.as_mut_ptr() as *mut _),
+                )
+                .ok()?;
+        }
+
+        (size as usize == hash.len()).then(|| u128::from_le_bytes(hash))
+    }
+}
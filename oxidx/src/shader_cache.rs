@@ -0,0 +1,506 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    time::UNIX_EPOCH,
+};
+
+use windows::{
+    core::w,
+    Win32::{
+        Foundation::FreeLibrary,
+        System::LibraryLoader::{GetModuleFileNameW, LoadLibraryW},
+    },
+};
+
+use crate::{
+    blob::{Blob, CompileOptions},
+    error::DxError,
+    hash::StableHasher,
+    include::IncludeHandler,
+    preprocessor::{PreprocessedSource, Preprocessor},
+    types::CompileFlags,
+};
+
+/// Version of the on-disk format. Entries of other versions are ignored.
+const CACHE_FORMAT_VERSION: u32 = 1;
+const CACHE_MAGIC: [u8; 4] = *b"OXSC";
+
+// magic + version + compiler identity + key + blob size + checksum
+const HEADER_SIZE: usize = 4 + 4 + 16 + 16 + 8 + 8;
+
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Key of a compiled shader permutation.
+///
+/// It is a hash of the expanded source, defines, entry point, target and flags.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ShaderCacheKey(u128);
+
+impl ShaderCacheKey {
+    #[inline]
+    pub fn as_u128(&self) -> u128 {
+        self.0
+    }
+}
+
+impl fmt::Display for ShaderCacheKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+/// A shader file together with the options it is compiled with.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct ShaderPermutation {
+    pub path: PathBuf,
+    pub options: CompileOptions,
+}
+
+impl ShaderPermutation {
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>, options: CompileOptions) -> Self {
+        Self {
+            path: path.into(),
+            options,
+        }
+    }
+}
+
+/// On-disk cache of compiled shader permutations.
+///
+/// Entries are stored in a subdirectory per format version and compiler identity, so updating `d3dcompiler_47.dll`
+/// invalidates all entries. Files are written to a temporary file and atomically renamed, and every entry is checksummed,
+/// so the cache can be shared between processes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShaderCache {
+    dir: PathBuf,
+    compiler: u128,
+}
+
+impl ShaderCache {
+    /// Opens a cache in `dir`. The identity of the compiler is derived from the loaded `d3dcompiler_47.dll`.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, DxError> {
+        Ok(Self::with_compiler_identity(dir, compiler_identity()?))
+    }
+
+    /// Opens a cache in `dir` with a custom compiler identity.
+    #[inline]
+    pub fn with_compiler_identity(dir: impl Into<PathBuf>, compiler: u128) -> Self {
+        Self {
+            dir: dir.into(),
+            compiler,
+        }
+    }
+
+    #[inline]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    #[inline]
+    pub fn compiler_identity(&self) -> u128 {
+        self.compiler
+    }
+
+    /// Computes the key of a permutation.
+    ///
+    /// Debug blobs embed the path of the source, so with [`CompileFlags::Debug`] the path is part of the key.
+    pub fn key(&self, source: &PreprocessedSource, options: &CompileOptions) -> ShaderCacheKey {
+        let mut hasher = StableHasher::new();
+
+        hasher.write_bytes(source.source().as_bytes());
+
+        if options.flags().contains(CompileFlags::Debug) {
+            hasher.write_bytes(source.files()[0].to_string_lossy().as_bytes());
+        }
+
+        hasher.write_bytes(options.entry_point().as_bytes());
+        hasher.write_bytes(options.target().as_bytes());
+        hasher.write_bytes(&(options.defines().len() as u64).to_le_bytes());
+
+        for (name, definition) in options.defines() {
+            hasher.write_bytes(name.as_bytes());
+            hasher.write_bytes(definition.as_bytes());
+        }
+
+        hasher.write_bytes(&options.flags().bits().to_le_bytes());
+        hasher.write_bytes(&(options.optimization_level() as u32).to_le_bytes());
+        hasher.write_bytes(&options.effect_flags().bits().to_le_bytes());
+
+        ShaderCacheKey(hasher.finish128())
+    }
+
+    /// Returns a cached blob. Corrupted or incompatible entries are removed and treated as missing.
+    pub fn get(&self, key: ShaderCacheKey) -> Option<Blob> {
+        let path = self.entry_path(key);
+        let data = fs::read(&path).ok()?;
+
+        match self.decode(key, &data) {
+            Some(blob) => Some(Blob::copy_from_slice(blob)),
+            None => {
+                let _ = fs::remove_file(&path);
+                None
+            }
+        }
+    }
+
+    /// Stores a blob.
+    pub fn insert(&self, key: ShaderCacheKey, blob: &[u8]) -> Result<(), DxError> {
+        let dir = self.entries_dir();
+        fs::create_dir_all(&dir).map_err(|err| io_error(&dir, err))?;
+
+        let mut data = Vec::with_capacity(HEADER_SIZE + blob.len());
+        data.extend_from_slice(&CACHE_MAGIC);
+        data.extend_from_slice(&CACHE_FORMAT_VERSION.to_le_bytes());
+        data.extend_from_slice(&self.compiler.to_le_bytes());
+        data.extend_from_slice(&key.0.to_le_bytes());
+        data.extend_from_slice(&(blob.len() as u64).to_le_bytes());
+        data.extend_from_slice(&checksum(blob).to_le_bytes());
+        data.extend_from_slice(blob);
+
+        // Readers never observe a partially written entry, the last writer wins.
+        let temp = dir.join(format!(
+            "{key}.{}.{}.tmp",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        fs::write(&temp, &data).map_err(|err| io_error(&temp, err))?;
+
+        let path = self.entry_path(key);
+
+        if let Err(err) = fs::rename(&temp, &path) {
+            let _ = fs::remove_file(&temp);
+
+            // Another process may hold the entry open, it has the same contents anyway.
+            if !path.is_file() {
+                return Err(io_error(&path, err));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes a cached blob.
+    pub fn remove(&self, key: ShaderCacheKey) {
+        let _ = fs::remove_file(self.entry_path(key));
+    }
+
+    /// Returns a cached blob of the file or compiles it and stores the result.
+    ///
+    /// Failing to write the cache isn't an error, the compiled blob is returned anyway.
+    pub fn compile_from_file(
+        &self,
+        path: impl AsRef<Path>,
+        options: &CompileOptions,
+        include: &dyn IncludeHandler,
+    ) -> Result<Blob, DxError> {
+        let path = path.as_ref();
+        let source = fs::read(path).map_err(|err| io_error(path, err))?;
+
+        self.compile_from_source(
+            String::from_utf8_lossy(&source),
+            path.to_string_lossy(),
+            options,
+            include,
+        )
+    }
+
+    /// Returns a cached blob of the source or compiles it and stores the result.
+    ///
+    /// Failing to write the cache isn't an error, the compiled blob is returned anyway.
+    pub fn compile_from_source(
+        &self,
+        source: impl AsRef<str>,
+        source_name: impl AsRef<str>,
+        options: &CompileOptions,
+        include: &dyn IncludeHandler,
+    ) -> Result<Blob, DxError> {
+        let expanded = preprocess(source.as_ref(), source_name.as_ref(), options, include)?;
+
+        self.compile_preprocessed(&expanded, source, source_name, options, include)
+    }
+
+    /// Like [`ShaderCache::compile_from_source`], but reuses the source that was already expanded by a
+    /// [`Preprocessor`] with the defines of `options`.
+    ///
+    /// The expanded source is only used for the key, FXC compiles the original source.
+    pub fn compile_preprocessed(
+        &self,
+        expanded: &PreprocessedSource,
+        source: impl AsRef<str>,
+        source_name: impl AsRef<str>,
+        options: &CompileOptions,
+        include: &dyn IncludeHandler,
+    ) -> Result<Blob, DxError> {
+        self.lookup_or_compile(
+            expanded,
+            source.as_ref(),
+            source_name.as_ref(),
+            options,
+            include,
+            false,
+        )
+        .map(|(_, blob)| blob)
+    }
+
+    /// Compiles a list of permutations ahead of time, skipping the ones that are already cached.
+    pub fn precompile<'a>(
+        &self,
+        permutations: impl IntoIterator<Item = &'a ShaderPermutation>,
+        include: &dyn IncludeHandler,
+    ) -> Vec<Result<ShaderCacheKey, DxError>> {
+        permutations
+            .into_iter()
+            .map(|permutation| {
+                let path = permutation.path.as_path();
+                let source = fs::read(path).map_err(|err| io_error(path, err))?;
+                let source = String::from_utf8_lossy(&source);
+                let source_name = path.to_string_lossy();
+                let expanded = preprocess(&source, &source_name, &permutation.options, include)?;
+
+                self.lookup_or_compile(
+                    &expanded,
+                    &source,
+                    &source_name,
+                    &permutation.options,
+                    include,
+                    true,
+                )
+                .map(|(key, _)| key)
+            })
+            .collect()
+    }
+
+    /// Removes entries written by other compilers or format versions.
+    pub fn prune(&self) -> Result<(), DxError> {
+        let current = self.entries_dir();
+
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(io_error(&self.dir, err)),
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_cache_dir = path.is_dir()
+                && path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with('v') && n.contains('-'));
+
+            if is_cache_dir && path != current {
+                fs::remove_dir_all(&path).map_err(|err| io_error(&path, err))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes all entries of the current compiler.
+    pub fn clear(&self) -> Result<(), DxError> {
+        let dir = self.entries_dir();
+
+        match fs::remove_dir_all(&dir) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(io_error(&dir, err)),
+            _ => Ok(()),
+        }
+    }
+
+    fn lookup_or_compile(
+        &self,
+        expanded: &PreprocessedSource,
+        source: &str,
+        source_name: &str,
+        options: &CompileOptions,
+        include: &dyn IncludeHandler,
+        require_insert: bool,
+    ) -> Result<(ShaderCacheKey, Blob), DxError> {
+        let key = self.key(expanded, options);
+
+        if let Some(blob) = self.get(key) {
+            return Ok((key, blob));
+        }
+
+        let compiled = options.compile_from_source(source, source_name, Some(include))?;
+        let inserted = self.insert(key, &compiled.blob);
+
+        if require_insert {
+            inserted?;
+        }
+
+        Ok((key, compiled.blob))
+    }
+
+    fn entries_dir(&self) -> PathBuf {
+        self.dir
+            .join(format!("v{}-{:032x}", CACHE_FORMAT_VERSION, self.compiler))
+    }
+
+    fn entry_path(&self, key: ShaderCacheKey) -> PathBuf {
+        self.entries_dir().join(format!("{key}.bin"))
+    }
+
+    fn decode<'a>(&self, key: ShaderCacheKey, data: &'a [u8]) -> Option<&'a [u8]> {
+        let header = data.get(..HEADER_SIZE)?;
+        let blob = &data[HEADER_SIZE..];
+
+        let read_u32 =
+            |offset: usize| u32::from_le_bytes(header[offset..offset + 4].try_into().unwrap());
+        let read_u64 =
+            |offset: usize| u64::from_le_bytes(header[offset..offset + 8].try_into().unwrap());
+        let read_u128 =
+            |offset: usize| u128::from_le_bytes(header[offset..offset + 16].try_into().unwrap());
+
+        let valid = header[..4] == CACHE_MAGIC
+            && read_u32(4) == CACHE_FORMAT_VERSION
+            && read_u128(8) == self.compiler
+            && read_u128(24) == key.0
+            && read_u64(40) == blob.len() as u64
+            && read_u64(48) == checksum(blob);
+
+        valid.then_some(blob)
+    }
+}
+
+fn preprocess(
+    source: &str,
+    source_name: &str,
+    options: &CompileOptions,
+    include: &dyn IncludeHandler,
+) -> Result<PreprocessedSource, DxError> {
+    Preprocessor::new(include)
+        .with_defines(options.defines().iter().map(|(n, d)| (n, d)))
+        .preprocess(source, source_name)
+}
+
+fn checksum(data: &[u8]) -> u64 {
+    use std::hash::Hasher;
+
+    let mut hasher = StableHasher::new();
+    hasher.write(data);
+    hasher.finish()
+}
+
+fn io_error(path: &Path, err: std::io::Error) -> DxError {
+    DxError::Io(format!("{}: {}", path.display(), err))
+}
+
+/// Identifies the loaded `d3dcompiler_47.dll` by its path, size and modification time.
+fn compiler_identity() -> Result<u128, DxError> {
+    let path = unsafe {
+        let module = LoadLibraryW(w!("d3dcompiler_47.dll"))?;
+
+        let mut buffer = [0u16; 1024];
+        let len = GetModuleFileNameW(Some(module), &mut buffer) as usize;
+        let _ = FreeLibrary(module);
+
+        PathBuf::from(String::from_utf16_lossy(&buffer[..len]))
+    };
+
+    let metadata = fs::metadata(&path).map_err(|err| io_error(&path, err))?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_nanos());
+
+    let mut hasher = StableHasher::new();
+    hasher.write_bytes(path.to_string_lossy().as_bytes());
+    hasher.write_bytes(&metadata.len().to_le_bytes());
+    hasher.write_bytes(&modified.to_le_bytes());
+
+    Ok(hasher.finish128())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::include::MemoryIncludeHandler;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "oxidx-shader-cache-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn key_test() {
+        let include = MemoryIncludeHandler::new().with_file("common.hlsli", "#define VALUE 1\n");
+        let cache = ShaderCache::with_compiler_identity(temp_dir("key"), 1);
+
+        let source = "#include \"common.hlsli\"\nfloat4 main() : SV_Target { return VALUE; }\n";
+        let expanded = Preprocessor::new(&include)
+            .preprocess(source, "main.hlsl")
+            .unwrap();
+        let other = Preprocessor::new(&include)
+            .preprocess(source.replace("main()", "main ()"), "main.hlsl")
+            .unwrap();
+
+        let options = CompileOptions::new("main", "ps_5_0");
+
+        assert_eq!(
+            cache.key(&expanded, &options),
+            cache.key(&expanded, &options)
+        );
+        assert_ne!(cache.key(&expanded, &options), cache.key(&other, &options));
+        assert_ne!(
+            cache.key(&expanded, &options),
+            cache.key(&expanded, &options.clone().with_define("A", "1"))
+        );
+        assert_ne!(
+            cache.key(&expanded, &options),
+            cache.key(&expanded, &CompileOptions::new("main", "ps_5_1"))
+        );
+
+        // The path only matters for debug blobs.
+        let renamed = Preprocessor::new(&include)
+            .preprocess(source, "other.hlsl")
+            .unwrap();
+        let debug = options.clone().with_flags(CompileFlags::Debug);
+
+        assert_eq!(
+            cache.key(&expanded, &options),
+            cache.key(&renamed, &options)
+        );
+        assert_ne!(cache.key(&expanded, &debug), cache.key(&renamed, &debug));
+    }
+
+    #[test]
+    fn insert_and_invalidate_test() {
+        let dir = temp_dir("insert");
+        let cache = ShaderCache::with_compiler_identity(&dir, 1);
+        let key = ShaderCacheKey(42);
+
+        assert_eq!(cache.get(key), None);
+
+        cache.insert(key, b"DXBC").unwrap();
+        assert_eq!(cache.get(key).as_deref(), Some(&b"DXBC"[..]));
+
+        // A different compiler doesn't see the entries and prunes them.
+        let updated = ShaderCache::with_compiler_identity(&dir, 2);
+        assert_eq!(updated.get(key), None);
+
+        updated.insert(key, b"DXIL").unwrap();
+        updated.prune().unwrap();
+        assert_eq!(cache.get(key), None);
+        assert_eq!(updated.get(key).as_deref(), Some(&b"DXIL"[..]));
+
+        // Corrupted entries are dropped.
+        let path = updated.entry_path(key);
+        let mut data = fs::read(&path).unwrap();
+        *data.last_mut().unwrap() ^= 0xff;
+        fs::write(&path, data).unwrap();
+
+        assert_eq!(updated.get(key), None);
+        assert!(!path.exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,400 @@
+use crate::{
+    error::DxError,
+    resources::Resource,
+    state_object::ShaderIdentifier,
+    types::{DispatchRaysDesc, GpuDescriptorHandle, GpuVirtualAddress},
+};
+
+/// Alignment of every shader record, `D3D12_RAYTRACING_SHADER_RECORD_BYTE_ALIGNMENT`.
+pub const SHADER_RECORD_ALIGNMENT: u64 = 32;
+
+/// Alignment of the start of every shader table, `D3D12_RAYTRACING_SHADER_TABLE_BYTE_ALIGNMENT`.
+pub const SHADER_TABLE_ALIGNMENT: u64 = 64;
+
+/// Maximum stride of the records in a shader table, `D3D12_RAYTRACING_MAX_SHADER_RECORD_STRIDE`.
+pub const MAX_SHADER_RECORD_STRIDE: u64 = 4096;
+
+/// Local root arguments of a shader record, laid out in the order of the local root signature parameters.
+///
+/// Root constants take 4 bytes each, descriptor tables and root descriptors take 8 bytes aligned to 8 bytes.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct LocalRootArguments(Vec<u8>);
+
+impl LocalRootArguments {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends 32-bit root constants.
+    #[inline]
+    pub fn with_constants(mut self, constants: &[u32]) -> Self {
+        for constant in constants {
+            self.0.extend_from_slice(&constant.to_le_bytes());
+        }
+        self
+    }
+
+    /// Appends the GPU handle of a descriptor table.
+    #[inline]
+    pub fn with_descriptor_table(self, handle: GpuDescriptorHandle) -> Self {
+        self.with_u64(handle.0.ptr)
+    }
+
+    /// Appends the GPU address of a root CBV, SRV or UAV.
+    #[inline]
+    pub fn with_root_descriptor(self, address: GpuVirtualAddress) -> Self {
+        self.with_u64(address)
+    }
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    fn with_u64(mut self, value: u64) -> Self {
+        self.0.resize(self.0.len().next_multiple_of(8), 0);
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+}
+
+/// A shader identifier followed by the local root arguments of the shader.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ShaderRecord {
+    identifier: ShaderIdentifier,
+    arguments: LocalRootArguments,
+}
+
+impl ShaderRecord {
+    #[inline]
+    pub fn new(identifier: ShaderIdentifier) -> Self {
+        Self {
+            identifier,
+            arguments: LocalRootArguments::default(),
+        }
+    }
+
+    #[inline]
+    pub fn with_local_root_arguments(mut self, arguments: LocalRootArguments) -> Self {
+        self.arguments = arguments;
+        self
+    }
+
+    #[inline]
+    pub fn identifier(&self) -> &ShaderIdentifier {
+        &self.identifier
+    }
+
+    #[inline]
+    pub fn local_root_arguments(&self) -> &LocalRootArguments {
+        &self.arguments
+    }
+
+    /// Size of the record before the alignment.
+    #[inline]
+    pub fn size(&self) -> u64 {
+        (ShaderIdentifier::SIZE + self.arguments.0.len()) as u64
+    }
+
+    fn write(&self, dst: &mut [u8]) {
+        let (identifier, arguments) = dst.split_at_mut(ShaderIdentifier::SIZE);
+        identifier.copy_from_slice(self.identifier.as_bytes());
+        arguments[..self.arguments.0.len()].copy_from_slice(&self.arguments.0);
+    }
+}
+
+/// Location of a shader table relative to the start of the shader binding table.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct ShaderTableRange {
+    pub offset: u64,
+    pub size: u64,
+    pub stride: u64,
+}
+
+impl ShaderTableRange {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    #[inline]
+    fn address(&self, base: GpuVirtualAddress) -> GpuVirtualAddress {
+        if self.is_empty() {
+            0
+        } else {
+            base + self.offset
+        }
+    }
+}
+
+/// Offsets and strides of the tables built by a [`ShaderTableBuilder`].
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct ShaderTableLayout {
+    pub ray_generation: ShaderTableRange,
+    pub miss: ShaderTableRange,
+    pub hit_group: ShaderTableRange,
+    pub callable: ShaderTableRange,
+    pub size: u64,
+}
+
+impl ShaderTableLayout {
+    /// Describes a dispatch of rays with the tables placed at `base`, which must be aligned to
+    /// [`SHADER_TABLE_ALIGNMENT`].
+    pub fn dispatch_rays_desc(
+        &self,
+        base: GpuVirtualAddress,
+        width: u32,
+        height: u32,
+        depth: u32,
+    ) -> DispatchRaysDesc {
+        debug_assert_eq!(base % SHADER_TABLE_ALIGNMENT, 0);
+
+        let range = |table: &ShaderTableRange| (table.address(base), table.size, table.stride);
+
+        let (address, size, stride) = range(&self.miss);
+        let desc = DispatchRaysDesc::new(width, height, depth)
+            .with_ray_generation_record(self.ray_generation.address(base), self.ray_generation.size)
+            .with_miss_table(address, size, stride);
+
+        let (address, size, stride) = range(&self.hit_group);
+        let desc = desc.with_hit_group_table(address, size, stride);
+
+        let (address, size, stride) = range(&self.callable);
+        desc.with_callable_table(address, size, stride)
+    }
+}
+
+/// Builds a shader binding table: the ray generation record followed by the miss, hit group and callable tables.
+///
+/// Every table starts at a multiple of [`SHADER_TABLE_ALIGNMENT`], and its records share a stride, which is the size
+/// of the largest record rounded up to [`SHADER_RECORD_ALIGNMENT`].
+#[derive(Clone, Debug, Default)]
+pub struct ShaderTableBuilder {
+    ray_generation: Option<ShaderRecord>,
+    miss: Vec<ShaderRecord>,
+    hit_groups: Vec<ShaderRecord>,
+    callable: Vec<ShaderRecord>,
+}
+
+impl ShaderTableBuilder {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_ray_generation(mut self, record: ShaderRecord) -> Self {
+        self.ray_generation = Some(record);
+        self
+    }
+
+    #[inline]
+    pub fn with_miss(mut self, record: ShaderRecord) -> Self {
+        self.miss.push(record);
+        self
+    }
+
+    #[inline]
+    pub fn with_hit_group(mut self, record: ShaderRecord) -> Self {
+        self.hit_groups.push(record);
+        self
+    }
+
+    #[inline]
+    pub fn with_callable(mut self, record: ShaderRecord) -> Self {
+        self.callable.push(record);
+        self
+    }
+
+    /// Computes the location of every table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a record is larger than [`MAX_SHADER_RECORD_STRIDE`].
+    pub fn layout(&self) -> ShaderTableLayout {
+        let mut offset = 0;
+
+        let mut place = |records: &[ShaderRecord]| {
+            let stride = records
+                .iter()
+                .map(|record| record.size().next_multiple_of(SHADER_RECORD_ALIGNMENT))
+                .max()
+                .unwrap_or(0);
+
+            assert!(
+                stride <= MAX_SHADER_RECORD_STRIDE,
+                "shader record stride {} exceeds {}",
+                stride,
+                MAX_SHADER_RECORD_STRIDE
+            );
+
+            let table = ShaderTableRange {
+                offset,
+                size: stride * records.len() as u64,
+                stride,
+            };
+            offset = (offset + table.size).next_multiple_of(SHADER_TABLE_ALIGNMENT);
+
+            table
+        };
+
+        let ray_generation = place(self.ray_generation.as_slice());
+        let miss = place(&self.miss);
+        let hit_group = place(&self.hit_groups);
+        let callable = place(&self.callable);
+
+        ShaderTableLayout {
+            ray_generation,
+            miss,
+            hit_group,
+            callable,
+            size: callable.offset + callable.size,
+        }
+    }
+
+    /// Writes the tables into `dst` and returns their layout. Bytes between the records are zeroed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dst` is smaller than [`ShaderTableLayout::size`].
+    pub fn write_to(&self, dst: &mut [u8]) -> ShaderTableLayout {
+        let layout = self.layout();
+        let dst = &mut dst[..layout.size as usize];
+        dst.fill(0);
+
+        let tables = [
+            (&layout.ray_generation, self.ray_generation.as_slice()),
+            (&layout.miss, &self.miss),
+            (&layout.hit_group, &self.hit_groups),
+            (&layout.callable, &self.callable),
+        ];
+
+        for (table, records) in tables {
+            for (i, record) in records.iter().enumerate() {
+                let start = (table.offset + table.stride * i as u64) as usize;
+                record.write(&mut dst[start..start + table.stride as usize]);
+            }
+        }
+
+        layout
+    }
+
+    /// Writes the tables to the start of a buffer in an upload heap and returns the dispatch description of
+    /// `width` x `height` x `depth` rays.
+    pub fn write(
+        &self,
+        resource: &Resource,
+        width: u32,
+        height: u32,
+        depth: u32,
+    ) -> Result<DispatchRaysDesc, DxError> {
+        let size = self.layout().size;
+        let capacity = resource.get_desc().width();
+
+        if capacity < size {
+            return Err(DxError::Other(format!(
+                "shader table needs {} bytes, but the buffer has {} bytes",
+                size, capacity
+            )));
+        }
+
+        let ptr = resource.map::<u8>(0, Some(0..0))?;
+        let dst = unsafe { std::slice::from_raw_parts_mut(ptr.as_ptr(), size as usize) };
+        let layout = self.write_to(dst);
+        resource.unmap(0, Some(0..size as usize));
+
+        Ok(layout.dispatch_rays_desc(resource.get_gpu_virtual_address(), width, height, depth))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identifier(value: u8) -> ShaderIdentifier {
+        ShaderIdentifier::from_bytes([value; ShaderIdentifier::SIZE])
+    }
+
+    #[test]
+    fn local_root_arguments_test() {
+        let arguments = LocalRootArguments::new()
+            .with_constants(&[1])
+            .with_root_descriptor(0x1000)
+            .with_constants(&[2, 3]);
+
+        assert_eq!(arguments.as_bytes().len(), 24);
+        assert_eq!(&arguments.as_bytes()[4..8], &[0; 4]);
+        assert_eq!(&arguments.as_bytes()[8..16], &0x1000u64.to_le_bytes());
+        assert_eq!(&arguments.as_bytes()[16..20], &2u32.to_le_bytes());
+    }
+
+    #[test]
+    fn layout_test() {
+        let builder = ShaderTableBuilder::new()
+            .with_ray_generation(ShaderRecord::new(identifier(1)))
+            .with_miss(ShaderRecord::new(identifier(2)))
+            .with_miss(ShaderRecord::new(identifier(3)))
+            .with_hit_group(
+                ShaderRecord::new(identifier(4)).with_local_root_arguments(
+                    LocalRootArguments::new()
+                        .with_root_descriptor(0xdead_0000)
+                        .with_constants(&[7]),
+                ),
+            )
+            .with_hit_group(ShaderRecord::new(identifier(5)));
+
+        let layout = builder.layout();
+
+        assert_eq!(
+            layout.ray_generation,
+            ShaderTableRange {
+                offset: 0,
+                size: 32,
+                stride: 32
+            }
+        );
+        assert_eq!(
+            layout.miss,
+            ShaderTableRange {
+                offset: 64,
+                size: 64,
+                stride: 32
+            }
+        );
+        // 32 bytes of identifier and 12 bytes of arguments are rounded up to 64.
+        assert_eq!(
+            layout.hit_group,
+            ShaderTableRange {
+                offset: 128,
+                size: 128,
+                stride: 64
+            }
+        );
+        assert!(layout.callable.is_empty());
+        assert_eq!(layout.size, 256);
+
+        let mut data = vec![0xff; 512];
+        assert_eq!(builder.write_to(&mut data), layout);
+
+        assert_eq!(&data[..32], identifier(1).as_bytes());
+        assert_eq!(&data[32..64], &[0; 32]);
+        assert_eq!(&data[96..128], identifier(3).as_bytes());
+        assert_eq!(&data[128..160], identifier(4).as_bytes());
+        assert_eq!(&data[160..168], &0xdead_0000u64.to_le_bytes());
+        assert_eq!(&data[168..172], &7u32.to_le_bytes());
+        assert_eq!(&data[172..192], &[0; 20]);
+        assert_eq!(&data[192..224], identifier(5).as_bytes());
+        assert_eq!(data[256], 0xff);
+
+        let desc = layout.dispatch_rays_desc(0x10000, 1920, 1080, 1);
+        assert_eq!(desc.0.RayGenerationShaderRecord.StartAddress, 0x10000);
+        assert_eq!(desc.0.RayGenerationShaderRecord.SizeInBytes, 32);
+        assert_eq!(desc.0.MissShaderTable.StartAddress, 0x10040);
+        assert_eq!(desc.0.MissShaderTable.StrideInBytes, 32);
+        assert_eq!(desc.0.HitGroupTable.StartAddress, 0x10080);
+        assert_eq!(desc.0.HitGroupTable.SizeInBytes, 128);
+        assert_eq!(desc.0.CallableShaderTable.StartAddress, 0);
+        assert_eq!((desc.width(), desc.height(), desc.depth()), (1920, 1080, 1));
+    }
+}
@@ -0,0 +1,1022 @@
+use std::{any::Any, ffi::c_void, marker::PhantomData, mem::ManuallyDrop};
+
+use windows::{
+    core::{Interface, HSTRING, PCWSTR},
+    Win32::Graphics::Direct3D12::*,
+};
+
+use crate::{
+    blob::Blob,
+    create_type,
+    dx::{BlobbyInternal, RootSignature, WorkGraphProperties},
+    error::DxError,
+    impl_interface,
+    types::*,
+};
+
+create_type! {
+    /// Represents a variable amount of configuration state, including shaders, that an application manages as a single unit.
+    ///
+    /// For more information: [`ID3D12StateObject interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12stateobject)
+    StateObject wrap ID3D12StateObject
+}
+
+create_type! {
+    /// Provides methods for getting and setting the properties of a state object.
+    ///
+    /// For more information: [`ID3D12StateObjectProperties interface`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nn-d3d12-id3d12stateobjectproperties)
+    StateObjectProperties wrap ID3D12StateObjectProperties
+}
+
+create_type! {
+    /// Adds the ability to retrieve program identifiers, used to set work graphs on a command list.
+    ///
+    /// For more information: [`ID3D12StateObjectProperties1 interface`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#id3d12stateobjectproperties1-methods)
+    StateObjectProperties1 wrap ID3D12StateObjectProperties1; decorator for StateObjectProperties
+}
+
+impl_interface! {
+    StateObject;
+
+    /// Gets the properties interface of the state object.
+    pub fn properties(&self) -> Result<StateObjectProperties, DxError> {
+        self.0.cast().map(StateObjectProperties).map_err(DxError::from)
+    }
+
+    /// Gets the properties interface with program identifiers, available since the work graphs release of D3D12.
+    pub fn properties1(&self) -> Result<StateObjectProperties1, DxError> {
+        self.0.cast().map(StateObjectProperties1).map_err(DxError::from)
+    }
+
+    /// Gets the properties of the work graphs in an executable state object.
+    pub fn work_graph_properties(&self) -> Result<WorkGraphProperties, DxError> {
+        self.0.cast().map(WorkGraphProperties).map_err(DxError::from)
+    }
+}
+
+impl_interface! {
+    StateObjectProperties,
+    StateObjectProperties1;
+
+    /// Retrieves the unique identifier of a shader or hit group exported by the state object.
+    /// Returns [`None`] if the export isn't found.
+    ///
+    /// For more information: [`ID3D12StateObjectProperties::GetShaderIdentifier method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12stateobjectproperties-getshaderidentifier)
+    pub fn get_shader_identifier(&self, export_name: &str) -> Option<ShaderIdentifier> {
+        unsafe {
+            let identifier = self.0.GetShaderIdentifier(&HSTRING::from(export_name));
+
+            if identifier.is_null() {
+                return None;
+            }
+
+            Some(ShaderIdentifier(std::ptr::read_unaligned(identifier as *const _)))
+        }
+    }
+
+    /// Gets the amount of stack memory required to invoke a raytracing shader in HLSL.
+    ///
+    /// For more information: [`ID3D12StateObjectProperties::GetShaderStackSize method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12stateobjectproperties-getshaderstacksize)
+    pub fn get_shader_stack_size(&self, export_name: &str) -> u64 {
+        unsafe {
+            self.0.GetShaderStackSize(&HSTRING::from(export_name))
+        }
+    }
+
+    /// Gets the current pipeline stack size.
+    ///
+    /// For more information: [`ID3D12StateObjectProperties::GetPipelineStackSize method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12stateobjectproperties-getpipelinestacksize)
+    pub fn get_pipeline_stack_size(&self) -> u64 {
+        unsafe {
+            self.0.GetPipelineStackSize()
+        }
+    }
+
+    /// Sets the current pipeline stack size.
+    ///
+    /// For more information: [`ID3D12StateObjectProperties::SetPipelineStackSize method`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/nf-d3d12-id3d12stateobjectproperties-setpipelinestacksize)
+    pub fn set_pipeline_stack_size(&self, pipeline_stack_size_in_bytes: u64) {
+        unsafe {
+            self.0.SetPipelineStackSize(pipeline_stack_size_in_bytes)
+        }
+    }
+}
+
+impl_interface! {
+    StateObjectProperties1;
+
+    /// Retrieves the identifier of a program, such as a work graph, exported by the state object.
+    /// Returns [`None`] if the program isn't found.
+    ///
+    /// For more information: [`ID3D12StateObjectProperties1::GetProgramIdentifier method`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#getprogramidentifier)
+    pub fn get_program_identifier(&self, program_name: &str) -> Option<ProgramIdentifier> {
+        unsafe {
+            let identifier = self.0.GetProgramIdentifier(&HSTRING::from(program_name));
+
+            (identifier.OpaqueData != [0; 4]).then_some(ProgramIdentifier(identifier))
+        }
+    }
+}
+
+/// Opaque identifier of a program in a state object, see [`SetProgramDesc`](crate::work_graph::SetProgramDesc).
+///
+/// For more information: [`D3D12_PROGRAM_IDENTIFIER structure`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#d3d12_program_identifier)
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct ProgramIdentifier(pub(crate) D3D12_PROGRAM_IDENTIFIER);
+
+impl Eq for ProgramIdentifier {}
+
+impl std::hash::Hash for ProgramIdentifier {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.OpaqueData.hash(state);
+    }
+}
+
+/// Opaque identifier of a shader or hit group, which starts every record of a shader table.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct ShaderIdentifier([u8; ShaderIdentifier::SIZE]);
+
+impl ShaderIdentifier {
+    /// Size of an identifier in bytes.
+    pub const SIZE: usize = D3D12_SHADER_IDENTIFIER_SIZE_IN_BYTES as usize;
+
+    #[inline]
+    pub fn from_bytes(bytes: [u8; Self::SIZE]) -> Self {
+        Self(bytes)
+    }
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; Self::SIZE] {
+        &self.0
+    }
+}
+
+/// Describes an export from a DXIL library or an existing collection.
+///
+/// For more information: [`D3D12_EXPORT_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_export_desc)
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ExportDesc {
+    name: String,
+    export_to_rename: Option<String>,
+}
+
+impl ExportDesc {
+    #[inline]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            export_to_rename: None,
+        }
+    }
+
+    /// Exports `export_to_rename` from the library under the new `name`.
+    #[inline]
+    pub fn renamed(name: impl Into<String>, export_to_rename: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            export_to_rename: Some(export_to_rename.into()),
+        }
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn export_to_rename(&self) -> Option<&str> {
+        self.export_to_rename.as_deref()
+    }
+}
+
+/// Describes a DXIL library state subobject. A library without exports exports all of its shaders.
+///
+/// For more information: [`D3D12_DXIL_LIBRARY_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_dxil_library_desc)
+#[derive(Clone, Debug)]
+pub struct DxilLibraryDesc<'a> {
+    library: D3D12_SHADER_BYTECODE,
+    exports: Vec<ExportDesc>,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> DxilLibraryDesc<'a> {
+    #[inline]
+    pub fn new(library: &'a Blob) -> Self {
+        Self {
+            library: library.as_shader_bytecode(),
+            exports: vec![],
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn with_export(mut self, export: impl Into<String>) -> Self {
+        self.exports.push(ExportDesc::new(export));
+        self
+    }
+
+    #[inline]
+    pub fn with_renamed_export(
+        mut self,
+        name: impl Into<String>,
+        export_to_rename: impl Into<String>,
+    ) -> Self {
+        self.exports
+            .push(ExportDesc::renamed(name, export_to_rename));
+        self
+    }
+
+    #[inline]
+    pub fn exports(&self) -> &[ExportDesc] {
+        &self.exports
+    }
+}
+
+/// Describes a raytracing hit group state subobject.
+///
+/// For more information: [`D3D12_HIT_GROUP_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_hit_group_desc)
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct HitGroupDesc {
+    name: String,
+    r#type: HitGroupType,
+    any_hit: Option<String>,
+    closest_hit: Option<String>,
+    intersection: Option<String>,
+}
+
+impl HitGroupDesc {
+    /// Creates a hit group for triangle geometry.
+    #[inline]
+    pub fn triangles(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            r#type: HitGroupType::Triangles,
+            any_hit: None,
+            closest_hit: None,
+            intersection: None,
+        }
+    }
+
+    /// Creates a hit group for procedural primitives with the intersection shader.
+    #[inline]
+    pub fn procedural(name: impl Into<String>, intersection: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            r#type: HitGroupType::ProceduralPrimitive,
+            any_hit: None,
+            closest_hit: None,
+            intersection: Some(intersection.into()),
+        }
+    }
+
+    #[inline]
+    pub fn with_any_hit(mut self, any_hit: impl Into<String>) -> Self {
+        self.any_hit = Some(any_hit.into());
+        self
+    }
+
+    #[inline]
+    pub fn with_closest_hit(mut self, closest_hit: impl Into<String>) -> Self {
+        self.closest_hit = Some(closest_hit.into());
+        self
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn r#type(&self) -> HitGroupType {
+        self.r#type
+    }
+
+    #[inline]
+    pub fn any_hit(&self) -> Option<&str> {
+        self.any_hit.as_deref()
+    }
+
+    #[inline]
+    pub fn closest_hit(&self) -> Option<&str> {
+        self.closest_hit.as_deref()
+    }
+
+    #[inline]
+    pub fn intersection(&self) -> Option<&str> {
+        self.intersection.as_deref()
+    }
+}
+
+/// Identifies a node of a work graph by its name and array index.
+///
+/// For more information: [`D3D12_NODE_ID structure`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#d3d12_node_id)
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct NodeId {
+    name: String,
+    array_index: u32,
+}
+
+impl NodeId {
+    #[inline]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            array_index: 0,
+        }
+    }
+
+    #[inline]
+    pub fn with_array_index(mut self, array_index: u32) -> Self {
+        self.array_index = array_index;
+        self
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn array_index(&self) -> u32 {
+        self.array_index
+    }
+}
+
+/// Describes a work graph state subobject.
+///
+/// The nodes of the graph come from the DXIL libraries of the state object, nodes that aren't entry points are
+/// reachable from them or added with [`WorkGraphFlags::IncludeAllAvailableNodes`].
+///
+/// For more information: [`D3D12_WORK_GRAPH_DESC structure`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#d3d12_work_graph_desc)
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct WorkGraphDesc {
+    program_name: String,
+    flags: WorkGraphFlags,
+    entrypoints: Vec<NodeId>,
+}
+
+impl WorkGraphDesc {
+    #[inline]
+    pub fn new(program_name: impl Into<String>) -> Self {
+        Self {
+            program_name: program_name.into(),
+            flags: WorkGraphFlags::empty(),
+            entrypoints: vec![],
+        }
+    }
+
+    #[inline]
+    pub fn with_flags(mut self, flags: WorkGraphFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Adds an entry point, entry points are indexed in the order they are added.
+    #[inline]
+    pub fn with_entrypoint(mut self, node: NodeId) -> Self {
+        self.entrypoints.push(node);
+        self
+    }
+
+    #[inline]
+    pub fn program_name(&self) -> &str {
+        &self.program_name
+    }
+
+    #[inline]
+    pub fn flags(&self) -> WorkGraphFlags {
+        self.flags
+    }
+
+    #[inline]
+    pub fn entrypoints(&self) -> &[NodeId] {
+        &self.entrypoints
+    }
+}
+
+#[derive(Clone, Debug)]
+enum StateSubobject<'a> {
+    Config(StateObjectFlags),
+    GlobalRootSignature(*mut c_void),
+    LocalRootSignature(*mut c_void),
+    NodeMask(u32),
+    DxilLibrary(DxilLibraryDesc<'a>),
+    ExistingCollection(*mut c_void, Vec<ExportDesc>),
+    Association(usize, Vec<String>),
+    DxilAssociation(String, Vec<String>),
+    ShaderConfig(u32, u32),
+    PipelineConfig(u32),
+    HitGroup(HitGroupDesc),
+    PipelineConfig1(u32, RaytracingPipelineFlags),
+    WorkGraph(WorkGraphDesc),
+}
+
+impl StateSubobject<'_> {
+    fn r#type(&self) -> StateSubobjectType {
+        match self {
+            StateSubobject::Config(_) => StateSubobjectType::StateObjectConfig,
+            StateSubobject::GlobalRootSignature(_) => StateSubobjectType::GlobalRootSignature,
+            StateSubobject::LocalRootSignature(_) => StateSubobjectType::LocalRootSignature,
+            StateSubobject::NodeMask(_) => StateSubobjectType::NodeMask,
+            StateSubobject::DxilLibrary(_) => StateSubobjectType::DxilLibrary,
+            StateSubobject::ExistingCollection(..) => StateSubobjectType::ExistingCollection,
+            StateSubobject::Association(..) => StateSubobjectType::SubobjectToExportsAssociation,
+            StateSubobject::DxilAssociation(..) => {
+                StateSubobjectType::DxilSubobjectToExportsAssociation
+            }
+            StateSubobject::ShaderConfig(..) => StateSubobjectType::RaytracingShaderConfig,
+            StateSubobject::PipelineConfig(_) => StateSubobjectType::RaytracingPipelineConfig,
+            StateSubobject::HitGroup(_) => StateSubobjectType::HitGroup,
+            StateSubobject::PipelineConfig1(..) => StateSubobjectType::RaytracingPipelineConfig1,
+            StateSubobject::WorkGraph(_) => StateSubobjectType::WorkGraph,
+        }
+    }
+}
+
+/// Describes a state object as a list of subobjects, such as DXIL libraries, hit groups and raytracing configs.
+///
+/// Subobjects that apply to a set of exports, like local root signatures and shader configs, are followed by a
+/// subobject-to-exports association. Without exports they become the default for all exports they can apply to.
+///
+/// For more information: [`D3D12_STATE_OBJECT_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_state_object_desc)
+#[derive(Clone, Debug)]
+pub struct StateObjectDesc<'a> {
+    r#type: StateObjectType,
+    subobjects: Vec<StateSubobject<'a>>,
+}
+
+impl<'a> StateObjectDesc<'a> {
+    #[inline]
+    pub fn new(r#type: StateObjectType) -> Self {
+        Self {
+            r#type,
+            subobjects: vec![],
+        }
+    }
+
+    #[inline]
+    pub fn raytracing_pipeline() -> Self {
+        Self::new(StateObjectType::RaytracingPipeline)
+    }
+
+    #[inline]
+    pub fn collection() -> Self {
+        Self::new(StateObjectType::Collection)
+    }
+
+    /// Creates an executable state object, which holds work graphs.
+    #[inline]
+    pub fn executable() -> Self {
+        Self::new(StateObjectType::Executable)
+    }
+
+    #[inline]
+    pub fn with_config(self, flags: StateObjectFlags) -> Self {
+        self.with(StateSubobject::Config(flags))
+    }
+
+    #[inline]
+    pub fn with_global_root_signature(self, root_signature: &'a RootSignature) -> Self {
+        self.with(StateSubobject::GlobalRootSignature(
+            root_signature.0.as_raw(),
+        ))
+    }
+
+    /// Adds a local root signature associated with the exports.
+    #[inline]
+    pub fn with_local_root_signature<S: Into<String>>(
+        self,
+        root_signature: &'a RootSignature,
+        exports: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.with(StateSubobject::LocalRootSignature(
+            root_signature.0.as_raw(),
+        ))
+        .with_association(exports)
+    }
+
+    #[inline]
+    pub fn with_node_mask(self, node_mask: u32) -> Self {
+        self.with(StateSubobject::NodeMask(node_mask))
+    }
+
+    #[inline]
+    pub fn with_dxil_library(self, library: DxilLibraryDesc<'a>) -> Self {
+        self.with(StateSubobject::DxilLibrary(library))
+    }
+
+    /// Adds the exports of a collection, all of them if `exports` is empty.
+    #[inline]
+    pub fn with_existing_collection(
+        self,
+        collection: &'a StateObject,
+        exports: impl IntoIterator<Item = ExportDesc>,
+    ) -> Self {
+        self.with(StateSubobject::ExistingCollection(
+            collection.0.as_raw(),
+            exports.into_iter().collect(),
+        ))
+    }
+
+    #[inline]
+    pub fn with_hit_group(self, hit_group: HitGroupDesc) -> Self {
+        self.with(StateSubobject::HitGroup(hit_group))
+    }
+
+    /// Adds a shader config associated with the exports.
+    #[inline]
+    pub fn with_shader_config<S: Into<String>>(
+        self,
+        max_payload_size_in_bytes: u32,
+        max_attribute_size_in_bytes: u32,
+        exports: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.with(StateSubobject::ShaderConfig(
+            max_payload_size_in_bytes,
+            max_attribute_size_in_bytes,
+        ))
+        .with_association(exports)
+    }
+
+    #[inline]
+    pub fn with_pipeline_config(self, max_trace_recursion_depth: u32) -> Self {
+        self.with(StateSubobject::PipelineConfig(max_trace_recursion_depth))
+    }
+
+    #[inline]
+    pub fn with_pipeline_config1(
+        self,
+        max_trace_recursion_depth: u32,
+        flags: RaytracingPipelineFlags,
+    ) -> Self {
+        self.with(StateSubobject::PipelineConfig1(
+            max_trace_recursion_depth,
+            flags,
+        ))
+    }
+
+    #[inline]
+    pub fn with_work_graph(self, work_graph: WorkGraphDesc) -> Self {
+        self.with(StateSubobject::WorkGraph(work_graph))
+    }
+
+    /// Associates a subobject defined in a DXIL library with the exports.
+    #[inline]
+    pub fn with_dxil_association<S: Into<String>>(
+        self,
+        subobject: impl Into<String>,
+        exports: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.with(StateSubobject::DxilAssociation(
+            subobject.into(),
+            exports.into_iter().map(Into::into).collect(),
+        ))
+    }
+
+    #[inline]
+    pub fn r#type(&self) -> StateObjectType {
+        self.r#type
+    }
+
+    /// Returns the types of the subobjects in the order they are passed to D3D12.
+    pub fn subobject_types(&self) -> impl Iterator<Item = StateSubobjectType> + '_ {
+        self.subobjects.iter().map(StateSubobject::r#type)
+    }
+
+    /// Returns the type of every subobject associated with exports, and the names of the exports.
+    pub fn associations(&self) -> impl Iterator<Item = (StateSubobjectType, &[String])> + '_ {
+        self.subobjects
+            .iter()
+            .filter_map(|subobject| match subobject {
+                StateSubobject::Association(index, exports) => {
+                    Some((self.subobjects[*index].r#type(), exports.as_slice()))
+                }
+                _ => None,
+            })
+    }
+
+    #[inline]
+    fn with(mut self, subobject: StateSubobject<'a>) -> Self {
+        self.subobjects.push(subobject);
+        self
+    }
+
+    fn with_association<S: Into<String>>(mut self, exports: impl IntoIterator<Item = S>) -> Self {
+        let exports = exports.into_iter().map(Into::into).collect::<Vec<_>>();
+
+        if !exports.is_empty() {
+            let index = self.subobjects.len() - 1;
+            self.subobjects
+                .push(StateSubobject::Association(index, exports));
+        }
+
+        self
+    }
+
+    pub(crate) fn encode(&self) -> EncodedStateObject {
+        let mut encoded = EncodedStateObject {
+            desc: D3D12_STATE_OBJECT_DESC {
+                Type: self.r#type.as_raw(),
+                NumSubobjects: 0,
+                pSubobjects: std::ptr::null(),
+            },
+            subobjects: Vec::with_capacity(self.subobjects.len()),
+            payloads: vec![],
+            strings: vec![],
+            names: vec![],
+            exports: vec![],
+        };
+
+        // The association payloads point into `subobjects`, they are patched once it stops growing.
+        let mut associations: Vec<(*mut D3D12_SUBOBJECT_TO_EXPORTS_ASSOCIATION, usize)> = vec![];
+
+        for subobject in &self.subobjects {
+            let r#type = subobject.r#type();
+
+            match subobject {
+                StateSubobject::Config(flags) => encoded.push(
+                    r#type,
+                    D3D12_STATE_OBJECT_CONFIG {
+                        Flags: flags.as_raw(),
+                    },
+                ),
+                StateSubobject::GlobalRootSignature(root_signature) => encoded.push(
+                    r#type,
+                    D3D12_GLOBAL_ROOT_SIGNATURE {
+                        pGlobalRootSignature: unsafe { borrow_interface(*root_signature) },
+                    },
+                ),
+                StateSubobject::LocalRootSignature(root_signature) => encoded.push(
+                    r#type,
+                    D3D12_LOCAL_ROOT_SIGNATURE {
+                        pLocalRootSignature: unsafe { borrow_interface(*root_signature) },
+                    },
+                ),
+                StateSubobject::NodeMask(node_mask) => encoded.push(
+                    r#type,
+                    D3D12_NODE_MASK {
+                        NodeMask: *node_mask,
+                    },
+                ),
+                StateSubobject::DxilLibrary(library) => {
+                    let (num_exports, exports) = encoded.export_list(&library.exports);
+
+                    encoded.push(
+                        r#type,
+                        D3D12_DXIL_LIBRARY_DESC {
+                            DXILLibrary: library.library,
+                            NumExports: num_exports,
+                            pExports: exports,
+                        },
+                    )
+                }
+                StateSubobject::ExistingCollection(collection, exports) => {
+                    let (num_exports, exports) = encoded.export_list(exports);
+
+                    encoded.push(
+                        r#type,
+                        D3D12_EXISTING_COLLECTION_DESC {
+                            pExistingCollection: unsafe { borrow_interface(*collection) },
+                            NumExports: num_exports,
+                            pExports: exports,
+                        },
+                    )
+                }
+                StateSubobject::Association(index, exports) => {
+                    let (num_exports, exports) = encoded.name_list(exports);
+
+                    let mut association = Box::new(D3D12_SUBOBJECT_TO_EXPORTS_ASSOCIATION {
+                        pSubobjectToAssociate: std::ptr::null(),
+                        NumExports: num_exports,
+                        pExports: exports,
+                    });
+                    associations.push((&mut *association as *mut _, *index));
+                    encoded.push_boxed(r#type, association)
+                }
+                StateSubobject::DxilAssociation(subobject, exports) => {
+                    let subobject = encoded.string(subobject);
+                    let (num_exports, exports) = encoded.name_list(exports);
+
+                    encoded.push(
+                        r#type,
+                        D3D12_DXIL_SUBOBJECT_TO_EXPORTS_ASSOCIATION {
+                            SubobjectToAssociate: subobject,
+                            NumExports: num_exports,
+                            pExports: exports,
+                        },
+                    )
+                }
+                StateSubobject::ShaderConfig(max_payload_size, max_attribute_size) => encoded.push(
+                    r#type,
+                    D3D12_RAYTRACING_SHADER_CONFIG {
+                        MaxPayloadSizeInBytes: *max_payload_size,
+                        MaxAttributeSizeInBytes: *max_attribute_size,
+                    },
+                ),
+                StateSubobject::PipelineConfig(max_trace_recursion_depth) => encoded.push(
+                    r#type,
+                    D3D12_RAYTRACING_PIPELINE_CONFIG {
+                        MaxTraceRecursionDepth: *max_trace_recursion_depth,
+                    },
+                ),
+                StateSubobject::HitGroup(hit_group) => {
+                    let desc = D3D12_HIT_GROUP_DESC {
+                        HitGroupExport: encoded.string(&hit_group.name),
+                        Type: hit_group.r#type.as_raw(),
+                        AnyHitShaderImport: encoded.optional_string(hit_group.any_hit()),
+                        ClosestHitShaderImport: encoded.optional_string(hit_group.closest_hit()),
+                        IntersectionShaderImport: encoded.optional_string(hit_group.intersection()),
+                    };
+
+                    encoded.push(r#type, desc)
+                }
+                StateSubobject::PipelineConfig1(max_trace_recursion_depth, flags) => encoded.push(
+                    r#type,
+                    D3D12_RAYTRACING_PIPELINE_CONFIG1 {
+                        MaxTraceRecursionDepth: *max_trace_recursion_depth,
+                        Flags: flags.as_raw(),
+                    },
+                ),
+                StateSubobject::WorkGraph(work_graph) => {
+                    let entrypoints = work_graph
+                        .entrypoints
+                        .iter()
+                        .map(|node| D3D12_NODE_ID {
+                            Name: encoded.string(&node.name),
+                            ArrayIndex: node.array_index,
+                        })
+                        .collect::<Vec<_>>();
+
+                    let desc = D3D12_WORK_GRAPH_DESC {
+                        ProgramName: encoded.string(&work_graph.program_name),
+                        Flags: work_graph.flags.as_raw(),
+                        NumEntrypoints: entrypoints.len() as u32,
+                        pEntrypoints: encoded.keep(entrypoints),
+                        NumExplicitlyDefinedNodes: 0,
+                        pExplicitlyDefinedNodes: std::ptr::null(),
+                    };
+
+                    encoded.push(r#type, desc)
+                }
+            }
+        }
+
+        for (association, index) in associations {
+            unsafe {
+                (*association).pSubobjectToAssociate = &encoded.subobjects[index];
+            }
+        }
+
+        encoded.desc.NumSubobjects = encoded.subobjects.len() as u32;
+        encoded.desc.pSubobjects = encoded.subobjects.as_ptr();
+
+        encoded
+    }
+}
+
+/// A [`StateObjectDesc`] converted to D3D12 structures, which owns all memory they point to.
+pub(crate) struct EncodedStateObject {
+    desc: D3D12_STATE_OBJECT_DESC,
+    subobjects: Vec<D3D12_STATE_SUBOBJECT>,
+    payloads: Vec<Box<dyn Any>>,
+    strings: Vec<Vec<u16>>,
+    names: Vec<Vec<PCWSTR>>,
+    exports: Vec<Vec<D3D12_EXPORT_DESC>>,
+}
+
+impl EncodedStateObject {
+    #[inline]
+    pub(crate) fn as_raw(&self) -> &D3D12_STATE_OBJECT_DESC {
+        &self.desc
+    }
+
+    fn push<T: 'static>(&mut self, r#type: StateSubobjectType, payload: T) {
+        self.push_boxed(r#type, Box::new(payload));
+    }
+
+    fn push_boxed<T: 'static>(&mut self, r#type: StateSubobjectType, payload: Box<T>) {
+        self.subobjects.push(D3D12_STATE_SUBOBJECT {
+            Type: r#type.as_raw(),
+            pDesc: &*payload as *const T as *const c_void,
+        });
+        self.payloads.push(payload);
+    }
+
+    /// Keeps the list alive as long as the encoded state object, and returns a pointer to it.
+    fn keep<T: 'static>(&mut self, list: Vec<T>) -> *const T {
+        let ptr = if list.is_empty() {
+            std::ptr::null()
+        } else {
+            list.as_ptr()
+        };
+        self.payloads.push(Box::new(list));
+        ptr
+    }
+
+    fn string(&mut self, value: &str) -> PCWSTR {
+        let wide = value.encode_utf16().chain(Some(0)).collect::<Vec<_>>();
+        let ptr = PCWSTR(wide.as_ptr());
+        self.strings.push(wide);
+        ptr
+    }
+
+    fn optional_string(&mut self, value: Option<&str>) -> PCWSTR {
+        value.map_or(PCWSTR::null(), |value| self.string(value))
+    }
+
+    fn name_list(&mut self, names: &[String]) -> (u32, *const PCWSTR) {
+        let names = names
+            .iter()
+            .map(|name| self.string(name))
+            .collect::<Vec<_>>();
+        let result = (names.len() as u32, names.as_ptr());
+        self.names.push(names);
+        result
+    }
+
+    fn export_list(&mut self, exports: &[ExportDesc]) -> (u32, *const D3D12_EXPORT_DESC) {
+        if exports.is_empty() {
+            return (0, std::ptr::null());
+        }
+
+        let exports = exports
+            .iter()
+            .map(|export| D3D12_EXPORT_DESC {
+                Name: self.string(&export.name),
+                ExportToRename: self.optional_string(export.export_to_rename()),
+                Flags: D3D12_EXPORT_FLAG_NONE,
+            })
+            .collect::<Vec<_>>();
+        let result = (exports.len() as u32, exports.as_ptr());
+        self.exports.push(exports);
+        result
+    }
+}
+
+/// Borrows a COM pointer for a D3D12 struct without touching its reference count.
+unsafe fn borrow_interface<T: Interface>(raw: *mut c_void) -> ManuallyDrop<Option<T>> {
+    std::mem::transmute_copy(&raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_wide(ptr: PCWSTR) -> String {
+        unsafe {
+            let len = (0..).take_while(|&i| *ptr.0.add(i) != 0).count();
+            String::from_utf16_lossy(std::slice::from_raw_parts(ptr.0, len))
+        }
+    }
+
+    #[test]
+    fn subobjects_test() {
+        let library = Blob::from_static(b"DXIL library");
+        let desc = StateObjectDesc::raytracing_pipeline()
+            .with_config(StateObjectFlags::AllowStateObjectAdditions)
+            .with_dxil_library(
+                DxilLibraryDesc::new(&library)
+                    .with_export("RayGen")
+                    .with_renamed_export("Miss", "MissMain"),
+            )
+            .with_hit_group(HitGroupDesc::triangles("HitGroup").with_closest_hit("ClosestHit"))
+            .with_shader_config(16, 8, ["RayGen", "Miss", "HitGroup"])
+            .with_shader_config(4, 8, Vec::<String>::new())
+            .with_pipeline_config(1);
+
+        assert_eq!(
+            desc.subobject_types().collect::<Vec<_>>(),
+            [
+                StateSubobjectType::StateObjectConfig,
+                StateSubobjectType::DxilLibrary,
+                StateSubobjectType::HitGroup,
+                StateSubobjectType::RaytracingShaderConfig,
+                StateSubobjectType::SubobjectToExportsAssociation,
+                StateSubobjectType::RaytracingShaderConfig,
+                StateSubobjectType::RaytracingPipelineConfig,
+            ]
+        );
+
+        let associations = desc.associations().collect::<Vec<_>>();
+        assert_eq!(associations.len(), 1);
+        assert_eq!(
+            associations[0].0,
+            StateSubobjectType::RaytracingShaderConfig
+        );
+        assert_eq!(associations[0].1, ["RayGen", "Miss", "HitGroup"]);
+    }
+
+    #[test]
+    fn encode_test() {
+        let library = Blob::from_static(b"DXIL library");
+        let desc = StateObjectDesc::collection()
+            .with_dxil_library(
+                DxilLibraryDesc::new(&library)
+                    .with_export("RayGen")
+                    .with_renamed_export("Miss", "MissMain"),
+            )
+            .with_hit_group(
+                HitGroupDesc::procedural("HitGroup", "Intersection").with_any_hit("AnyHit"),
+            )
+            .with_shader_config(16, 8, ["RayGen", "HitGroup"])
+            .with_dxil_association("LocalSignature", ["Miss"])
+            .with_pipeline_config1(2, RaytracingPipelineFlags::SkipTriangles);
+
+        let encoded = desc.encode();
+        let raw = encoded.as_raw();
+
+        assert_eq!(raw.Type, D3D12_STATE_OBJECT_TYPE_COLLECTION);
+        assert_eq!(raw.NumSubobjects, 6);
+
+        let subobjects =
+            unsafe { std::slice::from_raw_parts(raw.pSubobjects, raw.NumSubobjects as usize) };
+
+        unsafe {
+            let library_desc = &*(subobjects[0].pDesc as *const D3D12_DXIL_LIBRARY_DESC);
+            assert_eq!(library_desc.DXILLibrary.BytecodeLength, library.len());
+            assert_eq!(library_desc.NumExports, 2);
+            let exports = std::slice::from_raw_parts(library_desc.pExports, 2);
+            assert_eq!(read_wide(exports[0].Name), "RayGen");
+            assert!(exports[0].ExportToRename.is_null());
+            assert_eq!(read_wide(exports[1].Name), "Miss");
+            assert_eq!(read_wide(exports[1].ExportToRename), "MissMain");
+
+            let hit_group = &*(subobjects[1].pDesc as *const D3D12_HIT_GROUP_DESC);
+            assert_eq!(hit_group.Type, D3D12_HIT_GROUP_TYPE_PROCEDURAL_PRIMITIVE);
+            assert_eq!(read_wide(hit_group.HitGroupExport), "HitGroup");
+            assert_eq!(read_wide(hit_group.AnyHitShaderImport), "AnyHit");
+            assert!(hit_group.ClosestHitShaderImport.is_null());
+            assert_eq!(
+                read_wide(hit_group.IntersectionShaderImport),
+                "Intersection"
+            );
+
+            let config = &*(subobjects[2].pDesc as *const D3D12_RAYTRACING_SHADER_CONFIG);
+            assert_eq!(config.MaxPayloadSizeInBytes, 16);
+            assert_eq!(config.MaxAttributeSizeInBytes, 8);
+
+            assert_eq!(
+                subobjects[3].Type,
+                D3D12_STATE_SUBOBJECT_TYPE_SUBOBJECT_TO_EXPORTS_ASSOCIATION
+            );
+            let association =
+                &*(subobjects[3].pDesc as *const D3D12_SUBOBJECT_TO_EXPORTS_ASSOCIATION);
+            assert_eq!(
+                association.pSubobjectToAssociate,
+                &subobjects[2] as *const _
+            );
+            let exports = std::slice::from_raw_parts(association.pExports, 2);
+            assert_eq!(read_wide(exports[0]), "RayGen");
+            assert_eq!(read_wide(exports[1]), "HitGroup");
+
+            let association =
+                &*(subobjects[4].pDesc as *const D3D12_DXIL_SUBOBJECT_TO_EXPORTS_ASSOCIATION);
+            assert_eq!(
+                read_wide(association.SubobjectToAssociate),
+                "LocalSignature"
+            );
+            assert_eq!(association.NumExports, 1);
+            assert_eq!(read_wide(*association.pExports), "Miss");
+
+            let config = &*(subobjects[5].pDesc as *const D3D12_RAYTRACING_PIPELINE_CONFIG1);
+            assert_eq!(config.MaxTraceRecursionDepth, 2);
+            assert_eq!(config.Flags, D3D12_RAYTRACING_PIPELINE_FLAG_SKIP_TRIANGLES);
+        }
+    }
+
+    #[test]
+    fn work_graph_encode_test() {
+        let library = Blob::from_static(b"DXIL library");
+        let desc = StateObjectDesc::executable()
+            .with_dxil_library(DxilLibraryDesc::new(&library))
+            .with_work_graph(
+                WorkGraphDesc::new("Graph")
+                    .with_flags(WorkGraphFlags::IncludeAllAvailableNodes)
+                    .with_entrypoint(NodeId::new("Entry").with_array_index(3)),
+            );
+
+        let encoded = desc.encode();
+        let raw = encoded.as_raw();
+
+        assert_eq!(raw.Type, D3D12_STATE_OBJECT_TYPE_EXECUTABLE);
+        assert_eq!(raw.NumSubobjects, 2);
+
+        unsafe {
+            let subobject = &*raw.pSubobjects.add(1);
+            assert_eq!(subobject.Type, D3D12_STATE_SUBOBJECT_TYPE_WORK_GRAPH);
+
+            let work_graph = &*(subobject.pDesc as *const D3D12_WORK_GRAPH_DESC);
+            assert_eq!(read_wide(work_graph.ProgramName), "Graph");
+            assert_eq!(
+                work_graph.Flags,
+                D3D12_WORK_GRAPH_FLAG_INCLUDE_ALL_AVAILABLE_NODES
+            );
+            assert_eq!(work_graph.NumEntrypoints, 1);
+            assert_eq!(read_wide((*work_graph.pEntrypoints).Name), "Entry");
+            assert_eq!((*work_graph.pEntrypoints).ArrayIndex, 3);
+            assert_eq!(work_graph.NumExplicitlyDefinedNodes, 0);
+        }
+    }
+}
@@ -0,0 +1,640 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{resources::Resource, types::*};
+
+/// States a resource can be in while only being read, which can be combined with each other.
+const READ_ONLY_STATES: ResourceStates = ResourceStates::VertexAndConstantBuffer
+    .union(ResourceStates::IndexBuffer)
+    .union(ResourceStates::DepthRead)
+    .union(ResourceStates::NonPixelShaderResource)
+    .union(ResourceStates::PixelShaderResource)
+    .union(ResourceStates::IndirectArgument)
+    .union(ResourceStates::CopySource)
+    .union(ResourceStates::ResolveSource)
+    .union(ResourceStates::ShadingRateSource);
+
+/// States a texture without simultaneous access can be implicitly promoted to from [`ResourceStates::Common`].
+const TEXTURE_PROMOTABLE_STATES: ResourceStates = ResourceStates::NonPixelShaderResource
+    .union(ResourceStates::PixelShaderResource)
+    .union(ResourceStates::CopySource)
+    .union(ResourceStates::CopyDest);
+
+/// Describes how a resource takes part in implicit state promotion and decay.
+///
+/// For more information: [`Implicit state transitions`](https://learn.microsoft.com/en-us/windows/win32/direct3d12/using-resource-barriers-to-synchronize-resource-states-in-direct3d-12#implicit-state-transitions)
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum ResourceStateKind {
+    /// Promoted from [`ResourceStates::Common`] to any state, decays back after every submission.
+    Buffer,
+
+    /// Promoted from [`ResourceStates::Common`] to shader resource and copy states only.
+    /// Decays back after a submission only from promoted read-only states or when used on a copy queue.
+    Texture,
+
+    /// A texture created with [`ResourceFlags::AllowSimultaneousAccess`], which behaves like a buffer.
+    SimultaneousAccessTexture,
+}
+
+impl ResourceStateKind {
+    #[inline]
+    fn can_promote(&self, state: ResourceStates) -> bool {
+        match self {
+            ResourceStateKind::Buffer | ResourceStateKind::SimultaneousAccessTexture => true,
+            ResourceStateKind::Texture => {
+                TEXTURE_PROMOTABLE_STATES.contains(state)
+                    && (!state.contains(ResourceStates::CopyDest)
+                        || state == ResourceStates::CopyDest)
+            }
+        }
+    }
+
+    #[inline]
+    fn always_decays(&self) -> bool {
+        !matches!(self, ResourceStateKind::Texture)
+    }
+}
+
+/// Describes a resource tracked by a [`ResourceStateTracker`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct TrackedResourceDesc {
+    pub kind: ResourceStateKind,
+    pub subresource_count: u32,
+}
+
+impl TrackedResourceDesc {
+    #[inline]
+    pub fn buffer() -> Self {
+        Self {
+            kind: ResourceStateKind::Buffer,
+            subresource_count: 1,
+        }
+    }
+
+    #[inline]
+    pub fn texture(subresource_count: u32) -> Self {
+        Self {
+            kind: ResourceStateKind::Texture,
+            subresource_count,
+        }
+    }
+
+    #[inline]
+    pub fn simultaneous_access_texture(subresource_count: u32) -> Self {
+        Self {
+            kind: ResourceStateKind::SimultaneousAccessTexture,
+            subresource_count,
+        }
+    }
+}
+
+/// A transition computed by a [`ResourceStateTracker`]. A [`None`] subresource means all subresources.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct StateTransition<K> {
+    pub resource: K,
+    pub subresource: Option<u32>,
+    pub before: ResourceStates,
+    pub after: ResourceStates,
+}
+
+impl<'a> StateTransition<&'a Resource> {
+    #[inline]
+    pub fn barrier(&self) -> ResourceBarrier<'a> {
+        ResourceBarrier::transition(self.resource, self.before, self.after, self.subresource)
+    }
+}
+
+/// The states of all tracked resources between command list submissions.
+///
+/// Command lists record with their own [`ResourceStateTracker`] and are reconciled with the global
+/// states when submitted, see [`ResourceStateTracker::resolve_pending`] and [`ResourceStateTracker::commit`].
+#[derive(Clone, Debug)]
+pub struct GlobalResourceStates<K> {
+    resources: HashMap<K, (TrackedResourceDesc, Vec<ResourceStates>)>,
+}
+
+impl<K: Copy + Eq + Hash> Default for GlobalResourceStates<K> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            resources: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash> GlobalResourceStates<K> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts tracking a resource, all subresources being in `initial_state`.
+    pub fn register(
+        &mut self,
+        resource: K,
+        desc: TrackedResourceDesc,
+        initial_state: ResourceStates,
+    ) {
+        self.resources.insert(
+            resource,
+            (desc, vec![initial_state; desc.subresource_count as usize]),
+        );
+    }
+
+    #[inline]
+    pub fn unregister(&mut self, resource: K) {
+        self.resources.remove(&resource);
+    }
+
+    #[inline]
+    pub fn desc(&self, resource: K) -> Option<TrackedResourceDesc> {
+        self.resources.get(&resource).map(|(desc, _)| *desc)
+    }
+
+    #[inline]
+    pub fn state(&self, resource: K, subresource: u32) -> Option<ResourceStates> {
+        self.resources
+            .get(&resource)
+            .and_then(|(_, states)| states.get(subresource as usize).copied())
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct SubresourceState {
+    state: ResourceStates,
+    /// The state was reached by implicit promotion, so read-only states decay after the submission.
+    promoted: bool,
+    /// The state before the first use is unknown until the submission.
+    pending: bool,
+}
+
+#[derive(Clone, Debug)]
+struct LocalResource {
+    desc: TrackedResourceDesc,
+    subresources: Vec<Option<SubresourceState>>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct PendingTransition<K> {
+    resource: K,
+    subresource: u32,
+    after: ResourceStates,
+}
+
+/// Records the state of every subresource used by a command list and computes the transitions it needs.
+///
+/// The state of a resource before its first use in the command list is unknown, so the first transition is
+/// deferred. At submission time [`ResourceStateTracker::resolve_pending`] compares the deferred transitions
+/// with the [`GlobalResourceStates`], and the resulting barriers must be recorded in a small command list executed
+/// just before this one. [`ResourceStateTracker::commit`] then writes the final states back, applying state decay.
+#[derive(Clone, Debug)]
+pub struct ResourceStateTracker<K> {
+    resources: HashMap<K, LocalResource>,
+    pending: Vec<PendingTransition<K>>,
+}
+
+impl<K: Copy + Eq + Hash> Default for ResourceStateTracker<K> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            resources: HashMap::new(),
+            pending: vec![],
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash> ResourceStateTracker<K> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests `subresource` (all subresources for [`None`]) to be in `after` and returns the transitions to record.
+    ///
+    /// No transition is returned when the state already allows the access or when it is reached by implicit promotion.
+    pub fn transition(
+        &mut self,
+        resource: K,
+        desc: TrackedResourceDesc,
+        subresource: Option<u32>,
+        after: ResourceStates,
+    ) -> Vec<StateTransition<K>> {
+        let local = self
+            .resources
+            .entry(resource)
+            .or_insert_with(|| LocalResource {
+                desc,
+                subresources: vec![None; desc.subresource_count as usize],
+            });
+
+        let range = match subresource {
+            Some(index) => {
+                assert!(
+                    index < local.desc.subresource_count,
+                    "subresource {index} out of range, the resource has {} subresources",
+                    local.desc.subresource_count
+                );
+                index..index + 1
+            }
+            None => 0..local.desc.subresource_count,
+        };
+
+        let mut transitions = vec![];
+
+        for index in range {
+            let slot = &mut local.subresources[index as usize];
+
+            let Some(current) = slot.as_mut() else {
+                self.pending.push(PendingTransition {
+                    resource,
+                    subresource: index,
+                    after,
+                });
+                *slot = Some(SubresourceState {
+                    state: after,
+                    promoted: false,
+                    pending: true,
+                });
+                continue;
+            };
+
+            if allows(current.state, after) {
+                continue;
+            }
+
+            // The deferred first transition can target several read-only states at once.
+            if current.pending && is_read_only(current.state) && is_read_only(after) {
+                current.state |= after;
+
+                if let Some(pending) = self
+                    .pending
+                    .iter_mut()
+                    .rev()
+                    .find(|p| p.resource == resource && p.subresource == index)
+                {
+                    pending.after = current.state;
+                }
+                continue;
+            }
+
+            if current.promoted
+                && is_read_only(current.state)
+                && is_read_only(after)
+                && local.desc.kind.can_promote(current.state | after)
+            {
+                current.state |= after;
+                continue;
+            }
+
+            if current.state == ResourceStates::Common
+                && !current.pending
+                && local.desc.kind.can_promote(after)
+            {
+                current.state = after;
+                current.promoted = true;
+                continue;
+            }
+
+            transitions.push(StateTransition {
+                resource,
+                subresource: Some(index),
+                before: current.state,
+                after,
+            });
+            *current = SubresourceState {
+                state: after,
+                promoted: false,
+                pending: false,
+            };
+        }
+
+        collapse(&mut transitions, local.desc.subresource_count);
+
+        transitions
+    }
+
+    /// Resolves the deferred first transitions against the global states and returns the transitions
+    /// that must be executed before this command list. Unregistered resources are assumed to be in [`ResourceStates::Common`].
+    pub fn resolve_pending(&mut self, global: &GlobalResourceStates<K>) -> Vec<StateTransition<K>> {
+        let mut transitions: Vec<StateTransition<K>> = vec![];
+
+        for pending in self.pending.drain(..) {
+            let before = global
+                .state(pending.resource, pending.subresource)
+                .unwrap_or(ResourceStates::Common);
+            let local = self
+                .resources
+                .get_mut(&pending.resource)
+                .expect("pending transition of an untracked resource");
+
+            let promoted =
+                before == ResourceStates::Common && local.desc.kind.can_promote(pending.after);
+
+            // An explicit transition recorded after the first use replaces the state, so the promotion no longer applies to it.
+            if let Some(state) = &mut local.subresources[pending.subresource as usize] {
+                if state.pending {
+                    state.pending = false;
+                    state.promoted = promoted;
+                }
+            }
+
+            if promoted || before == pending.after {
+                continue;
+            }
+
+            transitions.push(StateTransition {
+                resource: pending.resource,
+                subresource: Some(pending.subresource),
+                before,
+                after: pending.after,
+            });
+        }
+
+        let mut collapsed = vec![];
+        let mut index = 0;
+
+        while index < transitions.len() {
+            let resource = transitions[index].resource;
+            let end = index
+                + transitions[index..]
+                    .iter()
+                    .take_while(|t| t.resource == resource)
+                    .count();
+
+            let mut group = transitions[index..end].to_vec();
+            collapse(&mut group, self.resources[&resource].desc.subresource_count);
+            collapsed.extend(group);
+
+            index = end;
+        }
+
+        collapsed
+    }
+
+    /// Writes the final states to `global` once the command list is submitted on a queue of `queue_type`,
+    /// applying implicit state decay, and resets the tracker for the next recording.
+    pub fn commit(&mut self, global: &mut GlobalResourceStates<K>, queue_type: CommandListType) {
+        debug_assert!(self.pending.is_empty(), "commit before resolve_pending");
+
+        for (resource, local) in self.resources.drain() {
+            let entry = global.resources.entry(resource).or_insert_with(|| {
+                (
+                    local.desc,
+                    vec![ResourceStates::Common; local.desc.subresource_count as usize],
+                )
+            });
+
+            assert_eq!(
+                entry.1.len(),
+                local.subresources.len(),
+                "subresource count of a tracked resource differs from its global states"
+            );
+
+            for (global_state, state) in entry.1.iter_mut().zip(local.subresources) {
+                let Some(state) = state else {
+                    continue;
+                };
+
+                let decays = queue_type == CommandListType::Copy
+                    || local.desc.kind.always_decays()
+                    || (state.promoted && is_read_only(state.state));
+
+                *global_state = if decays {
+                    ResourceStates::Common
+                } else {
+                    state.state
+                };
+            }
+        }
+
+        self.pending.clear();
+    }
+}
+
+#[inline]
+fn is_read_only(state: ResourceStates) -> bool {
+    !state.is_empty() && READ_ONLY_STATES.contains(state)
+}
+
+/// Returns `true` if a subresource in `current` can be accessed as `after` without a transition.
+#[inline]
+fn allows(current: ResourceStates, after: ResourceStates) -> bool {
+    current == after || (is_read_only(current) && !after.is_empty() && current.contains(after))
+}
+
+/// Replaces per-subresource transitions covering the whole resource with a single one.
+fn collapse<K: Copy + Eq>(transitions: &mut Vec<StateTransition<K>>, subresource_count: u32) {
+    if transitions.is_empty() {
+        return;
+    }
+
+    if subresource_count <= 1 && transitions.len() == 1 {
+        transitions[0].subresource = None;
+        return;
+    }
+
+    if transitions.len() != subresource_count as usize {
+        return;
+    }
+
+    let first = transitions[0];
+    if transitions
+        .iter()
+        .all(|t| t.before == first.before && t.after == first.after)
+    {
+        transitions.truncate(1);
+        transitions[0].subresource = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimal_transitions_test() {
+        let desc = TrackedResourceDesc::texture(4);
+        let mut global = GlobalResourceStates::new();
+        global.register(0, desc, ResourceStates::RenderTarget);
+
+        let mut tracker = ResourceStateTracker::new();
+        assert!(tracker
+            .transition(0, desc, None, ResourceStates::PixelShaderResource)
+            .is_empty());
+        assert!(tracker
+            .transition(0, desc, None, ResourceStates::PixelShaderResource)
+            .is_empty());
+
+        assert_eq!(
+            tracker.transition(0, desc, Some(2), ResourceStates::RenderTarget),
+            [StateTransition {
+                resource: 0,
+                subresource: Some(2),
+                before: ResourceStates::PixelShaderResource,
+                after: ResourceStates::RenderTarget
+            }]
+        );
+        assert_eq!(
+            tracker.transition(0, desc, None, ResourceStates::RenderTarget),
+            [
+                StateTransition {
+                    resource: 0,
+                    subresource: Some(0),
+                    before: ResourceStates::PixelShaderResource,
+                    after: ResourceStates::RenderTarget
+                },
+                StateTransition {
+                    resource: 0,
+                    subresource: Some(1),
+                    before: ResourceStates::PixelShaderResource,
+                    after: ResourceStates::RenderTarget
+                },
+                StateTransition {
+                    resource: 0,
+                    subresource: Some(3),
+                    before: ResourceStates::PixelShaderResource,
+                    after: ResourceStates::RenderTarget
+                },
+            ]
+        );
+
+        assert_eq!(
+            tracker.resolve_pending(&global),
+            [StateTransition {
+                resource: 0,
+                subresource: None,
+                before: ResourceStates::RenderTarget,
+                after: ResourceStates::PixelShaderResource
+            }]
+        );
+
+        tracker.commit(&mut global, CommandListType::Direct);
+        assert_eq!(global.state(0, 1), Some(ResourceStates::RenderTarget));
+    }
+
+    #[test]
+    fn read_states_test() {
+        let desc = TrackedResourceDesc::texture(1);
+        let mut global = GlobalResourceStates::new();
+        global.register(0, desc, ResourceStates::GenericRead);
+
+        let mut tracker = ResourceStateTracker::new();
+        tracker.transition(0, desc, None, ResourceStates::GenericRead);
+        assert!(tracker
+            .transition(0, desc, None, ResourceStates::CopySource)
+            .is_empty());
+        assert!(tracker.resolve_pending(&global).is_empty());
+    }
+
+    #[test]
+    fn promotion_and_decay_test() {
+        let buffer = TrackedResourceDesc::buffer();
+        let texture = TrackedResourceDesc::texture(1);
+        let mut global = GlobalResourceStates::new();
+        global.register(0, buffer, ResourceStates::Common);
+        global.register(1, texture, ResourceStates::Common);
+
+        let mut tracker = ResourceStateTracker::new();
+        tracker.transition(0, buffer, None, ResourceStates::UnorderedAccess);
+        tracker.transition(1, texture, None, ResourceStates::PixelShaderResource);
+        assert!(tracker
+            .transition(1, texture, None, ResourceStates::NonPixelShaderResource)
+            .is_empty());
+
+        assert!(tracker.resolve_pending(&global).is_empty());
+        tracker.commit(&mut global, CommandListType::Direct);
+
+        assert_eq!(global.state(0, 0), Some(ResourceStates::Common));
+        assert_eq!(global.state(1, 0), Some(ResourceStates::Common));
+
+        tracker.transition(1, texture, None, ResourceStates::RenderTarget);
+        assert_eq!(
+            tracker.resolve_pending(&global),
+            [StateTransition {
+                resource: 1,
+                subresource: None,
+                before: ResourceStates::Common,
+                after: ResourceStates::RenderTarget,
+            }]
+        );
+        tracker.commit(&mut global, CommandListType::Direct);
+        assert_eq!(global.state(1, 0), Some(ResourceStates::RenderTarget));
+    }
+
+    #[test]
+    fn promotion_in_command_list_test() {
+        let texture = TrackedResourceDesc::texture(1);
+        let mut global = GlobalResourceStates::new();
+        global.register(0, texture, ResourceStates::RenderTarget);
+
+        let mut tracker = ResourceStateTracker::new();
+        tracker.transition(0, texture, None, ResourceStates::RenderTarget);
+        assert_eq!(
+            tracker.transition(0, texture, None, ResourceStates::Common),
+            [StateTransition {
+                resource: 0,
+                subresource: None,
+                before: ResourceStates::RenderTarget,
+                after: ResourceStates::Common
+            }]
+        );
+        assert!(tracker
+            .transition(0, texture, None, ResourceStates::CopyDest)
+            .is_empty());
+        assert_eq!(
+            tracker.transition(0, texture, None, ResourceStates::UnorderedAccess),
+            [StateTransition {
+                resource: 0,
+                subresource: None,
+                before: ResourceStates::CopyDest,
+                after: ResourceStates::UnorderedAccess
+            }]
+        );
+
+        assert!(tracker.resolve_pending(&global).is_empty());
+        tracker.commit(&mut global, CommandListType::Copy);
+        assert_eq!(global.state(0, 0), Some(ResourceStates::Common));
+    }
+
+    #[test]
+    fn partial_read_state_test() {
+        let texture = TrackedResourceDesc::texture(1);
+        let mut global = GlobalResourceStates::new();
+        global.register(
+            0,
+            texture,
+            ResourceStates::DepthRead | ResourceStates::PixelShaderResource,
+        );
+
+        let mut tracker = ResourceStateTracker::new();
+        tracker.transition(0, texture, None, ResourceStates::PixelShaderResource);
+        tracker.transition(0, texture, None, ResourceStates::DepthWrite);
+
+        assert_eq!(
+            tracker.resolve_pending(&global),
+            [StateTransition {
+                resource: 0,
+                subresource: None,
+                before: ResourceStates::DepthRead | ResourceStates::PixelShaderResource,
+                after: ResourceStates::PixelShaderResource,
+            }]
+        );
+        tracker.commit(&mut global, CommandListType::Direct);
+        assert_eq!(global.state(0, 0), Some(ResourceStates::DepthWrite));
+    }
+
+    #[test]
+    fn promotion_replaced_test() {
+        let texture = TrackedResourceDesc::texture(1);
+        let mut global = GlobalResourceStates::new();
+        global.register(0, texture, ResourceStates::Common);
+
+        let mut tracker = ResourceStateTracker::new();
+        tracker.transition(0, texture, None, ResourceStates::PixelShaderResource);
+        tracker.transition(0, texture, None, ResourceStates::RenderTarget);
+        tracker.transition(0, texture, None, ResourceStates::CopySource);
+
+        assert!(tracker.resolve_pending(&global).is_empty());
+        tracker.commit(&mut global, CommandListType::Direct);
+        assert_eq!(global.state(0, 0), Some(ResourceStates::CopySource));
+    }
+}
@@ -25,8 +25,11 @@ pub const BARRIER_ALL_SUBRESOURCES: u32 = D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCE
 pub const TEXTURE_DATA_PITCH_ALIGNMENT: u32 = D3D12_TEXTURE_DATA_PITCH_ALIGNMENT;
 pub const APPEND_ALIGNED_ELEMENT: u32 = D3D12_APPEND_ALIGNED_ELEMENT;
 
+#[deprecated(note = "use `CompileFlags::Debug`")]
 pub const COMPILE_DEBUG: u32 = D3DCOMPILE_DEBUG;
+#[deprecated(note = "use `CompileFlags::SkipOptimization`")]
 pub const COMPILE_SKIP_OPT: u32 = D3DCOMPILE_SKIP_OPTIMIZATION;
+#[deprecated(note = "use `CompileFlags::PackMatrixRowMajor`")]
 pub const PACK_MATRIX_ROW_MAJOR: u32 = D3DCOMPILE_PACK_MATRIX_ROW_MAJOR;
 
 pub const DESCRIPTOR_RANGE_OFFSET_APPEND: u32 = D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND;
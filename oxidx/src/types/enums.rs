@@ -1,7 +1,10 @@
 use std::ffi::CStr;
 
 use strum::FromRepr;
-use windows::Win32::Graphics::{Direct3D::*, Direct3D12::*};
+use windows::Win32::Graphics::{
+    Direct3D::{Fxc::*, *},
+    Direct3D12::*,
+};
 
 #[allow(unused_imports)]
 use super::*;
@@ -53,6 +56,110 @@ pub enum AlphaMode {
     Ignore = DXGI_ALPHA_MODE_IGNORE.0,
 }
 
+/// Specifies the layout of a texture subresource, which describes how the GPU may access it.
+///
+/// For more information: [`D3D12_BARRIER_LAYOUT enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_barrier_layout)
+#[derive(Clone, Copy, Debug, Default, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum BarrierLayout {
+    /// The layout is unknown or doesn't matter; the contents may be discarded.
+    Undefined = D3D12_BARRIER_LAYOUT_UNDEFINED.0,
+
+    /// Default layout, compatible with copies and shader resources on any queue; same value as `PRESENT`.
+    #[default]
+    Common = D3D12_BARRIER_LAYOUT_COMMON.0,
+
+    /// Read-only layout that supports any read-only access.
+    GenericRead = D3D12_BARRIER_LAYOUT_GENERIC_READ.0,
+
+    /// Used as a render target.
+    RenderTarget = D3D12_BARRIER_LAYOUT_RENDER_TARGET.0,
+
+    /// Used through unordered access views.
+    UnorderedAccess = D3D12_BARRIER_LAYOUT_UNORDERED_ACCESS.0,
+
+    /// Used as a writable depth-stencil target.
+    DepthStencilWrite = D3D12_BARRIER_LAYOUT_DEPTH_STENCIL_WRITE.0,
+
+    /// Used as a read-only depth-stencil target.
+    DepthStencilRead = D3D12_BARRIER_LAYOUT_DEPTH_STENCIL_READ.0,
+
+    /// Read through shader resource views.
+    ShaderResource = D3D12_BARRIER_LAYOUT_SHADER_RESOURCE.0,
+
+    /// Used as the source of copies.
+    CopySource = D3D12_BARRIER_LAYOUT_COPY_SOURCE.0,
+
+    /// Used as the destination of copies.
+    CopyDest = D3D12_BARRIER_LAYOUT_COPY_DEST.0,
+
+    /// Used as the source of resolves.
+    ResolveSource = D3D12_BARRIER_LAYOUT_RESOLVE_SOURCE.0,
+
+    /// Used as the destination of resolves.
+    ResolveDest = D3D12_BARRIER_LAYOUT_RESOLVE_DEST.0,
+
+    /// Used as a shading rate image.
+    ShadingRateSource = D3D12_BARRIER_LAYOUT_SHADING_RATE_SOURCE.0,
+
+    /// Read by video decoding.
+    VideoDecodeRead = D3D12_BARRIER_LAYOUT_VIDEO_DECODE_READ.0,
+
+    /// Written by video decoding.
+    VideoDecodeWrite = D3D12_BARRIER_LAYOUT_VIDEO_DECODE_WRITE.0,
+
+    /// Read by video processing.
+    VideoProcessRead = D3D12_BARRIER_LAYOUT_VIDEO_PROCESS_READ.0,
+
+    /// Written by video processing.
+    VideoProcessWrite = D3D12_BARRIER_LAYOUT_VIDEO_PROCESS_WRITE.0,
+
+    /// Read by video encoding.
+    VideoEncodeRead = D3D12_BARRIER_LAYOUT_VIDEO_ENCODE_READ.0,
+
+    /// Written by video encoding.
+    VideoEncodeWrite = D3D12_BARRIER_LAYOUT_VIDEO_ENCODE_WRITE.0,
+
+    /// Common layout, only usable on direct queues.
+    DirectQueueCommon = D3D12_BARRIER_LAYOUT_DIRECT_QUEUE_COMMON.0,
+
+    /// Generic read layout, only usable on direct queues.
+    DirectQueueGenericRead = D3D12_BARRIER_LAYOUT_DIRECT_QUEUE_GENERIC_READ.0,
+
+    /// Unordered access layout, only usable on direct queues.
+    DirectQueueUnorderedAccess = D3D12_BARRIER_LAYOUT_DIRECT_QUEUE_UNORDERED_ACCESS.0,
+
+    /// Shader resource layout, only usable on direct queues.
+    DirectQueueShaderResource = D3D12_BARRIER_LAYOUT_DIRECT_QUEUE_SHADER_RESOURCE.0,
+
+    /// Copy source layout, only usable on direct queues.
+    DirectQueueCopySource = D3D12_BARRIER_LAYOUT_DIRECT_QUEUE_COPY_SOURCE.0,
+
+    /// Copy destination layout, only usable on direct queues.
+    DirectQueueCopyDest = D3D12_BARRIER_LAYOUT_DIRECT_QUEUE_COPY_DEST.0,
+
+    /// Common layout, only usable on compute queues.
+    ComputeQueueCommon = D3D12_BARRIER_LAYOUT_COMPUTE_QUEUE_COMMON.0,
+
+    /// Generic read layout, only usable on compute queues.
+    ComputeQueueGenericRead = D3D12_BARRIER_LAYOUT_COMPUTE_QUEUE_GENERIC_READ.0,
+
+    /// Unordered access layout, only usable on compute queues.
+    ComputeQueueUnorderedAccess = D3D12_BARRIER_LAYOUT_COMPUTE_QUEUE_UNORDERED_ACCESS.0,
+
+    /// Shader resource layout, only usable on compute queues.
+    ComputeQueueShaderResource = D3D12_BARRIER_LAYOUT_COMPUTE_QUEUE_SHADER_RESOURCE.0,
+
+    /// Copy source layout, only usable on compute queues.
+    ComputeQueueCopySource = D3D12_BARRIER_LAYOUT_COMPUTE_QUEUE_COPY_SOURCE.0,
+
+    /// Copy destination layout, only usable on compute queues.
+    ComputeQueueCopyDest = D3D12_BARRIER_LAYOUT_COMPUTE_QUEUE_COPY_DEST.0,
+
+    /// Common layout, only usable on video queues.
+    VideoQueueCommon = D3D12_BARRIER_LAYOUT_VIDEO_QUEUE_COMMON.0,
+}
+
 /// Specifies blend factors, which modulate values for the pixel shader and render target.
 ///
 /// For more information: [`D3D12_BLEND enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_blend)
@@ -488,6 +595,39 @@ pub enum DescriptorRangeType {
     Sampler = D3D12_DESCRIPTOR_RANGE_TYPE_SAMPLER.0,
 }
 
+/// Specifies how the inputs of a work graph dispatch are provided.
+///
+/// For more information: [`D3D12_DISPATCH_MODE enumeration`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#d3d12_dispatch_mode)
+#[derive(Clone, Copy, Debug, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum DispatchMode {
+    /// Input records of a single entry point node are provided from CPU memory.
+    NodeCpuInput = D3D12_DISPATCH_MODE_NODE_CPU_INPUT.0,
+
+    /// Input records of a single entry point node are provided from GPU memory.
+    NodeGpuInput = D3D12_DISPATCH_MODE_NODE_GPU_INPUT.0,
+
+    /// Input records of several entry point nodes are provided from CPU memory.
+    MultiNodeCpuInput = D3D12_DISPATCH_MODE_MULTI_NODE_CPU_INPUT.0,
+
+    /// Input records of several entry point nodes are provided from GPU memory.
+    MultiNodeGpuInput = D3D12_DISPATCH_MODE_MULTI_NODE_GPU_INPUT.0,
+}
+
+/// Defines constants that specify the level of support for `ExecuteIndirect`.
+///
+/// For more information: [`D3D12_EXECUTE_INDIRECT_TIER enumeration`](https://microsoft.github.io/DirectX-Specs/d3d/IndirectDrawing.html)
+#[derive(Clone, Copy, Debug, Default, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum ExecuteIndirectTier {
+    /// Specifies the original `ExecuteIndirect` support.
+    #[default]
+    Tier1_0 = D3D12_EXECUTE_INDIRECT_TIER_1_0.0,
+
+    /// Specifies that the command signature can change the pipeline state and root signature.
+    Tier1_1 = D3D12_EXECUTE_INDIRECT_TIER_1_1.0,
+}
+
 /// Describes the set of features targeted by a Direct3D device.
 ///
 /// For more information: [`D3D_FEATURE_LEVEL enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3dcommon/ne-d3dcommon-d3d_feature_level)
@@ -637,6 +777,9 @@ pub enum FeatureType {
     /// TBD
     Options20 = D3D12_FEATURE_D3D12_OPTIONS20.0,
 
+    /// Indicates the level of support for work graphs and `ExecuteIndirect`.
+    Options21 = D3D12_FEATURE_D3D12_OPTIONS21.0,
+
     /// TBD
     Predication = D3D12_FEATURE_PREDICATION.0,
 
@@ -862,6 +1005,9 @@ pub enum Format {
     /// A 32-bit typeless component, and two unsigned-integer components (with an additional 32 bits). This format has 32 bits unused, 8 bits for green channel, and 24 bits are unused.
     R32FloatX8X24Typeless = DXGI_FORMAT_R32_FLOAT_X8X24_TYPELESS.0,
 
+    /// A 32-bit typeless component, and two unsigned-integer components (with an additional 32 bits). This format has 32 bits unused, 8 bits for green channel, and 24 bits are unused.
+    X32TypelessG8X24Uint = DXGI_FORMAT_X32_TYPELESS_G8X24_UINT.0,
+
     /// A four-component, 32-bit typeless format that supports 10 bits for each color and 2 bits for alpha.
     Rgb10A2Typeless = DXGI_FORMAT_R10G10B10A2_TYPELESS.0,
 
@@ -1153,6 +1299,113 @@ pub enum Format {
     V408 = DXGI_FORMAT_V408.0,
 }
 
+impl Format {
+    /// Returns `true` for the `*_TYPELESS` resource formats that need a typed view, like [`Format::Rgba8Typeless`].
+    ///
+    /// The depth-stencil view formats with a typeless part, like [`Format::R24UnormX8Typeless`], are typed.
+    #[inline]
+    pub fn is_typeless(&self) -> bool {
+        matches!(
+            self,
+            Format::Rgba32Typeless
+                | Format::Rgb32Typeless
+                | Format::Rgba16Typeless
+                | Format::Rg32Typeless
+                | Format::R32G8X24Typeless
+                | Format::Rgb10A2Typeless
+                | Format::Rgba8Typeless
+                | Format::Rg16Typeless
+                | Format::R32Typeless
+                | Format::R24G8Typeless
+                | Format::Rg8Typeless
+                | Format::R16Typeless
+                | Format::R8Typeless
+                | Format::Bc1Typeless
+                | Format::Bc2Typeless
+                | Format::Bc3Typeless
+                | Format::Bc4Typeless
+                | Format::Bc5Typeless
+                | Format::Bgra8Typeless
+                | Format::Bgrx8Typeless
+                | Format::Bc6hTypeless
+                | Format::Bc7Typeless
+        )
+    }
+
+    /// Returns `true` for `UINT` and `SINT` formats, which can't be blended.
+    #[inline]
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            Format::Rgba32Uint
+                | Format::Rgba32Sint
+                | Format::Rgb32Uint
+                | Format::Rgb32Sint
+                | Format::Rgba16Uint
+                | Format::Rgba16Sint
+                | Format::Rg32Uint
+                | Format::Rg32Sint
+                | Format::Rgb10A2Uint
+                | Format::Rgba8Uint
+                | Format::Rgba8Sint
+                | Format::Rg16Uint
+                | Format::Rg16Sint
+                | Format::R32Uint
+                | Format::R32Sint
+                | Format::Rg8Uint
+                | Format::Rg8Sint
+                | Format::R16Uint
+                | Format::R16Sint
+                | Format::R8Uint
+                | Format::R8Sint
+        )
+    }
+
+    /// Returns `true` for formats that can be used by a depth-stencil view.
+    #[inline]
+    pub fn is_depth_stencil(&self) -> bool {
+        matches!(
+            self,
+            Format::D32FloatS8X24Uint | Format::D32Float | Format::D24UnormS8Uint | Format::D16Unorm
+        )
+    }
+
+    /// Returns `true` for formats with a stencil component.
+    #[inline]
+    pub fn has_stencil(&self) -> bool {
+        matches!(self, Format::D32FloatS8X24Uint | Format::D24UnormS8Uint)
+    }
+
+    /// Returns `true` for block-compressed formats.
+    #[inline]
+    pub fn is_block_compressed(&self) -> bool {
+        matches!(
+            self,
+            Format::Bc1Typeless
+                | Format::Bc1Unorm
+                | Format::Bc1UnormSrgb
+                | Format::Bc2Typeless
+                | Format::Bc2Unorm
+                | Format::Bc2UnormSrgb
+                | Format::Bc3Typeless
+                | Format::Bc3Unorm
+                | Format::Bc3UnormSrgb
+                | Format::Bc4Typeless
+                | Format::Bc4Unorm
+                | Format::Bc4Snorm
+                | Format::Bc5Typeless
+                | Format::Bc5Unorm
+                | Format::Bc5Snorm
+                | Format::Bc6hTypeless
+                | Format::Bc6hUf16
+                | Format::Bc6hSf16
+                | Format::Bc7Typeless
+                | Format::Bc7Unorm
+                | Format::Bc7UnormSrgb
+        )
+    }
+}
+
 /// The preference of GPU for the app to run on.
 ///
 /// For more information: [`DXGI_GPU_PREFERENCE enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/dxgi1_6/ne-dxgi1_6-dxgi_gpu_preference)
@@ -1227,6 +1480,34 @@ pub enum HeapType {
     GpuUpload = D3D12_HEAP_TYPE_GPU_UPLOAD.0,
 }
 
+/// Specifies the type of a raytracing hit group state subobject.
+///
+/// For more information: [`D3D12_HIT_GROUP_TYPE enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_hit_group_type)
+#[derive(Clone, Copy, Debug, Default, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum HitGroupType {
+    /// The hit group uses a list of triangles to calculate ray hits. Hit groups that use triangles can't contain an intersection shader.
+    #[default]
+    Triangles = D3D12_HIT_GROUP_TYPE_TRIANGLES.0,
+
+    /// The hit group uses a procedural primitive within a bounding box to calculate ray hits. Hit groups that use procedural primitives must contain an intersection shader.
+    ProceduralPrimitive = D3D12_HIT_GROUP_TYPE_PROCEDURAL_PRIMITIVE.0,
+}
+
+/// Values that indicate the location of a shader #include file.
+///
+/// For more information: [`D3D_INCLUDE_TYPE enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3dcommon/ne-d3dcommon-d3d_include_type)
+#[derive(Clone, Copy, Debug, Default, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum IncludeType {
+    /// The local directory.
+    #[default]
+    Local = D3D_INCLUDE_LOCAL.0,
+
+    /// The system directory.
+    System = D3D_INCLUDE_SYSTEM.0,
+}
+
 /// When using triangle strip primitive topology, vertex positions are interpreted as vertices of a continuous triangle “strip”.
 /// There is a special index value that represents the desire to have a discontinuity in the strip, the cut index value. This enum lists the supported cut values.
 ///
@@ -1245,6 +1526,26 @@ pub enum IndexBufferStripCutValue {
     _0xFFFFFFFF = D3D12_INDEX_BUFFER_STRIP_CUT_VALUE_0xFFFFFFFF.0,
 }
 
+/// Specifies the line rasterization mode.
+///
+/// For more information: [`D3D12_LINE_RASTERIZATION_MODE enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_line_rasterization_mode)
+#[derive(Clone, Copy, Debug, Default, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum LineRasterizationMode {
+    /// Aliased lines.
+    #[default]
+    Aliased = D3D12_LINE_RASTERIZATION_MODE_ALIASED.0,
+
+    /// Alpha antialiased lines.
+    AlphaAntialiased = D3D12_LINE_RASTERIZATION_MODE_ALPHA_ANTIALIASED.0,
+
+    /// Wide quadrilateral lines.
+    QuadrilateralWide = D3D12_LINE_RASTERIZATION_MODE_QUADRILATERAL_WIDE.0,
+
+    /// Narrow quadrilateral lines.
+    QuadrilateralNarrow = D3D12_LINE_RASTERIZATION_MODE_QUADRILATERAL_NARROW.0,
+}
+
 /// Defines constants that specify logical operations to configure for a render target.
 ///
 /// For more information: [`D3D12_LOGIC_OP enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_logic_op)
@@ -2713,6 +3014,26 @@ pub enum MinPrecisionSupport {
     Support16Bit = D3D12_SHADER_MIN_PRECISION_SUPPORT_16_BIT.0,
 }
 
+/// Optimization level of the shader compiler.
+///
+/// For more information: [`D3DCOMPILE Constants`](https://learn.microsoft.com/en-us/windows/win32/direct3dhlsl/d3dcompile-constants)
+#[derive(Clone, Copy, Debug, Default, FromRepr, Hash, PartialEq, Eq)]
+#[repr(u32)]
+pub enum OptimizationLevel {
+    /// The lowest optimization level.
+    Level0 = D3DCOMPILE_OPTIMIZATION_LEVEL0,
+
+    /// The second lowest optimization level.
+    #[default]
+    Level1 = D3DCOMPILE_OPTIMIZATION_LEVEL1,
+
+    /// The second highest optimization level.
+    Level2 = D3DCOMPILE_OPTIMIZATION_LEVEL0 | D3DCOMPILE_OPTIMIZATION_LEVEL3,
+
+    /// The highest optimization level.
+    Level3 = D3DCOMPILE_OPTIMIZATION_LEVEL3,
+}
+
 /// Specifies the level of support for programmable sample positions that's offered by the adapter.
 ///
 /// For more information: [`D3D12_PROGRAMMABLE_SAMPLE_POSITIONS_TIER enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_programmable_sample_positions_tier)
@@ -2736,6 +3057,97 @@ pub enum PipelinePrimitiveTopology {
     Patch = D3D12_PRIMITIVE_TOPOLOGY_TYPE_PATCH.0,
 }
 
+/// Specifies the type of a sub-object in a pipeline state stream description.
+///
+/// For more information: [`D3D12_PIPELINE_STATE_SUBOBJECT_TYPE enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_pipeline_state_subobject_type)
+#[derive(Clone, Copy, Debug, FromRepr, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(i32)]
+pub enum PipelineStateSubobjectType {
+    /// Indicates a root signature subobject type.
+    RootSignature = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_ROOT_SIGNATURE.0,
+
+    /// Indicates a vertex shader subobject type.
+    Vs = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_VS.0,
+
+    /// Indicates a pixel shader subobject type.
+    Ps = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_PS.0,
+
+    /// Indicates a domain shader subobject type.
+    Ds = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_DS.0,
+
+    /// Indicates a hull shader subobject type.
+    Hs = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_HS.0,
+
+    /// Indicates a geometry shader subobject type.
+    Gs = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_GS.0,
+
+    /// Indicates a compute shader subobject type.
+    Cs = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_CS.0,
+
+    /// Indicates a stream-output subobject type.
+    StreamOutput = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_STREAM_OUTPUT.0,
+
+    /// Indicates a blend subobject type.
+    Blend = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_BLEND.0,
+
+    /// Indicates a sample mask subobject type.
+    SampleMask = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_SAMPLE_MASK.0,
+
+    /// Indicates a rasterizer subobject type.
+    Rasterizer = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_RASTERIZER.0,
+
+    /// Indicates a depth stencil subobject type.
+    DepthStencil = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_DEPTH_STENCIL.0,
+
+    /// Indicates an input layout subobject type.
+    InputLayout = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_INPUT_LAYOUT.0,
+
+    /// Indicates an index buffer strip cut value subobject type.
+    IbStripCutValue = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_IB_STRIP_CUT_VALUE.0,
+
+    /// Indicates a primitive topology subobject type.
+    PrimitiveTopology = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_PRIMITIVE_TOPOLOGY.0,
+
+    /// Indicates a render target formats subobject type.
+    RenderTargetFormats = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_RENDER_TARGET_FORMATS.0,
+
+    /// Indicates a depth stencil format subobject.
+    DepthStencilFormat = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_DEPTH_STENCIL_FORMAT.0,
+
+    /// Indicates a sample description subobject type.
+    SampleDesc = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_SAMPLE_DESC.0,
+
+    /// Indicates a node mask subobject type.
+    NodeMask = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_NODE_MASK.0,
+
+    /// Indicates a cached pipeline state object subobject type.
+    CachedPso = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_CACHED_PSO.0,
+
+    /// Indicates a flags subobject type.
+    Flags = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_FLAGS.0,
+
+    /// Indicates an expanded depth stencil subobject type with depth bounds testing.
+    DepthStencil1 = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_DEPTH_STENCIL1.0,
+
+    /// Indicates a view instancing subobject type.
+    ViewInstancing = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_VIEW_INSTANCING.0,
+
+    /// Indicates an amplification shader subobject type.
+    As = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_AS.0,
+
+    /// Indicates a mesh shader subobject type.
+    Ms = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_MS.0,
+
+    /// Indicates a depth stencil subobject type with separate front and back stencil masks.
+    DepthStencil2 = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_DEPTH_STENCIL2.0,
+
+    /// Indicates a rasterizer subobject type with a floating-point depth bias.
+    Rasterizer1 = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_RASTERIZER1.0,
+
+    /// Indicates a rasterizer subobject type with a line rasterization mode.
+    Rasterizer2 = D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_RASTERIZER2.0,
+}
+
 /// Specifies the predication operation to apply.
 ///
 /// For more information: [`D3D12_PREDICATION_OP enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_predication_op)
@@ -2911,6 +3323,19 @@ pub enum ProgrammableSamplePositionsTier {
     Tier2 = D3D12_PROGRAMMABLE_SAMPLE_POSITIONS_TIER_2.0,
 }
 
+/// Specifies the status of a protected session.
+///
+/// For more information: [`D3D12_PROTECTED_SESSION_STATUS enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_protected_session_status)
+#[derive(Clone, Copy, Debug, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum ProtectedSessionStatus {
+    /// Indicates that the protected session is in a valid state.
+    Ok = D3D12_PROTECTED_SESSION_STATUS_OK.0,
+
+    /// Indicates that the protected session is not in a valid state.
+    Invalid = D3D12_PROTECTED_SESSION_STATUS_INVALID.0,
+}
+
 /// Specifies the type of query heap to create.
 ///
 /// For more information: [`D3D12_QUERY_HEAP_TYPE enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_query_heap_type)
@@ -2984,6 +3409,74 @@ pub enum QueryType {
     PipelineStatistics1 = D3D12_QUERY_TYPE_PIPELINE_STATISTICS1.0,
 }
 
+/// Specifies the mode of a copy of a raytracing acceleration structure.
+///
+/// For more information: [`D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_raytracing_acceleration_structure_copy_mode)
+#[derive(Clone, Copy, Debug, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum RaytracingAccelerationStructureCopyMode {
+    /// Copy an acceleration structure while fixing up any self-referential pointers that may be present so that the destination is a self-contained copy of the source.
+    Clone = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE_CLONE.0,
+
+    /// Produces a functionally equivalent acceleration structure to source in the destination, similar to the clone mode, but also fits the destination into a potentially smaller, and certainly not larger, memory footprint.
+    Compact = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE_COMPACT.0,
+
+    /// Destination takes the layout described in `D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_TOOLS_VISUALIZATION_HEADER`.
+    VisualizationDecodeForTools = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE_VISUALIZATION_DECODE_FOR_TOOLS.0,
+
+    /// Destination takes the layout and size described in the documentation for `D3D12_SERIALIZED_RAYTRACING_ACCELERATION_STRUCTURE_HEADER`.
+    Serialize = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE_SERIALIZE.0,
+
+    /// Source must be a serialized acceleration structure, with any pointers fixed up so they point to valid acceleration structures.
+    Deserialize = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_COPY_MODE_DESERIALIZE.0,
+}
+
+/// Specifies the type of acceleration structure post-build info that can be retrieved.
+///
+/// For more information: [`D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_TYPE enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_raytracing_acceleration_structure_postbuild_info_type)
+#[derive(Clone, Copy, Debug, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum RaytracingAccelerationStructurePostbuildInfoType {
+    /// The space requirements for an acceleration structure after compaction.
+    CompactedSize = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_COMPACTED_SIZE.0,
+
+    /// The space requirements for generating tools visualization for an acceleration structure.
+    ToolsVisualization = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_TOOLS_VISUALIZATION.0,
+
+    /// The space requirements for serializing an acceleration structure.
+    Serialization = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_SERIALIZATION.0,
+
+    /// The size of the current acceleration structure.
+    CurrentSize = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO_CURRENT_SIZE.0,
+}
+
+/// Specifies the type of a raytracing acceleration structure.
+///
+/// For more information: [`D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_raytracing_acceleration_structure_type)
+#[derive(Clone, Copy, Debug, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum RaytracingAccelerationStructureType {
+    /// Top-level acceleration structure, which contains instances of bottom-level acceleration structures.
+    TopLevel = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE_TOP_LEVEL.0,
+
+    /// Bottom-level acceleration structure, which contains geometry.
+    BottomLevel = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE_BOTTOM_LEVEL.0,
+}
+
+/// Specifies the type of geometry used for raytracing.
+///
+/// For more information: [`D3D12_RAYTRACING_GEOMETRY_TYPE enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_raytracing_geometry_type)
+#[derive(Clone, Copy, Debug, Default, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum RaytracingGeometryType {
+    /// The geometry consists of triangles.
+    #[default]
+    Triangles = D3D12_RAYTRACING_GEOMETRY_TYPE_TRIANGLES.0,
+
+    /// The geometry procedurally is defined during raytracing by intersection shaders. For the purpose of acceleration structure builds, the geometry's bounds are described with axis-aligned bounding boxes.
+    ProceduralPrimitiveAabbs = D3D12_RAYTRACING_GEOMETRY_TYPE_PROCEDURAL_PRIMITIVE_AABBS.0,
+}
+
 /// Specifies the level of ray tracing support on the graphics device.
 ///
 /// For more information: [`D3D12_RAYTRACING_TIER enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_raytracing_tier)
@@ -3060,6 +3553,32 @@ pub enum RenderPassTier {
     Tier2 = D3D12_RENDER_PASS_TIER_2.0,
 }
 
+/// Specifies the operation of a resolve of a multi-sampled resource.
+///
+/// For more information: [`D3D12_RESOLVE_MODE enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_resolve_mode)
+#[derive(Clone, Copy, Debug, Default, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum ResolveMode {
+    /// Resolves compressed source samples to their uncompressed values.
+    Decompress = D3D12_RESOLVE_MODE_DECOMPRESS.0,
+
+    /// Resolves the source samples to their minimum value.
+    Min = D3D12_RESOLVE_MODE_MIN.0,
+
+    /// Resolves the source samples to their maximum value.
+    Max = D3D12_RESOLVE_MODE_MAX.0,
+
+    /// Resolves the source samples to their average value.
+    #[default]
+    Average = D3D12_RESOLVE_MODE_AVERAGE.0,
+
+    /// Resolves the source samples to their encoded sampler feedback map values.
+    EncodeSamplerFeedback = D3D12_RESOLVE_MODE_ENCODE_SAMPLER_FEEDBACK.0,
+
+    /// Resolves the source samples to their decoded sampler feedback map values.
+    DecodeSamplerFeedback = D3D12_RESOLVE_MODE_DECODE_SAMPLER_FEEDBACK.0,
+}
+
 /// Identifies the tier of resource binding being used.
 ///
 /// For more information: [`D3D12_RESOURCE_BINDING_TIER enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_resource_binding_tier)
@@ -3579,6 +4098,58 @@ pub enum ShaderVisibility {
     Mesh = D3D12_SHADER_VISIBILITY_MESH.0,
 }
 
+/// Defines constants that specify the shading rate for variable-rate shading.
+///
+/// For more information: [`D3D12_SHADING_RATE enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_shading_rate)
+#[derive(Clone, Copy, Debug, Default, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum ShadingRate {
+    /// Specifies no change to the shading rate.
+    #[default]
+    _1x1 = D3D12_SHADING_RATE_1X1.0,
+
+    /// Specifies that the shading rate should reduce vertical resolution 2x.
+    _1x2 = D3D12_SHADING_RATE_1X2.0,
+
+    /// Specifies that the shading rate should reduce horizontal resolution 2x.
+    _2x1 = D3D12_SHADING_RATE_2X1.0,
+
+    /// Specifies that the shading rate should reduce the resolution of both axes 2x.
+    _2x2 = D3D12_SHADING_RATE_2X2.0,
+
+    /// Specifies that the shading rate should reduce horizontal resolution 2x, and reduce vertical resolution 4x.
+    _2x4 = D3D12_SHADING_RATE_2X4.0,
+
+    /// Specifies that the shading rate should reduce horizontal resolution 4x, and reduce vertical resolution 2x.
+    _4x2 = D3D12_SHADING_RATE_4X2.0,
+
+    /// Specifies that the shading rate should reduce the resolution of both axes 4x.
+    _4x4 = D3D12_SHADING_RATE_4X4.0,
+}
+
+/// Defines constants that specify a shading rate combiner for variable-rate shading.
+///
+/// For more information: [`D3D12_SHADING_RATE_COMBINER enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_shading_rate_combiner)
+#[derive(Clone, Copy, Debug, Default, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum ShadingRateCombiner {
+    /// Specifies the combiner `C.xy = A.xy`, for combiner (C) and inputs (A and B).
+    #[default]
+    Passthrough = D3D12_SHADING_RATE_COMBINER_PASSTHROUGH.0,
+
+    /// Specifies the combiner `C.xy = B.xy`, for combiner (C) and inputs (A and B).
+    Override = D3D12_SHADING_RATE_COMBINER_OVERRIDE.0,
+
+    /// Specifies the combiner `C.xy = max(A.xy, B.xy)`, for combiner (C) and inputs (A and B).
+    Min = D3D12_SHADING_RATE_COMBINER_MIN.0,
+
+    /// Specifies the combiner `C.xy = min(A.xy, B.xy)`, for combiner (C) and inputs (A and B).
+    Max = D3D12_SHADING_RATE_COMBINER_MAX.0,
+
+    /// Specifies the combiner `C.xy = min(maxRate, A.xy + B.xy)`, for combiner (C) and inputs (A and B).
+    Sum = D3D12_SHADING_RATE_COMBINER_SUM.0,
+}
+
 /// Defines constants that specify a cross-API sharing support tier.
 ///
 /// For more information: [`D3D12_SHARED_RESOURCE_COMPATIBILITY_TIER enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_shared_resource_compatibility_tier)
@@ -3616,6 +4187,68 @@ pub enum SrvDimension {
     BufferEx = D3D_SRV_DIMENSION_BUFFEREX.0,
 }
 
+/// Specifies the type of a state object.
+///
+/// For more information: [`D3D12_STATE_OBJECT_TYPE enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_state_object_type)
+#[derive(Clone, Copy, Debug, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum StateObjectType {
+    /// Collection state object.
+    Collection = D3D12_STATE_OBJECT_TYPE_COLLECTION.0,
+
+    /// Raytracing pipeline state object.
+    RaytracingPipeline = D3D12_STATE_OBJECT_TYPE_RAYTRACING_PIPELINE.0,
+
+    /// Executable state object, used for work graphs.
+    Executable = D3D12_STATE_OBJECT_TYPE_EXECUTABLE.0,
+}
+
+/// The type of a state subobject.
+///
+/// For more information: [`D3D12_STATE_SUBOBJECT_TYPE enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_state_subobject_type)
+#[derive(Clone, Copy, Debug, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum StateSubobjectType {
+    /// Subobject type is state object config.
+    StateObjectConfig = D3D12_STATE_SUBOBJECT_TYPE_STATE_OBJECT_CONFIG.0,
+
+    /// Subobject type is global root signature.
+    GlobalRootSignature = D3D12_STATE_SUBOBJECT_TYPE_GLOBAL_ROOT_SIGNATURE.0,
+
+    /// Subobject type is local root signature.
+    LocalRootSignature = D3D12_STATE_SUBOBJECT_TYPE_LOCAL_ROOT_SIGNATURE.0,
+
+    /// Subobject type is node mask.
+    NodeMask = D3D12_STATE_SUBOBJECT_TYPE_NODE_MASK.0,
+
+    /// Subobject type is DXIL library.
+    DxilLibrary = D3D12_STATE_SUBOBJECT_TYPE_DXIL_LIBRARY.0,
+
+    /// Subobject type is existing collection.
+    ExistingCollection = D3D12_STATE_SUBOBJECT_TYPE_EXISTING_COLLECTION.0,
+
+    /// Subobject type is subobject to exports association.
+    SubobjectToExportsAssociation = D3D12_STATE_SUBOBJECT_TYPE_SUBOBJECT_TO_EXPORTS_ASSOCIATION.0,
+
+    /// Subobject type is DXIL subobject to exports association.
+    DxilSubobjectToExportsAssociation = D3D12_STATE_SUBOBJECT_TYPE_DXIL_SUBOBJECT_TO_EXPORTS_ASSOCIATION.0,
+
+    /// Subobject type is raytracing shader config.
+    RaytracingShaderConfig = D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_SHADER_CONFIG.0,
+
+    /// Subobject type is raytracing pipeline config.
+    RaytracingPipelineConfig = D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_PIPELINE_CONFIG.0,
+
+    /// Subobject type is hit group.
+    HitGroup = D3D12_STATE_SUBOBJECT_TYPE_HIT_GROUP.0,
+
+    /// Subobject type is raytracing pipeline config with flags.
+    RaytracingPipelineConfig1 = D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_PIPELINE_CONFIG1.0,
+
+    /// Subobject type is work graph.
+    WorkGraph = D3D12_STATE_SUBOBJECT_TYPE_WORK_GRAPH.0,
+}
+
 /// Identifies the stencil operations that can be performed during depth-stencil testing.
 ///
 /// For more information: [`D3D12_STENCIL_OP enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_stencil_op)
@@ -3834,3 +4467,34 @@ pub enum WaveMmaTier {
     /// Specifies that WaveMMA (wave_matrix) operations are supported.
     Tier1_0 = D3D12_WAVE_MMA_TIER_1_0.0,
 }
+
+/// Defines constants that specify the level of support for work graphs.
+///
+/// For more information: [`D3D12_WORK_GRAPHS_TIER enumeration`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#d3d12_work_graphs_tier)
+#[derive(Clone, Copy, Debug, Default, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum WorkGraphsTier {
+    /// Specifies that work graphs are not supported.
+    #[default]
+    NotSupported = D3D12_WORK_GRAPHS_TIER_NOT_SUPPORTED.0,
+
+    /// Specifies that work graphs are supported.
+    Tier1_0 = D3D12_WORK_GRAPHS_TIER_1_0.0,
+}
+
+/// Specifies the mode used by a [`GraphicsCommandList2::write_buffer_immediate`](crate::command_list::GraphicsCommandList2::write_buffer_immediate) operation.
+///
+/// For more information: [`D3D12_WRITEBUFFERIMMEDIATE_MODE enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_writebufferimmediate_mode)
+#[derive(Clone, Copy, Debug, Default, FromRepr, Hash, PartialEq, Eq)]
+#[repr(i32)]
+pub enum WriteBufferImmediateMode {
+    /// The write operation behaves the same as normal copy-write operations.
+    #[default]
+    Default = D3D12_WRITEBUFFERIMMEDIATE_MODE_DEFAULT.0,
+
+    /// The write operation is guaranteed to occur after all preceding commands in the command stream have started.
+    MarkerIn = D3D12_WRITEBUFFERIMMEDIATE_MODE_MARKER_IN.0,
+
+    /// The write operation is deferred until all previous commands in the command stream have completed through the GPU pipeline.
+    MarkerOut = D3D12_WRITEBUFFERIMMEDIATE_MODE_MARKER_OUT.0,
+}
@@ -788,6 +788,12 @@ impl Options7Feature {
     pub fn sampler_feedback_tier(&self) -> SamplerFeedbackTier {
         self.0.SamplerFeedbackTier.into()
     }
+
+    /// Returns `true` if mesh and amplification shaders can be used.
+    #[inline]
+    pub fn supports_mesh_shaders(&self) -> bool {
+        self.mesh_shader_tier() != MeshShaderTier::NotSupported
+    }
 }
 
 impl __Sealed for Options7Feature {}
@@ -970,3 +976,69 @@ impl __Sealed for Options11Feature {}
 impl FeatureObject for Options11Feature {
     const TYPE: FeatureType = FeatureType::Options11;
 }
+
+/// Indicates whether or not enhanced barriers and relaxed format casting are supported.
+///
+/// For more information: [`D3D12_FEATURE_DATA_D3D12_OPTIONS12 structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_feature_data_d3d12_options12)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct Options12Feature(pub(crate) D3D12_FEATURE_DATA_D3D12_OPTIONS12);
+
+impl Options12Feature {
+    #[inline]
+    pub fn enhanced_barriers_supported(&self) -> bool {
+        self.0.EnhancedBarriersSupported.into()
+    }
+
+    #[inline]
+    pub fn relaxed_format_casting_supported(&self) -> bool {
+        self.0.RelaxedFormatCastingSupported.into()
+    }
+}
+
+impl __Sealed for Options12Feature {}
+
+impl FeatureObject for Options12Feature {
+    const TYPE: FeatureType = FeatureType::Options12;
+}
+
+/// Indicates the level of support for work graphs and `ExecuteIndirect`.
+///
+/// For more information: [`D3D12_FEATURE_DATA_D3D12_OPTIONS21 structure`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#d3d12_feature_data_d3d12_options21)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct Options21Feature(pub(crate) D3D12_FEATURE_DATA_D3D12_OPTIONS21);
+
+impl Options21Feature {
+    #[inline]
+    pub fn work_graphs_tier(&self) -> WorkGraphsTier {
+        self.0.WorkGraphsTier.into()
+    }
+
+    #[inline]
+    pub fn execute_indirect_tier(&self) -> ExecuteIndirectTier {
+        self.0.ExecuteIndirectTier.into()
+    }
+
+    #[inline]
+    pub fn sample_cmp_gradient_and_bias_supported(&self) -> bool {
+        self.0.SampleCmpGradientAndBiasSupported.into()
+    }
+
+    #[inline]
+    pub fn extended_command_info_supported(&self) -> bool {
+        self.0.ExtendedCommandInfoSupported.into()
+    }
+
+    /// Returns `true` if work graphs can be used.
+    #[inline]
+    pub fn supports_work_graphs(&self) -> bool {
+        self.work_graphs_tier() != WorkGraphsTier::NotSupported
+    }
+}
+
+impl __Sealed for Options21Feature {}
+
+impl FeatureObject for Options21Feature {
+    const TYPE: FeatureType = FeatureType::Options21;
+}
@@ -1,6 +1,7 @@
 use windows::Win32::Graphics::{
     Direct3D::{
-        D3D_SVF_INTERFACE_PARAMETER, D3D_SVF_INTERFACE_POINTER, D3D_SVF_USED, D3D_SVF_USERPACKED,
+        Fxc::*, D3D_SVF_INTERFACE_PARAMETER, D3D_SVF_INTERFACE_POINTER, D3D_SVF_USED,
+        D3D_SVF_USERPACKED,
     },
     Direct3D12::*,
 };
@@ -24,6 +25,172 @@ bitflags::bitflags! {
     }
 }
 
+bitflags::bitflags! {
+    /// Specifies how a subresource is accessed by the GPU before or after an enhanced barrier.
+    ///
+    /// For more information: [`D3D12_BARRIER_ACCESS enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_barrier_access)
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct BarrierAccess: i32 {
+        /// Default access, compatible with any layout of the `Common` family.
+        const Common = D3D12_BARRIER_ACCESS_COMMON.0;
+
+        /// Read as a vertex buffer.
+        const VertexBuffer = D3D12_BARRIER_ACCESS_VERTEX_BUFFER.0;
+
+        /// Read as a constant buffer.
+        const ConstantBuffer = D3D12_BARRIER_ACCESS_CONSTANT_BUFFER.0;
+
+        /// Read as an index buffer.
+        const IndexBuffer = D3D12_BARRIER_ACCESS_INDEX_BUFFER.0;
+
+        /// Written as a render target.
+        const RenderTarget = D3D12_BARRIER_ACCESS_RENDER_TARGET.0;
+
+        /// Read or written through an unordered access view.
+        const UnorderedAccess = D3D12_BARRIER_ACCESS_UNORDERED_ACCESS.0;
+
+        /// Written as a depth-stencil target.
+        const DepthStencilWrite = D3D12_BARRIER_ACCESS_DEPTH_STENCIL_WRITE.0;
+
+        /// Read as a read-only depth-stencil target.
+        const DepthStencilRead = D3D12_BARRIER_ACCESS_DEPTH_STENCIL_READ.0;
+
+        /// Read through a shader resource view.
+        const ShaderResource = D3D12_BARRIER_ACCESS_SHADER_RESOURCE.0;
+
+        /// Written as a stream output target.
+        const StreamOutput = D3D12_BARRIER_ACCESS_STREAM_OUTPUT.0;
+
+        /// Read as an `ExecuteIndirect` argument buffer.
+        const IndirectArgument = D3D12_BARRIER_ACCESS_INDIRECT_ARGUMENT.0;
+
+        /// Read as a predication buffer; same value as [`BarrierAccess::IndirectArgument`].
+        const Predication = D3D12_BARRIER_ACCESS_PREDICATION.0;
+
+        /// Written as the destination of a copy.
+        const CopyDest = D3D12_BARRIER_ACCESS_COPY_DEST.0;
+
+        /// Read as the source of a copy.
+        const CopySource = D3D12_BARRIER_ACCESS_COPY_SOURCE.0;
+
+        /// Written as the destination of a resolve.
+        const ResolveDest = D3D12_BARRIER_ACCESS_RESOLVE_DEST.0;
+
+        /// Read as the source of a resolve.
+        const ResolveSource = D3D12_BARRIER_ACCESS_RESOLVE_SOURCE.0;
+
+        /// Read as a raytracing acceleration structure.
+        const RaytracingAccelerationStructureRead = D3D12_BARRIER_ACCESS_RAYTRACING_ACCELERATION_STRUCTURE_READ.0;
+
+        /// Written as a raytracing acceleration structure.
+        const RaytracingAccelerationStructureWrite = D3D12_BARRIER_ACCESS_RAYTRACING_ACCELERATION_STRUCTURE_WRITE.0;
+
+        /// Read as a shading rate image.
+        const ShadingRateSource = D3D12_BARRIER_ACCESS_SHADING_RATE_SOURCE.0;
+
+        /// Read by video decoding.
+        const VideoDecodeRead = D3D12_BARRIER_ACCESS_VIDEO_DECODE_READ.0;
+
+        /// Written by video decoding.
+        const VideoDecodeWrite = D3D12_BARRIER_ACCESS_VIDEO_DECODE_WRITE.0;
+
+        /// Read by video processing.
+        const VideoProcessRead = D3D12_BARRIER_ACCESS_VIDEO_PROCESS_READ.0;
+
+        /// Written by video processing.
+        const VideoProcessWrite = D3D12_BARRIER_ACCESS_VIDEO_PROCESS_WRITE.0;
+
+        /// Read by video encoding.
+        const VideoEncodeRead = D3D12_BARRIER_ACCESS_VIDEO_ENCODE_READ.0;
+
+        /// Written by video encoding.
+        const VideoEncodeWrite = D3D12_BARRIER_ACCESS_VIDEO_ENCODE_WRITE.0;
+
+        /// The subresource isn't accessed; required when discarding or when the layout is `Undefined`.
+        const NoAccess = D3D12_BARRIER_ACCESS_NO_ACCESS.0;
+    }
+}
+
+bitflags::bitflags! {
+    /// Specifies the GPU work that an enhanced barrier synchronizes with.
+    ///
+    /// For more information: [`D3D12_BARRIER_SYNC enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_barrier_sync)
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct BarrierSync: i32 {
+        /// No synchronization is needed; the subresource isn't accessed before or after the barrier.
+        const None = D3D12_BARRIER_SYNC_NONE.0;
+
+        /// Synchronizes with all GPU work.
+        const All = D3D12_BARRIER_SYNC_ALL.0;
+
+        /// Synchronizes with all draw work.
+        const Draw = D3D12_BARRIER_SYNC_DRAW.0;
+
+        /// Synchronizes with the index input stage.
+        const IndexInput = D3D12_BARRIER_SYNC_INDEX_INPUT.0;
+
+        /// Synchronizes with all vertex, hull, domain, geometry, amplification and mesh shading.
+        const VertexShading = D3D12_BARRIER_SYNC_VERTEX_SHADING.0;
+
+        /// Synchronizes with pixel shading.
+        const PixelShading = D3D12_BARRIER_SYNC_PIXEL_SHADING.0;
+
+        /// Synchronizes with depth-stencil reads and writes, and depth-stencil clears.
+        const DepthStencil = D3D12_BARRIER_SYNC_DEPTH_STENCIL.0;
+
+        /// Synchronizes with render target writes and render target clears.
+        const RenderTarget = D3D12_BARRIER_SYNC_RENDER_TARGET.0;
+
+        /// Synchronizes with compute shading.
+        const ComputeShading = D3D12_BARRIER_SYNC_COMPUTE_SHADING.0;
+
+        /// Synchronizes with raytracing shading.
+        const Raytracing = D3D12_BARRIER_SYNC_RAYTRACING.0;
+
+        /// Synchronizes with copy commands.
+        const Copy = D3D12_BARRIER_SYNC_COPY.0;
+
+        /// Synchronizes with resolve commands.
+        const Resolve = D3D12_BARRIER_SYNC_RESOLVE.0;
+
+        /// Synchronizes with the argument buffer reads of `ExecuteIndirect`.
+        const ExecuteIndirect = D3D12_BARRIER_SYNC_EXECUTE_INDIRECT.0;
+
+        /// Synchronizes with predication; same value as [`BarrierSync::ExecuteIndirect`].
+        const Predication = D3D12_BARRIER_SYNC_PREDICATION.0;
+
+        /// Synchronizes with all shading stages.
+        const AllShading = D3D12_BARRIER_SYNC_ALL_SHADING.0;
+
+        /// Synchronizes with all shading stages except pixel shading.
+        const NonPixelShading = D3D12_BARRIER_SYNC_NON_PIXEL_SHADING.0;
+
+        /// Synchronizes with the emission of acceleration structure postbuild info.
+        const EmitRaytracingAccelerationStructurePostbuildInfo = D3D12_BARRIER_SYNC_EMIT_RAYTRACING_ACCELERATION_STRUCTURE_POSTBUILD_INFO.0;
+
+        /// Synchronizes with unordered access view clears.
+        const ClearUnorderedAccessView = D3D12_BARRIER_SYNC_CLEAR_UNORDERED_ACCESS_VIEW.0;
+
+        /// Synchronizes with video decoding.
+        const VideoDecode = D3D12_BARRIER_SYNC_VIDEO_DECODE.0;
+
+        /// Synchronizes with video processing.
+        const VideoProcess = D3D12_BARRIER_SYNC_VIDEO_PROCESS.0;
+
+        /// Synchronizes with video encoding.
+        const VideoEncode = D3D12_BARRIER_SYNC_VIDEO_ENCODE.0;
+
+        /// Synchronizes with acceleration structure builds.
+        const BuildRaytracingAccelerationStructure = D3D12_BARRIER_SYNC_BUILD_RAYTRACING_ACCELERATION_STRUCTURE.0;
+
+        /// Synchronizes with acceleration structure copies.
+        const CopyRaytracingAccelerationStructure = D3D12_BARRIER_SYNC_COPY_RAYTRACING_ACCELERATION_STRUCTURE.0;
+
+        /// Marks the beginning or the end of a split barrier.
+        const Split = D3D12_BARRIER_SYNC_SPLIT.0;
+    }
+}
+
 bitflags::bitflags! {
     /// Identifies how to view a buffer resource.
     ///
@@ -182,6 +349,99 @@ bitflags::bitflags! {
     }
 }
 
+bitflags::bitflags! {
+    /// Options that specify how the shader compiler compiles HLSL code.
+    ///
+    /// Empty flag - Default compilation with optimization level 1.
+    /// Other levels are set with [`OptimizationLevel`], which converts into the flags.
+    ///
+    /// For more information: [`D3DCOMPILE Constants`](https://learn.microsoft.com/en-us/windows/win32/direct3dhlsl/d3dcompile-constants)
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct CompileFlags: u32 {
+        /// Directs the compiler to insert debug file/line/type/symbol information into the output code.
+        const Debug = D3DCOMPILE_DEBUG;
+
+        /// Directs the compiler not to validate the generated code against known capabilities and constraints.
+        const SkipValidation = D3DCOMPILE_SKIP_VALIDATION;
+
+        /// Directs the compiler to skip optimization steps during code generation.
+        const SkipOptimization = D3DCOMPILE_SKIP_OPTIMIZATION;
+
+        /// Directs the compiler to pack matrices in row-major order on input and output from the shader.
+        const PackMatrixRowMajor = D3DCOMPILE_PACK_MATRIX_ROW_MAJOR;
+
+        /// Directs the compiler to pack matrices in column-major order on input and output from the shader.
+        const PackMatrixColumnMajor = D3DCOMPILE_PACK_MATRIX_COLUMN_MAJOR;
+
+        /// Directs the compiler to perform all computations with partial precision.
+        const PartialPrecision = D3DCOMPILE_PARTIAL_PRECISION;
+
+        /// Directs the compiler to compile a vertex shader for the next highest shader profile.
+        const ForceVsSoftwareNoOpt = D3DCOMPILE_FORCE_VS_SOFTWARE_NO_OPT;
+
+        /// Directs the compiler to compile a pixel shader for the next highest shader profile.
+        const ForcePsSoftwareNoOpt = D3DCOMPILE_FORCE_PS_SOFTWARE_NO_OPT;
+
+        /// Directs the compiler to disable Preshaders.
+        const NoPreshader = D3DCOMPILE_NO_PRESHADER;
+
+        /// Directs the compiler to not use flow-control constructs where possible.
+        const AvoidFlowControl = D3DCOMPILE_AVOID_FLOW_CONTROL;
+
+        /// Directs the compiler to use flow-control constructs where possible.
+        const PreferFlowControl = D3DCOMPILE_PREFER_FLOW_CONTROL;
+
+        /// Forces strict compile, which might not allow for legacy syntax.
+        const EnableStrictness = D3DCOMPILE_ENABLE_STRICTNESS;
+
+        /// Directs the compiler to enable older shaders to compile to 5_0 targets.
+        const EnableBackwardsCompatibility = D3DCOMPILE_ENABLE_BACKWARDS_COMPATIBILITY;
+
+        /// Forces the IEEE strict compile.
+        const IeeeStrictness = D3DCOMPILE_IEEE_STRICTNESS;
+
+        /// Directs the compiler to treat all warnings as errors when it compiles the shader code.
+        const WarningsAreErrors = D3DCOMPILE_WARNINGS_ARE_ERRORS;
+
+        /// Directs the compiler to assume that unordered access views (UAVs) and shader resource views (SRVs) may alias for cs_5_0.
+        const ResourcesMayAlias = D3DCOMPILE_RESOURCES_MAY_ALIAS;
+
+        /// Directs the compiler to enable unbounded descriptor tables.
+        const EnableUnboundedDescriptorTables = D3DCOMPILE_ENABLE_UNBOUNDED_DESCRIPTOR_TABLES;
+
+        /// Directs the compiler to ensure all resources are bound.
+        const AllResourcesBound = D3DCOMPILE_ALL_RESOURCES_BOUND;
+
+        /// Directs the compiler to name the debug data after the source, the PDB file is named with the hash of the source.
+        const DebugNameForSource = D3DCOMPILE_DEBUG_NAME_FOR_SOURCE;
+
+        /// Directs the compiler to name the debug data after the binary, the PDB file is named with the hash of the binary.
+        const DebugNameForBinary = D3DCOMPILE_DEBUG_NAME_FOR_BINARY;
+    }
+}
+
+bitflags::bitflags! {
+    /// Options that specify how the shader compiler compiles effects and root signatures.
+    ///
+    /// Empty flag - Compile the effect or root signature with default options.
+    ///
+    /// For more information: [`D3DCOMPILE_EFFECT Constants`](https://learn.microsoft.com/en-us/windows/win32/direct3dhlsl/d3dcompile-effect-constants)
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct CompileEffectFlags: u32 {
+        /// Compile the effects (.fx) file to a child effect. Child effects have no initializers for any shared variables because these child effects are initialized in the master effect (the effect pool).
+        const ChildEffect = D3DCOMPILE_EFFECT_CHILD_EFFECT;
+
+        /// Disables performance mode and allows for mutable state objects.
+        const AllowSlowOps = D3DCOMPILE_EFFECT_ALLOW_SLOW_OPS;
+
+        /// Compiles a root signature as version 1.0.
+        const ForceRootSignature1_0 = D3DCOMPILE_FLAGS2_FORCE_ROOT_SIGNATURE_1_0;
+
+        /// Compiles a root signature as version 1.1.
+        const ForceRootSignature1_1 = D3DCOMPILE_FLAGS2_FORCE_ROOT_SIGNATURE_1_1;
+    }
+}
+
 bitflags::bitflags! {
     /// Options for enumerating display modes.
     ///
@@ -606,6 +866,103 @@ bitflags::bitflags! {
     }
 }
 
+bitflags::bitflags! {
+    /// Specifies flags for the build of a raytracing acceleration structure.
+    ///
+    /// For more information: [`D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_raytracing_acceleration_structure_build_flags)
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct RaytracingAccelerationStructureBuildFlags: i32 {
+        /// Build the acceleration structure such that it supports future updates instead of the app having to entirely rebuild the structure.
+        const AllowUpdate = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_ALLOW_UPDATE.0;
+
+        /// Enables the option to compact the acceleration structure by copying it with [`RaytracingAccelerationStructureCopyMode::Compact`].
+        const AllowCompaction = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_ALLOW_COMPACTION.0;
+
+        /// Construct a high quality acceleration structure that maximizes raytracing performance at the expense of additional build time.
+        const PreferFastTrace = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_PREFER_FAST_TRACE.0;
+
+        /// Construct a lower quality acceleration structure, trading raytracing performance for build speed.
+        const PreferFastBuild = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_PREFER_FAST_BUILD.0;
+
+        /// Minimize the amount of scratch memory used during the acceleration structure build as well as the size of the result.
+        const MinimizeMemory = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_MINIMIZE_MEMORY.0;
+
+        /// Perform an acceleration structure update, as opposed to building from scratch.
+        const PerformUpdate = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_PERFORM_UPDATE.0;
+    }
+}
+
+bitflags::bitflags! {
+    /// Specifies flags for raytracing geometry.
+    ///
+    /// For more information: [`D3D12_RAYTRACING_GEOMETRY_FLAGS enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_raytracing_geometry_flags)
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct RaytracingGeometryFlags: i32 {
+        /// When rays encounter this geometry, the geometry acts as if no any hit shader is present.
+        const Opaque = D3D12_RAYTRACING_GEOMETRY_FLAG_OPAQUE.0;
+
+        /// The any hit shader is invoked only once per primitive in this geometry.
+        const NoDuplicateAnyhitInvocation = D3D12_RAYTRACING_GEOMETRY_FLAG_NO_DUPLICATE_ANYHIT_INVOCATION.0;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags for a raytracing acceleration structure instance.
+    ///
+    /// For more information: [`D3D12_RAYTRACING_INSTANCE_FLAGS enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_raytracing_instance_flags)
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct RaytracingInstanceFlags: i32 {
+        /// Disables front/back face culling for this instance.
+        const TriangleCullDisable = D3D12_RAYTRACING_INSTANCE_FLAG_TRIANGLE_CULL_DISABLE.0;
+
+        /// Counterclockwise triangles in object space are considered front facing.
+        const TriangleFrontCounterclockwise = D3D12_RAYTRACING_INSTANCE_FLAG_TRIANGLE_FRONT_COUNTERCLOCKWISE.0;
+
+        /// All geometries of the instance are treated as opaque.
+        const ForceOpaque = D3D12_RAYTRACING_INSTANCE_FLAG_FORCE_OPAQUE.0;
+
+        /// All geometries of the instance are treated as non-opaque.
+        const ForceNonOpaque = D3D12_RAYTRACING_INSTANCE_FLAG_FORCE_NON_OPAQUE.0;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags used to specify options for a raytracing pipeline.
+    ///
+    /// For more information: [`D3D12_RAYTRACING_PIPELINE_FLAGS enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_raytracing_pipeline_flags)
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct RaytracingPipelineFlags: i32 {
+        /// Triangle geometries are skipped by ray traversal, as if `RAY_FLAG_SKIP_TRIANGLES` was set for every ray.
+        const SkipTriangles = D3D12_RAYTRACING_PIPELINE_FLAG_SKIP_TRIANGLES.0;
+
+        /// Procedural primitives are skipped by ray traversal, as if `RAY_FLAG_SKIP_PROCEDURAL_PRIMITIVES` was set for every ray.
+        const SkipProceduralPrimitives = D3D12_RAYTRACING_PIPELINE_FLAG_SKIP_PROCEDURAL_PRIMITIVES.0;
+    }
+}
+
+bitflags::bitflags! {
+    /// Specifies the nature of the render pass; for example, whether it is a suspending or a resuming render pass.
+    ///
+    /// For more information: [`D3D12_RENDER_PASS_FLAGS enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_render_pass_flags)
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct RenderPassFlags: i32 {
+        /// Indicates that writes to unordered access view(s) should be allowed during the render pass.
+        const AllowUavWrites = D3D12_RENDER_PASS_FLAG_ALLOW_UAV_WRITES.0;
+
+        /// Indicates that this is a suspension of a render pass, which is resumed by a later render pass.
+        const SuspendingPass = D3D12_RENDER_PASS_FLAG_SUSPENDING_PASS.0;
+
+        /// Indicates that this is a resumption of a previously suspended render pass.
+        const ResumingPass = D3D12_RENDER_PASS_FLAG_RESUMING_PASS.0;
+
+        /// Binds the depth plane of the depth-stencil view as read-only.
+        const BindReadOnlyDepth = D3D12_RENDER_PASS_FLAG_BIND_READ_ONLY_DEPTH.0;
+
+        /// Binds the stencil plane of the depth-stencil view as read-only.
+        const BindReadOnlyStencil = D3D12_RENDER_PASS_FLAG_BIND_READ_ONLY_STENCIL.0;
+    }
+}
+
 bitflags::bitflags! {
     /// Flags for setting split resource barriers.
     ///
@@ -798,6 +1155,17 @@ bitflags::bitflags! {
     }
 }
 
+bitflags::bitflags! {
+    /// Flags for a texture barrier.
+    ///
+    /// For more information: [`D3D12_TEXTURE_BARRIER_FLAGS enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_texture_barrier_flags)
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct TextureBarrierFlags: i32 {
+        /// Discards the contents of the subresources; the layout before the barrier must be `Undefined` or the access before must be `NoAccess`.
+        const Discard = D3D12_TEXTURE_BARRIER_FLAG_DISCARD.0;
+    }
+}
+
 bitflags::bitflags! {
     /// Specifies how to copy a tile.
     ///
@@ -931,6 +1299,17 @@ bitflags::bitflags! {
     }
 }
 
+bitflags::bitflags! {
+    /// Flags used when a work graph program is set on a command list.
+    ///
+    /// For more information: [`D3D12_SET_WORK_GRAPH_FLAGS enumeration`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#d3d12_set_work_graph_flags)
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct SetWorkGraphFlags: i32 {
+        /// Initializes the backing memory. Required the first time the backing memory is used with the program.
+        const Initialize = D3D12_SET_WORK_GRAPH_FLAG_INITIALIZE.0;
+    }
+}
+
 bitflags::bitflags! {
     /// Values that identify information about a shader variable.
     ///
@@ -944,6 +1323,23 @@ bitflags::bitflags! {
     }
 }
 
+bitflags::bitflags! {
+    /// Specifies constraints for state objects.
+    ///
+    /// For more information: [`D3D12_STATE_OBJECT_FLAGS enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_state_object_flags)
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct StateObjectFlags: i32 {
+        /// Allows shaders of a collection to depend on definitions outside of it, e.g. root signatures.
+        const AllowLocalDependenciesOnExternalDefinitions = D3D12_STATE_OBJECT_FLAG_ALLOW_LOCAL_DEPENDENCIES_ON_EXTERNAL_DEFINITIONS.0;
+
+        /// Allows definitions of a collection to be used by shaders outside of it.
+        const AllowExternalDependenciesOnLocalDefinitions = D3D12_STATE_OBJECT_FLAG_ALLOW_EXTERNAL_DEPENDENCIES_ON_LOCAL_DEFINITIONS.0;
+
+        /// Allows the state object to be extended with `AddToStateObject`.
+        const AllowStateObjectAdditions = D3D12_STATE_OBJECT_FLAG_ALLOW_STATE_OBJECT_ADDITIONS.0;
+    }
+}
+
 bitflags::bitflags! {
     /// Specifies color space support for the swap chain.
     ///
@@ -1007,6 +1403,30 @@ bitflags::bitflags! {
     }
 }
 
+bitflags::bitflags! {
+    /// Flags of a work graph state subobject.
+    ///
+    /// For more information: [`D3D12_WORK_GRAPH_FLAGS enumeration`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#d3d12_work_graph_flags)
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct WorkGraphFlags: i32 {
+        /// Adds all nodes of the state object that aren't referenced by other work graphs to the graph.
+        const IncludeAllAvailableNodes = D3D12_WORK_GRAPH_FLAG_INCLUDE_ALL_AVAILABLE_NODES.0;
+    }
+}
+
+bitflags::bitflags! {
+    /// Specifies options for view instancing.
+    ///
+    /// Empty flag - Indicates a default view instancing configuration.
+    ///
+    /// For more information: [`D3D12_VIEW_INSTANCING_FLAGS enumeration`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ne-d3d12-d3d12_view_instancing_flags)
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct ViewInstancingFlags: i32 {
+        /// Enables view instance masking.
+        const EnableViewInstanceMasking = D3D12_VIEW_INSTANCING_FLAG_ENABLE_VIEW_INSTANCE_MASKING.0;
+    }
+}
+
 bitflags::bitflags! {
     /// TBD
     #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
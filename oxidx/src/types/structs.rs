@@ -12,6 +12,7 @@ use crate::{
     blob::Blob,
     dx::{BlobbyInternal, Resource},
     error::DxError,
+    hash::{impl_content_hash, ContentHash, StableHasher},
     root_signature::RootSignature,
 };
 
@@ -368,6 +369,16 @@ impl<'a> ComputePipelineStateDesc<'a> {
         self.0.Flags = flags.as_raw();
         self
     }
+
+    /// Hashes the shader, the root signature and the flags. The cached blob isn't part of the hash.
+    ///
+    /// The root signature is hashed by its content only if it was created by
+    /// [`Device::create_root_signature`](crate::device::Device::create_root_signature).
+    pub fn content_hash(&self) -> u128 {
+        let mut hasher = StableHasher::new();
+        self.0.hash_content(&mut hasher);
+        hasher.finish128()
+    }
 }
 
 /// Describes a constant buffer to view.
@@ -493,6 +504,138 @@ impl Default for DepthStencilDesc {
     }
 }
 
+/// Describes depth-stencil state with depth bounds testing.
+///
+/// For more information: [`D3D12_DEPTH_STENCIL_DESC1 structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_depth_stencil_desc1)
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct DepthStencilDesc1(pub(crate) D3D12_DEPTH_STENCIL_DESC1);
+
+impl DepthStencilDesc1 {
+    #[inline]
+    pub fn enable_depth(mut self, depth_func: ComparisonFunc) -> Self {
+        self.0.DepthEnable = true.into();
+        self.0.DepthFunc = depth_func.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn with_depth_write_mask(mut self, mask: DepthWriteMask) -> Self {
+        self.0.DepthWriteMask = mask.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn enable_stencil(mut self, stencil_read_mask: u8, stencil_write_mask: u8) -> Self {
+        self.0.StencilEnable = true.into();
+        self.0.StencilReadMask = stencil_read_mask;
+        self.0.StencilWriteMask = stencil_write_mask;
+        self
+    }
+
+    #[inline]
+    pub fn with_front_face(mut self, front_face: DepthStencilOpDesc) -> Self {
+        self.0.FrontFace = front_face.0;
+        self
+    }
+
+    #[inline]
+    pub fn with_back_face(mut self, back_face: DepthStencilOpDesc) -> Self {
+        self.0.BackFace = back_face.0;
+        self
+    }
+
+    #[inline]
+    pub fn enable_depth_bounds_test(mut self) -> Self {
+        self.0.DepthBoundsTestEnable = true.into();
+        self
+    }
+}
+
+impl Default for DepthStencilDesc1 {
+    fn default() -> Self {
+        Self(D3D12_DEPTH_STENCIL_DESC1 {
+            DepthWriteMask: D3D12_DEPTH_WRITE_MASK_ALL,
+            ..Default::default()
+        })
+    }
+}
+
+impl From<DepthStencilDesc> for DepthStencilDesc1 {
+    fn from(value: DepthStencilDesc) -> Self {
+        let desc = value.0;
+
+        Self(D3D12_DEPTH_STENCIL_DESC1 {
+            DepthEnable: desc.DepthEnable,
+            DepthWriteMask: desc.DepthWriteMask,
+            DepthFunc: desc.DepthFunc,
+            StencilEnable: desc.StencilEnable,
+            StencilReadMask: desc.StencilReadMask,
+            StencilWriteMask: desc.StencilWriteMask,
+            FrontFace: desc.FrontFace,
+            BackFace: desc.BackFace,
+            DepthBoundsTestEnable: false.into(),
+        })
+    }
+}
+
+/// Describes depth-stencil state with separate stencil masks for front and back faces.
+///
+/// For more information: [`D3D12_DEPTH_STENCIL_DESC2 structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_depth_stencil_desc2)
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct DepthStencilDesc2(pub(crate) D3D12_DEPTH_STENCIL_DESC2);
+
+impl DepthStencilDesc2 {
+    #[inline]
+    pub fn enable_depth(mut self, depth_func: ComparisonFunc) -> Self {
+        self.0.DepthEnable = true.into();
+        self.0.DepthFunc = depth_func.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn with_depth_write_mask(mut self, mask: DepthWriteMask) -> Self {
+        self.0.DepthWriteMask = mask.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn enable_stencil(mut self) -> Self {
+        self.0.StencilEnable = true.into();
+        self
+    }
+
+    #[inline]
+    pub fn with_front_face(mut self, front_face: DepthStencilOpDesc1) -> Self {
+        self.0.FrontFace = front_face.0;
+        self
+    }
+
+    #[inline]
+    pub fn with_back_face(mut self, back_face: DepthStencilOpDesc1) -> Self {
+        self.0.BackFace = back_face.0;
+        self
+    }
+
+    #[inline]
+    pub fn enable_depth_bounds_test(mut self) -> Self {
+        self.0.DepthBoundsTestEnable = true.into();
+        self
+    }
+}
+
+impl Default for DepthStencilDesc2 {
+    fn default() -> Self {
+        Self(D3D12_DEPTH_STENCIL_DESC2 {
+            DepthWriteMask: D3D12_DEPTH_WRITE_MASK_ALL,
+            FrontFace: DepthStencilOpDesc1::default().0,
+            BackFace: DepthStencilOpDesc1::default().0,
+            ..Default::default()
+        })
+    }
+}
+
 /// Describes stencil operations that can be performed based on the results of stencil test.
 ///
 /// For more information: [`D3D12_DEPTH_STENCILOP_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_depth_stencilop_desc)
@@ -526,6 +669,64 @@ impl DepthStencilOpDesc {
     }
 }
 
+/// Describes stencil operations and stencil masks of a face.
+///
+/// For more information: [`D3D12_DEPTH_STENCILOP_DESC1 structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_depth_stencilop_desc1)
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct DepthStencilOpDesc1(pub(crate) D3D12_DEPTH_STENCILOP_DESC1);
+
+impl DepthStencilOpDesc1 {
+    #[inline]
+    pub fn with_stencil_fail_op(mut self, stencil_fail_op: StencilOp) -> Self {
+        self.0.StencilFailOp = stencil_fail_op.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn with_stencil_depth_fail_op(mut self, stencil_depth_fail_op: StencilOp) -> Self {
+        self.0.StencilDepthFailOp = stencil_depth_fail_op.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn with_stencil_pass_op(mut self, stencil_pass_op: StencilOp) -> Self {
+        self.0.StencilPassOp = stencil_pass_op.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn with_stencil_func(mut self, stencil_func: ComparisonFunc) -> Self {
+        self.0.StencilFunc = stencil_func.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn with_stencil_read_mask(mut self, stencil_read_mask: u8) -> Self {
+        self.0.StencilReadMask = stencil_read_mask;
+        self
+    }
+
+    #[inline]
+    pub fn with_stencil_write_mask(mut self, stencil_write_mask: u8) -> Self {
+        self.0.StencilWriteMask = stencil_write_mask;
+        self
+    }
+}
+
+impl Default for DepthStencilOpDesc1 {
+    fn default() -> Self {
+        Self(D3D12_DEPTH_STENCILOP_DESC1 {
+            StencilFailOp: D3D12_STENCIL_OP_KEEP,
+            StencilDepthFailOp: D3D12_STENCIL_OP_KEEP,
+            StencilPassOp: D3D12_STENCIL_OP_KEEP,
+            StencilFunc: D3D12_COMPARISON_FUNC_ALWAYS,
+            StencilReadMask: D3D12_DEFAULT_STENCIL_READ_MASK as u8,
+            StencilWriteMask: D3D12_DEFAULT_STENCIL_WRITE_MASK as u8,
+        })
+    }
+}
+
 /// Describes the subresources of a texture that are accessible from a depth-stencil view.
 ///
 /// For more information: [`D3D12_DEPTH_STENCIL_VIEW_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_depth_stencil_view_desc)
@@ -804,6 +1005,80 @@ impl<'a> DiscardRegion<'a> {
     }
 }
 
+/// Describes the ray generation shader record and the shader tables used by a dispatch of rays.
+///
+/// For more information: [`D3D12_DISPATCH_RAYS_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_dispatch_rays_desc)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct DispatchRaysDesc(pub(crate) D3D12_DISPATCH_RAYS_DESC);
+
+impl DispatchRaysDesc {
+    #[inline]
+    pub fn new(width: u32, height: u32, depth: u32) -> Self {
+        Self(D3D12_DISPATCH_RAYS_DESC {
+            Width: width,
+            Height: height,
+            Depth: depth,
+            ..Default::default()
+        })
+    }
+
+    #[inline]
+    pub fn with_ray_generation_record(mut self, start_address: GpuVirtualAddress, size: u64) -> Self {
+        self.0.RayGenerationShaderRecord = D3D12_GPU_VIRTUAL_ADDRESS_RANGE {
+            StartAddress: start_address,
+            SizeInBytes: size,
+        };
+        self
+    }
+
+    #[inline]
+    pub fn with_miss_table(mut self, start_address: GpuVirtualAddress, size: u64, stride: u64) -> Self {
+        self.0.MissShaderTable = address_range_and_stride(start_address, size, stride);
+        self
+    }
+
+    #[inline]
+    pub fn with_hit_group_table(mut self, start_address: GpuVirtualAddress, size: u64, stride: u64) -> Self {
+        self.0.HitGroupTable = address_range_and_stride(start_address, size, stride);
+        self
+    }
+
+    #[inline]
+    pub fn with_callable_table(mut self, start_address: GpuVirtualAddress, size: u64, stride: u64) -> Self {
+        self.0.CallableShaderTable = address_range_and_stride(start_address, size, stride);
+        self
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.0.Width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.0.Height
+    }
+
+    #[inline]
+    pub fn depth(&self) -> u32 {
+        self.0.Depth
+    }
+}
+
+#[inline]
+fn address_range_and_stride(
+    start_address: GpuVirtualAddress,
+    size: u64,
+    stride: u64,
+) -> D3D12_GPU_VIRTUAL_ADDRESS_RANGE_AND_STRIDE {
+    D3D12_GPU_VIRTUAL_ADDRESS_RANGE_AND_STRIDE {
+        StartAddress: start_address,
+        SizeInBytes: size,
+        StrideInBytes: stride,
+    }
+}
+
 /// Describes timing and presentation statistics for a frame.
 ///
 /// For more information: [`DXGI_FRAME_STATISTICS structure`](https://learn.microsoft.com/en-us/windows/win32/api/dxgi/ns-dxgi-dxgi_frame_statistics)
@@ -1013,6 +1288,16 @@ impl<'a> GraphicsPipelineDesc<'a> {
         self.0.Flags = flags.as_raw();
         self
     }
+
+    /// Hashes the shaders, the input layout, the root signature and all state. The cached blob isn't part of the hash.
+    ///
+    /// The root signature is hashed by its content only if it was created by
+    /// [`Device::create_root_signature`](crate::device::Device::create_root_signature).
+    pub fn content_hash(&self) -> u128 {
+        let mut hasher = StableHasher::new();
+        self.0.hash_content(&mut hasher);
+        hasher.finish128()
+    }
 }
 
 /// Describes a heap.
@@ -1231,6 +1516,15 @@ impl IndirectArgumentDesc {
         })
     }
 
+    /// Indirect mesh dispatch, the arguments are three `u32` thread group counts.
+    #[inline]
+    pub fn dispatch_mesh() -> Self {
+        Self(D3D12_INDIRECT_ARGUMENT_DESC {
+            Type: D3D12_INDIRECT_ARGUMENT_TYPE_DISPATCH_MESH,
+            Anonymous: Default::default(),
+        })
+    }
+
     #[inline]
     pub fn vertex_buffer_view(slot: u32) -> Self {
         Self(D3D12_INDIRECT_ARGUMENT_DESC {
@@ -1313,7 +1607,7 @@ pub struct InputElementDesc<'a>(pub(crate) D3D12_INPUT_ELEMENT_DESC, PhantomData
 
 impl<'a> InputElementDesc<'a> {
     #[inline]
-    pub fn from_raw_per_vertex(
+    pub const fn from_raw_per_vertex(
         semantic_name: &'a CStr,
         semantic_index: u32,
         format: Format,
@@ -1325,7 +1619,7 @@ impl<'a> InputElementDesc<'a> {
             D3D12_INPUT_ELEMENT_DESC {
                 SemanticName: semantic_name,
                 SemanticIndex: semantic_index,
-                Format: format.as_raw(),
+                Format: DXGI_FORMAT(format as i32),
                 InputSlot: input_slot,
                 AlignedByteOffset: APPEND_ALIGNED_ELEMENT,
                 InputSlotClass: D3D12_INPUT_CLASSIFICATION_PER_VERTEX_DATA,
@@ -1377,10 +1671,53 @@ impl<'a> InputElementDesc<'a> {
     }
 
     #[inline]
-    pub fn with_offset(mut self, offset: u32) -> Self {
+    pub const fn with_offset(mut self, offset: u32) -> Self {
         self.0.AlignedByteOffset = offset;
         self
     }
+
+    /// Makes the element per-instance data, which advances every `step_rate` instances.
+    #[inline]
+    pub const fn with_instance_data_step_rate(mut self, step_rate: u32) -> Self {
+        self.0.InputSlotClass = D3D12_INPUT_CLASSIFICATION_PER_INSTANCE_DATA;
+        self.0.InstanceDataStepRate = step_rate;
+        self
+    }
+
+    #[inline]
+    pub fn semantic_name(&self) -> &CStr {
+        unsafe { CStr::from_ptr(self.0.SemanticName.as_ptr() as *const _) }
+    }
+
+    #[inline]
+    pub fn semantic_index(&self) -> u32 {
+        self.0.SemanticIndex
+    }
+
+    #[inline]
+    pub fn format(&self) -> Format {
+        self.0.Format.into()
+    }
+
+    #[inline]
+    pub fn input_slot(&self) -> u32 {
+        self.0.InputSlot
+    }
+
+    #[inline]
+    pub fn offset(&self) -> u32 {
+        self.0.AlignedByteOffset
+    }
+
+    #[inline]
+    pub fn is_per_instance(&self) -> bool {
+        self.0.InputSlotClass == D3D12_INPUT_CLASSIFICATION_PER_INSTANCE_DATA
+    }
+
+    #[inline]
+    pub fn instance_data_step_rate(&self) -> u32 {
+        self.0.InstanceDataStepRate
+    }
 }
 
 /// The LUID structure is an opaque structure that specifies an identifier that is guaranteed to be unique on the local machine.
@@ -1782,7 +2119,7 @@ impl QueryHeapDesc {
 /// Describes rasterizer state.
 ///
 /// For more information: [`D3D12_RASTERIZER_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_rasterizer_desc)
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug)]
 #[repr(transparent)]
 pub struct RasterizerDesc(pub(crate) D3D12_RASTERIZER_DESC);
 
@@ -1864,6 +2201,173 @@ impl Default for RasterizerDesc {
     }
 }
 
+/// Describes rasterizer state with a floating-point depth bias.
+///
+/// For more information: [`D3D12_RASTERIZER_DESC1 structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_rasterizer_desc1)
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct RasterizerDesc1(pub(crate) D3D12_RASTERIZER_DESC1);
+
+impl RasterizerDesc1 {
+    #[inline]
+    pub fn with_fill_mode(mut self, fill_mode: FillMode) -> Self {
+        self.0.FillMode = fill_mode.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn with_cull_mode(mut self, cull_mode: CullMode) -> Self {
+        self.0.CullMode = cull_mode.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn enable_front_counter_clockwise(mut self) -> Self {
+        self.0.FrontCounterClockwise = true.into();
+        self
+    }
+
+    #[inline]
+    pub fn with_depth_bias(mut self, depth_bias: f32) -> Self {
+        self.0.DepthBias = depth_bias;
+        self
+    }
+
+    #[inline]
+    pub fn with_depth_bias_clamp(mut self, depth_bias_clamp: f32) -> Self {
+        self.0.DepthBiasClamp = depth_bias_clamp;
+        self
+    }
+
+    #[inline]
+    pub fn with_slope_scaled_depth_bias(mut self, slope_scaled_depth_bias: f32) -> Self {
+        self.0.SlopeScaledDepthBias = slope_scaled_depth_bias;
+        self
+    }
+
+    #[inline]
+    pub fn enable_multisample(mut self) -> Self {
+        self.0.MultisampleEnable = true.into();
+        self
+    }
+
+    #[inline]
+    pub fn enable_antialiased_line(mut self) -> Self {
+        self.0.AntialiasedLineEnable = true.into();
+        self
+    }
+
+    #[inline]
+    pub fn enable_depth_clip(mut self) -> Self {
+        self.0.DepthClipEnable = true.into();
+        self
+    }
+
+    #[inline]
+    pub fn with_forced_sample_count(mut self, forced_sample_count: u32) -> Self {
+        self.0.ForcedSampleCount = forced_sample_count;
+        self
+    }
+
+    #[inline]
+    pub fn with_conservative_raster(mut self, conservative_raster: ConservativeRaster) -> Self {
+        self.0.ConservativeRaster = conservative_raster.as_raw();
+        self
+    }
+}
+
+impl Default for RasterizerDesc1 {
+    fn default() -> Self {
+        Self(D3D12_RASTERIZER_DESC1 {
+            FillMode: D3D12_FILL_MODE_SOLID,
+            CullMode: D3D12_CULL_MODE_BACK,
+            ..Default::default()
+        })
+    }
+}
+
+/// Describes rasterizer state with a line rasterization mode.
+///
+/// For more information: [`D3D12_RASTERIZER_DESC2 structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_rasterizer_desc2)
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct RasterizerDesc2(pub(crate) D3D12_RASTERIZER_DESC2);
+
+impl RasterizerDesc2 {
+    #[inline]
+    pub fn with_fill_mode(mut self, fill_mode: FillMode) -> Self {
+        self.0.FillMode = fill_mode.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn with_cull_mode(mut self, cull_mode: CullMode) -> Self {
+        self.0.CullMode = cull_mode.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn enable_front_counter_clockwise(mut self) -> Self {
+        self.0.FrontCounterClockwise = true.into();
+        self
+    }
+
+    #[inline]
+    pub fn with_depth_bias(mut self, depth_bias: f32) -> Self {
+        self.0.DepthBias = depth_bias;
+        self
+    }
+
+    #[inline]
+    pub fn with_depth_bias_clamp(mut self, depth_bias_clamp: f32) -> Self {
+        self.0.DepthBiasClamp = depth_bias_clamp;
+        self
+    }
+
+    #[inline]
+    pub fn with_slope_scaled_depth_bias(mut self, slope_scaled_depth_bias: f32) -> Self {
+        self.0.SlopeScaledDepthBias = slope_scaled_depth_bias;
+        self
+    }
+
+    #[inline]
+    pub fn with_line_rasterization_mode(
+        mut self,
+        line_rasterization_mode: LineRasterizationMode,
+    ) -> Self {
+        self.0.LineRasterizationMode = line_rasterization_mode.as_raw();
+        self
+    }
+
+    #[inline]
+    pub fn enable_depth_clip(mut self) -> Self {
+        self.0.DepthClipEnable = true.into();
+        self
+    }
+
+    #[inline]
+    pub fn with_forced_sample_count(mut self, forced_sample_count: u32) -> Self {
+        self.0.ForcedSampleCount = forced_sample_count;
+        self
+    }
+
+    #[inline]
+    pub fn with_conservative_raster(mut self, conservative_raster: ConservativeRaster) -> Self {
+        self.0.ConservativeRaster = conservative_raster.as_raw();
+        self
+    }
+}
+
+impl Default for RasterizerDesc2 {
+    fn default() -> Self {
+        Self(D3D12_RASTERIZER_DESC2 {
+            FillMode: D3D12_FILL_MODE_SOLID,
+            CullMode: D3D12_CULL_MODE_BACK,
+            ..Default::default()
+        })
+    }
+}
+
 /// Represents a rational number.
 ///
 /// For more information: [`DXGI_RATIONAL structure`](https://learn.microsoft.com/en-us/windows/win32/api/dxgicommon/ns-dxgicommon-dxgi_rational)
@@ -2531,10 +3035,35 @@ impl Default for SampleDesc {
     }
 }
 
+/// Describes a sub-pixel sample position for use with programmable sample positions.
+///
+/// For more information: [`D3D12_SAMPLE_POSITION structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_sample_position)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct SamplePosition(pub(crate) D3D12_SAMPLE_POSITION);
+
+impl SamplePosition {
+    /// Coordinates are in 1/16th of a pixel, from -8 (top-left) to 7 (bottom-right).
+    #[inline]
+    pub fn new(x: i8, y: i8) -> Self {
+        Self(D3D12_SAMPLE_POSITION { X: x, Y: y })
+    }
+
+    #[inline]
+    pub fn x(&self) -> i8 {
+        self.0.X
+    }
+
+    #[inline]
+    pub fn y(&self) -> i8 {
+        self.0.Y
+    }
+}
+
 /// Describes a sampler state.
 ///
 /// For more information: [`D3D12_SAMPLER_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_sampler_desc)
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[derive(Clone, Copy, Debug, Default)]
 #[repr(transparent)]
 pub struct SamplerDesc(pub(crate) D3D12_SAMPLER_DESC);
 
@@ -2869,6 +3398,16 @@ impl<'a> ShaderMacro<'a> {
             Default::default(),
         )
     }
+
+    #[inline]
+    pub fn name(&self) -> &'a CStr {
+        unsafe { CStr::from_ptr(self.0.Name.as_ptr() as *const _) }
+    }
+
+    #[inline]
+    pub fn definition(&self) -> &'a CStr {
+        unsafe { CStr::from_ptr(self.0.Definition.as_ptr() as *const _) }
+    }
 }
 
 /// Describes how a shader resource is bound to a shader input.
@@ -3235,6 +3774,11 @@ impl ShaderVariableDesc {
         unsafe { CStr::from_ptr(self.0.Name.as_ptr() as *const _) }
     }
 
+    #[inline]
+    pub fn start_offset(&self) -> u32 {
+        self.0.StartOffset
+    }
+
     #[inline]
     pub fn size(&self) -> u32 {
         self.0.Size
@@ -3332,7 +3876,7 @@ impl SignatureParameterDesc {
 /// Describes a static sampler.
 ///
 /// For more information: [`D3D12_STATIC_SAMPLER_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_static_sampler_desc)
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[derive(Clone, Copy, Debug, Default)]
 #[repr(transparent)]
 pub struct StaticSamplerDesc(pub(crate) D3D12_STATIC_SAMPLER_DESC);
 
@@ -4013,6 +4557,50 @@ impl VertexBufferView {
     }
 }
 
+/// Specifies the viewport and render target array slice of a view instance.
+///
+/// For more information: [`D3D12_VIEW_INSTANCE_LOCATION structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_view_instance_location)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct ViewInstanceLocation(pub(crate) D3D12_VIEW_INSTANCE_LOCATION);
+
+impl ViewInstanceLocation {
+    #[inline]
+    pub fn new(viewport_array_index: u32, render_target_array_index: u32) -> Self {
+        Self(D3D12_VIEW_INSTANCE_LOCATION {
+            ViewportArrayIndex: viewport_array_index,
+            RenderTargetArrayIndex: render_target_array_index,
+        })
+    }
+}
+
+/// Specifies parameters used during view instancing configuration.
+///
+/// For more information: [`D3D12_VIEW_INSTANCING_DESC structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_view_instancing_desc)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct ViewInstancingDesc<'a>(pub(crate) D3D12_VIEW_INSTANCING_DESC, PhantomData<&'a ()>);
+
+impl<'a> ViewInstancingDesc<'a> {
+    #[inline]
+    pub fn new(locations: &'a [ViewInstanceLocation]) -> Self {
+        Self(
+            D3D12_VIEW_INSTANCING_DESC {
+                ViewInstanceCount: locations.len() as u32,
+                pViewInstanceLocations: locations.as_ptr() as *const _,
+                Flags: D3D12_VIEW_INSTANCING_FLAG_NONE,
+            },
+            PhantomData,
+        )
+    }
+
+    #[inline]
+    pub fn with_flags(mut self, flags: ViewInstancingFlags) -> Self {
+        self.0.Flags = flags.as_raw();
+        self
+    }
+}
+
 /// Describes the dimensions of a viewport.
 ///
 /// For more information: [`D3D12_VIEWPORT structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_viewport)
@@ -4045,3 +4633,52 @@ impl Viewport {
         Self::from_position_and_size((0.0, 0.0), size)
     }
 }
+
+impl_content_hash!(
+    hash_eq: BlendDesc,
+    RenderTargetBlendDesc,
+    DepthStencilDesc,
+    DepthStencilDesc1,
+    DepthStencilDesc2,
+    DepthStencilOpDesc,
+    DepthStencilOpDesc1,
+    SampleDesc,
+);
+
+impl_content_hash!(
+    float_eq: RasterizerDesc,
+    RasterizerDesc1,
+    RasterizerDesc2,
+    SamplerDesc,
+    StaticSamplerDesc,
+);
+
+impl_content_hash!(content: DeclarationEntry, InputElementDesc<'_>, StreamOutputDesc<'_>);
+
+/// Specifies the value and the destination of a [`GraphicsCommandList2::write_buffer_immediate`](crate::command_list::GraphicsCommandList2::write_buffer_immediate) operation.
+///
+/// For more information: [`D3D12_WRITEBUFFERIMMEDIATE_PARAMETER structure`](https://learn.microsoft.com/en-us/windows/win32/api/d3d12/ns-d3d12-d3d12_writebufferimmediate_parameter)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct WriteBufferImmediateParameter(pub(crate) D3D12_WRITEBUFFERIMMEDIATE_PARAMETER);
+
+impl WriteBufferImmediateParameter {
+    /// The destination address must be aligned to 4 bytes.
+    #[inline]
+    pub fn new(dest: GpuVirtualAddress, value: u32) -> Self {
+        Self(D3D12_WRITEBUFFERIMMEDIATE_PARAMETER {
+            Dest: dest,
+            Value: value,
+        })
+    }
+
+    #[inline]
+    pub fn dest(&self) -> GpuVirtualAddress {
+        self.0.Dest
+    }
+
+    #[inline]
+    pub fn value(&self) -> u32 {
+        self.0.Value
+    }
+}
@@ -0,0 +1,214 @@
+use crate::types::{Format, InputElementDesc};
+
+#[cfg(feature = "derive")]
+pub use oxidx_derive::VertexLayout;
+
+/// A type that can be fetched by the input assembler as a single vertex attribute.
+pub trait VertexAttribute: Copy {
+    /// Format used when a field of this type isn't annotated with `#[format(...)]`.
+    const FORMAT: Format;
+}
+
+/// A vertex struct with a known input layout.
+///
+/// Usually implemented with `#[derive(VertexLayout)]`, which computes offsets with `offset_of!`
+/// and takes semantics from `#[vertex(semantic = "...", index = N)]` attributes or field names.
+///
+/// ```ignore
+/// #[repr(C)]
+/// #[derive(Clone, Copy, VertexLayout)]
+/// struct Vertex {
+///     position: [f32; 3],
+///     #[format(R8G8B8A8Unorm)]
+///     color: [u8; 4],
+///     #[vertex(semantic = "TEXCOORD", index = 1)]
+///     material: u32,
+/// }
+///
+/// let desc = GraphicsPipelineDesc::new(&vs).with_input_layout(Vertex::INPUT_LAYOUT);
+/// ```
+pub trait VertexLayout {
+    /// Elements of the input layout in declaration order.
+    const INPUT_LAYOUT: &'static [InputElementDesc<'static>];
+}
+
+macro_rules! impl_vertex_attribute {
+    ($($t:ty => [$f1:ident, $f2:ident, $f3:ident, $f4:ident]),* $(,)?) => {
+        $(
+            impl VertexAttribute for $t {
+                const FORMAT: Format = Format::$f1;
+            }
+
+            impl VertexAttribute for [$t; 1] {
+                const FORMAT: Format = Format::$f1;
+            }
+
+            impl VertexAttribute for [$t; 2] {
+                const FORMAT: Format = Format::$f2;
+            }
+
+            impl VertexAttribute for [$t; 3] {
+                const FORMAT: Format = Format::$f3;
+            }
+
+            impl VertexAttribute for [$t; 4] {
+                const FORMAT: Format = Format::$f4;
+            }
+        )*
+    };
+}
+
+impl_vertex_attribute!(
+    f32 => [R32Float, Rg32Float, Rgb32Float, Rgba32Float],
+    u32 => [R32Uint, Rg32Uint, Rgb32Uint, Rgba32Uint],
+    i32 => [R32Sint, Rg32Sint, Rgb32Sint, Rgba32Sint],
+);
+
+macro_rules! impl_small_vertex_attribute {
+    ($($t:ty => [$f1:ident, $f2:ident, $f4:ident]),* $(,)?) => {
+        $(
+            impl VertexAttribute for $t {
+                const FORMAT: Format = Format::$f1;
+            }
+
+            impl VertexAttribute for [$t; 1] {
+                const FORMAT: Format = Format::$f1;
+            }
+
+            impl VertexAttribute for [$t; 2] {
+                const FORMAT: Format = Format::$f2;
+            }
+
+            impl VertexAttribute for [$t; 4] {
+                const FORMAT: Format = Format::$f4;
+            }
+        )*
+    };
+}
+
+// There are no three-component 8-bit and 16-bit formats.
+impl_small_vertex_attribute!(
+    u16 => [R16Uint, Rg16Uint, Rgba16Uint],
+    i16 => [R16Sint, Rg16Sint, Rgba16Sint],
+    u8 => [R8Uint, Rg8Uint, Rgba8Uint],
+    i8 => [R8Sint, Rg8Sint, Rgba8Sint],
+);
+
+#[cfg(any(feature = "glam", feature = "mint"))]
+macro_rules! impl_vertex_attribute_for {
+    ($($t:ty => $f:ident),* $(,)?) => {
+        $(
+            impl VertexAttribute for $t {
+                const FORMAT: Format = Format::$f;
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "glam")]
+mod glam_impls {
+    use super::*;
+
+    impl_vertex_attribute_for!(
+        glam::Vec2 => Rg32Float,
+        glam::Vec3 => Rgb32Float,
+        glam::Vec4 => Rgba32Float,
+        glam::UVec2 => Rg32Uint,
+        glam::UVec3 => Rgb32Uint,
+        glam::UVec4 => Rgba32Uint,
+        glam::IVec2 => Rg32Sint,
+        glam::IVec3 => Rgb32Sint,
+        glam::IVec4 => Rgba32Sint,
+    );
+}
+
+#[cfg(feature = "mint")]
+mod mint_impls {
+    use super::*;
+
+    impl_vertex_attribute_for!(
+        mint::Vector2<f32> => Rg32Float,
+        mint::Vector3<f32> => Rgb32Float,
+        mint::Vector4<f32> => Rgba32Float,
+        mint::Point2<f32> => Rg32Float,
+        mint::Point3<f32> => Rgb32Float,
+        mint::Vector2<u32> => Rg32Uint,
+        mint::Vector3<u32> => Rgb32Uint,
+        mint::Vector4<u32> => Rgba32Uint,
+        mint::Vector2<i32> => Rg32Sint,
+        mint::Vector3<i32> => Rgb32Sint,
+        mint::Vector4<i32> => Rgba32Sint,
+    );
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use std::mem::offset_of;
+
+    use super::*;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, VertexLayout)]
+    struct Vertex {
+        position: [f32; 3],
+        #[format(R8G8B8A8Unorm)]
+        color: [u8; 4],
+        texcoord1: [f32; 2],
+        #[vertex(semantic = "BLENDINDICES")]
+        bone: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy, VertexLayout)]
+    #[vertex(slot = 1, instance_step_rate = 1)]
+    struct Instance {
+        #[vertex(semantic = "TRANSFORM", index = 0)]
+        row0: [f32; 4],
+        #[vertex(semantic = "TRANSFORM", index = 1)]
+        row1: [f32; 4],
+        #[vertex(skip)]
+        _padding: [u8; 4],
+        #[format(Rgba8Unorm)]
+        tint: [u8; 4],
+    }
+
+    #[test]
+    fn vertex_layout_test() {
+        let layout = Vertex::INPUT_LAYOUT;
+
+        assert_eq!(layout.len(), 4);
+
+        assert_eq!(layout[0].semantic_name(), c"POSITION");
+        assert_eq!(layout[0].format(), Format::Rgb32Float);
+        assert_eq!(layout[0].offset(), 0);
+
+        assert_eq!(layout[1].semantic_name(), c"COLOR");
+        assert_eq!(layout[1].format(), Format::Rgba8Unorm);
+        assert_eq!(layout[1].offset(), offset_of!(Vertex, color) as u32);
+
+        assert_eq!(layout[2].semantic_name(), c"TEXCOORD");
+        assert_eq!(layout[2].semantic_index(), 1);
+        assert_eq!(layout[2].format(), Format::Rg32Float);
+
+        assert_eq!(layout[3].semantic_name(), c"BLENDINDICES");
+        assert_eq!(layout[3].format(), Format::R32Uint);
+        assert_eq!(layout[3].offset(), offset_of!(Vertex, bone) as u32);
+        assert!(layout
+            .iter()
+            .all(|e| e.input_slot() == 0 && !e.is_per_instance()));
+    }
+
+    #[test]
+    fn instance_layout_test() {
+        let layout = Instance::INPUT_LAYOUT;
+
+        assert_eq!(layout.len(), 3);
+        assert_eq!(layout[1].semantic_name(), c"TRANSFORM");
+        assert_eq!(layout[1].semantic_index(), 1);
+        assert_eq!(layout[2].semantic_name(), c"TINT");
+        assert_eq!(layout[2].offset(), offset_of!(Instance, tint) as u32);
+        assert!(layout.iter().all(|e| e.input_slot() == 1
+            && e.is_per_instance()
+            && e.instance_data_step_rate() == 1));
+    }
+}
@@ -0,0 +1,410 @@
+use std::{ffi::c_void, marker::PhantomData};
+
+use windows::{core::HSTRING, Win32::Graphics::Direct3D12::*};
+
+use crate::{
+    create_type, impl_interface,
+    state_object::{NodeId, ProgramIdentifier},
+    types::*,
+};
+
+create_type! {
+    /// Provides methods to query the work graphs of an executable state object.
+    ///
+    /// For more information: [`ID3D12WorkGraphProperties interface`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#id3d12workgraphproperties-methods)
+    WorkGraphProperties wrap ID3D12WorkGraphProperties
+}
+
+impl_interface! {
+    WorkGraphProperties;
+
+    /// Gets the number of work graphs in the state object.
+    pub fn get_num_work_graphs(&self) -> u32 {
+        unsafe {
+            self.0.GetNumWorkGraphs()
+        }
+    }
+
+    /// Gets the index of a work graph by its program name. Returns [`None`] if the program isn't found.
+    pub fn get_work_graph_index(&self, program_name: &str) -> Option<u32> {
+        unsafe {
+            let index = self.0.GetWorkGraphIndex(&HSTRING::from(program_name));
+
+            (index != u32::MAX).then_some(index)
+        }
+    }
+
+    /// Gets the number of nodes of a work graph.
+    pub fn get_num_nodes(&self, work_graph_index: u32) -> u32 {
+        unsafe {
+            self.0.GetNumNodes(work_graph_index)
+        }
+    }
+
+    /// Gets the number of entry points of a work graph.
+    pub fn get_num_entrypoints(&self, work_graph_index: u32) -> u32 {
+        unsafe {
+            self.0.GetNumEntrypoints(work_graph_index)
+        }
+    }
+
+    /// Gets the index of an entry point, used by [`NodeCpuInput`] and [`NodeGpuInput`].
+    /// Returns [`None`] if the node isn't an entry point of the work graph.
+    pub fn get_entrypoint_index(&self, work_graph_index: u32, node: &NodeId) -> Option<u32> {
+        unsafe {
+            let name = HSTRING::from(node.name());
+            let index = self.0.GetEntrypointIndex(
+                work_graph_index,
+                D3D12_NODE_ID {
+                    Name: windows::core::PCWSTR(name.as_ptr()),
+                    ArrayIndex: node.array_index(),
+                }
+            );
+
+            (index != u32::MAX).then_some(index)
+        }
+    }
+
+    /// Gets the size of an input record of an entry point.
+    pub fn get_entrypoint_record_size(&self, work_graph_index: u32, entrypoint_index: u32) -> u32 {
+        unsafe {
+            self.0.GetEntrypointRecordSizeInBytes(work_graph_index, entrypoint_index)
+        }
+    }
+
+    /// Gets the size of the backing memory a work graph needs, see [`SetProgramDesc::work_graph`].
+    pub fn get_work_graph_memory_requirements(&self, work_graph_index: u32) -> WorkGraphMemoryRequirements {
+        unsafe {
+            let mut requirements = Default::default();
+            self.0.GetWorkGraphMemoryRequirements(work_graph_index, &mut requirements);
+
+            WorkGraphMemoryRequirements(requirements)
+        }
+    }
+}
+
+/// Describes the backing memory requirements of a work graph.
+///
+/// For more information: [`D3D12_WORK_GRAPH_MEMORY_REQUIREMENTS structure`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#d3d12_work_graph_memory_requirements)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct WorkGraphMemoryRequirements(pub(crate) D3D12_WORK_GRAPH_MEMORY_REQUIREMENTS);
+
+impl WorkGraphMemoryRequirements {
+    #[inline]
+    pub fn min_size(&self) -> u64 {
+        self.0.MinSizeInBytes
+    }
+
+    /// Size above which the work graph doesn't get faster.
+    #[inline]
+    pub fn max_size(&self) -> u64 {
+        self.0.MaxSizeInBytes
+    }
+
+    /// Sizes between the minimum and the maximum must be a multiple of the granularity.
+    #[inline]
+    pub fn size_granularity(&self) -> u32 {
+        self.0.SizeGranularityInBytes
+    }
+}
+
+/// Describes the program to set on a command list.
+///
+/// For more information: [`D3D12_SET_PROGRAM_DESC structure`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#d3d12_set_program_desc)
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct SetProgramDesc(pub(crate) D3D12_SET_PROGRAM_DESC);
+
+impl SetProgramDesc {
+    /// Sets a work graph with its backing memory, whose size comes from [`WorkGraphMemoryRequirements`].
+    ///
+    /// The memory must be initialized with [`SetWorkGraphFlags::Initialize`] before the first dispatch.
+    #[inline]
+    pub fn work_graph(
+        program: ProgramIdentifier,
+        backing_memory: GpuVirtualAddress,
+        backing_memory_size: u64,
+    ) -> Self {
+        Self(D3D12_SET_PROGRAM_DESC {
+            Type: D3D12_PROGRAM_TYPE_WORK_GRAPH,
+            Anonymous: D3D12_SET_PROGRAM_DESC_0 {
+                WorkGraph: D3D12_SET_WORK_GRAPH_DESC {
+                    ProgramIdentifier: program.0,
+                    Flags: D3D12_SET_WORK_GRAPH_FLAG_NONE,
+                    BackingMemory: D3D12_GPU_VIRTUAL_ADDRESS_RANGE {
+                        StartAddress: backing_memory,
+                        SizeInBytes: backing_memory_size,
+                    },
+                    NodeLocalRootArgumentsTable: Default::default(),
+                },
+            },
+        })
+    }
+
+    /// Sets the flags of a work graph. Ignored by other programs.
+    #[inline]
+    pub fn with_flags(mut self, flags: SetWorkGraphFlags) -> Self {
+        if self.0.Type == D3D12_PROGRAM_TYPE_WORK_GRAPH {
+            self.0.Anonymous.WorkGraph.Flags = flags.as_raw();
+        }
+        self
+    }
+
+    /// Sets the table of the local root arguments of the nodes of a work graph. Ignored by other programs.
+    #[inline]
+    pub fn with_node_local_root_arguments_table(
+        mut self,
+        start_address: GpuVirtualAddress,
+        size: u64,
+        stride: u64,
+    ) -> Self {
+        if self.0.Type == D3D12_PROGRAM_TYPE_WORK_GRAPH {
+            self.0.Anonymous.WorkGraph.NodeLocalRootArgumentsTable =
+                D3D12_GPU_VIRTUAL_ADDRESS_RANGE_AND_STRIDE {
+                    StartAddress: start_address,
+                    SizeInBytes: size,
+                    StrideInBytes: stride,
+                };
+        }
+        self
+    }
+}
+
+/// Describes the input records of an entry point node in CPU memory.
+///
+/// For more information: [`D3D12_NODE_CPU_INPUT structure`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#d3d12_node_cpu_input)
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct NodeCpuInput<'a>(pub(crate) D3D12_NODE_CPU_INPUT, PhantomData<&'a ()>);
+
+impl<'a> NodeCpuInput<'a> {
+    /// The records must match the input record struct of the node, its size is returned by
+    /// [`WorkGraphProperties::get_entrypoint_record_size`].
+    #[inline]
+    pub fn new<T: Copy>(entrypoint_index: u32, records: &'a [T]) -> Self {
+        Self(
+            D3D12_NODE_CPU_INPUT {
+                EntrypointIndex: entrypoint_index,
+                NumRecords: records.len() as u32,
+                pRecords: records.as_ptr() as *const c_void,
+                RecordStrideInBytes: size_of::<T>() as u64,
+            },
+            PhantomData,
+        )
+    }
+
+    /// Launches `num_records` empty records of a node without input record struct.
+    #[inline]
+    pub fn empty(entrypoint_index: u32, num_records: u32) -> Self {
+        Self(
+            D3D12_NODE_CPU_INPUT {
+                EntrypointIndex: entrypoint_index,
+                NumRecords: num_records,
+                pRecords: std::ptr::null(),
+                RecordStrideInBytes: 0,
+            },
+            PhantomData,
+        )
+    }
+
+    #[inline]
+    pub fn entrypoint_index(&self) -> u32 {
+        self.0.EntrypointIndex
+    }
+
+    #[inline]
+    pub fn num_records(&self) -> u32 {
+        self.0.NumRecords
+    }
+}
+
+/// Describes the input records of an entry point node in GPU memory.
+///
+/// The struct has the layout the GPU expects, so it can be written to a buffer read by
+/// [`DispatchGraphDesc::node_gpu_input`].
+///
+/// For more information: [`D3D12_NODE_GPU_INPUT structure`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#d3d12_node_gpu_input)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct NodeGpuInput(pub(crate) D3D12_NODE_GPU_INPUT);
+
+impl NodeGpuInput {
+    #[inline]
+    pub fn new(
+        entrypoint_index: u32,
+        num_records: u32,
+        records: GpuVirtualAddress,
+        stride: u64,
+    ) -> Self {
+        Self(D3D12_NODE_GPU_INPUT {
+            EntrypointIndex: entrypoint_index,
+            NumRecords: num_records,
+            Records: D3D12_GPU_VIRTUAL_ADDRESS_AND_STRIDE {
+                StartAddress: records,
+                StrideInBytes: stride,
+            },
+        })
+    }
+}
+
+/// Describes the inputs of several entry point nodes in GPU memory.
+///
+/// The struct has the layout the GPU expects, so it can be written to a buffer read by
+/// [`DispatchGraphDesc::multi_node_gpu_input`].
+///
+/// For more information: [`D3D12_MULTI_NODE_GPU_INPUT structure`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#d3d12_multi_node_gpu_input)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct MultiNodeGpuInput(pub(crate) D3D12_MULTI_NODE_GPU_INPUT);
+
+impl MultiNodeGpuInput {
+    /// `node_inputs` points to `num_node_inputs` of [`NodeGpuInput`] placed `stride` bytes apart.
+    #[inline]
+    pub fn new(num_node_inputs: u32, node_inputs: GpuVirtualAddress, stride: u64) -> Self {
+        Self(D3D12_MULTI_NODE_GPU_INPUT {
+            NumNodeInputs: num_node_inputs,
+            NodeInputs: D3D12_GPU_VIRTUAL_ADDRESS_AND_STRIDE {
+                StartAddress: node_inputs,
+                StrideInBytes: stride,
+            },
+        })
+    }
+}
+
+/// Describes the inputs of a work graph dispatch.
+///
+/// For more information: [`D3D12_DISPATCH_GRAPH_DESC structure`](https://microsoft.github.io/DirectX-Specs/d3d/WorkGraphs.html#d3d12_dispatch_graph_desc)
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct DispatchGraphDesc<'a>(pub(crate) D3D12_DISPATCH_GRAPH_DESC, PhantomData<&'a ()>);
+
+impl<'a> DispatchGraphDesc<'a> {
+    /// Feeds the records of a single entry point from CPU memory. The records are copied into the command list.
+    #[inline]
+    pub fn node_cpu_input(input: NodeCpuInput<'a>) -> Self {
+        Self(
+            D3D12_DISPATCH_GRAPH_DESC {
+                Mode: D3D12_DISPATCH_MODE_NODE_CPU_INPUT,
+                Anonymous: D3D12_DISPATCH_GRAPH_DESC_0 {
+                    NodeCPUInput: input.0,
+                },
+            },
+            PhantomData,
+        )
+    }
+
+    /// Feeds the records of several entry points from CPU memory.
+    #[inline]
+    pub fn multi_node_cpu_input(inputs: &'a [NodeCpuInput<'a>]) -> Self {
+        Self(
+            D3D12_DISPATCH_GRAPH_DESC {
+                Mode: D3D12_DISPATCH_MODE_MULTI_NODE_CPU_INPUT,
+                Anonymous: D3D12_DISPATCH_GRAPH_DESC_0 {
+                    MultiNodeCPUInput: D3D12_MULTI_NODE_CPU_INPUT {
+                        NumNodeInputs: inputs.len() as u32,
+                        pNodeInputs: inputs.as_ptr() as *const _,
+                        NodeInputStrideInBytes: size_of::<NodeCpuInput<'_>>() as u64,
+                    },
+                },
+            },
+            PhantomData,
+        )
+    }
+
+    /// Reads a [`NodeGpuInput`] from GPU memory at the time of the dispatch.
+    #[inline]
+    pub fn node_gpu_input(input: GpuVirtualAddress) -> Self {
+        Self(
+            D3D12_DISPATCH_GRAPH_DESC {
+                Mode: D3D12_DISPATCH_MODE_NODE_GPU_INPUT,
+                Anonymous: D3D12_DISPATCH_GRAPH_DESC_0 {
+                    NodeGPUInput: input,
+                },
+            },
+            PhantomData,
+        )
+    }
+
+    /// Reads a [`MultiNodeGpuInput`] from GPU memory at the time of the dispatch.
+    #[inline]
+    pub fn multi_node_gpu_input(inputs: GpuVirtualAddress) -> Self {
+        Self(
+            D3D12_DISPATCH_GRAPH_DESC {
+                Mode: D3D12_DISPATCH_MODE_MULTI_NODE_GPU_INPUT,
+                Anonymous: D3D12_DISPATCH_GRAPH_DESC_0 {
+                    MultiNodeGPUInput: inputs,
+                },
+            },
+            PhantomData,
+        )
+    }
+
+    #[inline]
+    pub fn mode(&self) -> DispatchMode {
+        self.0.Mode.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Record {
+        grid: [u32; 3],
+        material: u16,
+    }
+
+    #[test]
+    fn dispatch_graph_desc_test() {
+        let records = [
+            Record {
+                grid: [4, 4, 1],
+                material: 1,
+            },
+            Record {
+                grid: [8, 1, 1],
+                material: 2,
+            },
+        ];
+
+        let desc = DispatchGraphDesc::node_cpu_input(NodeCpuInput::new(1, &records));
+        assert_eq!(desc.mode(), DispatchMode::NodeCpuInput);
+
+        unsafe {
+            let input = desc.0.Anonymous.NodeCPUInput;
+            assert_eq!(input.EntrypointIndex, 1);
+            assert_eq!(input.NumRecords, 2);
+            assert_eq!(input.RecordStrideInBytes, 16);
+            assert_eq!(input.pRecords, records.as_ptr() as *const c_void);
+        }
+
+        let inputs = [
+            NodeCpuInput::new(0, &records[..1]),
+            NodeCpuInput::empty(2, 64),
+        ];
+        let desc = DispatchGraphDesc::multi_node_cpu_input(&inputs);
+        assert_eq!(desc.mode(), DispatchMode::MultiNodeCpuInput);
+
+        unsafe {
+            let multi = desc.0.Anonymous.MultiNodeCPUInput;
+            assert_eq!(multi.NumNodeInputs, 2);
+            assert_eq!(
+                multi.NodeInputStrideInBytes as usize,
+                size_of::<D3D12_NODE_CPU_INPUT>()
+            );
+
+            let second = &*multi.pNodeInputs.add(1);
+            assert_eq!(second.EntrypointIndex, 2);
+            assert_eq!(second.NumRecords, 64);
+            assert!(second.pRecords.is_null());
+        }
+
+        let desc = DispatchGraphDesc::multi_node_gpu_input(0x2000);
+        assert_eq!(desc.mode(), DispatchMode::MultiNodeGpuInput);
+        assert_eq!(unsafe { desc.0.Anonymous.MultiNodeGPUInput }, 0x2000);
+        assert_eq!(size_of::<NodeGpuInput>(), 24);
+    }
+}